@@ -20,6 +20,7 @@ pub fn generate_entities() {
 // The contents of this file are automatically generated and should not be modified directly.  See the `build` directory.
 
 use ::{
+    AcadTableCell,
     CodePair,
     Color,
     Drawing,
@@ -28,8 +29,15 @@ use ::{
     DxfError,
     DxfResult,
     ExtensionGroup,
+    HatchBoundaryPath,
+    HatchGradientColor,
+    HatchPatternDefinitionLine,
     LwPolylineVertex,
+    MLeaderArrowheadOverride,
+    MLeaderBlockAttribute,
+    MLeaderContextData,
     Point,
+    TransformationMatrix,
     Vector,
     XData,
 };
@@ -23,6 +23,7 @@ pub fn generate_objects() {
 // The contents of this file are automatically generated and should not be modified directly.  See the `build` directory.
 
 use ::{
+    BlockVisibilityState,
     CodePair,
     Color,
     DataTableValue,
@@ -0,0 +1,483 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::io::Write;
+
+use ::{
+    CodePair,
+    Color,
+    DxfError,
+    DxfResult,
+};
+
+use code_pair_cursor::CodePairCursor;
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use helper_functions::*;
+
+// The longest string written in a single table cell text chunk.
+const CELL_TEXT_CHUNK_LENGTH: usize = 250;
+
+/// The value of an attribute of a block in an `AcadTableCell`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AcadTableCellAttribute {
+    /// The handle of the `AttributeDefinition` in the block.
+    pub attribute_definition_handle: u32,
+    pub value: String,
+}
+
+/// The content of an `AcadTableCell`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AcadTableCellContent {
+    Text(String),
+    /// A field; the text is the last evaluated value of the field.
+    Field {
+        field_handle: u32,
+        text: String,
+    },
+    Block {
+        block_record_handle: u32,
+        scale: f64,
+        attributes: Vec<AcadTableCellAttribute>,
+    },
+}
+
+impl Default for AcadTableCellContent {
+    fn default() -> Self {
+        AcadTableCellContent::Text(String::new())
+    }
+}
+
+/// The values of an `AcadTableCell` that override the cell style of the table style.  Values that
+/// are `None` are taken from the style.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AcadTableCellOverrides {
+    pub text_style_name: Option<String>,
+    pub text_height: Option<f64>,
+    pub alignment: Option<i16>,
+    pub text_color: Option<Color>,
+    pub fill_color: Option<Color>,
+    pub is_background_color_enabled: Option<bool>,
+    pub data_type: Option<i32>,
+    pub unit_type: Option<i32>,
+    pub top_border_lineweight: Option<i16>,
+    pub right_border_lineweight: Option<i16>,
+    pub bottom_border_lineweight: Option<i16>,
+    pub left_border_lineweight: Option<i16>,
+    pub is_top_border_visible: Option<bool>,
+    pub is_right_border_visible: Option<bool>,
+    pub is_bottom_border_visible: Option<bool>,
+    pub is_left_border_visible: Option<bool>,
+    pub top_border_color: Option<Color>,
+    pub right_border_color: Option<Color>,
+    pub bottom_border_color: Option<Color>,
+    pub left_border_color: Option<Color>,
+}
+
+/// A cell of an `AcadTable`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcadTableCell {
+    pub content: AcadTableCellContent,
+    pub flags: i16,
+    /// Whether the cell is part of a merged range of cells.
+    pub is_merged: bool,
+    pub is_autofit: bool,
+    /// The number of columns spanned by the top-left cell of a merged range.
+    pub merged_column_count: i32,
+    /// The number of rows spanned by the top-left cell of a merged range.
+    pub merged_row_count: i32,
+    pub override_flags: i32,
+    pub virtual_edge_flags: i16,
+    pub rotation: f64,
+    pub overrides: AcadTableCellOverrides,
+}
+
+impl Default for AcadTableCell {
+    fn default() -> Self {
+        AcadTableCell {
+            content: AcadTableCellContent::default(),
+            flags: 0,
+            is_merged: false,
+            is_autofit: false,
+            merged_column_count: 1,
+            merged_row_count: 1,
+            override_flags: 0,
+            virtual_edge_flags: 0,
+            rotation: 0.0,
+            overrides: AcadTableCellOverrides::default(),
+        }
+    }
+}
+
+impl AcadTableCell {
+    /// Creates a new cell containing the specified text.
+    pub fn new_text(text: &str) -> Self {
+        AcadTableCell {
+            content: AcadTableCellContent::Text(String::from(text)),
+            .. Default::default()
+        }
+    }
+    /// The text of the cell, or an empty string for block cells.
+    pub fn get_text(&self) -> &str {
+        match self.content {
+            AcadTableCellContent::Text(ref text) | AcadTableCellContent::Field { ref text, .. } => text,
+            AcadTableCellContent::Block { .. } => "",
+        }
+    }
+}
+
+impl AcadTable {
+    /// Creates a table with one text cell for each string; rows shorter than the longest row are
+    /// padded with empty cells.
+    pub fn from_text_grid<S>(rows: &[Vec<S>], row_height: f64, column_width: f64) -> Self
+        where S: AsRef<str> {
+
+        let column_count = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut table = AcadTable::default();
+        for row in rows {
+            table.row_heights.push(row_height);
+            for column in 0..column_count {
+                let text = row.get(column).map(|s| s.as_ref()).unwrap_or("");
+                table.cells.push(AcadTableCell::new_text(text));
+            }
+        }
+
+        table.column_widths = vec![column_width; column_count];
+        table
+    }
+    /// The number of rows in the table.
+    pub fn row_count(&self) -> usize {
+        self.row_heights.len()
+    }
+    /// The number of columns in the table.
+    pub fn column_count(&self) -> usize {
+        self.column_widths.len()
+    }
+    /// Gets the cell at the specified row and column.
+    pub fn get_cell(&self, row: usize, column: usize) -> Option<&AcadTableCell> {
+        match self.cell_index(row, column) {
+            Some(index) => self.cells.get(index),
+            None => None,
+        }
+    }
+    /// Gets a mutable reference to the cell at the specified row and column.
+    pub fn get_cell_mut(&mut self, row: usize, column: usize) -> Option<&mut AcadTableCell> {
+        match self.cell_index(row, column) {
+            Some(index) => self.cells.get_mut(index),
+            None => None,
+        }
+    }
+    /// The text of each cell, by row then column.
+    pub fn get_text_grid(&self) -> Vec<Vec<String>> {
+        (0..self.row_count())
+            .map(|row| (0..self.column_count())
+                .map(|column| self.get_cell(row, column).map_or(String::new(), |c| String::from(c.get_text())))
+                .collect())
+            .collect()
+    }
+    /// Merges the range of cells starting at the specified row and column.  Returns `false` if the
+    /// range is outside of the table or spans more rows or columns than can be written.
+    pub fn merge_cells(&mut self, row: usize, column: usize, row_count: usize, column_count: usize) -> bool {
+        let max_count = i16::MAX as usize;
+        if row_count == 0 || column_count == 0 || row_count > max_count || column_count > max_count ||
+            row + row_count > self.row_count() || column + column_count > self.column_count() {
+            return false;
+        }
+
+        for r in row..row + row_count {
+            for c in column..column + column_count {
+                if let Some(cell) = self.get_cell_mut(r, c) {
+                    cell.is_merged = true;
+                }
+            }
+        }
+
+        if let Some(cell) = self.get_cell_mut(row, column) {
+            cell.merged_row_count = row_count as i32;
+            cell.merged_column_count = column_count as i32;
+        }
+
+        true
+    }
+    fn cell_index(&self, row: usize, column: usize) -> Option<usize> {
+        if row < self.row_count() && column < self.column_count() {
+            Some(row * self.column_count() + column)
+        }
+        else {
+            None
+        }
+    }
+    pub(crate) fn read_code_pairs(&mut self, pairs: &[CodePair]) -> DxfResult<()> {
+        let mut reader = CodePairCursor::new(pairs);
+        while let Some(pair) = reader.next() {
+            if !self.cells.is_empty() {
+                // the cells are followed by the table-level overrides
+                if pair.code == 171 {
+                    self.cells.push(reader.read_table_cell(pair.value.assert_i16()?)?);
+                }
+                else {
+                    self.table_overrides.push(pair.clone());
+                }
+
+                continue;
+            }
+
+            match pair.code {
+                2 => { self.block_name = pair.value.assert_string()?; },
+                10 => { self.insertion_point.x = pair.value.assert_f64()?; },
+                20 => { self.insertion_point.y = pair.value.assert_f64()?; },
+                30 => { self.insertion_point.z = pair.value.assert_f64()?; },
+                280 => { self.version = pair.value.assert_i16()?; },
+                342 => { self.__table_style_handle = as_u32(pair.value.assert_string()?)?; },
+                343 => { self.__block_record_handle = as_u32(pair.value.assert_string()?)?; },
+                11 => { self.horizontal_direction.x = pair.value.assert_f64()?; },
+                21 => { self.horizontal_direction.y = pair.value.assert_f64()?; },
+                31 => { self.horizontal_direction.z = pair.value.assert_f64()?; },
+                90 => { self.value_flags = pair.value.assert_i32()?; },
+                93 => { self.override_flags = pair.value.assert_i32()?; },
+                94 => { self.border_color_override_flags = pair.value.assert_i32()?; },
+                95 => { self.border_lineweight_override_flags = pair.value.assert_i32()?; },
+                96 => { self.border_visibility_override_flags = pair.value.assert_i32()?; },
+                141 => { self.row_heights.push(pair.value.assert_f64()?); },
+                142 => { self.column_widths.push(pair.value.assert_f64()?); },
+                171 => { self.cells.push(reader.read_table_cell(pair.value.assert_i16()?)?); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(())
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockReference"))?;
+        writer.write_code_pair(&CodePair::new_string(2, &self.block_name))?;
+        writer.write_code_pair(&CodePair::new_f64(10, self.insertion_point.x))?;
+        writer.write_code_pair(&CodePair::new_f64(20, self.insertion_point.y))?;
+        writer.write_code_pair(&CodePair::new_f64(30, self.insertion_point.z))?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbTable"))?;
+        writer.write_code_pair(&CodePair::new_i16(280, self.version))?;
+        writer.write_code_pair(&CodePair::new_string(342, &as_handle(self.__table_style_handle)))?;
+        writer.write_code_pair(&CodePair::new_string(343, &as_handle(self.__block_record_handle)))?;
+        writer.write_code_pair(&CodePair::new_f64(11, self.horizontal_direction.x))?;
+        writer.write_code_pair(&CodePair::new_f64(21, self.horizontal_direction.y))?;
+        writer.write_code_pair(&CodePair::new_f64(31, self.horizontal_direction.z))?;
+        writer.write_code_pair(&CodePair::new_i32(90, self.value_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(91, self.row_heights.len() as i32))?;
+        writer.write_code_pair(&CodePair::new_i32(92, self.column_widths.len() as i32))?;
+        writer.write_code_pair(&CodePair::new_i32(93, self.override_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(94, self.border_color_override_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(95, self.border_lineweight_override_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(96, self.border_visibility_override_flags))?;
+        for row_height in &self.row_heights {
+            writer.write_code_pair(&CodePair::new_f64(141, *row_height))?;
+        }
+
+        for column_width in &self.column_widths {
+            writer.write_code_pair(&CodePair::new_f64(142, *column_width))?;
+        }
+
+        for cell in &self.cells {
+            write_cell(writer, cell)?;
+        }
+
+        for pair in &self.table_overrides {
+            writer.write_code_pair(pair)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_cell<T>(writer: &mut CodePairWriter<T>, cell: &AcadTableCell) -> DxfResult<()>
+    where T: Write {
+
+    let cell_type = match cell.content {
+        AcadTableCellContent::Block { .. } => 2,
+        _ => 1,
+    };
+    writer.write_code_pair(&CodePair::new_i16(171, cell_type))?;
+    writer.write_code_pair(&CodePair::new_i16(172, cell.flags))?;
+    writer.write_code_pair(&CodePair::new_i16(173, as_i16(cell.is_merged)))?;
+    writer.write_code_pair(&CodePair::new_i16(174, as_i16(cell.is_autofit)))?;
+    writer.write_code_pair(&CodePair::new_i16(175, merged_count_as_i16(cell.merged_column_count)?))?;
+    writer.write_code_pair(&CodePair::new_i16(176, merged_count_as_i16(cell.merged_row_count)?))?;
+    writer.write_code_pair(&CodePair::new_i32(91, cell.override_flags))?;
+    writer.write_code_pair(&CodePair::new_i16(178, cell.virtual_edge_flags))?;
+    writer.write_code_pair(&CodePair::new_f64(145, cell.rotation))?;
+    match cell.content {
+        AcadTableCellContent::Text(ref text) => {
+            write_cell_text(writer, text)?;
+        },
+        AcadTableCellContent::Field { field_handle, ref text } => {
+            writer.write_code_pair(&CodePair::new_string(344, &as_handle(field_handle)))?;
+            write_cell_text(writer, text)?;
+        },
+        AcadTableCellContent::Block { block_record_handle, scale, ref attributes } => {
+            writer.write_code_pair(&CodePair::new_string(340, &as_handle(block_record_handle)))?;
+            writer.write_code_pair(&CodePair::new_f64(144, scale))?;
+            writer.write_code_pair(&CodePair::new_i16(179, attributes.len() as i16))?;
+            for attribute in attributes {
+                writer.write_code_pair(&CodePair::new_string(331, &as_handle(attribute.attribute_definition_handle)))?;
+                writer.write_code_pair(&CodePair::new_string(300, &attribute.value))?;
+            }
+        },
+    }
+
+    let overrides = &cell.overrides;
+    if let Some(ref text_style_name) = overrides.text_style_name {
+        writer.write_code_pair(&CodePair::new_string(7, text_style_name))?;
+    }
+    if let Some(text_height) = overrides.text_height {
+        writer.write_code_pair(&CodePair::new_f64(140, text_height))?;
+    }
+    if let Some(alignment) = overrides.alignment {
+        writer.write_code_pair(&CodePair::new_i16(170, alignment))?;
+    }
+    if let Some(ref color) = overrides.text_color {
+        writer.write_code_pair(&CodePair::new_i16(64, color.get_raw_value()))?;
+    }
+    if let Some(ref color) = overrides.fill_color {
+        writer.write_code_pair(&CodePair::new_i16(63, color.get_raw_value()))?;
+    }
+    if let Some(is_enabled) = overrides.is_background_color_enabled {
+        writer.write_code_pair(&CodePair::new_i16(283, as_i16(is_enabled)))?;
+    }
+    if let Some(data_type) = overrides.data_type {
+        writer.write_code_pair(&CodePair::new_i32(90, data_type))?;
+    }
+    if let Some(unit_type) = overrides.unit_type {
+        writer.write_code_pair(&CodePair::new_i32(91, unit_type))?;
+    }
+    let borders = [
+        (279, 289, 69, &overrides.top_border_lineweight, &overrides.is_top_border_visible, &overrides.top_border_color),
+        (275, 285, 65, &overrides.right_border_lineweight, &overrides.is_right_border_visible, &overrides.right_border_color),
+        (276, 286, 66, &overrides.bottom_border_lineweight, &overrides.is_bottom_border_visible, &overrides.bottom_border_color),
+        (278, 288, 68, &overrides.left_border_lineweight, &overrides.is_left_border_visible, &overrides.left_border_color),
+    ];
+    for &(lineweight_code, _, _, lineweight, _, _) in &borders {
+        if let Some(lineweight) = *lineweight {
+            writer.write_code_pair(&CodePair::new_i16(lineweight_code, lineweight))?;
+        }
+    }
+    for &(_, visibility_code, _, _, is_visible, _) in &borders {
+        if let Some(is_visible) = *is_visible {
+            writer.write_code_pair(&CodePair::new_i16(visibility_code, as_i16(is_visible)))?;
+        }
+    }
+    for &(_, _, color_code, _, _, color) in &borders {
+        if let Some(ref color) = *color {
+            writer.write_code_pair(&CodePair::new_i16(color_code, color.get_raw_value()))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn merged_count_as_i16(count: i32) -> DxfResult<i16> {
+    if count < 0 || count > i32::from(i16::MAX) {
+        return Err(DxfError::ValueOutOfRange(format!("the merged cell count {} must be between 0 and {}", count, i16::MAX)));
+    }
+
+    Ok(count as i16)
+}
+
+fn write_cell_text<T>(writer: &mut CodePairWriter<T>, text: &str) -> DxfResult<()>
+    where T: Write {
+
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut chunks = chars.chunks(CELL_TEXT_CHUNK_LENGTH).map(|c| c.iter().collect::<String>()).collect::<Vec<_>>();
+    let last = chunks.pop().unwrap_or_default();
+    for chunk in &chunks {
+        writer.write_code_pair(&CodePair::new_string(2, chunk))?;
+    }
+
+    writer.write_code_pair(&CodePair::new_string(1, &last))?;
+    Ok(())
+}
+
+// The parts of a cell in the order they're written.
+#[derive(Clone, Copy, PartialEq)]
+enum CellSection {
+    Header,
+    Content,
+    Overrides,
+}
+
+impl<'a> CodePairCursor<'a> {
+    // Reads the pairs of a cell up to the next cell or the table-level overrides.
+    fn read_table_cell(&mut self, cell_type: i16) -> DxfResult<AcadTableCell> {
+        let mut cell = AcadTableCell::default();
+        let mut text = String::new();
+        let mut field_handle = None;
+        let mut block_record_handle = 0;
+        let mut block_scale = 1.0;
+        let mut attributes: Vec<AcadTableCellAttribute> = vec![];
+        let mut section = CellSection::Header;
+        while let Some(code) = self.peek_code(0) {
+            if code == 171 || code == 70 {
+                break;
+            }
+
+            let pair = self.next().unwrap();
+            section = match pair.code {
+                344 | 1..=3 | 340 | 144 | 179 | 331 | 300 | 301 if section == CellSection::Header => CellSection::Content,
+                7 | 140 | 170 | 64 | 63 | 283 | 90 | 275..=279 | 285..=289 | 65..=69 => CellSection::Overrides,
+                _ => section,
+            };
+            let overrides = &mut cell.overrides;
+            match pair.code {
+                172 => { cell.flags = pair.value.assert_i16()?; },
+                173 => { cell.is_merged = as_bool(pair.value.assert_i16()?); },
+                174 => { cell.is_autofit = as_bool(pair.value.assert_i16()?); },
+                175 => { cell.merged_column_count = i32::from(pair.value.assert_i16()?); },
+                176 => { cell.merged_row_count = i32::from(pair.value.assert_i16()?); },
+                // the override flags are in the header and the unit type is with the overrides
+                91 if section == CellSection::Header => { cell.override_flags = pair.value.assert_i32()?; },
+                91 => { overrides.unit_type = Some(pair.value.assert_i32()?); },
+                178 => { cell.virtual_edge_flags = pair.value.assert_i16()?; },
+                145 => { cell.rotation = pair.value.assert_f64()?; },
+                344 => { field_handle = Some(as_u32(pair.value.assert_string()?)?); },
+                1..=3 => { text.push_str(&pair.value.assert_string()?); },
+                340 => { block_record_handle = as_u32(pair.value.assert_string()?)?; },
+                144 => { block_scale = pair.value.assert_f64()?; },
+                331 => { attributes.push(AcadTableCellAttribute { attribute_definition_handle: as_u32(pair.value.assert_string()?)?, value: String::new() }); },
+                300 => { vec_last!(attributes).value = pair.value.assert_string()?; },
+                301 => {
+                    // the cached value of the cell is rebuilt from the content
+                    while let Some(pair) = self.next() {
+                        if pair.code == 304 && pair.value.assert_string()? == "ACVALUE_END" {
+                            break;
+                        }
+                    }
+                },
+                7 => { overrides.text_style_name = Some(pair.value.assert_string()?); },
+                140 => { overrides.text_height = Some(pair.value.assert_f64()?); },
+                170 => { overrides.alignment = Some(pair.value.assert_i16()?); },
+                64 => { overrides.text_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                63 => { overrides.fill_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                283 => { overrides.is_background_color_enabled = Some(as_bool(pair.value.assert_i16()?)); },
+                90 => { overrides.data_type = Some(pair.value.assert_i32()?); },
+                279 => { overrides.top_border_lineweight = Some(pair.value.assert_i16()?); },
+                275 => { overrides.right_border_lineweight = Some(pair.value.assert_i16()?); },
+                276 => { overrides.bottom_border_lineweight = Some(pair.value.assert_i16()?); },
+                278 => { overrides.left_border_lineweight = Some(pair.value.assert_i16()?); },
+                289 => { overrides.is_top_border_visible = Some(as_bool(pair.value.assert_i16()?)); },
+                285 => { overrides.is_right_border_visible = Some(as_bool(pair.value.assert_i16()?)); },
+                286 => { overrides.is_bottom_border_visible = Some(as_bool(pair.value.assert_i16()?)); },
+                288 => { overrides.is_left_border_visible = Some(as_bool(pair.value.assert_i16()?)); },
+                69 => { overrides.top_border_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                65 => { overrides.right_border_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                66 => { overrides.bottom_border_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                68 => { overrides.left_border_color = Some(Color::from_raw_value(pair.value.assert_i16()?)); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        cell.content = match (cell_type, field_handle) {
+            (2, _) => AcadTableCellContent::Block { block_record_handle, scale: block_scale, attributes },
+            (_, Some(field_handle)) => AcadTableCellContent::Field { field_handle, text },
+            _ => AcadTableCellContent::Text(text),
+        };
+        Ok(cell)
+    }
+}
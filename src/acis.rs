@@ -0,0 +1,393 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::collections::HashSet;
+use std::io::Write;
+
+use ::{
+    BoundingBox,
+    CodePair,
+    DxfError,
+    DxfResult,
+    Point,
+    Vector,
+};
+
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use enums::AcadVersion;
+use helper_functions::*;
+
+// Every binary ACIS (SAB) stream starts with this.
+const SAB_SIGNATURE: &[u8] = b"ACIS BinaryFile";
+
+// The number of bytes written in a single line of binary data.
+const SAB_CHUNK_LENGTH: usize = 128;
+
+// The most characters written in a single pair of modeler data; the rest of a longer line is
+// written in code 3 continuation pairs.
+const MAX_DATA_PAIR_LENGTH: usize = 255;
+
+/// The ACIS modeler data of a `Solid3D`, `Body`, `Region`, or surface.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AcisData {
+    /// Standard ACIS text (SAT) with one record or header line per line.
+    Sat(String),
+    /// Standard ACIS binary (SAB) bytes.
+    Sab(Vec<u8>),
+}
+
+/// An ACIS model parsed from SAT text.  Only enough is understood to inventory and measure it; the
+/// tokens of every record are kept for anything else.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcisModel {
+    /// The ACIS version from the header, e.g., 400 for ACIS 4.0 or 21800 for ACIS 218.
+    pub version: i32,
+    /// The records in file order.  References of the form `$n` are indices into this list.
+    pub records: Vec<AcisRecord>,
+}
+
+/// A single record of SAT text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcisRecord {
+    /// The record type, e.g., `body`, `face`, or `plane-surface`.
+    pub name: String,
+    /// The whitespace-separated tokens between the name and the terminating `#`.
+    pub tokens: Vec<String>,
+}
+
+/// A body of an ACIS model and the geometry that belongs to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcisBody {
+    pub faces: Vec<AcisFace>,
+    /// The box around the body's vertices, or `None` if it has none (e.g., a sphere).
+    pub bounding_box: Option<BoundingBox>,
+}
+
+/// A face of an ACIS body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcisFace {
+    pub surface: AcisSurface,
+    /// Whether the face normal is opposite the surface normal.
+    pub is_reversed: bool,
+}
+
+/// The surface underlying an ACIS face.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AcisSurface {
+    Plane {
+        origin: Point,
+        normal: Vector,
+    },
+    Cylinder {
+        center: Point,
+        axis: Vector,
+        radius: f64,
+    },
+    /// Any other surface, e.g., `sphere-surface` or `spline-surface`, by its record name.
+    Other(String),
+}
+
+impl AcisData {
+    /// Gets the ACIS data from the lines of `custom_data`.  SAT text written for R13 through R2004
+    /// is obfuscated and is decoded; lines of hexadecimal that hold a SAB stream are returned as
+    /// bytes.
+    pub fn from_lines(lines: &[String]) -> AcisData {
+        if let Some(bytes) = decode_sab(lines) {
+            return AcisData::Sab(bytes);
+        }
+
+        // plain SAT text starts with the numeric version; obfuscated text never starts with a digit
+        let is_plain = lines.first().and_then(|l| l.chars().next()).map_or(false, |c| c.is_ascii_digit());
+        let text = lines.iter()
+            .map(|l| if is_plain { l.clone() } else { decode_sat_line(l) })
+            .collect::<Vec<_>>()
+            .join("\n");
+        AcisData::Sat(text)
+    }
+    /// Converts the ACIS data to lines of `custom_data`.  SAT text is obfuscated as AutoCAD expects
+    /// in R13 through R2004 and SAB bytes are written as lines of hexadecimal.
+    pub fn to_lines(&self) -> Vec<String> {
+        match *self {
+            AcisData::Sat(ref text) => text.lines().map(decode_sat_line).collect(),
+            AcisData::Sab(ref bytes) => bytes.chunks(SAB_CHUNK_LENGTH)
+                .map(|c| c.iter().map(|b| format!("{:02X}", b)).collect())
+                .collect(),
+        }
+    }
+    /// Parses the SAT text; SAB data isn't parsed and returns `None`.
+    pub fn parse_model(&self) -> Option<DxfResult<AcisModel>> {
+        match *self {
+            AcisData::Sat(ref text) => Some(AcisModel::parse(text)),
+            AcisData::Sab(_) => None,
+        }
+    }
+}
+
+impl AcisModel {
+    /// Parses SAT text.  The three header lines are followed by records that each end with `#`,
+    /// up to the optional `End-of-ACIS-data` marker.
+    pub fn parse(text: &str) -> DxfResult<AcisModel> {
+        let mut lines = text.lines().skip_while(|l| l.trim().is_empty());
+        let header = lines.next().ok_or(DxfError::UnexpectedEndOfInput)?;
+        let version = header.split_whitespace().next().ok_or(DxfError::ParseError)?.parse::<i32>().map_err(DxfError::ParseIntError)?;
+        // the product and unit lines aren't needed
+        let body = lines.skip(2).collect::<Vec<_>>().join("\n");
+
+        let mut records = vec![];
+        for record in body.split('#') {
+            let mut tokens = record.split_whitespace().map(String::from).collect::<Vec<_>>();
+            // records can be preceded by their explicit index, e.g., `-0 body ...`
+            if tokens.first().map_or(false, |t| t.starts_with('-') && t[1..].parse::<usize>().is_ok()) {
+                tokens.remove(0);
+            }
+
+            if tokens.is_empty() || tokens[0].starts_with("End-of-ACIS-data") {
+                break;
+            }
+
+            let name = tokens.remove(0);
+            records.push(AcisRecord { name, tokens });
+        }
+
+        Ok(AcisModel { version, records })
+    }
+    /// Gets each body with the faces and vertices it owns.
+    pub fn get_bodies(&self) -> Vec<AcisBody> {
+        self.records.iter().enumerate()
+            .filter(|&(_, r)| r.name == "body")
+            .map(|(i, _)| self.get_body(i))
+            .collect()
+    }
+    /// Gets the box around every vertex of the model.
+    pub fn get_bounding_box(&self) -> Option<BoundingBox> {
+        let points = self.records.iter()
+            .filter(|r| r.name == "point")
+            .filter_map(|r| self.read_point(r, 0))
+            .collect::<Vec<_>>();
+        BoundingBox::from_points(&points)
+    }
+}
+
+// private implementation
+impl AcisModel {
+    fn get_body(&self, body_index: usize) -> AcisBody {
+        // everything a body reaches without passing through another body belongs to it
+        let mut visited = HashSet::new();
+        let mut pending = vec![body_index];
+        while let Some(index) = pending.pop() {
+            if !visited.insert(index) {
+                continue;
+            }
+
+            for reference in self.records[index].tokens.iter().filter_map(|t| self.reference(t)) {
+                if reference != body_index && self.records[reference].name != "body" {
+                    pending.push(reference);
+                }
+            }
+        }
+
+        let mut indices = visited.into_iter().collect::<Vec<_>>();
+        indices.sort();
+        let faces = indices.iter()
+            .map(|&i| &self.records[i])
+            .filter(|r| r.name == "face")
+            .map(|r| self.read_face(r))
+            .collect();
+        let points = indices.iter()
+            .map(|&i| &self.records[i])
+            .filter(|r| r.name == "point")
+            .filter_map(|r| self.read_point(r, 0))
+            .collect::<Vec<_>>();
+        AcisBody {
+            faces,
+            bounding_box: BoundingBox::from_points(&points),
+        }
+    }
+    fn read_face(&self, face: &AcisRecord) -> AcisFace {
+        let surface = face.tokens.iter()
+            .filter_map(|t| self.reference(t))
+            .map(|i| &self.records[i])
+            .find(|r| r.name.ends_with("-surface"));
+        let surface = match surface {
+            Some(s) if s.name == "plane-surface" => {
+                match (self.read_point(s, 0), self.read_point(s, 3)) {
+                    (Some(origin), Some(normal)) => AcisSurface::Plane { origin, normal: normal.as_vector() },
+                    _ => AcisSurface::Other(s.name.clone()),
+                }
+            },
+            Some(s) if s.name == "cone-surface" => {
+                // the sine of the half-angle follows the radius ratio and the u range; a cone whose
+                // half-angle has a sine of 0 is a cylinder and its major axis is the radius
+                let values = self.values(s);
+                match (self.read_point(s, 0), self.read_point(s, 3), self.read_point(s, 6), values.get(12)) {
+                    (Some(center), Some(axis), Some(major_axis), Some(&0.0)) => {
+                        AcisSurface::Cylinder { center, axis: axis.as_vector(), radius: major_axis.as_vector().length() }
+                    },
+                    _ => AcisSurface::Other(s.name.clone()),
+                }
+            },
+            Some(s) => AcisSurface::Other(s.name.clone()),
+            None => AcisSurface::Other(String::new()),
+        };
+        AcisFace {
+            surface,
+            is_reversed: face.tokens.iter().any(|t| t == "reversed"),
+        }
+    }
+    // Returns the record index of a `$n` reference.
+    fn reference(&self, token: &str) -> Option<usize> {
+        token.strip_prefix('$')
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|&i| i < self.records.len())
+    }
+    // Returns the leading numbers of a geometry record, after its attribute, id, and other references.
+    // Unbounded values (`I`) are infinite.
+    fn values(&self, record: &AcisRecord) -> Vec<f64> {
+        let mut tokens = record.tokens.iter().skip(1);
+        if self.version >= 700 {
+            // ACIS 7.0 added an id after the attribute
+            tokens.next();
+        }
+
+        tokens
+            .skip_while(|t| t.starts_with('$'))
+            .take_while(|t| *t == "I" || t.parse::<f64>().is_ok())
+            .map(|t| t.parse::<f64>().unwrap_or(f64::INFINITY))
+            .collect()
+    }
+    fn read_point(&self, record: &AcisRecord, offset: usize) -> Option<Point> {
+        let values = self.values(record);
+        if values.len() >= offset + 3 {
+            Some(Point::new(values[offset], values[offset + 1], values[offset + 2]))
+        }
+        else {
+            None
+        }
+    }
+}
+
+macro_rules! impl_acis_data {
+    ($typ:ty) => {
+        impl $typ {
+            /// Gets the decoded ACIS data from the lines of `custom_data`.
+            pub fn get_acis_data(&self) -> AcisData {
+                AcisData::from_lines(&self.custom_data)
+            }
+            /// Sets the encoded ACIS data, clearing any unattached continuation lines.  Lines too
+            /// long for a single code pair are split into continuation pairs when written.
+            pub fn set_acis_data(&mut self, data: &AcisData) {
+                self.custom_data = data.to_lines();
+                self.custom_data2.clear();
+            }
+        }
+    };
+}
+
+// Writes the pairs of the `AcDbModelerGeometry` subclass of entities that have nothing else.
+macro_rules! impl_modeler_geometry {
+    ($typ:ty) => {
+        impl $typ {
+            pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+                where T: Write {
+
+                writer.write_code_pair(&CodePair::new_str(100, "AcDbModelerGeometry"))?;
+                writer.write_code_pair(&CodePair::new_i16(70, self.format_version_number))?;
+                write_modeler_data(&self.custom_data, &self.custom_data2, writer)
+            }
+        }
+    };
+}
+
+impl_modeler_geometry!(Body);
+impl_modeler_geometry!(Region);
+
+//------------------------------------------------------------------------------
+//                                                                       Solid3D
+//------------------------------------------------------------------------------
+impl Solid3D {
+    pub(crate) fn write<T>(&self, version: &AcadVersion, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbModelerGeometry"))?;
+        writer.write_code_pair(&CodePair::new_i16(70, self.format_version_number))?;
+        write_modeler_data(&self.custom_data, &self.custom_data2, writer)?;
+        if *version >= AcadVersion::R2007 {
+            writer.write_code_pair(&CodePair::new_str(100, "AcDb3dSolid"))?;
+            writer.write_code_pair(&CodePair::new_string(350, &as_handle(self.__history_object_handle)))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Reads a pair of modeler data.  A line too long for one pair continues in code 3 pairs, which are
+// joined onto the line they follow; continuation pairs that don't follow a line are kept in
+// `custom_data2`.
+pub(crate) fn read_modeler_data(pair: &CodePair, custom_data: &mut Vec<String>, custom_data2: &mut Vec<String>) -> DxfResult<()> {
+    let value = pair.value.assert_string()?;
+    match (pair.code, custom_data.last_mut()) {
+        (1, _) => custom_data.push(value),
+        (_, Some(line)) => line.push_str(&value),
+        (_, None) => custom_data2.push(value),
+    }
+
+    Ok(())
+}
+
+// Writes the lines of modeler data, splitting lines too long for one pair into code 3 continuation
+// pairs.
+pub(crate) fn write_modeler_data<T>(custom_data: &[String], custom_data2: &[String], writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where T: Write {
+
+    for line in custom_data {
+        let chars = line.chars().collect::<Vec<_>>();
+        if chars.is_empty() {
+            writer.write_code_pair(&CodePair::new_str(1, ""))?;
+        }
+        for (i, chunk) in chars.chunks(MAX_DATA_PAIR_LENGTH).enumerate() {
+            let code = if i == 0 { 1 } else { 3 };
+            writer.write_code_pair(&CodePair::new_string(code, &chunk.iter().collect::<String>()))?;
+        }
+    }
+    for data in custom_data2 {
+        writer.write_code_pair(&CodePair::new_string(3, data))?;
+    }
+
+    Ok(())
+}
+
+impl_acis_data!(Body);
+impl_acis_data!(Region);
+impl_acis_data!(Solid3D);
+impl_acis_data!(ExtrudedSurface);
+impl_acis_data!(LoftedSurface);
+impl_acis_data!(PlaneSurface);
+impl_acis_data!(RevolvedSurface);
+impl_acis_data!(SweptSurface);
+
+// Every character but the space is mirrored, so the same function encodes and decodes.
+fn decode_sat_line(line: &str) -> String {
+    line.chars()
+        .map(|c| match c as u32 {
+            32 => ' ',
+            c @ 33..=126 => ::std::char::from_u32(159 - c).unwrap_or(' '),
+            _ => c,
+        })
+        .collect()
+}
+
+fn decode_sab(lines: &[String]) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    for line in lines {
+        if line.is_empty() || line.len() % 2 != 0 || !line.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        parse_hex_string(line, &mut bytes).ok()?;
+        if bytes.len() >= SAB_SIGNATURE.len() && !bytes.starts_with(SAB_SIGNATURE) {
+            return None;
+        }
+    }
+
+    if bytes.starts_with(SAB_SIGNATURE) { Some(bytes) } else { None }
+}
@@ -0,0 +1,306 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::f64::consts::PI;
+
+use ::{
+    AcisData,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use enums::*;
+use hatch::*;
+use tessellation::{
+    arc_points,
+    arc_segment_count,
+    bulge_to_arc,
+    normalize_sweep,
+    to_wcs,
+};
+
+// the tessellation tolerance relative to the size of the curve used when computing bounding boxes
+const RELATIVE_BOUNDS_TOLERANCE: f64 = 1.0e-6;
+
+// Any tolerance larger than the curve gives the coarsest tessellation.
+const COARSE_TOLERANCE: f64 = f64::MAX;
+
+// Returns the bounds of the insertion point of a `Text`-like entity and its second alignment point if it's used.
+macro_rules! text_bounds {
+    ($text:expr) => {{
+        let mut points = vec![$text.location];
+        if $text.horizontal_text_justification != HorizontalTextJustification::Left || $text.vertical_text_justification != VerticalTextJustification::Baseline {
+            points.push($text.second_alignment_point);
+        }
+        BoundingBox::from_points(&to_wcs(&$text.normal, points))
+    }};
+}
+
+/// An axis-aligned box in world coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// Creates a new `BoundingBox` containing both of the specified corners.
+    pub fn new(corner1: Point, corner2: Point) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(corner1.x.min(corner2.x), corner1.y.min(corner2.y), corner1.z.min(corner2.z)),
+            max: Point::new(corner1.x.max(corner2.x), corner1.y.max(corner2.y), corner1.z.max(corner2.z)),
+        }
+    }
+    /// Returns the smallest `BoundingBox` containing all of the points, or `None` if there are none.
+    pub fn from_points<'a, I>(points: I) -> Option<BoundingBox>
+        where I: IntoIterator<Item=&'a Point> {
+
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(BoundingBox::new(first, first), |b, p| b.union(&BoundingBox::new(*p, *p))))
+    }
+    /// Returns the smallest `BoundingBox` containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+        }
+    }
+    /// Returns the box grown by `distance` in every direction.
+    pub fn inflate(&self, distance: f64) -> BoundingBox {
+        let offset = Vector::new(distance, distance, distance);
+        BoundingBox {
+            min: self.min - offset,
+            max: self.max + offset,
+        }
+    }
+    /// Returns whether the boxes overlap or touch.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x &&
+            self.min.y <= other.max.y && other.min.y <= self.max.y &&
+            self.min.z <= other.max.z && other.min.z <= self.max.z
+    }
+    /// Returns whether `other` is entirely inside this box.
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.min.x && other.max.x <= self.max.x &&
+            self.min.y <= other.min.y && other.max.y <= self.max.y &&
+            self.min.z <= other.min.z && other.max.z <= self.max.z
+    }
+    /// Returns whether the point is inside or on the edge of the box.
+    pub fn contains_point(&self, point: &Point) -> bool {
+        self.contains(&BoundingBox::new(*point, *point))
+    }
+    /// Returns the shortest distance from the point to the box, or 0 if the point is inside.
+    pub fn distance_to_point(&self, point: &Point) -> f64 {
+        let axis_distance = |value: f64, min: f64, max: f64| if value < min { min - value } else if value > max { value - max } else { 0.0 };
+        Vector::new(
+            axis_distance(point.x, self.min.x, self.max.x),
+            axis_distance(point.y, self.min.y, self.max.y),
+            axis_distance(point.z, self.min.z, self.max.z)).length()
+    }
+    /// The center of the box.
+    pub fn center(&self) -> Point {
+        Point::new((self.min.x + self.max.x) / 2.0, (self.min.y + self.max.y) / 2.0, (self.min.z + self.max.z) / 2.0)
+    }
+    /// The size of the box along each axis.
+    pub fn size(&self) -> Vector {
+        self.max - self.min
+    }
+}
+
+impl Entity {
+    /// Returns the axis-aligned box in world coordinates that contains the entity's geometry.
+    ///
+    /// Arcs and circles are bounded exactly, other curves are bounded to within a millionth of their
+    /// size, and splines are bounded by their control points.  `Text`, `MText`, and similar annotations only include their insertion and
+    /// alignment points because the extents of the glyphs depend on the font.  Entities with ACIS
+    /// data (e.g., `Solid3D` and surfaces) are bounded by the vertices of their SAT text, which can
+    /// miss curved faces, and binary ACIS data isn't bounded.  Thickness is ignored.
+    /// Returns `None` for unbounded entities (`Ray`, `XLine`), for `Insert` (see
+    /// `Drawing::entity_bounding_box`), and for entities without any accessible geometry.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        match self.specific {
+            EntityType::AcadTable(ref table) => {
+                // the rows extend down from the insertion point at the top-left corner
+                let right = table.horizontal_direction.normalize() * table.column_widths.iter().sum::<f64>();
+                let down = table.horizontal_direction.cross(&Vector::z_axis()).normalize() * table.row_heights.iter().sum::<f64>();
+                let p = table.insertion_point;
+                BoundingBox::from_points(&[p, p + right, p + down, p + right + down])
+            },
+            EntityType::Arc(ref arc) => {
+                let start = arc.start_angle.to_radians();
+                let sweep = normalize_sweep(arc.end_angle.to_radians() - start);
+                arc_bounds(&arc.center, arc.radius, &arc.normal, start, sweep)
+            },
+            EntityType::Circle(ref circle) => arc_bounds(&circle.center, circle.radius, &circle.normal, 0.0, PI * 2.0),
+            EntityType::Ellipse(_) | EntityType::Helix(_) | EntityType::LwPolyline(_) | EntityType::Polyline(_) => {
+
+                // first find the rough size of the curve to pick a tolerance that's relative to it
+                let rough = bounds_of_tessellation(&self.tessellate(COARSE_TOLERANCE))?;
+                let tolerance = rough.size().length().max(1.0) * RELATIVE_BOUNDS_TOLERANCE;
+                bounds_of_tessellation(&self.tessellate(tolerance)).map(|b| b.inflate(tolerance))
+            },
+            EntityType::Body(ref body) => acis_bounds(&body.get_acis_data()),
+            EntityType::ExtrudedSurface(ref surface) => acis_bounds(&surface.get_acis_data()),
+            EntityType::LoftedSurface(ref surface) => acis_bounds(&surface.get_acis_data()),
+            EntityType::PlaneSurface(ref surface) => acis_bounds(&surface.get_acis_data()),
+            EntityType::Region(ref region) => acis_bounds(&region.get_acis_data()),
+            EntityType::RevolvedSurface(ref surface) => acis_bounds(&surface.get_acis_data()),
+            EntityType::Solid3D(ref solid) => acis_bounds(&solid.get_acis_data()),
+            EntityType::SweptSurface(ref surface) => acis_bounds(&surface.get_acis_data()),
+            EntityType::Attribute(ref att) => text_bounds!(att),
+            EntityType::AttributeDefinition(ref att) => text_bounds!(att),
+            EntityType::RotatedDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2, dim.definition_point_3]),
+            EntityType::RadialDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2]),
+            EntityType::DiameterDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2]),
+            EntityType::AngularThreePointDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2, dim.definition_point_3, dim.definition_point_4]),
+            EntityType::OrdinateDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2, dim.definition_point_3]),
+            EntityType::AngularTwoLineDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2, dim.definition_point_3, dim.definition_point_4]),
+            EntityType::ArcDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.definition_point_2, dim.definition_point_3, dim.arc_center]),
+            EntityType::LargeRadialDimension(ref dim) => BoundingBox::from_points(&[dim.dimension_base.definition_point_1, dim.chord_point, dim.override_center, dim.jog_point]),
+            EntityType::Face3D(ref face) => BoundingBox::from_points(&[face.first_corner, face.second_corner, face.third_corner, face.fourth_corner]),
+            EntityType::Hatch(ref hatch) => BoundingBox::from_points(&to_wcs(&hatch.extrusion_direction, hatch_boundary_points(hatch))),
+            EntityType::Image(ref image) => {
+                let u = image.u_vector * image.image_size.x;
+                let v = image.v_vector * image.image_size.y;
+                BoundingBox::from_points(&[image.location, image.location + u, image.location + v, image.location + u + v])
+            },
+            EntityType::Leader(ref leader) => BoundingBox::from_points(&leader.vertices),
+            EntityType::Line(ref line) => BoundingBox::from_points(&[line.p1, line.p2]),
+            EntityType::Mesh(ref mesh) => BoundingBox::from_points(&mesh.vertices),
+            EntityType::MLine(ref mline) => bounds_of_tessellation(&self.tessellate(COARSE_TOLERANCE)).or_else(|| BoundingBox::from_points(&mline.vertices)),
+            EntityType::MLeader(ref mleader) => {
+                let ctx = &mleader.context_data;
+                let mut points = vec![];
+                for leader in &ctx.leaders {
+                    points.push(leader.last_leader_line_point);
+                    for line in &leader.lines {
+                        points.extend(line.vertices.iter().cloned());
+                    }
+                }
+                points.extend(ctx.text_content.iter().map(|t| t.location));
+                points.extend(ctx.block_content.iter().map(|b| b.location));
+                BoundingBox::from_points(&points)
+            },
+            EntityType::MText(ref mtext) => BoundingBox::from_points(&[mtext.insertion_point]),
+            EntityType::ModelPoint(ref point) => BoundingBox::from_points(&[point.location]),
+            EntityType::Solid(ref solid) => BoundingBox::from_points(&to_wcs(&solid.extrusion_direction, vec![solid.first_corner, solid.second_corner, solid.third_corner, solid.fourth_corner])),
+            EntityType::Spline(ref spline) => {
+                if spline.control_points.is_empty() {
+                    BoundingBox::from_points(&spline.fit_points)
+                }
+                else {
+                    // a spline always lies within the convex hull of its control points
+                    BoundingBox::from_points(&spline.control_points)
+                }
+            },
+            EntityType::Text(ref text) => text_bounds!(text),
+            EntityType::Tolerance(ref tolerance) => BoundingBox::from_points(&[tolerance.insertion_point]),
+            EntityType::Viewport(ref viewport) => {
+                let half_size = Vector::new(viewport.width / 2.0, viewport.height / 2.0, 0.0);
+                BoundingBox::from_points(&[viewport.center - half_size, viewport.center + half_size])
+            },
+            EntityType::Trace(ref trace) => BoundingBox::from_points(&to_wcs(&trace.extrusion_direction, vec![trace.first_corner, trace.second_corner, trace.third_corner, trace.fourth_corner])),
+            _ => None,
+        }
+    }
+}
+
+fn acis_bounds(data: &AcisData) -> Option<BoundingBox> {
+    match data.parse_model() {
+        Some(Ok(model)) => model.get_bounding_box(),
+        _ => None,
+    }
+}
+
+// Returns the bounds of the arc in the OCS defined by `normal` with angles in radians.  Besides the ends, only the
+// points where a world axis is at its extreme can touch the bounds, i.e., the quadrant points when the normal is the
+// Z axis.
+fn arc_bounds(center: &Point, radius: f64, normal: &Vector, start: f64, sweep: f64) -> Option<BoundingBox> {
+    let (x_axis, y_axis, _) = TransformationMatrix::ocs_axes(normal);
+    let center = to_wcs(normal, vec![*center])[0];
+    let point_at = |angle: f64| center + x_axis * (radius * angle.cos()) + y_axis * (radius * angle.sin());
+    let mut points = vec![point_at(start), point_at(start + sweep)];
+    for &(x, y) in &[(x_axis.x, y_axis.x), (x_axis.y, y_axis.y), (x_axis.z, y_axis.z)] {
+        if x == 0.0 && y == 0.0 {
+            // the arc is perpendicular to this axis
+            continue;
+        }
+
+        let extreme = y.atan2(x);
+        for &angle in &[extreme, extreme + PI] {
+            if normalize_sweep(angle - start) <= sweep {
+                points.push(point_at(angle));
+            }
+        }
+    }
+
+    BoundingBox::from_points(&points)
+}
+
+fn bounds_of_tessellation(polylines: &[Vec<Point>]) -> Option<BoundingBox> {
+    BoundingBox::from_points(polylines.iter().flat_map(|p| p.iter()))
+}
+
+// Returns points in the OCS that bound the boundary paths of a `Hatch`.  Curved edges are sampled to within a
+// millionth of their radius and splines are bounded by their control points.
+fn hatch_boundary_points(hatch: &Hatch) -> Vec<Point> {
+    let elevation = hatch.elevation_point.z;
+    let at_elevation = |p: &Point| Point::new(p.x, p.y, elevation);
+    let mut points = vec![];
+    for path in &hatch.boundary_paths {
+        match path.geometry {
+            HatchBoundaryPathGeometry::Polyline { is_closed, ref vertices } => {
+                let segment_count = if is_closed { vertices.len() } else { vertices.len().saturating_sub(1) };
+                points.extend(vertices.iter().map(|v| at_elevation(&v.location)));
+                for i in 0..segment_count {
+                    let (v1, v2) = (&vertices[i], &vertices[(i + 1) % vertices.len()]);
+                    if v1.bulge != 0.0 && v1.location != v2.location {
+                        let (center, radius, start, sweep) = bulge_to_arc(&at_elevation(&v1.location), &at_elevation(&v2.location), v1.bulge);
+                        points.extend(arc_points(&center, radius, start, sweep, radius * RELATIVE_BOUNDS_TOLERANCE));
+                    }
+                }
+            },
+            HatchBoundaryPathGeometry::Edges(ref edges) => {
+                for edge in edges {
+                    match *edge {
+                        HatchBoundaryEdge::Line { ref start, ref end } => {
+                            points.push(at_elevation(start));
+                            points.push(at_elevation(end));
+                        },
+                        HatchBoundaryEdge::CircularArc { ref center, radius, start_angle, end_angle, is_counter_clockwise } => {
+                            let a = Vector::new(radius, 0.0, 0.0);
+                            let b = Vector::new(0.0, radius, 0.0);
+                            points.extend(elliptic_edge_points(&at_elevation(center), &a, &b, start_angle, end_angle, is_counter_clockwise));
+                        },
+                        HatchBoundaryEdge::EllipticArc { ref center, ref major_axis, minor_axis_ratio, start_angle, end_angle, is_counter_clockwise } => {
+                            let a = Vector::new(major_axis.x, major_axis.y, 0.0);
+                            let b = Vector::new(-major_axis.y, major_axis.x, 0.0) * minor_axis_ratio;
+                            points.extend(elliptic_edge_points(&at_elevation(center), &a, &b, start_angle, end_angle, is_counter_clockwise));
+                        },
+                        HatchBoundaryEdge::Spline { ref control_points, ref fit_points, .. } => {
+                            let spline_points = if control_points.is_empty() { fit_points } else { control_points };
+                            points.extend(spline_points.iter().map(&at_elevation));
+                        },
+                    }
+                }
+            },
+        }
+    }
+    points
+}
+
+// Samples the elliptic `Hatch` edge `center + a cos(t) + b sin(t)` with angles in degrees; clockwise edges run along
+// the mirrored ellipse.
+fn elliptic_edge_points(center: &Point, a: &Vector, b: &Vector, start_angle: f64, end_angle: f64, is_counter_clockwise: bool) -> Vec<Point> {
+    let b = if is_counter_clockwise { *b } else { -*b };
+    let start = start_angle.to_radians();
+    let sweep = normalize_sweep(end_angle.to_radians() - start);
+    let radius = a.length().max(b.length());
+    let count = arc_segment_count(radius, sweep, radius * RELATIVE_BOUNDS_TOLERANCE);
+    (0..count + 1).map(|i| {
+        let t = start + sweep * i as f64 / count as f64;
+        *center + *a * t.cos() + b * t.sin()
+    }).collect()
+}
@@ -0,0 +1,68 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::{
+    CodePair,
+    DxfError,
+    DxfResult,
+    Point,
+    Vector,
+};
+
+// Reads the nested structures of an entity from its collected code pairs.  These entities reuse the
+// same codes at different levels, so readers look ahead to decide where a structure ends.
+pub(crate) struct CodePairCursor<'a> {
+    pairs: &'a [CodePair],
+    position: usize,
+}
+
+impl<'a> CodePairCursor<'a> {
+    pub fn new(pairs: &'a [CodePair]) -> Self {
+        CodePairCursor { pairs, position: 0 }
+    }
+    pub fn next(&mut self) -> Option<&'a CodePair> {
+        let pair = self.pairs.get(self.position);
+        if pair.is_some() {
+            self.position += 1;
+        }
+
+        pair
+    }
+    pub fn peek(&self, offset: usize) -> Option<&'a CodePair> {
+        self.pairs.get(self.position + offset)
+    }
+    pub fn peek_code(&self, offset: usize) -> Option<i32> {
+        self.peek(offset).map(|p| p.code)
+    }
+    // Returns the next pair if it has one of the specified codes.
+    pub fn next_if(&mut self, codes: &[i32]) -> Option<&'a CodePair> {
+        match self.peek_code(0) {
+            Some(code) if codes.contains(&code) => self.next(),
+            _ => None,
+        }
+    }
+    pub fn expect(&mut self, code: i32) -> DxfResult<&'a CodePair> {
+        match self.next() {
+            Some(pair) if pair.code == code => Ok(pair),
+            Some(pair) => Err(DxfError::UnexpectedCodePair(pair.clone(), format!("expected code {}", code))),
+            None => Err(DxfError::UnexpectedEndOfInput),
+        }
+    }
+    // Reads a point from the X code and the Y and Z codes that follow it.
+    pub fn read_point(&mut self, code: i32) -> DxfResult<Point> {
+        let mut point = Point::origin();
+        point.x = self.expect(code)?.value.assert_f64()?;
+        if let Some(pair) = self.next_if(&[code + 10]) {
+            point.y = pair.value.assert_f64()?;
+        }
+
+        if let Some(pair) = self.next_if(&[code + 20]) {
+            point.z = pair.value.assert_f64()?;
+        }
+
+        Ok(point)
+    }
+    pub fn read_vector(&mut self, code: i32) -> DxfResult<Vector> {
+        let point = self.read_point(code)?;
+        Ok(Vector::new(point.x, point.y, point.z))
+    }
+}
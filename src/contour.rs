@@ -0,0 +1,365 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::collections::HashMap;
+
+use ::{
+    BoundingBox,
+    Drawing,
+    LwPolylineVertex,
+    Orientation,
+    Point,
+    RTree,
+    Segment,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use measurement::is_point_in_polygon;
+
+/// A chain of connected `Line`, `Arc`, `Circle`, `LwPolyline`, and `Polyline` entities in the XY
+/// plane, as found by `Drawing::chain_contours`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    /// The indices into `Drawing::entities` of the entities that make up the contour, in order.
+    pub entity_indices: Vec<usize>,
+    /// The segments of the contour in world coordinates; each starts where the previous one ends
+    /// (to within the chaining tolerance).
+    pub segments: Vec<Segment>,
+    /// Whether the last segment ends where the first segment starts.
+    pub is_closed: bool,
+    /// The index of the smallest closed contour that contains this one, or `None` if it isn't
+    /// inside another contour or isn't closed.
+    pub parent: Option<usize>,
+    /// The number of closed contours that contain this one; outer profiles are at an even depth and
+    /// holes are at an odd depth.
+    pub depth: usize,
+}
+
+// A chain of segments that hasn't been joined with the others yet.
+struct Piece {
+    entity_indices: Vec<usize>,
+    segments: Vec<Segment>,
+}
+
+impl Contour {
+    /// Returns a `LwPolyline` with the same vertices, bulges, and widths as the contour.
+    pub fn to_lw_polyline(&self) -> LwPolyline {
+        let mut poly = LwPolyline::default();
+        poly.set_is_closed(self.is_closed);
+        for segment in &self.segments {
+            let start = segment.start_point();
+            let (starting_width, ending_width) = (segment.width_at(0.0), segment.width_at(1.0));
+            poly.vertices.push(LwPolylineVertex {
+                x: start.x,
+                y: start.y,
+                bulge: (segment.sweep_angle().to_radians() / 4.0).tan(),
+                starting_width,
+                ending_width,
+                .. Default::default()
+            });
+        }
+
+        if !self.is_closed {
+            if let Some(last) = self.segments.last() {
+                let end = last.end_point();
+                poly.vertices.push(LwPolylineVertex { x: end.x, y: end.y, .. Default::default() });
+            }
+        }
+
+        poly
+    }
+    /// Returns the area enclosed by a closed contour, positive if it runs counter-clockwise, or
+    /// `None` if the contour is open.
+    pub fn signed_area(&self) -> Option<f64> {
+        if self.is_closed {
+            EntityType::LwPolyline(self.to_lw_polyline()).signed_area()
+        }
+        else {
+            None
+        }
+    }
+    /// Returns the direction of a closed contour, or `None` if it's open or encloses no area.
+    pub fn orientation(&self) -> Option<Orientation> {
+        EntityType::LwPolyline(self.to_lw_polyline()).orientation().filter(|_| self.is_closed)
+    }
+    /// Reverses the direction of the contour.
+    pub fn reverse(&mut self) {
+        self.entity_indices.reverse();
+        self.segments = self.segments.iter().rev().map(|s| s.reversed()).collect();
+    }
+}
+
+impl Drawing {
+    /// Joins the `Line`, `Arc`, `Circle`, `LwPolyline`, and `Polyline` entities of the drawing whose
+    /// endpoints are within `tolerance` of each other into ordered chains.
+    ///
+    /// Closed contours are nested inside each other; outer profiles (and islands inside holes) run
+    /// counter-clockwise and holes run clockwise.  Where more than two endpoints meet, the closest
+    /// unused one is followed.  Only entities parallel to the XY plane are considered and their Z
+    /// values are ignored.  Closed entities always form their own contour.
+    pub fn chain_contours(&self, tolerance: f64) -> Vec<Contour> {
+        let mut contours = vec![];
+        let mut pieces = vec![];
+        for (i, entity) in self.entities.iter().enumerate() {
+            if let Some((segments, is_closed)) = planar_segments(entity) {
+                if segments.is_empty() {
+                    continue;
+                }
+
+                if is_closed {
+                    contours.push(Contour { entity_indices: vec![i], segments, is_closed: true, parent: None, depth: 0 });
+                }
+                else {
+                    pieces.push(Piece { entity_indices: vec![i], segments });
+                }
+            }
+        }
+
+        contours.extend(chain_pieces(pieces, tolerance));
+        contours.sort_by_key(|c| c.entity_indices.iter().cloned().min());
+        nest_contours(&mut contours, tolerance);
+        contours
+    }
+    /// Replaces the entities of every closed contour found by `chain_contours` that's made of more
+    /// than one entity with a single closed `LwPolyline` with bulges.  Each polyline takes the place
+    /// and the `common` values of the first entity of its contour.  Returns the number of polylines
+    /// that were created.
+    pub fn join_contours(&mut self, tolerance: f64) -> usize {
+        let mut replacements = HashMap::new();
+        let mut removed = vec![false; self.entities.len()];
+        for contour in self.chain_contours(tolerance) {
+            if !contour.is_closed || contour.entity_indices.len() < 2 {
+                continue;
+            }
+
+            let first = *contour.entity_indices.iter().min().unwrap();
+            let mut common = self.entities[first].common.clone();
+            common.handle = 0;
+            common.elevation = 0.0;
+            replacements.insert(first, Entity { common, specific: EntityType::LwPolyline(contour.to_lw_polyline()) });
+            for &i in &contour.entity_indices {
+                removed[i] = true;
+            }
+        }
+
+        let count = replacements.len();
+        let entities = ::std::mem::take(&mut self.entities);
+        for (i, entity) in entities.into_iter().enumerate() {
+            match replacements.remove(&i) {
+                Some(replacement) => self.entities.push(replacement),
+                None if !removed[i] => self.entities.push(entity),
+                None => (),
+            }
+        }
+
+        count
+    }
+}
+
+// Returns the segments of the entity in the XY plane and whether they're closed.
+fn planar_segments(entity: &Entity) -> Option<(Vec<Segment>, bool)> {
+    match entity.specific {
+        EntityType::Arc(ref arc) => {
+            let start = arc.start_angle.to_radians();
+            let end = arc.end_angle.to_radians();
+            let segment = Segment::Arc {
+                start: arc.center + Vector::new(start.cos(), start.sin(), 0.0) * arc.radius,
+                end: arc.center + Vector::new(end.cos(), end.sin(), 0.0) * arc.radius,
+                center: arc.center,
+                radius: arc.radius,
+                start_angle: arc.start_angle,
+                end_angle: arc.end_angle,
+                is_counter_clockwise: true,
+                starting_width: 0.0,
+                ending_width: 0.0,
+            };
+            Some((vec![segment_to_wcs(&segment, &arc.normal)?], false))
+        },
+        EntityType::Circle(ref circle) => {
+            let center = flatten(&TransformationMatrix::from_ocs(&circle.normal).transform_point(&circle.center));
+            parallel_to_xy(&circle.normal)?;
+            let half = |start_angle: f64, end_angle: f64| {
+                let point = |angle: f64| center + Vector::new(angle.to_radians().cos(), angle.to_radians().sin(), 0.0) * circle.radius;
+                Segment::Arc {
+                    start: point(start_angle),
+                    end: point(end_angle),
+                    center,
+                    radius: circle.radius,
+                    start_angle,
+                    end_angle: end_angle % 360.0,
+                    is_counter_clockwise: true,
+                    starting_width: 0.0,
+                    ending_width: 0.0,
+                }
+            };
+            Some((vec![half(0.0, 180.0), half(180.0, 360.0)], true))
+        },
+        EntityType::Line(ref line) => {
+            let (start, end) = (flatten(&line.p1), flatten(&line.p2));
+            if start == end {
+                Some((vec![], false))
+            }
+            else {
+                Some((vec![Segment::Line { start, end, starting_width: 0.0, ending_width: 0.0 }], false))
+            }
+        },
+        EntityType::LwPolyline(ref poly) => {
+            let segments = poly.segments().map(|s| segment_to_wcs(&s, &poly.extrusion_direction)).collect::<Option<Vec<_>>>()?;
+            Some((segments, poly.get_is_closed()))
+        },
+        EntityType::Polyline(ref poly) => {
+            if poly.get_is_3d_polyline() || poly.get_is_3d_polygon_mesh() || poly.get_is_polyface_mesh() {
+                return None;
+            }
+
+            let segments = poly.segments().map(|s| segment_to_wcs(&s, &poly.normal)).collect::<Option<Vec<_>>>()?;
+            Some((segments, poly.get_is_closed()))
+        },
+        _ => None,
+    }
+}
+
+fn parallel_to_xy(normal: &Vector) -> Option<()> {
+    let normal = normal.normalize();
+    if normal.x.abs() < 1.0e-12 && normal.y.abs() < 1.0e-12 {
+        Some(())
+    }
+    else {
+        None
+    }
+}
+
+fn flatten(p: &Point) -> Point {
+    Point::new(p.x, p.y, 0.0)
+}
+
+// Converts a segment in the OCS of `normal` into the XY plane, or returns `None` if the OCS isn't
+// parallel to it.  Looking at the XY plane from below reverses the direction of arcs.
+fn segment_to_wcs(segment: &Segment, normal: &Vector) -> Option<Segment> {
+    parallel_to_xy(normal)?;
+    let matrix = TransformationMatrix::from_ocs(normal);
+    let to_wcs = |p: &Point| flatten(&matrix.transform_point(p));
+    Some(match *segment {
+        Segment::Line { start, end, starting_width, ending_width } => Segment::Line { start: to_wcs(&start), end: to_wcs(&end), starting_width, ending_width },
+        Segment::Arc { start, end, center, radius, is_counter_clockwise, starting_width, ending_width, .. } => {
+            let (start, end, center) = (to_wcs(&start), to_wcs(&end), to_wcs(&center));
+            let angle = |p: &Point| (p.y - center.y).atan2(p.x - center.x).to_degrees().rem_euclid(360.0);
+            Segment::Arc {
+                start,
+                end,
+                center,
+                radius,
+                start_angle: angle(&start),
+                end_angle: angle(&end),
+                is_counter_clockwise: is_counter_clockwise == (normal.z > 0.0),
+                starting_width,
+                ending_width,
+            }
+        },
+    })
+}
+
+// Joins the pieces end to end into contours.
+fn chain_pieces(pieces: Vec<Piece>, tolerance: f64) -> Vec<Contour> {
+    let mut endpoints = RTree::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        for &(point, is_end) in &[(piece.segments[0].start_point(), false), (piece.segments[piece.segments.len() - 1].end_point(), true)] {
+            endpoints.insert((i, is_end), BoundingBox::new(point, point));
+        }
+    }
+
+    let endpoint = |&(i, is_end): &(usize, bool)| {
+        let segments: &Vec<Segment> = &pieces[i].segments;
+        if is_end { segments[segments.len() - 1].end_point() } else { segments[0].start_point() }
+    };
+    let mut used = vec![false; pieces.len()];
+    let mut contours = vec![];
+    for i in 0..pieces.len() {
+        if used[i] {
+            continue;
+        }
+
+        used[i] = true;
+        let mut entity_indices = pieces[i].entity_indices.clone();
+        let mut segments = pieces[i].segments.clone();
+        let is_closed = |segments: &Vec<Segment>| segments.len() > 1 && segments[0].start_point().distance_to(&segments[segments.len() - 1].end_point()) <= tolerance;
+
+        // extend the end of the chain, then its start
+        for &at_end in &[true, false] {
+            while !is_closed(&segments) {
+                let point = if at_end { segments[segments.len() - 1].end_point() } else { segments[0].start_point() };
+                let next = endpoints.within_distance(&point, tolerance).into_iter()
+                    .filter(|&&(j, _)| !used[j])
+                    .min_by(|a, b| endpoint(a).distance_to(&point).partial_cmp(&endpoint(b).distance_to(&point)).unwrap());
+                let (j, is_end) = match next {
+                    Some(&next) => next,
+                    None => break,
+                };
+                used[j] = true;
+
+                // the piece has to be reversed if it meets the chain with the same kind of endpoint
+                let piece = &pieces[j];
+                let (mut new_indices, mut new_segments) = if is_end == at_end {
+                    (piece.entity_indices.iter().rev().cloned().collect(), piece.segments.iter().rev().map(|s| s.reversed()).collect())
+                }
+                else {
+                    (piece.entity_indices.clone(), piece.segments.clone())
+                };
+                if at_end {
+                    entity_indices.append(&mut new_indices);
+                    segments.append(&mut new_segments);
+                }
+                else {
+                    new_indices.append(&mut entity_indices);
+                    new_segments.append(&mut segments);
+                    entity_indices = new_indices;
+                    segments = new_segments;
+                }
+            }
+        }
+
+        let is_closed = is_closed(&segments);
+        contours.push(Contour { entity_indices, segments, is_closed, parent: None, depth: 0 });
+    }
+
+    contours
+}
+
+// Finds the parent and depth of every closed contour and orients them.
+fn nest_contours(contours: &mut [Contour], tolerance: f64) {
+    let polygons = contours.iter().map(|c| {
+        if !c.is_closed {
+            return (vec![], 0.0);
+        }
+
+        let mut poly = c.to_lw_polyline();
+        for v in poly.vertices.iter_mut() {
+            v.starting_width = 0.0;
+            v.ending_width = 0.0;
+        }
+
+        let entity = Entity::new(EntityType::LwPolyline(poly));
+        let area = entity.area().unwrap_or(0.0);
+        let polygon = entity.tessellate(tolerance.max(area.sqrt() * 1.0e-4)).into_iter().next().unwrap_or_default();
+        (polygon, area)
+    }).collect::<Vec<_>>();
+
+    for i in 0..contours.len() {
+        if !contours[i].is_closed {
+            continue;
+        }
+
+        let sample = contours[i].segments[0].point_at(0.5);
+        let area = polygons[i].1;
+        let containers = (0..contours.len())
+            .filter(|&j| j != i && contours[j].is_closed && polygons[j].1 > area && is_point_in_polygon(&sample, &polygons[j].0))
+            .collect::<Vec<_>>();
+        contours[i].depth = containers.len();
+        contours[i].parent = containers.into_iter().min_by(|&a, &b| polygons[a].1.partial_cmp(&polygons[b].1).unwrap());
+        let expected = if contours[i].depth % 2 == 0 { Orientation::CounterClockwise } else { Orientation::Clockwise };
+        if contours[i].orientation().map_or(false, |o| o != expected) {
+            contours[i].reverse();
+        }
+    }
+}
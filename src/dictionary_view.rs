@@ -0,0 +1,417 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use ::{
+    CodePair,
+    Drawing,
+    DrawingItemMut,
+    ExtensionGroup,
+    ExtensionGroupItem,
+    Point,
+};
+
+use entities::*;
+use extension_data::{
+    get_extension_dictionary_handle,
+    EXTENSION_DICTIONARY_GROUP,
+};
+use helper_functions::*;
+use objects::*;
+
+// separates the names of nested dictionaries in a path, e.g., `ACAD_LAYOUT/Layout1`
+const PATH_SEPARATOR: char = '/';
+
+/// A read-only view of a `Dictionary` and the objects it names.
+pub struct DictionaryView<'a> {
+    drawing: &'a Drawing,
+    object: &'a Object,
+}
+
+/// A view of a `Dictionary` or `DictionaryWithDefault` that can add and remove entries.  Objects added to the dictionary are
+/// owned by it and objects removed from it are removed from the drawing along with everything they
+/// own.
+pub struct DictionaryViewMut<'a> {
+    drawing: &'a mut Drawing,
+    handle: u32,
+}
+
+impl Drawing {
+    /// Returns the root dictionary of named objects, e.g., the one containing `ACAD_GROUP` and
+    /// `ACAD_LAYOUT`, or `None` if the drawing doesn't have one.  The root dictionary is the first
+    /// dictionary without an owner.
+    pub fn named_objects<'a>(&'a self) -> Option<DictionaryView<'a>> {
+        self.objects.iter()
+            .find(|o| is_root_dictionary(o))
+            .map(|object| DictionaryView { drawing: self, object })
+    }
+    /// Returns the root dictionary of named objects for changes, creating it as the first object if
+    /// the drawing doesn't have one.
+    pub fn named_objects_mut<'a>(&'a mut self) -> DictionaryViewMut<'a> {
+        let handle = match self.objects.iter().position(is_root_dictionary) {
+            Some(index) => {
+                let mut root = self.objects.remove(index);
+                let handle = self.assign_and_get_handle(&mut DrawingItemMut::Object(&mut root));
+                self.objects.insert(index, root);
+                handle
+            },
+            None => {
+                let mut root = Object::new(ObjectType::Dictionary(Dictionary::default()));
+                let handle = self.assign_and_get_handle(&mut DrawingItemMut::Object(&mut root));
+                self.objects.insert(0, root);
+                handle
+            },
+        };
+        DictionaryViewMut { drawing: self, handle }
+    }
+    /// Returns the extension dictionary of the entity or object with the specified handle for
+    /// changes, creating it like `Entity::get_or_create_extension_dictionary` does.  Unlike that
+    /// method, this works for entities and objects that are already in the drawing.  Returns `None`
+    /// if the drawing doesn't contain an entity or object with the handle.
+    pub fn get_or_create_extension_dictionary<'a>(&'a mut self, handle: u32) -> Option<DictionaryViewMut<'a>> {
+        let mut groups = ::std::mem::take(get_extension_data_groups_mut(self, handle)?);
+        let xdict = get_or_create_extension_dictionary(self, handle, &mut groups);
+        if let Some(owner_groups) = get_extension_data_groups_mut(self, handle) {
+            *owner_groups = groups;
+        }
+
+        Some(DictionaryViewMut { drawing: self, handle: xdict })
+    }
+}
+
+impl Entity {
+    /// Returns the extension dictionary referenced by the entity's `{ACAD_XDICTIONARY` group, if any.
+    pub fn extension_dictionary<'a>(&self, drawing: &'a Drawing) -> Option<DictionaryView<'a>> {
+        DictionaryView::new(drawing, get_extension_dictionary_handle(&self.common.extension_data_groups)?)
+    }
+    /// Returns the extension dictionary of the entity for changes, which may be a `Dictionary` or a
+    /// `DictionaryWithDefault`.  If the entity doesn't have one, a `Dictionary` owned by the entity is
+    /// added to the drawing and referenced from the entity's `{ACAD_XDICTIONARY` group.  Any other
+    /// object the group referred to is removed if the entity owns it.  The entity is given a handle if it
+    /// doesn't already have one.  Use `Drawing::get_or_create_extension_dictionary` for an entity
+    /// that's already in the drawing.
+    pub fn get_or_create_extension_dictionary<'a>(&mut self, drawing: &'a mut Drawing) -> DictionaryViewMut<'a> {
+        let owner = drawing.assign_and_get_handle(&mut DrawingItemMut::Entity(self));
+        let handle = get_or_create_extension_dictionary(drawing, owner, &mut self.common.extension_data_groups);
+        DictionaryViewMut { drawing, handle }
+    }
+}
+
+impl Object {
+    /// Returns the extension dictionary referenced by the object's `{ACAD_XDICTIONARY` group, if any.
+    pub fn extension_dictionary<'a>(&self, drawing: &'a Drawing) -> Option<DictionaryView<'a>> {
+        DictionaryView::new(drawing, get_extension_dictionary_handle(&self.common.extension_data_groups)?)
+    }
+    /// Returns the extension dictionary of the object for changes, which may be a `Dictionary` or a
+    /// `DictionaryWithDefault`.  If the object doesn't have one, a `Dictionary` owned by the object is
+    /// added to the drawing and referenced from the object's `{ACAD_XDICTIONARY` group.  Any other
+    /// object the group referred to is removed if the object owns it.  The object is given a handle if it
+    /// doesn't already have one.  Use `Drawing::get_or_create_extension_dictionary` for an object
+    /// that's already in the drawing.
+    pub fn get_or_create_extension_dictionary<'a>(&mut self, drawing: &'a mut Drawing) -> DictionaryViewMut<'a> {
+        let owner = drawing.assign_and_get_handle(&mut DrawingItemMut::Object(self));
+        let handle = get_or_create_extension_dictionary(drawing, owner, &mut self.common.extension_data_groups);
+        DictionaryViewMut { drawing, handle }
+    }
+}
+
+impl<'a> DictionaryView<'a> {
+    pub(crate) fn new(drawing: &'a Drawing, handle: u32) -> Option<Self> {
+        drawing.objects.iter()
+            .find(|o| o.common.handle == handle && get_entries(o).is_some())
+            .map(|object| DictionaryView { drawing, object })
+    }
+    /// The `Dictionary` or `DictionaryWithDefault` object.
+    pub fn object(&self) -> &'a Object {
+        self.object
+    }
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+    /// Whether the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+    /// The entry names in order.
+    pub fn keys(&self) -> Vec<&'a str> {
+        self.entries().keys().sorted().into_iter().map(|k| k.as_str()).collect()
+    }
+    /// The entries in name order.  Entries that refer to missing objects are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a Object)> + 'a {
+        let drawing = self.drawing;
+        self.entries().iter()
+            .sorted_by(|a, b| Ord::cmp(a.0, b.0))
+            .into_iter()
+            .filter_map(move |(key, &handle)| get_object(drawing, handle).map(|o| (key.as_str(), o)))
+    }
+    /// Gets the object at the specified path of entry names separated by `/`, e.g.,
+    /// `ACAD_LAYOUT/Layout1`.
+    pub fn get(&self, path: &str) -> Option<&'a Object> {
+        get_path(self.drawing, self.object.common.handle, path)
+    }
+    /// Gets the nested dictionary at the specified path.
+    pub fn get_dictionary(&self, path: &str) -> Option<DictionaryView<'a>> {
+        let object = self.get(path)?;
+        get_entries(object)?;
+        Some(DictionaryView { drawing: self.drawing, object })
+    }
+    /// Gets the string value of the `XRecordObject` at the specified path.
+    pub fn get_string(&self, path: &str) -> Option<String> {
+        self.get_xrecord(path)?.get_string()
+    }
+    /// Gets the real value of the `XRecordObject` at the specified path.
+    pub fn get_f64(&self, path: &str) -> Option<f64> {
+        self.get_xrecord(path)?.get_f64()
+    }
+    /// Gets the integer value of the `XRecordObject` at the specified path.
+    pub fn get_i32(&self, path: &str) -> Option<i32> {
+        self.get_xrecord(path)?.get_i32()
+    }
+    /// Gets the point value of the `XRecordObject` at the specified path.
+    pub fn get_point(&self, path: &str) -> Option<Point> {
+        self.get_xrecord(path)?.get_point()
+    }
+    /// Gets the handle value of the `XRecordObject` at the specified path.
+    pub fn get_handle(&self, path: &str) -> Option<u32> {
+        self.get_xrecord(path)?.get_handle()
+    }
+    fn get_xrecord(&self, path: &str) -> Option<&'a XRecordObject> {
+        match self.get(path)?.specific {
+            ObjectType::XRecordObject(ref xr) => Some(xr),
+            _ => None,
+        }
+    }
+    fn entries(&self) -> &'a HashMap<String, u32> {
+        get_entries(self.object).expect("a dictionary view always refers to a dictionary")
+    }
+}
+
+impl<'a> DictionaryViewMut<'a> {
+    pub(crate) fn new(drawing: &'a mut Drawing, handle: u32) -> Option<Self> {
+        get_entries(get_object(drawing, handle)?)?;
+        Some(DictionaryViewMut { drawing, handle })
+    }
+    /// The handle of the `Dictionary` or `DictionaryWithDefault` object.
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+    /// Returns a read-only view of the dictionary, or `None` if the handle no longer refers to a
+    /// dictionary.
+    pub fn as_view<'b>(&'b self) -> Option<DictionaryView<'b>> {
+        DictionaryView::new(self.drawing, self.handle)
+    }
+    /// Gets the object at the specified path of entry names separated by `/`.
+    pub fn get(&self, path: &str) -> Option<&Object> {
+        get_path(self.drawing, self.handle, path)
+    }
+    /// Gets the object at the specified path of entry names separated by `/` for changes.
+    pub fn get_mut(&mut self, path: &str) -> Option<&mut Object> {
+        let handle = get_path(self.drawing, self.handle, path)?.common.handle;
+        self.drawing.objects.iter_mut().find(|o| o.common.handle == handle)
+    }
+    /// Gets the nested `Dictionary` or `DictionaryWithDefault` at the specified path for changes.
+    pub fn get_dictionary_mut<'b>(&'b mut self, path: &str) -> Option<DictionaryViewMut<'b>> {
+        let handle = get_path(self.drawing, self.handle, path)?.common.handle;
+        DictionaryViewMut::new(self.drawing, handle)
+    }
+    /// Gets the nested `Dictionary` at the specified path for changes, creating any missing
+    /// dictionaries along the way.  Returns `None` if an entry on the path isn't a `Dictionary` or
+    /// `DictionaryWithDefault`.
+    pub fn get_or_create_dictionary<'b>(&'b mut self, path: &str) -> Option<DictionaryViewMut<'b>> {
+        let mut handle = self.handle;
+        for name in path.split(PATH_SEPARATOR) {
+            let mut dictionary = DictionaryViewMut::new(self.drawing, handle)?;
+            handle = match dictionary.get(name) {
+                Some(object) => object.common.handle,
+                None => dictionary.insert(name, Object::new(ObjectType::Dictionary(Dictionary::default())))?,
+            };
+        }
+
+        DictionaryViewMut::new(self.drawing, handle)
+    }
+    /// Adds the object to the drawing as an entry owned by this dictionary and returns its handle.
+    /// An object already owned by the dictionary under the same name is removed from the drawing.
+    /// Returns `None` if the handle no longer refers to a dictionary.
+    pub fn insert(&mut self, name: &str, object: Object) -> Option<u32> {
+        self.get_entries_mut()?;
+        self.remove(name);
+        let mut object = object;
+        if object.common.handle != 0 && get_object(self.drawing, object.common.handle).is_some() {
+            // the handle is already used
+            object.common.handle = 0;
+        }
+
+        let handle = self.drawing.assign_and_get_handle(&mut DrawingItemMut::Object(&mut object));
+        object.common.__owner_handle = self.handle;
+        self.drawing.objects.push(object);
+        self.get_entries_mut()?.insert(String::from(name), handle);
+        Some(handle)
+    }
+    /// Adds an entry that refers to an object owned by something else.  A dictionary that's a hard
+    /// owner claims every entry so it can't hold references and `false` is returned.
+    pub fn insert_reference(&mut self, name: &str, handle: u32) -> bool {
+        if self.is_hard_owner() || self.get_entries_mut().is_none() {
+            return false;
+        }
+
+        self.remove(name);
+        match self.get_entries_mut() {
+            Some(entries) => { entries.insert(String::from(name), handle); true },
+            None => false,
+        }
+    }
+    /// Removes the entry.  If the dictionary owns the object it's removed from the drawing along with
+    /// the objects it owns and returned.
+    pub fn remove(&mut self, name: &str) -> Option<Object> {
+        let handle = self.get_entries_mut()?.remove(name)?;
+        match get_object(self.drawing, handle) {
+            Some(object) if object.common.__owner_handle == self.handle => remove_owned(self.drawing, handle),
+            _ => None,
+        }
+    }
+    /// Stores a string in the `XRecordObject` named `name`, adding the record if needed.  This and the
+    /// other setters return the handle of the record, or `None` if the handle no longer refers to a
+    /// dictionary.
+    pub fn set_string(&mut self, name: &str, value: &str) -> Option<u32> {
+        self.set_xrecord(name, |xr| xr.set_string(value))
+    }
+    /// Stores a real value in the `XRecordObject` named `name`, adding the record if needed.
+    pub fn set_f64(&mut self, name: &str, value: f64) -> Option<u32> {
+        self.set_xrecord(name, |xr| xr.set_f64(value))
+    }
+    /// Stores an integer in the `XRecordObject` named `name`, adding the record if needed.
+    pub fn set_i32(&mut self, name: &str, value: i32) -> Option<u32> {
+        self.set_xrecord(name, |xr| xr.set_i32(value))
+    }
+    /// Stores a point in the `XRecordObject` named `name`, adding the record if needed.
+    pub fn set_point(&mut self, name: &str, value: &Point) -> Option<u32> {
+        self.set_xrecord(name, |xr| xr.set_point(value))
+    }
+    /// Stores a handle in the `XRecordObject` named `name`, adding the record if needed.
+    pub fn set_handle(&mut self, name: &str, handle: u32) -> Option<u32> {
+        self.set_xrecord(name, |xr| xr.set_handle(handle))
+    }
+    // Updates the record owned by this dictionary or replaces whatever else the entry names with a
+    // new record.  Returns the handle of the record.
+    fn set_xrecord<F>(&mut self, name: &str, set: F) -> Option<u32>
+        where F: FnOnce(&mut XRecordObject) {
+
+        let dictionary = self.handle;
+        if let Some(object) = self.get_mut(name) {
+            if object.common.__owner_handle == dictionary {
+                if let ObjectType::XRecordObject(ref mut xr) = object.specific {
+                    set(xr);
+                    return Some(object.common.handle);
+                }
+            }
+        }
+
+        let mut xr = XRecordObject::default();
+        set(&mut xr);
+        self.insert(name, Object::new(ObjectType::XRecordObject(xr)))
+    }
+    fn is_hard_owner(&self) -> bool {
+        match get_object(self.drawing, self.handle) {
+            Some(&Object { specific: ObjectType::Dictionary(ref dict), .. }) => dict.is_hard_owner,
+            // a `DictionaryWithDefault` always writes soft references
+            Some(&Object { specific: ObjectType::DictionaryWithDefault(_), .. }) => false,
+            _ => false,
+        }
+    }
+    // The object can be removed or changed through `get_mut` or the drawing after the view was made.
+    fn get_entries_mut(&mut self) -> Option<&mut HashMap<String, u32>> {
+        let handle = self.handle;
+        match self.drawing.objects.iter_mut().find(|o| o.common.handle == handle) {
+            Some(&mut Object { specific: ObjectType::Dictionary(ref mut dict), .. }) => Some(&mut dict.value_handles),
+            Some(&mut Object { specific: ObjectType::DictionaryWithDefault(ref mut dict), .. }) => Some(&mut dict.value_handles),
+            _ => None,
+        }
+    }
+}
+
+fn is_root_dictionary(object: &Object) -> bool {
+    object.common.__owner_handle == 0 && matches!(object.specific, ObjectType::Dictionary(_))
+}
+
+fn get_entries(object: &Object) -> Option<&HashMap<String, u32>> {
+    match object.specific {
+        ObjectType::Dictionary(ref dict) => Some(&dict.value_handles),
+        ObjectType::DictionaryWithDefault(ref dict) => Some(&dict.value_handles),
+        _ => None,
+    }
+}
+
+fn get_object(drawing: &Drawing, handle: u32) -> Option<&Object> {
+    if handle == 0 {
+        return None;
+    }
+
+    drawing.objects.iter().find(|o| o.common.handle == handle)
+}
+
+fn get_path<'a>(drawing: &'a Drawing, dictionary_handle: u32, path: &str) -> Option<&'a Object> {
+    let mut object = get_object(drawing, dictionary_handle)?;
+    for name in path.split(PATH_SEPARATOR) {
+        let handle = *get_entries(object)?.get(name)?;
+        object = get_object(drawing, handle)?;
+    }
+
+    Some(object)
+}
+
+fn get_or_create_extension_dictionary(drawing: &mut Drawing, owner: u32, groups: &mut Vec<ExtensionGroup>) -> u32 {
+    let existing = get_extension_dictionary_handle(groups)
+        .and_then(|handle| get_object(drawing, handle))
+        .map(|object| (object.common.handle, get_entries(object).is_some(), object.common.__owner_handle == owner));
+    match existing {
+        Some((handle, true, _)) => return handle,
+        // the group refers to something else the owner has; don't leave it behind
+        Some((handle, false, true)) => { remove_owned(drawing, handle); },
+        _ => (),
+    }
+
+    // extension dictionaries own their entries
+    let mut dictionary = Dictionary::default();
+    dictionary.is_hard_owner = true;
+    let mut object = Object::new(ObjectType::Dictionary(dictionary));
+    object.common.__owner_handle = owner;
+    let handle = drawing.assign_and_get_handle(&mut DrawingItemMut::Object(&mut object));
+    drawing.objects.push(object);
+
+    groups.retain(|g| g.application_name != EXTENSION_DICTIONARY_GROUP);
+    groups.push(ExtensionGroup {
+        application_name: String::from(EXTENSION_DICTIONARY_GROUP),
+        items: vec![ExtensionGroupItem::CodePair(CodePair::new_string(360, &as_handle(handle)))],
+    });
+    handle
+}
+
+fn get_extension_data_groups_mut(drawing: &mut Drawing, handle: u32) -> Option<&mut Vec<ExtensionGroup>> {
+    if handle == 0 {
+        return None;
+    }
+
+    match drawing.get_item_by_handle_mut(handle)? {
+        DrawingItemMut::Entity(entity) => Some(&mut entity.common.extension_data_groups),
+        DrawingItemMut::Object(object) => Some(&mut object.common.extension_data_groups),
+        _ => None,
+    }
+}
+
+// Removes the object and, recursively, every object it owns.
+fn remove_owned(drawing: &mut Drawing, handle: u32) -> Option<Object> {
+    let index = drawing.objects.iter().position(|o| o.common.handle == handle)?;
+    let object = drawing.objects.remove(index);
+    let owned = drawing.objects.iter()
+        .filter(|o| o.common.__owner_handle == handle)
+        .map(|o| o.common.handle)
+        .collect::<Vec<_>>();
+    for owned_handle in owned {
+        remove_owned(drawing, owned_handle);
+    }
+
+    Some(object)
+}
@@ -0,0 +1,792 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::f64::consts::PI;
+
+use ::{
+    Block,
+    Color,
+    Drawing,
+    LineWeight,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use dimension_text::{
+    expand_dimension_text,
+    format_measurement,
+};
+use entities::*;
+use enums::*;
+use tables::*;
+
+// The layer AutoCAD uses for the points at the definition points of a dimension.
+const DEFPOINTS_LAYER: &str = "Defpoints";
+
+// The width of a character as a fraction of the text height, used to estimate the extents of the
+// measurement text.
+const CHARACTER_WIDTH_FACTOR: f64 = 0.6;
+
+impl Entity {
+    /// The values shared by all dimension types, or `None` if the entity isn't a dimension.
+    pub fn dimension_base(&self) -> Option<&DimensionBase> {
+        match self.specific {
+            EntityType::RotatedDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::RadialDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::DiameterDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::AngularThreePointDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::OrdinateDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::AngularTwoLineDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::ArcDimension(ref dim) => Some(&dim.dimension_base),
+            EntityType::LargeRadialDimension(ref dim) => Some(&dim.dimension_base),
+            _ => None,
+        }
+    }
+    /// The values shared by all dimension types, or `None` if the entity isn't a dimension.
+    pub fn dimension_base_mut(&mut self) -> Option<&mut DimensionBase> {
+        match self.specific {
+            EntityType::RotatedDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::RadialDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::DiameterDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::AngularThreePointDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::OrdinateDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::AngularTwoLineDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::ArcDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            EntityType::LargeRadialDimension(ref mut dim) => Some(&mut dim.dimension_base),
+            _ => None,
+        }
+    }
+    /// The value measured by a dimension from its definition points: the distance along the
+    /// dimension line of linear dimensions, the radius or diameter of radial and diameter
+    /// dimensions, the angle in degrees on the side of the dimension arc of angular dimensions, the
+    /// length of the measured arc of arc length dimensions, and the distance from the origin along
+    /// the measured axis of ordinate dimensions.  Returns `None` if the entity isn't a dimension.
+    pub fn dimension_measurement(&self) -> Option<f64> {
+        let base = self.dimension_base()?;
+        let axes = TransformationMatrix::ocs_axes(&base.normal);
+        Some(match self.specific {
+            EntityType::RotatedDimension(ref dim) => {
+                let (q1, q2, _) = linear_dimension_line(dim, &axes);
+                q1.distance_to(&q2)
+            },
+            EntityType::RadialDimension(ref dim) => flat(to_ocs(&axes, &dim.definition_point_2) - to_ocs(&axes, &base.definition_point_1)).length(),
+            EntityType::DiameterDimension(ref dim) => flat(to_ocs(&axes, &dim.definition_point_2) - to_ocs(&axes, &base.definition_point_1)).length(),
+            EntityType::AngularThreePointDimension(ref dim) => angular_dimension_arc(dim, &axes).3.to_degrees(),
+            EntityType::OrdinateDimension(ref dim) => ordinate_measurement(dim, &axes),
+            EntityType::AngularTwoLineDimension(ref dim) => two_line_angular_dimension_arc(dim, &axes).map_or(0.0, |arc| arc.3.to_degrees()),
+            EntityType::ArcDimension(ref dim) => {
+                let radius = flat(to_ocs(&axes, &dim.definition_point_2) - to_ocs(&axes, &dim.arc_center)).length();
+                radius * normalize_radians(dim.end_angle - dim.start_angle)
+            },
+            EntityType::LargeRadialDimension(ref dim) => flat(to_ocs(&axes, &dim.chord_point) - to_ocs(&axes, &base.definition_point_1)).length(),
+            _ => return None,
+        })
+    }
+}
+
+impl Drawing {
+    /// Returns the dimension style with the specified name, or the one named by `$DIMSTYLE` if
+    /// `name` is empty.  If the drawing doesn't contain that style, one with the values of the
+    /// `$DIM*` header variables is returned instead.
+    pub fn resolve_dim_style(&self, name: &str) -> DimStyle {
+        let name = if name.is_empty() { &self.header.dimension_style_name } else { name };
+        match self.dim_styles.iter().find(|s| s.name.eq_ignore_ascii_case(name)) {
+            Some(style) => style.clone(),
+            None => self.header_dim_style(name),
+        }
+    }
+    /// Returns the entities of the anonymous block that displays the specified dimension: the
+    /// extension lines, the dimension line or arc, the arrowheads, and the measurement text, drawn
+    /// with the dimension's resolved style (see `resolve_dim_style`).  Returns `None` if the entity
+    /// isn't a dimension.
+    pub fn dimension_block_entities(&self, entity: &Entity) -> Option<Vec<Entity>> {
+        self.build_dimension(entity).map(|dim| dim.entities)
+    }
+    /// Generates the anonymous `*D` block of every dimension in `entities` and in `blocks` and links
+    /// it through `DimensionBase::block_name`.  Dimensions that already reference a `*D` block have
+    /// that block's contents replaced; otherwise a new block and block record are added.  Each
+    /// dimension's `actual_measurement` is also updated, as is its `text_mid_point` unless the text
+    /// is at a user-defined location.  Returns the number of blocks generated.
+    pub fn generate_dimension_blocks(&mut self) -> usize {
+        let mut next_index = self.blocks.iter().map(|b| &b.name)
+            .chain(self.block_records.iter().map(|r| &r.name))
+            .filter_map(|name| anonymous_dimension_index(name))
+            .max()
+            .unwrap_or(0) + 1;
+        let mut entities = ::std::mem::take(&mut self.entities);
+        let mut count = self.generate_dimension_blocks_for(&mut entities, &mut next_index);
+        self.entities = entities;
+
+        // the blocks generated here only hold what displays a dimension, so they're skipped
+        for i in 0..self.blocks.len() {
+            if anonymous_dimension_index(&self.blocks[i].name).is_some() {
+                continue;
+            }
+
+            let mut entities = ::std::mem::take(&mut self.blocks[i].entities);
+            count += self.generate_dimension_blocks_for(&mut entities, &mut next_index);
+            self.blocks[i].entities = entities;
+        }
+
+        if count > 0 && !self.layers.iter().any(|l| l.name.eq_ignore_ascii_case(DEFPOINTS_LAYER)) {
+            self.layers.push(Layer {
+                name: String::from(DEFPOINTS_LAYER),
+                is_layer_plotted: false,
+                .. Default::default()
+            });
+        }
+
+        count
+    }
+}
+
+// private implementation
+impl Drawing {
+    fn generate_dimension_blocks_for(&mut self, entities: &mut [Entity], next_index: &mut usize) -> usize {
+        let mut count = 0;
+        for entity in entities.iter_mut() {
+            let generated = match self.build_dimension(entity) {
+                Some(generated) => generated,
+                None => continue,
+            };
+            if let Some(base) = entity.dimension_base_mut() {
+                if anonymous_dimension_index(&base.block_name).is_none() {
+                    base.block_name = format!("*D{}", next_index);
+                    *next_index += 1;
+                }
+
+                base.actual_measurement = generated.measurement;
+                if !base.is_at_user_defined_location {
+                    base.text_mid_point = generated.text_location;
+                }
+
+                self.set_dimension_block(&base.block_name, generated.entities);
+                count += 1;
+            }
+        }
+
+        count
+    }
+    fn header_dim_style(&self, name: &str) -> DimStyle {
+        let header = &self.header;
+        DimStyle {
+            name: String::from(name),
+            dimensioning_suffix: header.dimensioning_suffix.clone(),
+            alternate_dimensioning_suffix: header.alternate_dimensioning_suffix.clone(),
+            arrow_block_name: header.arrow_block_name.clone(),
+            first_arrow_block_name: header.first_arrow_block_name.clone(),
+            second_arrow_block_name: header.second_arrow_block_name.clone(),
+            dimensioning_scale_factor: header.dimensioning_scale_factor,
+            dimensioning_arrow_size: header.dimensioning_arrow_size,
+            dimension_extension_line_offset: header.dimension_extension_line_offset,
+            dimension_line_increment: header.dimension_line_increment,
+            dimension_extension_line_extension: header.dimension_extension_line_extension,
+            dimension_distance_rounding_value: header.dimension_distance_rounding_value,
+            dimension_line_extension: header.dimension_line_extension,
+            dimension_plus_tolerance: header.dimension_plus_tolerance,
+            dimension_minus_tolerance: header.dimension_minus_tolerance,
+            generate_dimension_tolerances: header.generate_dimension_tolerances,
+            generate_dimension_limits: header.generate_dimension_limits,
+            dimension_text_inside_horizontal: header.dimension_text_inside_horizontal,
+            dimension_text_outside_horizontal: header.dimension_text_outside_horizontal,
+            suppress_first_dimension_extension_line: header.suppress_first_dimension_extension_line,
+            suppress_second_dimension_extension_line: header.suppress_second_dimension_extension_line,
+            text_above_dimension_line: header.text_above_dimension_line,
+            dimension_unit_zero_suppression: header.dimension_unit_zero_suppression,
+            dimension_angle_zero_suppression: header.dimension_angle_zero_suppression,
+            dimensioning_text_height: header.dimensioning_text_height,
+            center_mark_size: header.center_mark_size,
+            dimensioning_tick_size: header.dimensioning_tick_size,
+            alternate_dimensioning_scale_factor: header.alternate_dimensioning_scale_factor,
+            dimension_linear_measurement_scale_factor: header.dimension_linear_measurements_scale_factor,
+            dimension_vertical_text_position: header.dimension_vertical_text_position,
+            dimension_tolerance_displace_scale_factor: header.dimension_tolerance_display_scale_factor,
+            dimension_line_gap: header.dimension_line_gap,
+            alternate_dimensioning_unit_rounding: header.alternate_dimensioning_unit_rounding,
+            use_alternate_dimensioning: header.use_alternate_dimensioning,
+            alternate_dimensioning_decimal_places: header.alternate_dimensioning_decimal_places,
+            force_dimension_line_extensions_outside_if_text_exists: header.force_dimension_line_extensions_outside_if_text_is,
+            use_separate_arrow_blocks_for_dimensions: header.use_separate_arrow_blocks_for_dimensions,
+            force_dimension_text_inside_extensions: header.force_dimension_text_inside_extensions,
+            suppress_outside_extension_dimension_lines: header.suppress_outside_extension_dimension_lines,
+            dimension_line_color: header.dimension_line_color.clone(),
+            dimension_extension_line_color: header.dimension_extension_line_color.clone(),
+            dimension_text_color: header.dimension_text_color.clone(),
+            angular_dimension_precision: header.angular_dimension_precision,
+            dimension_unit_format: header.dimension_unit_format,
+            dimension_unit_tolerance_decimal_places: header.dimension_unit_tolerance_decimal_places,
+            dimension_tolerace_decimal_places: header.dimension_tolerance_decimal_places,
+            alternate_dimensioning_units: header.alternate_dimensioning_units,
+            alternate_dimensioning_tolerance_decimal_places: header.alternate_dimensioning_tolerance_decimal_places,
+            dimensioning_angle_format: header.dimensioning_angle_format,
+            dimension_non_angular_units: header.dimension_non_angular_units,
+            dimension_decilam_separator_char: header.dimension_decimal_separator_char,
+            dimension_text_movement_rule: header.dimension_text_movement_rule,
+            dimension_text_justification: header.dimension_text_justification,
+            dimension_tolerance_vertical_justification: header.dimension_tolerance_vertical_justification,
+            dimension_tolerance_zero_suppression: header.dimension_tolerance_zero_suppression,
+            alternate_dimensioning_zero_suppression: header.alternate_dimensioning_zero_supression,
+            alternate_dimensioning_tolerance_zero_suppression: header.alternate_dimensioning_tolerance_zero_supression,
+            dimension_text_and_arrow_placement: header.dimension_text_and_arrow_placement,
+            dimension_cursor_controls_text_position: header.dimension_cursor_controls_text_position,
+            dimension_text_style: header.dimension_text_style.clone(),
+            dimension_leader_block_name: header.dimension_leader_block_name.clone(),
+            dimension_line_weight: header.dimension_line_weight.clone(),
+            dimension_extension_line_weight: header.dimension_extension_line_weight.clone(),
+            .. Default::default()
+        }
+    }
+    fn build_dimension(&self, entity: &Entity) -> Option<GeneratedDimension> {
+        let base = entity.dimension_base()?;
+        let style = self.resolve_dim_style(&base.dimension_style_name);
+        let text_style_name = if style.dimension_text_style.is_empty() { self.header.dimension_text_style.clone() } else { style.dimension_text_style.clone() };
+        let mut builder = DimensionBuilder::new(&style, entity, base, text_style_name);
+        let (measurement, text_location) = match entity.specific {
+            EntityType::RotatedDimension(ref dim) => builder.linear(dim),
+            EntityType::RadialDimension(ref dim) => builder.radial(dim),
+            EntityType::DiameterDimension(ref dim) => builder.diameter(dim),
+            EntityType::AngularThreePointDimension(ref dim) => builder.angular(dim),
+            EntityType::OrdinateDimension(ref dim) => builder.ordinate(dim),
+            EntityType::AngularTwoLineDimension(ref dim) => builder.two_line_angular(dim),
+            EntityType::ArcDimension(ref dim) => builder.arc_length(dim),
+            EntityType::LargeRadialDimension(ref dim) => builder.jogged_radius(dim),
+            _ => return None,
+        };
+        Some(GeneratedDimension {
+            entities: builder.entities,
+            measurement,
+            text_location,
+        })
+    }
+    fn set_dimension_block(&mut self, name: &str, entities: Vec<Entity>) {
+        match self.blocks.iter_mut().find(|b| b.name.eq_ignore_ascii_case(name)) {
+            Some(block) => block.entities = entities,
+            None => {
+                let mut block = Block {
+                    name: String::from(name),
+                    layer: String::from("0"),
+                    entities,
+                    .. Default::default()
+                };
+                block.set_is_anonymous(true);
+                self.blocks.push(block);
+            },
+        }
+
+        if !self.block_records.iter().any(|r| r.name.eq_ignore_ascii_case(name)) {
+            self.block_records.push(BlockRecord {
+                name: String::from(name),
+                .. Default::default()
+            });
+        }
+    }
+}
+
+// Returns `n` for anonymous dimension block names of the form `*Dn`.
+fn anonymous_dimension_index(name: &str) -> Option<usize> {
+    if name.len() > 2 && name[..2].eq_ignore_ascii_case("*D") {
+        name[2..].parse().ok()
+    }
+    else {
+        None
+    }
+}
+
+struct GeneratedDimension {
+    entities: Vec<Entity>,
+    measurement: f64,
+    text_location: Point,
+}
+
+// Returns the ends of the dimension line of a linear dimension in its OCS and the direction in
+// which it measures.
+fn linear_dimension_line(dim: &RotatedDimension, axes: &(Vector, Vector, Vector)) -> (Point, Point, Vector) {
+    let p1 = to_ocs(axes, &dim.definition_point_2);
+    let p2 = to_ocs(axes, &dim.definition_point_3);
+    let on_line = to_ocs(axes, &dim.dimension_base.definition_point_1);
+    let direction = match dim.dimension_base.dimension_type {
+        DimensionType::Aligned if flat(p2 - p1).length() > 0.0 => flat(p2 - p1).normalize(),
+        _ => direction_at(dim.rotation_angle.to_radians()),
+    };
+    let q1 = on_line + direction * (p1 - on_line).dot(&direction);
+    let q2 = on_line + direction * (p2 - on_line).dot(&direction);
+    (q1, q2, direction)
+}
+
+// Returns the vertex and radius of the dimension arc of an angular dimension in its OCS and the
+// start and sweep angles in radians of the side of the angle that contains the arc.
+fn angular_dimension_arc(dim: &AngularThreePointDimension, axes: &(Vector, Vector, Vector)) -> (Point, f64, f64, f64) {
+    let vertex = to_ocs(axes, &dim.definition_point_4);
+    let first = to_ocs(axes, &dim.definition_point_2);
+    let second = to_ocs(axes, &dim.definition_point_3);
+    angle_arc(vertex, first, second, to_ocs(axes, &dim.dimension_base.definition_point_1))
+}
+
+// Returns the same values as `angular_dimension_arc` with the vertex at the intersection of the two
+// lines, or `None` if the lines are parallel.
+fn two_line_angular_dimension_arc(dim: &AngularTwoLineDimension, axes: &(Vector, Vector, Vector)) -> Option<(Point, f64, f64, f64)> {
+    let (vertex, first, second) = two_line_angle_sides(dim, axes)?;
+    // the arc point is already in the OCS
+    Some(angle_arc(vertex, first, second, dim.arc_definition_point))
+}
+
+// Returns the intersection of the two lines of a two-line angular dimension in its OCS and the end
+// of each line farthest from it, or `None` if the lines are parallel.
+fn two_line_angle_sides(dim: &AngularTwoLineDimension, axes: &(Vector, Vector, Vector)) -> Option<(Point, Point, Point)> {
+    let (a1, a2) = (to_ocs(axes, &dim.definition_point_2), to_ocs(axes, &dim.definition_point_3));
+    let (b1, b2) = (to_ocs(axes, &dim.definition_point_4), to_ocs(axes, &dim.dimension_base.definition_point_1));
+    let (u, v) = (flat(a2 - a1), flat(b2 - b1));
+    let denominator = u.x * v.y - u.y * v.x;
+    if denominator.abs() < 1e-12 {
+        return None;
+    }
+
+    let t = ((b1.x - a1.x) * v.y - (b1.y - a1.y) * v.x) / denominator;
+    let vertex = a1 + u * t;
+    // each side of the angle runs toward the end of its line farthest from the vertex
+    let farthest = |p: Point, q: Point| if flat(p - vertex).length() >= flat(q - vertex).length() { p } else { q };
+    Some((vertex, farthest(a1, a2), farthest(b1, b2)))
+}
+
+// Returns the vertex, the radius through `on_arc`, and the start and sweep angles in radians of the
+// side of the angle between `first` and `second` that contains `on_arc`.
+fn angle_arc(vertex: Point, first: Point, second: Point, on_arc: Point) -> (Point, f64, f64, f64) {
+    let angle_to = |p: &Point| {
+        let v = flat(*p - vertex);
+        normalize_radians(v.y.atan2(v.x))
+    };
+    let first_angle = angle_to(&first);
+    let second_angle = angle_to(&second);
+    let arc_angle = angle_to(&on_arc);
+    let radius = flat(on_arc - vertex).length();
+    let sweep = normalize_radians(second_angle - first_angle);
+    if normalize_radians(arc_angle - first_angle) <= sweep {
+        (vertex, radius, first_angle, sweep)
+    }
+    else {
+        (vertex, radius, second_angle, 2.0 * PI - sweep)
+    }
+}
+
+fn ordinate_measurement(dim: &OrdinateDimension, axes: &(Vector, Vector, Vector)) -> f64 {
+    let origin = to_ocs(axes, &dim.dimension_base.definition_point_1);
+    let feature = to_ocs(axes, &dim.definition_point_2);
+    if dim.dimension_base.is_ordinate_x_type { (feature.x - origin.x).abs() } else { (feature.y - origin.y).abs() }
+}
+
+// Draws the contents of a dimension block.  Points are in the object coordinate system (OCS) of the
+// dimension unless otherwise noted and sizes are multiplied by the style's overall scale.
+struct DimensionBuilder<'a> {
+    style: &'a DimStyle,
+    entity: &'a Entity,
+    base: &'a DimensionBase,
+    text_style_name: String,
+    axes: (Vector, Vector, Vector),
+    scale: f64,
+    entities: Vec<Entity>,
+}
+
+impl<'a> DimensionBuilder<'a> {
+    fn new(style: &'a DimStyle, entity: &'a Entity, base: &'a DimensionBase, text_style_name: String) -> Self {
+        DimensionBuilder {
+            style,
+            entity,
+            base,
+            text_style_name,
+            axes: TransformationMatrix::ocs_axes(&base.normal),
+            // a scale of 0 is only meaningful in paperspace layouts
+            scale: if style.dimensioning_scale_factor > 0.0 { style.dimensioning_scale_factor } else { 1.0 },
+            entities: vec![],
+        }
+    }
+    fn linear(&mut self, dim: &RotatedDimension) -> (f64, Point) {
+        let p1 = self.ocs(&dim.definition_point_2);
+        let p2 = self.ocs(&dim.definition_point_3);
+        let (q1, q2, direction) = linear_dimension_line(dim, &self.axes);
+        let measurement = q1.distance_to(&q2);
+
+        self.extension_line(&p1, &q1, self.style.suppress_first_dimension_extension_line);
+        self.extension_line(&p2, &q2, self.style.suppress_second_dimension_extension_line);
+        let along = if measurement > 0.0 { (q2 - q1).normalize() } else { direction };
+        self.arrow(&q1, &along);
+        self.arrow(&q2, &-along);
+
+        let text = self.display_text(measurement);
+        let text_direction = readable(&along);
+        let middle = q1 + (q2 - q1) * 0.5;
+        let half_width = self.text_half_width(&text);
+        let (location, gap) = if self.base.is_at_user_defined_location {
+            (self.base.text_mid_point, None)
+        }
+        else if self.style.text_above_dimension_line {
+            (middle + perpendicular(&text_direction) * (self.gap() + self.text_height() / 2.0), None)
+        }
+        else {
+            (middle, Some(half_width + self.gap()))
+        };
+        match gap {
+            // break the dimension line around the text
+            Some(gap) if !text.is_empty() && 2.0 * gap < measurement => {
+                self.dimension_line(&q1, &(middle - along * gap));
+                self.dimension_line(&(middle + along * gap), &q2);
+            },
+            _ => self.dimension_line(&q1, &q2),
+        }
+        self.text(&text, &location, &text_direction);
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        self.defpoint(&dim.definition_point_3);
+        (measurement, location)
+    }
+    fn radial(&mut self, dim: &RadialDimension) -> (f64, Point) {
+        let center = self.ocs(&self.base.definition_point_1);
+        let on_circle = self.ocs(&dim.definition_point_2);
+        let measurement = flat(on_circle - center).length();
+        let direction = if measurement > 0.0 { flat(on_circle - center).normalize() } else { Vector::x_axis() };
+        self.center_mark(&center);
+
+        let text = self.display_text(measurement);
+        let text_direction = if self.style.dimension_text_outside_horizontal { Vector::x_axis() } else { readable(&direction) };
+        let extent = self.text_extent_along(&text, &text_direction, &direction) + self.gap();
+        let location = if self.base.is_at_user_defined_location {
+            self.base.text_mid_point
+        }
+        else {
+            on_circle + direction * (self.arrow_size() + extent)
+        };
+        if flat(location - center).length() > measurement {
+            // the text is outside the circle so a leader runs from the arrowhead to the text
+            let leader_end = location - direction * extent;
+            if flat(leader_end - center).length() > measurement {
+                self.dimension_line(&on_circle, &leader_end);
+            }
+
+            self.arrow(&on_circle, &direction);
+        }
+        else {
+            self.dimension_line(&center, &on_circle);
+            self.arrow(&on_circle, &-direction);
+        }
+        self.text(&text, &location, &text_direction);
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        (measurement, location)
+    }
+    fn diameter(&mut self, dim: &DiameterDimension) -> (f64, Point) {
+        let p1 = self.ocs(&dim.definition_point_2);
+        let p2 = self.ocs(&self.base.definition_point_1);
+        let measurement = flat(p2 - p1).length();
+        let direction = if measurement > 0.0 { flat(p2 - p1).normalize() } else { Vector::x_axis() };
+        self.dimension_line(&p1, &p2);
+        self.arrow(&p1, &direction);
+        self.arrow(&p2, &-direction);
+
+        let text = self.display_text(measurement);
+        let text_direction = readable(&direction);
+        let location = if self.base.is_at_user_defined_location {
+            self.base.text_mid_point
+        }
+        else {
+            p1 + (p2 - p1) * 0.5 + perpendicular(&text_direction) * (self.gap() + self.text_height() / 2.0)
+        };
+        self.text(&text, &location, &text_direction);
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        (measurement, location)
+    }
+    fn angular(&mut self, dim: &AngularThreePointDimension) -> (f64, Point) {
+        let first = self.ocs(&dim.definition_point_2);
+        let second = self.ocs(&dim.definition_point_3);
+        let (vertex, radius, start_angle, sweep) = angular_dimension_arc(dim, &self.axes);
+        let result = self.dimension_arc(vertex, radius, start_angle, sweep, &[first, second], sweep.to_degrees());
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        self.defpoint(&dim.definition_point_3);
+        self.defpoint(&dim.definition_point_4);
+        result
+    }
+    fn two_line_angular(&mut self, dim: &AngularTwoLineDimension) -> (f64, Point) {
+        let result = match two_line_angle_sides(dim, &self.axes) {
+            Some((vertex, first, second)) => {
+                let (vertex, radius, start_angle, sweep) = angle_arc(vertex, first, second, dim.arc_definition_point);
+                self.dimension_arc(vertex, radius, start_angle, sweep, &[first, second], sweep.to_degrees())
+            },
+            // parallel lines have no angle to draw
+            None => (0.0, self.base.text_mid_point),
+        };
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        self.defpoint(&dim.definition_point_3);
+        self.defpoint(&dim.definition_point_4);
+        result
+    }
+    fn arc_length(&mut self, dim: &ArcDimension) -> (f64, Point) {
+        let center = self.ocs(&dim.arc_center);
+        let measured_radius = flat(self.ocs(&dim.definition_point_2) - center).length();
+        let radius = flat(self.ocs(&self.base.definition_point_1) - center).length();
+        let sweep = normalize_radians(dim.end_angle - dim.start_angle);
+        // the extension lines start at the ends of the measured arc
+        let ends = [center + direction_at(dim.start_angle) * measured_radius, center + direction_at(dim.end_angle) * measured_radius];
+        let result = self.dimension_arc(center, radius, normalize_radians(dim.start_angle), sweep, &ends, measured_radius * sweep);
+        if dim.has_leader {
+            let (leader_start, leader_end) = (self.ocs(&dim.leader_point_1), self.ocs(&dim.leader_point_2));
+            self.dimension_line(&leader_start, &leader_end);
+        }
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.definition_point_2);
+        self.defpoint(&dim.definition_point_3);
+        result
+    }
+    fn jogged_radius(&mut self, dim: &LargeRadialDimension) -> (f64, Point) {
+        let center = self.ocs(&self.base.definition_point_1);
+        let on_circle = self.ocs(&dim.chord_point);
+        let override_center = self.ocs(&dim.override_center);
+        let jog = self.ocs(&dim.jog_point);
+        let measurement = flat(on_circle - center).length();
+        let direction = if measurement > 0.0 { flat(on_circle - center).normalize() } else { Vector::x_axis() };
+
+        // the dimension line runs parallel to the radius from the displayed center to the jog and
+        // then along the radius to the arrowhead
+        let inner_end = override_center + direction * (jog - override_center).dot(&direction);
+        let outer_start = on_circle - direction * (on_circle - jog).dot(&direction);
+        self.dimension_line(&override_center, &inner_end);
+        self.dimension_line(&inner_end, &jog);
+        self.dimension_line(&jog, &outer_start);
+        self.dimension_line(&outer_start, &on_circle);
+        self.arrow(&on_circle, &-direction);
+
+        let text = self.display_text(measurement);
+        let text_direction = readable(&direction);
+        let location = if self.base.is_at_user_defined_location {
+            self.base.text_mid_point
+        }
+        else {
+            outer_start + (on_circle - outer_start) * 0.5 + perpendicular(&text_direction) * (self.gap() + self.text_height() / 2.0)
+        };
+        self.text(&text, &location, &text_direction);
+
+        self.defpoint(&self.base.definition_point_1);
+        self.defpoint(&dim.chord_point);
+        self.defpoint(&dim.override_center);
+        self.defpoint(&dim.jog_point);
+        (measurement, location)
+    }
+    // Draws the dimension arc of an angular or arc length dimension centered at `vertex` with
+    // extension lines running from the measured points to it, and returns the measurement and the
+    // text location.
+    fn dimension_arc(&mut self, vertex: Point, radius: f64, start_angle: f64, sweep: f64, measured: &[Point; 2], measurement: f64) -> (f64, Point) {
+        let (first, second) = (measured[0], measured[1]);
+        let end_angle = start_angle + sweep;
+
+        let suppressed = [self.style.suppress_first_dimension_extension_line, self.style.suppress_second_dimension_extension_line];
+        for (point, &is_suppressed) in [first, second].iter().zip(suppressed.iter()) {
+            let length = flat(*point - vertex).length();
+            let direction = if length > 0.0 { flat(*point - vertex).normalize() } else { continue };
+            let (start, end) = if radius > length + self.extension_offset() {
+                (length + self.extension_offset(), radius + self.extension_extension())
+            }
+            else if radius < length - self.extension_offset() {
+                (length - self.extension_offset(), (radius - self.extension_extension()).max(0.0))
+            }
+            else {
+                continue;
+            };
+            if !is_suppressed {
+                self.add(EntityType::Line(Line::new(self.wcs(&(vertex + direction * start)), self.wcs(&(vertex + direction * end)))), true);
+            }
+        }
+
+        let mut arc = Arc::new(vertex, radius, start_angle.to_degrees(), normalize_radians(end_angle).to_degrees());
+        arc.normal = self.axes.2;
+        self.add(EntityType::Arc(arc), false);
+        self.arrow(&(vertex + direction_at(start_angle) * radius), &direction_at(start_angle + PI / 2.0));
+        self.arrow(&(vertex + direction_at(end_angle) * radius), &direction_at(end_angle - PI / 2.0));
+
+        let text = self.display_text(measurement);
+        let middle_angle = start_angle + sweep / 2.0;
+        let location = if self.base.is_at_user_defined_location {
+            self.base.text_mid_point
+        }
+        else {
+            vertex + direction_at(middle_angle) * (radius + self.gap() + self.text_height() / 2.0)
+        };
+        self.text(&text, &location, &readable(&direction_at(middle_angle + PI / 2.0)));
+        (measurement, location)
+    }
+    fn ordinate(&mut self, dim: &OrdinateDimension) -> (f64, Point) {
+        let feature = self.ocs(&dim.definition_point_2);
+        let leader_end = self.ocs(&dim.definition_point_3);
+        let measurement = ordinate_measurement(dim, &self.axes);
+        // x-ordinates are read along a vertical leader and y-ordinates along a horizontal one
+        let axis = if self.base.is_ordinate_x_type { Vector::y_axis() } else { Vector::x_axis() };
+        let axis = if (leader_end - feature).dot(&axis) < 0.0 { -axis } else { axis };
+        self.extension_line(&feature, &leader_end, false);
+
+        let text = self.display_text(measurement);
+        let location = if self.base.is_at_user_defined_location {
+            self.base.text_mid_point
+        }
+        else {
+            leader_end + axis * (self.gap() + self.text_half_width(&text))
+        };
+        self.text(&text, &location, &readable(&axis));
+
+        self.defpoint(&dim.definition_point_2);
+        self.defpoint(&dim.definition_point_3);
+        (measurement, location)
+    }
+    fn display_text(&self, measurement: f64) -> String {
+        expand_dimension_text(&self.base.text, &format_measurement(self.style, &self.entity.specific, measurement))
+    }
+    fn arrow_size(&self) -> f64 {
+        self.style.dimensioning_arrow_size * self.scale
+    }
+    fn extension_offset(&self) -> f64 {
+        self.style.dimension_extension_line_offset * self.scale
+    }
+    fn extension_extension(&self) -> f64 {
+        self.style.dimension_extension_line_extension * self.scale
+    }
+    fn text_height(&self) -> f64 {
+        self.style.dimensioning_text_height * self.scale
+    }
+    fn gap(&self) -> f64 {
+        self.style.dimension_line_gap.abs() * self.scale
+    }
+    fn text_half_width(&self, text: &str) -> f64 {
+        // special character codes like `%%c` are drawn as a single character
+        text.replace("%%", "").chars().count() as f64 * self.text_height() * CHARACTER_WIDTH_FACTOR / 2.0
+    }
+    // The distance from the middle of the text to its edge in the specified direction.
+    fn text_extent_along(&self, text: &str, text_direction: &Vector, direction: &Vector) -> f64 {
+        direction.dot(text_direction).abs() * self.text_half_width(text) + direction.dot(&perpendicular(text_direction)).abs() * self.text_height() / 2.0
+    }
+    fn ocs(&self, p: &Point) -> Point {
+        to_ocs(&self.axes, p)
+    }
+    fn wcs(&self, p: &Point) -> Point {
+        let (x, y, z) = self.axes;
+        Point::origin() + x * p.x + y * p.y + z * p.z
+    }
+    fn add(&mut self, specific: EntityType, is_extension_line: bool) {
+        let (color, line_weight) = if is_extension_line {
+            (&self.style.dimension_extension_line_color, &self.style.dimension_extension_line_weight)
+        }
+        else {
+            (&self.style.dimension_line_color, &self.style.dimension_line_weight)
+        };
+        self.push(specific, color.clone(), line_weight.clone());
+    }
+    fn push(&mut self, specific: EntityType, color: Color, line_weight: LineWeight) {
+        let mut entity = Entity::new(specific);
+        entity.common.layer = String::from("0");
+        entity.common.color = color;
+        entity.common.lineweight_enum_value = line_weight.get_raw_value();
+        self.entities.push(entity);
+    }
+    fn dimension_line(&mut self, p1: &Point, p2: &Point) {
+        let mut line = Line::new(self.wcs(p1), self.wcs(p2));
+        line.extrusion_direction = self.axes.2;
+        self.add(EntityType::Line(line), false);
+    }
+    // Draws the extension line from near the measured point out past the dimension line.
+    fn extension_line(&mut self, from: &Point, to: &Point, is_suppressed: bool) {
+        let v = flat(*to - *from);
+        if is_suppressed || v.length() == 0.0 {
+            return;
+        }
+
+        let direction = v.normalize();
+        let mut line = Line::new(self.wcs(&(*from + direction * self.extension_offset())), self.wcs(&(*to + direction * self.extension_extension())));
+        line.extrusion_direction = self.axes.2;
+        self.add(EntityType::Line(line), true);
+    }
+    // Draws an arrowhead with its tip at `tip` and its tail in the direction `direction`, or an
+    // oblique tick if the style has a tick size.
+    fn arrow(&mut self, tip: &Point, direction: &Vector) {
+        let tick_size = self.style.dimensioning_tick_size * self.scale;
+        if tick_size > 0.0 {
+            let oblique = (*direction + perpendicular(direction)).normalize() * tick_size;
+            self.dimension_line(&(*tip - oblique), &(*tip + oblique));
+            return;
+        }
+
+        let size = self.arrow_size();
+        if size <= 0.0 {
+            return;
+        }
+
+        let tail = *tip + *direction * size;
+        let half_width = perpendicular(direction) * (size / 6.0);
+        let mut solid = Solid::new(*tip, tail + half_width, tail - half_width, tail - half_width);
+        solid.extrusion_direction = self.axes.2;
+        self.add(EntityType::Solid(solid), false);
+    }
+    fn center_mark(&mut self, center: &Point) {
+        let size = self.style.center_mark_size.abs() * self.scale;
+        if size > 0.0 {
+            self.dimension_line(&(*center - Vector::x_axis() * size), &(*center + Vector::x_axis() * size));
+            self.dimension_line(&(*center - Vector::y_axis() * size), &(*center + Vector::y_axis() * size));
+        }
+    }
+    fn text(&mut self, text: &str, location: &Point, direction: &Vector) {
+        if text.is_empty() {
+            return;
+        }
+
+        let (x, y, _) = self.axes;
+        let mtext = MText {
+            insertion_point: self.wcs(location),
+            initial_text_height: self.text_height(),
+            reference_rectangle_width: 0.0,
+            attachment_point: AttachmentPoint::MiddleCenter,
+            text: String::from(text),
+            text_style_name: self.text_style_name.clone(),
+            extrusion_direction: self.axes.2,
+            x_axis_direction: x * direction.x + y * direction.y,
+            rotation_angle: direction.y.atan2(direction.x),
+            .. Default::default()
+        };
+        let color = self.style.dimension_text_color.clone();
+        self.push(EntityType::MText(mtext), color, LineWeight::by_block());
+    }
+    // Marks a definition point in world coordinates.
+    fn defpoint(&mut self, p: &Point) {
+        let mut entity = Entity::new(EntityType::ModelPoint(ModelPoint::new(*p)));
+        entity.common.layer = String::from(DEFPOINTS_LAYER);
+        self.entities.push(entity);
+    }
+}
+
+fn to_ocs(axes: &(Vector, Vector, Vector), p: &Point) -> Point {
+    let v = p.as_vector();
+    Point::new(v.dot(&axes.0), v.dot(&axes.1), v.dot(&axes.2))
+}
+
+fn flat(v: Vector) -> Vector {
+    Vector::new(v.x, v.y, 0.0)
+}
+
+fn direction_at(radians: f64) -> Vector {
+    Vector::new(radians.cos(), radians.sin(), 0.0)
+}
+
+fn perpendicular(v: &Vector) -> Vector {
+    Vector::new(-v.y, v.x, 0.0)
+}
+
+// Flips a text direction that would otherwise be read upside-down.
+fn readable(v: &Vector) -> Vector {
+    if v.x < -1.0e-9 || (v.x.abs() <= 1.0e-9 && v.y < 0.0) { -*v } else { *v }
+}
+
+fn normalize_radians(angle: f64) -> f64 {
+    let angle = angle % (2.0 * PI);
+    if angle < 0.0 { angle + 2.0 * PI } else { angle }
+}
@@ -0,0 +1,291 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::Drawing;
+
+use entities::*;
+use enums::*;
+use tables::*;
+
+impl DimStyle {
+    /// Formats a measured distance for display.  The distance is multiplied by the linear scale
+    /// factor (`DIMLFAC`), rounded (`DIMRND`), and written in the linear units (`DIMLUNIT`) with
+    /// the style's precision (`DIMDEC`), zero suppression (`DIMZIN`), and decimal separator
+    /// (`DIMDSEP`).  Tolerances or limits (`DIMTOL`, `DIMLIM`), the prefix and suffix (`DIMPOST`),
+    /// and alternate units in brackets (`DIMALT`) are added when enabled.
+    pub fn format_distance(&self, distance: f64) -> String {
+        self.format_linear(distance, "")
+    }
+    /// Formats a measured angle in degrees for display in the angular units (`DIMAUNIT`) with the
+    /// angular precision (`DIMADEC`, or `DIMDEC` when that's out of range) and zero suppression
+    /// (`DIMAZIN`).
+    pub fn format_angle(&self, degrees: f64) -> String {
+        let precision = if self.angular_dimension_precision >= 0 && self.angular_dimension_precision <= 8 {
+            self.angular_dimension_precision
+        }
+        else {
+            self.dimension_unit_tolerance_decimal_places
+        };
+        let precision = precision.max(0).min(8) as usize;
+        // angular zero suppression uses 1 for leading and 2 for trailing zeros
+        let zero_suppression = self.dimension_angle_zero_suppression as i16;
+        let decimal_zero_suppression = (zero_suppression & 1) * 4 + (zero_suppression & 2) * 4;
+        let separator = self.dimension_decilam_separator_char;
+        match self.dimensioning_angle_format {
+            AngleFormat::DecimalDegrees => format!("{}%%d", format_decimal(degrees, precision, decimal_zero_suppression, separator)),
+            AngleFormat::DegreesMinutesSeconds | AngleFormat::SurveyorsUnits => format_degrees_minutes_seconds(degrees, precision, separator),
+            AngleFormat::Gradians => format!("{}g", format_decimal(degrees / 0.9, precision, decimal_zero_suppression, separator)),
+            AngleFormat::Radians => format!("{}r", format_decimal(degrees.to_radians(), precision, decimal_zero_suppression, separator)),
+        }
+    }
+    // Formats a distance with the specified prefix, e.g., `R` for radii.
+    pub(crate) fn format_linear(&self, distance: f64, prefix: &str) -> String {
+        let factor = self.dimension_linear_measurement_scale_factor.abs();
+        // negative factors only apply in paperspace and 0 isn't meaningful
+        let value = distance * if factor > 0.0 { factor } else { 1.0 };
+        let value = round_to(value, self.dimension_distance_rounding_value);
+        let format = NumberFormat {
+            units: self.dimension_non_angular_units,
+            precision: self.dimension_unit_tolerance_decimal_places,
+            zero_suppression: self.dimension_unit_zero_suppression as i16,
+            separator: self.dimension_decilam_separator_char,
+        };
+        let tolerance_format = NumberFormat {
+            precision: self.dimension_tolerace_decimal_places,
+            zero_suppression: self.dimension_tolerance_zero_suppression as i16,
+            .. format
+        };
+        let height = self.dimension_tolerance_displace_scale_factor;
+        let number = if self.generate_dimension_limits {
+            let upper = tolerance_format.format(value + self.dimension_plus_tolerance);
+            let lower = tolerance_format.format(value - self.dimension_minus_tolerance);
+            stacked(&upper, &lower, height)
+        }
+        else if self.generate_dimension_tolerances && self.dimension_plus_tolerance == self.dimension_minus_tolerance {
+            format!("{}%%p{}", format.format(value), tolerance_format.format(self.dimension_plus_tolerance))
+        }
+        else if self.generate_dimension_tolerances {
+            let plus = signed(&tolerance_format, self.dimension_plus_tolerance);
+            let minus = signed(&tolerance_format, -self.dimension_minus_tolerance);
+            format!("{}{}", format.format(value), stacked(&plus, &minus, height))
+        }
+        else {
+            format.format(value)
+        };
+        let primary = apply_suffix(&self.dimensioning_suffix, &format!("{}{}", prefix, number));
+        if !self.use_alternate_dimensioning {
+            return primary;
+        }
+
+        let alternate_format = NumberFormat {
+            units: match self.alternate_dimensioning_units {
+                UnitFormat::Scientific => NonAngularUnits::Scientific,
+                UnitFormat::Decimal => NonAngularUnits::Decimal,
+                UnitFormat::Engineering => NonAngularUnits::Engineering,
+                UnitFormat::ArchitecturalStacked | UnitFormat::Architectural => NonAngularUnits::Architectural,
+                UnitFormat::FractionalStacked | UnitFormat::Fractional => NonAngularUnits::Fractional,
+            },
+            precision: self.alternate_dimensioning_decimal_places,
+            zero_suppression: self.alternate_dimensioning_zero_suppression as i16,
+            separator: self.dimension_decilam_separator_char,
+        };
+        let alternate = round_to(value * self.alternate_dimensioning_scale_factor, self.alternate_dimensioning_unit_rounding);
+        let alternate = apply_suffix(&self.alternate_dimensioning_suffix, &format!("{}{}", prefix, alternate_format.format(alternate)));
+        format!("{} [{}]", primary, alternate)
+    }
+}
+
+impl Drawing {
+    /// Returns the text displayed by a dimension: its measurement (see
+    /// `Entity::dimension_measurement`) formatted with the dimension's resolved style (see
+    /// `resolve_dim_style`) and substituted for `<>` in the dimension's text.  An empty text shows
+    /// only the measurement and a single space hides the text.  Radii are prefixed with `R` and
+    /// diameters with `%%c`.  Returns `None` if the entity isn't a dimension.
+    pub fn dimension_text(&self, entity: &Entity) -> Option<String> {
+        let base = entity.dimension_base()?;
+        let measurement = entity.dimension_measurement()?;
+        let style = self.resolve_dim_style(&base.dimension_style_name);
+        Some(expand_dimension_text(&base.text, &format_measurement(&style, &entity.specific, measurement)))
+    }
+}
+
+// Formats a dimension's measurement for its type.
+pub(crate) fn format_measurement(style: &DimStyle, specific: &EntityType, measurement: f64) -> String {
+    match *specific {
+        EntityType::RadialDimension(_) => style.format_linear(measurement, "R"),
+        EntityType::DiameterDimension(_) => style.format_linear(measurement, "%%c"),
+        EntityType::AngularThreePointDimension(_) => style.format_angle(measurement),
+        EntityType::AngularTwoLineDimension(_) => style.format_angle(measurement),
+        EntityType::LargeRadialDimension(_) => style.format_linear(measurement, "R"),
+        _ => style.format_linear(measurement, ""),
+    }
+}
+
+// Substitutes the measurement into a dimension's text override.
+pub(crate) fn expand_dimension_text(text: &str, measurement: &str) -> String {
+    match text {
+        "" => String::from(measurement),
+        " " => String::new(),
+        _ => text.replace("<>", measurement),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct NumberFormat {
+    units: NonAngularUnits,
+    precision: i16,
+    zero_suppression: i16,
+    separator: char,
+}
+
+impl NumberFormat {
+    fn format(&self, value: f64) -> String {
+        let precision = self.precision.max(0).min(8) as usize;
+        match self.units {
+            NonAngularUnits::Scientific => format_scientific(value, precision, self.separator),
+            NonAngularUnits::Decimal | NonAngularUnits::WindowsDesktop => format_decimal(value, precision, self.zero_suppression, self.separator),
+            NonAngularUnits::Engineering => {
+                let step = 10f64.powi(-(precision as i32));
+                format_feet_and_inches(value, step, self.zero_suppression, |inches| format_decimal(inches, precision, 0, self.separator))
+            },
+            NonAngularUnits::Architectural => {
+                let step = 1.0 / f64::from(1 << precision);
+                format_feet_and_inches(value, step, self.zero_suppression, |inches| format_fraction(inches, precision))
+            },
+            NonAngularUnits::Fractional => format_fraction(value, precision),
+        }
+    }
+}
+
+fn round_to(value: f64, increment: f64) -> f64 {
+    if increment > 0.0 { (value / increment).round() * increment } else { value }
+}
+
+// Writes a value with `precision` decimal places.  Zero suppression bit 4 removes the leading zero
+// before the decimal point and bit 8 removes trailing zeros after it.
+fn format_decimal(value: f64, precision: usize, zero_suppression: i16, separator: char) -> String {
+    let mut text = format!("{:.*}", precision, value);
+    if text.trim_start_matches('-').trim_start_matches(|c| c == '0' || c == '.').is_empty() {
+        // avoid writing `-0.00`
+        text = format!("{:.*}", precision, 0.0);
+    }
+
+    if zero_suppression & 8 != 0 && text.contains('.') {
+        text = String::from(text.trim_end_matches('0').trim_end_matches('.'));
+    }
+
+    if zero_suppression & 4 != 0 {
+        if text.starts_with("0.") {
+            text.remove(0);
+        }
+        else if text.starts_with("-0.") {
+            text.remove(1);
+        }
+    }
+
+    if separator != '.' {
+        text = text.replace('.', &separator.to_string());
+    }
+
+    text
+}
+
+fn format_scientific(value: f64, precision: usize, separator: char) -> String {
+    let mut exponent = if value == 0.0 { 0 } else { value.abs().log10().floor() as i32 };
+    let mut mantissa = value / 10f64.powi(exponent);
+    // rounding can carry the mantissa to 10
+    if format!("{:.*}", precision, mantissa.abs()).starts_with("10") {
+        exponent += 1;
+        mantissa /= 10.0;
+    }
+
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{}E{}{:02}", format_decimal(mantissa, precision, 0, separator), sign, exponent.abs())
+}
+
+// Writes a value as a whole number and a reduced fraction with a denominator of 2^`precision`,
+// e.g., `2 1/2`.
+fn format_fraction(value: f64, precision: usize) -> String {
+    let denominator = 1i64 << precision;
+    let total = (value.abs() * denominator as f64).round() as i64;
+    let whole = total / denominator;
+    let mut numerator = total % denominator;
+    let mut denominator = denominator;
+    while numerator != 0 && numerator % 2 == 0 {
+        numerator /= 2;
+        denominator /= 2;
+    }
+
+    let sign = if total != 0 && value < 0.0 { "-" } else { "" };
+    match (whole, numerator) {
+        (_, 0) => format!("{}{}", sign, whole),
+        (0, _) => format!("{}{}/{}", sign, numerator, denominator),
+        _ => format!("{}{} {}/{}", sign, whole, numerator, denominator),
+    }
+}
+
+// Writes a value in inches as feet and inches, e.g., `1'-2 1/2"`, rounding to a multiple of
+// `step` inches.  The lower two bits of the zero suppression control whether zero feet and zero
+// inches are written.
+fn format_feet_and_inches<F>(value: f64, step: f64, zero_suppression: i16, format_inches: F) -> String
+    where F: Fn(f64) -> String {
+
+    let total = round_to(value.abs(), step);
+    let feet = (total / 12.0 + 1.0e-9).floor();
+    let inches = (total - feet * 12.0).max(0.0);
+    let (include_zero_feet, include_zero_inches) = match zero_suppression & 3 {
+        1 => (true, true),
+        2 => (true, false),
+        3 => (false, true),
+        _ => (false, false),
+    };
+    let show_feet = feet != 0.0 || include_zero_feet;
+    let show_inches = inches != 0.0 || include_zero_inches || !show_feet;
+    let sign = if total != 0.0 && value < 0.0 { "-" } else { "" };
+    match (show_feet, show_inches) {
+        (true, true) => format!("{}{}'-{}\"", sign, feet, format_inches(inches)),
+        (true, false) => format!("{}{}'", sign, feet),
+        _ => format!("{}{}\"", sign, format_inches(inches)),
+    }
+}
+
+// Writes an angle as degrees, minutes, and seconds where the precision selects how many of those
+// are written and precisions above 4 add decimal places to the seconds.
+fn format_degrees_minutes_seconds(degrees: f64, precision: usize, separator: char) -> String {
+    let sign = if degrees < 0.0 { "-" } else { "" };
+    let degrees = degrees.abs();
+    match precision {
+        0 => format!("{}{}%%d", sign, degrees.round()),
+        1 | 2 => {
+            let minutes = (degrees * 60.0).round();
+            format!("{}{}%%d{}'", sign, (minutes / 60.0).floor(), minutes % 60.0)
+        },
+        _ => {
+            let decimals = precision.saturating_sub(4);
+            let seconds = round_to(degrees * 3600.0, 10f64.powi(-(decimals as i32)));
+            let whole_minutes = (seconds / 60.0 + 1.0e-9).floor();
+            let seconds = (seconds - whole_minutes * 60.0).max(0.0);
+            format!("{}{}%%d{}'{}\"", sign, (whole_minutes / 60.0).floor(), whole_minutes % 60.0, format_decimal(seconds, decimals, 0, separator))
+        },
+    }
+}
+
+fn signed(format: &NumberFormat, value: f64) -> String {
+    let text = format.format(value);
+    if text.starts_with('-') { text } else { format!("+{}", text) }
+}
+
+// Builds an MText stack of two values, e.g., for tolerances and limits.
+fn stacked(upper: &str, lower: &str, height: f64) -> String {
+    format!("{{\\H{}x;\\S{}^{};}}", height, upper, lower)
+}
+
+// Applies a `DIMPOST`-style suffix where `<>` marks the position of the value; without `<>` the
+// whole string is a suffix.
+fn apply_suffix(suffix: &str, value: &str) -> String {
+    if suffix.contains("<>") {
+        suffix.replace("<>", value)
+    }
+    else {
+        format!("{}{}", value, suffix)
+    }
+}
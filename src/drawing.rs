@@ -842,6 +842,9 @@ impl Drawing {
                 &EntityType::DiameterDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
                 &EntityType::AngularThreePointDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
                 &EntityType::OrdinateDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
+                &EntityType::AngularTwoLineDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
+                &EntityType::ArcDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
+                &EntityType::LargeRadialDimension(ref d) => add_to_existing(&mut to_add, &d.dimension_base.dimension_style_name),
                 &EntityType::Leader(ref l) => add_to_existing(&mut to_add, &l.dimension_style_name),
                 &EntityType::Tolerance(ref t) => add_to_existing(&mut to_add, &t.dimension_style_name),
                 _ => (),
@@ -26,6 +26,8 @@ pub enum DxfError {
     WrongValueType,
     InvalidBinaryFile,
     WrongItemType,
+    InvalidSpline(String),
+    ValueOutOfRange(String),
 }
 
 impl From<io::Error> for DxfError {
@@ -58,6 +60,8 @@ impl fmt::Display for DxfError {
             &DxfError::WrongValueType => write!(formatter, "the CodePairValue does not contain the requested type"),
             &DxfError::InvalidBinaryFile => write!(formatter, "the binary file is invalid"),
             &DxfError::WrongItemType => write!(formatter, "the specified item type is not correct"),
+            &DxfError::InvalidSpline(ref s) => write!(formatter, "the spline is not valid for this operation: {}", s),
+            &DxfError::ValueOutOfRange(ref s) => write!(formatter, "the value can't be written: {}", s),
         }
     }
 }
@@ -80,6 +84,8 @@ impl error::Error for DxfError {
             &DxfError::WrongValueType => "the CodePairValue does not contain the requested type",
             &DxfError::InvalidBinaryFile => "the binary file is invalid",
             &DxfError::WrongItemType => "the specified item type is not correct",
+            &DxfError::InvalidSpline(_) => "the spline is not valid for this operation",
+            &DxfError::ValueOutOfRange(_) => "the value can't be written",
         }
     }
     fn cause(&self) -> Option<&error::Error> {
@@ -0,0 +1,295 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `generated/objects.rs`
+
+use std::io::Write;
+
+use ::{
+    Block,
+    BlockVisibilityState,
+    CodePair,
+    Drawing,
+    DrawingItem,
+    DxfResult,
+    Point,
+    XDataItem,
+};
+
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use extension_data::get_extension_dictionary_handle;
+use helper_functions::*;
+use objects::*;
+use tables::*;
+
+// the XData on an anonymous block record that points back to the dynamic block record
+const BLOCK_REPRESENTATION_XDATA: &str = "AcDbBlockRepBTag";
+
+// the entries of an insert's extension dictionary that point to the dynamic block record
+const BLOCK_REPRESENTATION_DICTIONARY: &str = "AcDbBlockRepresentation";
+const BLOCK_REPRESENTATION_DATA: &str = "AcDbRepData";
+
+// the entry of a block record's extension dictionary with the evaluation graph that owns the parameters
+const ENHANCED_BLOCK_GRAPH: &str = "ACAD_ENHANCEDBLOCK";
+
+impl Drawing {
+    /// Returns the name of the block displayed by an `Insert`.  A reference to a dynamic block whose
+    /// parameters were changed displays an anonymous `*U` copy of the block and the name of the
+    /// original dynamic block is returned instead.  Returns `None` if the entity isn't an `Insert`.
+    pub fn effective_block_name(&self, entity: &Entity) -> Option<String> {
+        let insert = match entity.specific {
+            EntityType::Insert(ref insert) => insert,
+            _ => return None,
+        };
+        match self.get_dynamic_block_record(entity) {
+            Some(record) => Some(record.name.clone()),
+            None => Some(insert.name.clone()),
+        }
+    }
+    /// Returns the definition of the dynamic block displayed by an `Insert`, or `None` if it doesn't
+    /// reference a dynamic block.
+    pub fn dynamic_block_definition(&self, entity: &Entity) -> Option<&Block> {
+        let record = self.get_dynamic_block_record(entity)
+            .or_else(|| self.get_insert_block_record(entity).filter(|r| self.get_enhanced_block_graph(r).is_some()))?;
+        self.blocks.iter().find(|b| b.name.eq_ignore_ascii_case(&record.name))
+    }
+    /// Returns the `BlockLinearParameter`, `BlockLookupParameter`, `BlockRotationParameter`, and
+    /// `BlockVisibilityParameter` objects of the dynamic block displayed by an `Insert`.  The
+    /// parameters of the block the insert references directly are preferred over those of the
+    /// original dynamic block.
+    pub fn dynamic_block_parameters(&self, entity: &Entity) -> Vec<&Object> {
+        let graph = self.get_insert_block_record(entity)
+            .and_then(|r| self.get_enhanced_block_graph(r))
+            .or_else(|| self.get_dynamic_block_record(entity).and_then(|r| self.get_enhanced_block_graph(r)));
+        let graph = match graph {
+            Some(graph) => graph,
+            None => return vec![],
+        };
+        self.objects.iter()
+            .filter(|o| o.common.__owner_handle == graph)
+            .filter(|o| matches!(o.specific,
+                ObjectType::BlockLinearParameter(_) |
+                ObjectType::BlockLookupParameter(_) |
+                ObjectType::BlockRotationParameter(_) |
+                ObjectType::BlockVisibilityParameter(_)))
+            .collect()
+    }
+}
+
+// private implementation
+impl Drawing {
+    fn get_insert_block_record(&self, entity: &Entity) -> Option<&BlockRecord> {
+        match entity.specific {
+            EntityType::Insert(ref insert) => self.block_records.iter().find(|r| r.name.eq_ignore_ascii_case(&insert.name)),
+            _ => None,
+        }
+    }
+    // Finds the record of the original dynamic block through the XData of the anonymous block record
+    // or through the representation data in the insert's extension dictionary.
+    fn get_dynamic_block_record(&self, entity: &Entity) -> Option<&BlockRecord> {
+        let from_x_data = self.get_insert_block_record(entity)
+            .and_then(|r| r.x_data.iter().find(|x| x.application_name == BLOCK_REPRESENTATION_XDATA))
+            .and_then(|x| x.items.iter().filter_map(|item| match *item {
+                XDataItem::Handle(handle) => Some(handle),
+                _ => None,
+            }).next());
+        let from_dictionary = || get_extension_dictionary_handle(&entity.common.extension_data_groups)
+            .and_then(|h| self.get_dictionary_entry(h, BLOCK_REPRESENTATION_DICTIONARY))
+            .and_then(|h| self.get_dictionary_entry(h, BLOCK_REPRESENTATION_DATA))
+            .and_then(|h| match self.get_item_by_handle(h) {
+                Some(DrawingItem::Object(&Object { specific: ObjectType::BlockRepresentationData(ref data), .. })) => Some(data.__block_record_handle),
+                _ => None,
+            });
+        match self.get_item_by_handle(from_x_data.or_else(from_dictionary)?) {
+            Some(DrawingItem::BlockRecord(record)) => Some(record),
+            _ => None,
+        }
+    }
+    fn get_enhanced_block_graph(&self, record: &BlockRecord) -> Option<u32> {
+        get_extension_dictionary_handle(&record.extension_data_groups)
+            .and_then(|h| self.get_dictionary_entry(h, ENHANCED_BLOCK_GRAPH))
+    }
+    fn get_dictionary_entry(&self, dictionary_handle: u32, key: &str) -> Option<u32> {
+        match self.get_item_by_handle(dictionary_handle) {
+            Some(DrawingItem::Object(&Object { specific: ObjectType::Dictionary(ref dict), .. })) => dict.value_handles.get(key).cloned(),
+            _ => None,
+        }
+    }
+}
+
+impl BlockLinearParameter {
+    /// The distance between the base point and the end point.
+    pub fn distance(&self) -> f64 {
+        self.base_point.distance_to(&self.end_point)
+    }
+    pub(crate) fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            90 => self.node_id = pair.value.assert_i32()?,
+            300 => self.element_name = pair.value.assert_string()?,
+            1010 => self.base_point.x = pair.value.assert_f64()?,
+            1020 => self.base_point.y = pair.value.assert_f64()?,
+            1030 => self.base_point.z = pair.value.assert_f64()?,
+            1011 => self.end_point.x = pair.value.assert_f64()?,
+            1021 => self.end_point.y = pair.value.assert_f64()?,
+            1031 => self.end_point.z = pair.value.assert_f64()?,
+            305 => self.label = pair.value.assert_string()?,
+            306 => self.description = pair.value.assert_string()?,
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        write_parameter_header(writer, self.node_id, &self.element_name)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlock2PtParameter"))?;
+        write_point(writer, 1010, &self.base_point)?;
+        write_point(writer, 1011, &self.end_point)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockLinearParameter"))?;
+        writer.write_code_pair(&CodePair::new_string(305, &self.label))?;
+        writer.write_code_pair(&CodePair::new_string(306, &self.description))?;
+        Ok(())
+    }
+}
+
+impl BlockLookupParameter {
+    pub(crate) fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            90 => self.node_id = pair.value.assert_i32()?,
+            300 => self.element_name = pair.value.assert_string()?,
+            1010 => self.location.x = pair.value.assert_f64()?,
+            1020 => self.location.y = pair.value.assert_f64()?,
+            1030 => self.location.z = pair.value.assert_f64()?,
+            303 => self.label = pair.value.assert_string()?,
+            304 => self.description = pair.value.assert_string()?,
+            94 => self.action_node_id = pair.value.assert_i32()?,
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        write_parameter_header(writer, self.node_id, &self.element_name)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlock1PtParameter"))?;
+        write_point(writer, 1010, &self.location)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockLookupParameter"))?;
+        writer.write_code_pair(&CodePair::new_string(303, &self.label))?;
+        writer.write_code_pair(&CodePair::new_string(304, &self.description))?;
+        writer.write_code_pair(&CodePair::new_i32(94, self.action_node_id))?;
+        Ok(())
+    }
+}
+
+impl BlockRotationParameter {
+    /// The angle in degrees from the base point to the angle point.
+    pub fn angle(&self) -> f64 {
+        let direction = self.angle_point - self.base_point;
+        direction.y.atan2(direction.x).to_degrees()
+    }
+    pub(crate) fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            90 => self.node_id = pair.value.assert_i32()?,
+            300 => self.element_name = pair.value.assert_string()?,
+            1010 => self.base_point.x = pair.value.assert_f64()?,
+            1020 => self.base_point.y = pair.value.assert_f64()?,
+            1030 => self.base_point.z = pair.value.assert_f64()?,
+            1011 => self.angle_point.x = pair.value.assert_f64()?,
+            1021 => self.angle_point.y = pair.value.assert_f64()?,
+            1031 => self.angle_point.z = pair.value.assert_f64()?,
+            305 => self.label = pair.value.assert_string()?,
+            306 => self.description = pair.value.assert_string()?,
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        write_parameter_header(writer, self.node_id, &self.element_name)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlock1PtParameter"))?;
+        write_point(writer, 1010, &self.base_point)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockRotationParameter"))?;
+        write_point(writer, 1011, &self.angle_point)?;
+        writer.write_code_pair(&CodePair::new_string(305, &self.label))?;
+        writer.write_code_pair(&CodePair::new_string(306, &self.description))?;
+        Ok(())
+    }
+}
+
+impl BlockVisibilityParameter {
+    /// Gets the visibility state with the specified name.
+    pub fn get_state(&self, name: &str) -> Option<&BlockVisibilityState> {
+        self.states.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+    pub(crate) fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            90 => self.node_id = pair.value.assert_i32()?,
+            300 => self.element_name = pair.value.assert_string()?,
+            1010 => self.location.x = pair.value.assert_f64()?,
+            1020 => self.location.y = pair.value.assert_f64()?,
+            1030 => self.location.z = pair.value.assert_f64()?,
+            301 => self.label = pair.value.assert_string()?,
+            302 => self.description = pair.value.assert_string()?,
+            331 => self.__entities_handle.push(as_u32(pair.value.assert_string()?)?),
+            303 => self.states.push(BlockVisibilityState::new(pair.value.assert_string()?)),
+            332 => {
+                let handle = as_u32(pair.value.assert_string()?)?;
+                if let Some(state) = self.states.last_mut() {
+                    state.__entities_handle.push(handle);
+                }
+            },
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        write_parameter_header(writer, self.node_id, &self.element_name)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlock1PtParameter"))?;
+        write_point(writer, 1010, &self.location)?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockVisibilityParameter"))?;
+        writer.write_code_pair(&CodePair::new_string(301, &self.label))?;
+        writer.write_code_pair(&CodePair::new_string(302, &self.description))?;
+        writer.write_code_pair(&CodePair::new_i32(93, self.__entities_handle.len() as i32))?;
+        for handle in &self.__entities_handle {
+            writer.write_code_pair(&CodePair::new_string(331, &as_handle(*handle)))?;
+        }
+        writer.write_code_pair(&CodePair::new_i32(92, self.states.len() as i32))?;
+        for state in &self.states {
+            writer.write_code_pair(&CodePair::new_string(303, &state.name))?;
+            writer.write_code_pair(&CodePair::new_i32(94, state.__entities_handle.len() as i32))?;
+            for handle in &state.__entities_handle {
+                writer.write_code_pair(&CodePair::new_string(332, &as_handle(*handle)))?;
+            }
+            writer.write_code_pair(&CodePair::new_i32(95, 0))?;
+        }
+        Ok(())
+    }
+}
+
+// Writes the evaluation node, element, and parameter subclasses shared by all parameters.
+fn write_parameter_header<T>(writer: &mut CodePairWriter<T>, node_id: i32, element_name: &str) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_str(100, "AcDbEvalExpr"))?;
+    writer.write_code_pair(&CodePair::new_i32(90, node_id))?;
+    writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockElement"))?;
+    writer.write_code_pair(&CodePair::new_str(300, element_name))?;
+    writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockParameter"))?;
+    Ok(())
+}
+
+fn write_point<T>(writer: &mut CodePairWriter<T>, code: i32, point: &Point) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_f64(code, point.x))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 10, point.y))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 20, point.z))?;
+    Ok(())
+}
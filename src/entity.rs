@@ -15,11 +15,14 @@ use ::{
     Vector,
 };
 
+use acis::read_modeler_data;
 use code_pair_writer::CodePairWriter;
 use enums::*;
 use entities::*;
 use handle_tracker::HandleTracker;
+use extension_data;
 use helper_functions::*;
+use x_data;
 
 //------------------------------------------------------------------------------
 //                                                                           Arc
@@ -53,12 +56,14 @@ impl Circle {
 //                                                                 DimensionBase
 //------------------------------------------------------------------------------
 impl DimensionBase {
-    fn set_dimension_type(&mut self, val: i16) -> DxfResult<()> {
+    fn set_dimension_type(&mut self, val: i16) {
         self.is_block_reference_referenced_by_this_block_only = (val & 32) == 32;
         self.is_ordinate_x_type = (val & 64) == 64;
         self.is_at_user_defined_location = (val & 128) == 128;
-        self.dimension_type = try_result!(DimensionType::from_i16(val & 0x0F)); // only take the lower 4 bits
-        Ok(())
+        // only take the lower 4 bits; unknown types keep the default so the rest of the drawing can still be read
+        if let Some(dimension_type) = DimensionType::from_i16(val & 0x0F) {
+            self.dimension_type = dimension_type;
+        }
     }
     pub(crate) fn get_dimension_type(&self) -> i16 {
         let mut val = self.dimension_type as i16;
@@ -191,6 +196,44 @@ impl Vertex {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                                      Viewport
+//------------------------------------------------------------------------------
+impl Viewport {
+    /// Creates a paper space viewport of the specified size showing the model space area centered
+    /// at `view_center` at the specified scale.  A scale that isn't positive and finite shows the
+    /// model at 1:1.
+    pub fn new(center: Point, width: f64, height: f64, view_center: Point, scale: f64) -> Self {
+        let scale = if is_valid_viewport_scale(scale) { scale } else { 1.0 };
+        Viewport {
+            center,
+            width,
+            height,
+            view_center,
+            view_height: height / scale,
+            .. Default::default()
+        }
+    }
+    /// Gets the ratio of paper space units to model space units.
+    pub fn get_scale(&self) -> f64 {
+        self.height / self.view_height
+    }
+    /// Sets the ratio of paper space units to model space units by adjusting the `view_height`.
+    /// Returns `false` and leaves the view unchanged if the scale isn't positive and finite.
+    pub fn set_scale(&mut self, scale: f64) -> bool {
+        if !is_valid_viewport_scale(scale) {
+            return false;
+        }
+
+        self.view_height = self.height / scale;
+        true
+    }
+}
+
+fn is_valid_viewport_scale(scale: f64) -> bool {
+    scale > 0.0 && scale.is_finite()
+}
+
 //------------------------------------------------------------------------------
 //                                                                    EntityType
 //------------------------------------------------------------------------------
@@ -259,6 +302,62 @@ impl EntityType {
                     _ => { return Ok(false); },
                 }
             },
+            &mut EntityType::AngularTwoLineDimension(ref mut dim) => {
+                match pair.code {
+                    13 => { dim.definition_point_2.x = pair.value.assert_f64()?; },
+                    23 => { dim.definition_point_2.y = pair.value.assert_f64()?; },
+                    33 => { dim.definition_point_2.z = pair.value.assert_f64()?; },
+                    14 => { dim.definition_point_3.x = pair.value.assert_f64()?; },
+                    24 => { dim.definition_point_3.y = pair.value.assert_f64()?; },
+                    34 => { dim.definition_point_3.z = pair.value.assert_f64()?; },
+                    15 => { dim.definition_point_4.x = pair.value.assert_f64()?; },
+                    25 => { dim.definition_point_4.y = pair.value.assert_f64()?; },
+                    35 => { dim.definition_point_4.z = pair.value.assert_f64()?; },
+                    16 => { dim.arc_definition_point.x = pair.value.assert_f64()?; },
+                    26 => { dim.arc_definition_point.y = pair.value.assert_f64()?; },
+                    36 => { dim.arc_definition_point.z = pair.value.assert_f64()?; },
+                    _ => { return Ok(false); },
+                }
+            },
+            &mut EntityType::ArcDimension(ref mut dim) => {
+                match pair.code {
+                    13 => { dim.definition_point_2.x = pair.value.assert_f64()?; },
+                    23 => { dim.definition_point_2.y = pair.value.assert_f64()?; },
+                    33 => { dim.definition_point_2.z = pair.value.assert_f64()?; },
+                    14 => { dim.definition_point_3.x = pair.value.assert_f64()?; },
+                    24 => { dim.definition_point_3.y = pair.value.assert_f64()?; },
+                    34 => { dim.definition_point_3.z = pair.value.assert_f64()?; },
+                    15 => { dim.arc_center.x = pair.value.assert_f64()?; },
+                    25 => { dim.arc_center.y = pair.value.assert_f64()?; },
+                    35 => { dim.arc_center.z = pair.value.assert_f64()?; },
+                    40 => { dim.start_angle = pair.value.assert_f64()?; },
+                    41 => { dim.end_angle = pair.value.assert_f64()?; },
+                    70 => { dim.is_partial = as_bool(pair.value.assert_i16()?); },
+                    71 => { dim.has_leader = as_bool(pair.value.assert_i16()?); },
+                    16 => { dim.leader_point_1.x = pair.value.assert_f64()?; },
+                    26 => { dim.leader_point_1.y = pair.value.assert_f64()?; },
+                    36 => { dim.leader_point_1.z = pair.value.assert_f64()?; },
+                    17 => { dim.leader_point_2.x = pair.value.assert_f64()?; },
+                    27 => { dim.leader_point_2.y = pair.value.assert_f64()?; },
+                    37 => { dim.leader_point_2.z = pair.value.assert_f64()?; },
+                    _ => { return Ok(false); },
+                }
+            },
+            &mut EntityType::LargeRadialDimension(ref mut dim) => {
+                match pair.code {
+                    13 => { dim.chord_point.x = pair.value.assert_f64()?; },
+                    23 => { dim.chord_point.y = pair.value.assert_f64()?; },
+                    33 => { dim.chord_point.z = pair.value.assert_f64()?; },
+                    14 => { dim.override_center.x = pair.value.assert_f64()?; },
+                    24 => { dim.override_center.y = pair.value.assert_f64()?; },
+                    34 => { dim.override_center.z = pair.value.assert_f64()?; },
+                    15 => { dim.jog_point.x = pair.value.assert_f64()?; },
+                    25 => { dim.jog_point.y = pair.value.assert_f64()?; },
+                    35 => { dim.jog_point.z = pair.value.assert_f64()?; },
+                    40 => { dim.jog_angle = pair.value.assert_f64()?; },
+                    _ => { return Ok(false); },
+                }
+            },
             _ => { return Err(DxfError::UnexpectedEnumValue); },
         }
         Ok(true)
@@ -273,6 +372,39 @@ impl EntityCommon {
     pub fn normalize(&mut self) {
         default_if_empty(&mut self.layer, "0");
     }
+    /// Applies the common pairs of an entity and returns the pairs that follow the specified subclass marker.
+    /// Entities whose nested data (e.g., table cells, hatch boundary paths, or mesh faces) reuse codes
+    /// collect their subclass with this so it can be read as a whole.
+    fn read_subclass_pairs<I>(&mut self, subclass: &str, iter: &mut PutBack<I>) -> DxfResult<Vec<CodePair>>
+        where I: Iterator<Item = DxfResult<CodePair>> {
+
+        let mut subclass_pairs = vec![];
+        let mut is_in_subclass = false;
+        loop {
+            match iter.next() {
+                Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                    // new entity or ENDSEC
+                    iter.put_back(Ok(pair));
+                    break;
+                },
+                Some(Ok(pair)) => {
+                    if pair.code == 100 && pair.value.assert_string()? == subclass {
+                        is_in_subclass = true;
+                    }
+                    else if is_in_subclass && pair.code != extension_data::EXTENSION_DATA_GROUP && pair.code != x_data::XDATA_APPLICATIONNAME {
+                        subclass_pairs.push(pair);
+                    }
+                    else {
+                        self.apply_individual_pair(&pair, iter)?;
+                    }
+                },
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            }
+        }
+
+        Ok(subclass_pairs)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -305,7 +437,7 @@ impl Entity {
                     }
 
                     match &*type_string {
-                        "DIMENSION" => {
+                        "DIMENSION" | "ARC_DIMENSION" | "LARGE_RADIAL_DIMENSION" => {
                             // dimensions require special handling
                             let mut common = EntityCommon::default();
                             let mut dimension_entity: Option<EntityType> = None;
@@ -339,7 +471,7 @@ impl Entity {
                                                     42 => { dimension_base.actual_measurement = pair.value.assert_f64()?; },
                                                     51 => { dimension_base.horizontal_direction_angle = pair.value.assert_f64()?; },
                                                     53 => { dimension_base.text_rotation_angle = pair.value.assert_f64()?; },
-                                                    70 => { dimension_base.set_dimension_type(pair.value.assert_i16()?)?; },
+                                                    70 => { dimension_base.set_dimension_type(pair.value.assert_i16()?); },
                                                     71 => { dimension_base.attachment_point = try_result!(AttachmentPoint::from_i16(pair.value.assert_i16()?)); },
                                                     72 => { dimension_base.text_line_spacing_style = try_result!(TextLineSpacingStyle::from_i16(pair.value.assert_i16()?)); },
                                                     210 => { dimension_base.normal.x = pair.value.assert_f64()?; },
@@ -353,6 +485,9 @@ impl Entity {
                                                             "AcDbDiametricDimension" => { dimension_entity = Some(EntityType::DiameterDimension(DiameterDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
                                                             "AcDb3PointAngularDimension" => { dimension_entity = Some(EntityType::AngularThreePointDimension(AngularThreePointDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
                                                             "AcDbOrdinateDimension" => { dimension_entity = Some(EntityType::OrdinateDimension(OrdinateDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
+                                                            "AcDb2LineAngularDimension" => { dimension_entity = Some(EntityType::AngularTwoLineDimension(AngularTwoLineDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
+                                                            "AcDbArcDimension" => { dimension_entity = Some(EntityType::ArcDimension(ArcDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
+                                                            "AcDbRadialDimensionLarge" => { dimension_entity = Some(EntityType::LargeRadialDimension(LargeRadialDimension { dimension_base: dimension_base.clone(), .. Default::default() })); },
                                                             _ => {}, // unexpected dimension type
                                                         }
                                                     },
@@ -470,6 +605,11 @@ impl Entity {
         where I: Iterator<Item = DxfResult<CodePair>> {
 
         match self.specific {
+            EntityType::AcadTable(ref mut table) => {
+                let table_pairs = self.common.read_subclass_pairs("AcDbBlockReference", iter)?;
+                table.read_code_pairs(&table_pairs)?;
+                Ok(true)
+            },
             EntityType::Attribute(ref mut att) => {
                 let xrecord_text = "AcDbXrecord";
                 let mut last_subclass_marker = String::new();
@@ -665,6 +805,77 @@ impl Entity {
                     }
                 }
             },
+            EntityType::Hatch(ref mut hatch) => {
+                let hatch_pairs = self.common.read_subclass_pairs("AcDbHatch", iter)?;
+                hatch.read_code_pairs(&hatch_pairs)?;
+                Ok(true)
+            },
+            EntityType::MLeader(ref mut mleader) => {
+                let mleader_pairs = self.common.read_subclass_pairs("AcDbMLeader", iter)?;
+                mleader.read_code_pairs(&mleader_pairs)?;
+                Ok(true)
+            },
+            EntityType::Mesh(ref mut mesh) => {
+                let mesh_pairs = self.common.read_subclass_pairs("AcDbSubDMesh", iter)?;
+                mesh.read_code_pairs(&mesh_pairs)?;
+                Ok(true)
+            },
+            EntityType::Body(ref mut body) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    match pair.code {
+                        70 => { body.format_version_number = pair.value.assert_i16()?; },
+                        1 | 3 => { read_modeler_data(&pair, &mut body.custom_data, &mut body.custom_data2)?; },
+                        _ => { self.common.apply_individual_pair(&pair, iter)?; },
+                    }
+                }
+            },
+            EntityType::Region(ref mut region) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    match pair.code {
+                        70 => { region.format_version_number = pair.value.assert_i16()?; },
+                        1 | 3 => { read_modeler_data(&pair, &mut region.custom_data, &mut region.custom_data2)?; },
+                        _ => { self.common.apply_individual_pair(&pair, iter)?; },
+                    }
+                }
+            },
+            EntityType::Solid3D(ref mut solid) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    match pair.code {
+                        70 => { solid.format_version_number = pair.value.assert_i16()?; },
+                        1 | 3 => { read_modeler_data(&pair, &mut solid.custom_data, &mut solid.custom_data2)?; },
+                        350 => { solid.__history_object_handle = as_u32(pair.value.assert_string()?)?; },
+                        _ => { self.common.apply_individual_pair(&pair, iter)?; },
+                    }
+                }
+            },
+            EntityType::ExtrudedSurface(ref mut surface) => {
+                let surface_pairs = self.common.read_subclass_pairs("AcDbModelerGeometry", iter)?;
+                surface.read_code_pairs(&surface_pairs)?;
+                Ok(true)
+            },
+            EntityType::LoftedSurface(ref mut surface) => {
+                let surface_pairs = self.common.read_subclass_pairs("AcDbModelerGeometry", iter)?;
+                surface.read_code_pairs(&surface_pairs)?;
+                Ok(true)
+            },
+            EntityType::PlaneSurface(ref mut surface) => {
+                let surface_pairs = self.common.read_subclass_pairs("AcDbModelerGeometry", iter)?;
+                surface.read_code_pairs(&surface_pairs)?;
+                Ok(true)
+            },
+            EntityType::RevolvedSurface(ref mut surface) => {
+                let surface_pairs = self.common.read_subclass_pairs("AcDbModelerGeometry", iter)?;
+                surface.read_code_pairs(&surface_pairs)?;
+                Ok(true)
+            },
+            EntityType::SweptSurface(ref mut surface) => {
+                let surface_pairs = self.common.read_subclass_pairs("AcDbModelerGeometry", iter)?;
+                surface.read_code_pairs(&surface_pairs)?;
+                Ok(true)
+            },
             EntityType::LwPolyline(ref mut poly) => {
                 loop {
                     let pair = next_pair!(iter);
@@ -826,6 +1037,42 @@ impl Entity {
                 writer.write_code_pair(&CodePair::new_f64(26, dim.definition_point_5.y))?;
                 writer.write_code_pair(&CodePair::new_f64(36, dim.definition_point_5.z))?;
             },
+            EntityType::AcadTable(ref table) => {
+                table.write(writer)?;
+            },
+            EntityType::Hatch(ref hatch) => {
+                hatch.write(version, writer)?;
+            },
+            EntityType::MLeader(ref mleader) => {
+                mleader.write(version, writer)?;
+            },
+            EntityType::Mesh(ref mesh) => {
+                mesh.write(writer)?;
+            },
+            EntityType::Body(ref body) => {
+                body.write(writer)?;
+            },
+            EntityType::Region(ref region) => {
+                region.write(writer)?;
+            },
+            EntityType::Solid3D(ref solid) => {
+                solid.write(version, writer)?;
+            },
+            EntityType::ExtrudedSurface(ref surface) => {
+                surface.write(writer)?;
+            },
+            EntityType::LoftedSurface(ref surface) => {
+                surface.write(writer)?;
+            },
+            EntityType::PlaneSurface(ref surface) => {
+                surface.write(writer)?;
+            },
+            EntityType::RevolvedSurface(ref surface) => {
+                surface.write(writer)?;
+            },
+            EntityType::SweptSurface(ref surface) => {
+                surface.write(writer)?;
+            },
             EntityType::OrdinateDimension(ref dim) => {
                 dim.dimension_base.write(version, writer)?;
                 writer.write_code_pair(&CodePair::new_str(100, "AcDbOrdinateDimension"))?;
@@ -836,6 +1083,59 @@ impl Entity {
                 writer.write_code_pair(&CodePair::new_f64(24, dim.definition_point_3.y))?;
                 writer.write_code_pair(&CodePair::new_f64(34, dim.definition_point_3.z))?;
             },
+            EntityType::AngularTwoLineDimension(ref dim) => {
+                dim.dimension_base.write(version, writer)?;
+                writer.write_code_pair(&CodePair::new_str(100, "AcDb2LineAngularDimension"))?;
+                writer.write_code_pair(&CodePair::new_f64(13, dim.definition_point_2.x))?;
+                writer.write_code_pair(&CodePair::new_f64(23, dim.definition_point_2.y))?;
+                writer.write_code_pair(&CodePair::new_f64(33, dim.definition_point_2.z))?;
+                writer.write_code_pair(&CodePair::new_f64(14, dim.definition_point_3.x))?;
+                writer.write_code_pair(&CodePair::new_f64(24, dim.definition_point_3.y))?;
+                writer.write_code_pair(&CodePair::new_f64(34, dim.definition_point_3.z))?;
+                writer.write_code_pair(&CodePair::new_f64(15, dim.definition_point_4.x))?;
+                writer.write_code_pair(&CodePair::new_f64(25, dim.definition_point_4.y))?;
+                writer.write_code_pair(&CodePair::new_f64(35, dim.definition_point_4.z))?;
+                writer.write_code_pair(&CodePair::new_f64(16, dim.arc_definition_point.x))?;
+                writer.write_code_pair(&CodePair::new_f64(26, dim.arc_definition_point.y))?;
+                writer.write_code_pair(&CodePair::new_f64(36, dim.arc_definition_point.z))?;
+            },
+            EntityType::ArcDimension(ref dim) => {
+                dim.dimension_base.write(version, writer)?;
+                writer.write_code_pair(&CodePair::new_str(100, "AcDbArcDimension"))?;
+                writer.write_code_pair(&CodePair::new_f64(13, dim.definition_point_2.x))?;
+                writer.write_code_pair(&CodePair::new_f64(23, dim.definition_point_2.y))?;
+                writer.write_code_pair(&CodePair::new_f64(33, dim.definition_point_2.z))?;
+                writer.write_code_pair(&CodePair::new_f64(14, dim.definition_point_3.x))?;
+                writer.write_code_pair(&CodePair::new_f64(24, dim.definition_point_3.y))?;
+                writer.write_code_pair(&CodePair::new_f64(34, dim.definition_point_3.z))?;
+                writer.write_code_pair(&CodePair::new_f64(15, dim.arc_center.x))?;
+                writer.write_code_pair(&CodePair::new_f64(25, dim.arc_center.y))?;
+                writer.write_code_pair(&CodePair::new_f64(35, dim.arc_center.z))?;
+                writer.write_code_pair(&CodePair::new_f64(40, dim.start_angle))?;
+                writer.write_code_pair(&CodePair::new_f64(41, dim.end_angle))?;
+                writer.write_code_pair(&CodePair::new_i16(70, as_i16(dim.is_partial)))?;
+                writer.write_code_pair(&CodePair::new_i16(71, as_i16(dim.has_leader)))?;
+                writer.write_code_pair(&CodePair::new_f64(16, dim.leader_point_1.x))?;
+                writer.write_code_pair(&CodePair::new_f64(26, dim.leader_point_1.y))?;
+                writer.write_code_pair(&CodePair::new_f64(36, dim.leader_point_1.z))?;
+                writer.write_code_pair(&CodePair::new_f64(17, dim.leader_point_2.x))?;
+                writer.write_code_pair(&CodePair::new_f64(27, dim.leader_point_2.y))?;
+                writer.write_code_pair(&CodePair::new_f64(37, dim.leader_point_2.z))?;
+            },
+            EntityType::LargeRadialDimension(ref dim) => {
+                dim.dimension_base.write(version, writer)?;
+                writer.write_code_pair(&CodePair::new_str(100, "AcDbRadialDimensionLarge"))?;
+                writer.write_code_pair(&CodePair::new_f64(13, dim.chord_point.x))?;
+                writer.write_code_pair(&CodePair::new_f64(23, dim.chord_point.y))?;
+                writer.write_code_pair(&CodePair::new_f64(33, dim.chord_point.z))?;
+                writer.write_code_pair(&CodePair::new_f64(14, dim.override_center.x))?;
+                writer.write_code_pair(&CodePair::new_f64(24, dim.override_center.y))?;
+                writer.write_code_pair(&CodePair::new_f64(34, dim.override_center.z))?;
+                writer.write_code_pair(&CodePair::new_f64(15, dim.jog_point.x))?;
+                writer.write_code_pair(&CodePair::new_f64(25, dim.jog_point.y))?;
+                writer.write_code_pair(&CodePair::new_f64(35, dim.jog_point.z))?;
+                writer.write_code_pair(&CodePair::new_f64(40, dim.jog_angle))?;
+            },
             _ => return Ok(false), // no custom writer
         }
 
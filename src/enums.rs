@@ -310,6 +310,8 @@ pub enum DimensionType {
     Radius = 4,
     AngularThreePoint = 5,
     Ordinate = 6,
+    ArcLength = 8,
+    JoggedRadius = 9,
 }
 }
 
@@ -430,6 +432,24 @@ pub enum GeoDataVersion
 }
 }
 
+enum_from_primitive! {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HatchPatternType {
+    UserDefined = 0,
+    Predefined = 1,
+    Custom = 2,
+}
+}
+
+enum_from_primitive! {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HatchStyle {
+    OddParity = 0,
+    Outermost = 1,
+    EntireArea = 2,
+}
+}
+
 enum_from_primitive! {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum HelixConstraint {
@@ -973,6 +993,16 @@ pub enum SubentityType
 }
 }
 
+enum_from_primitive! {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SweepAlignment {
+    NoAlignment = 0,
+    AlignSweepEntityToPath = 1,
+    TranslateSweepEntityToPath = 2,
+    TranslatePathToSweepEntity = 3,
+}
+}
+
 enum_from_primitive! {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TextAttachmentDirection {
@@ -1277,5 +1307,8 @@ pub enum UnitZeroSuppression {
     IncludeZeroFeetAndZeroInches = 1,
     IncludeZeroFeetAndSuppressZeroInches = 2,
     IncludeZeroInchesAndSuppressZeroFeet = 3,
+    SuppressLeadingZeros = 4,
+    SuppressTrailingZeros = 8,
+    SuppressLeadingAndTrailingZeros = 12,
 }
 }
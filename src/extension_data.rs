@@ -84,3 +84,18 @@ impl ExtensionGroup {
         Ok(())
     }
 }
+
+pub(crate) const EXTENSION_DICTIONARY_GROUP: &str = "ACAD_XDICTIONARY";
+
+// Returns the handle of the extension dictionary referenced by a `{ACAD_XDICTIONARY` group.
+pub(crate) fn get_extension_dictionary_handle(groups: &[ExtensionGroup]) -> Option<u32> {
+    groups.iter()
+        .filter(|g| g.application_name == EXTENSION_DICTIONARY_GROUP)
+        .flat_map(|g| g.items.iter())
+        .filter_map(|item| match *item {
+            ExtensionGroupItem::CodePair(ref pair) if pair.code == 360 => pair.value.assert_string().ok(),
+            _ => None,
+        })
+        .filter_map(|handle| u32::from_str_radix(&handle, 16).ok())
+        .next()
+}
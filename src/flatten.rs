@@ -0,0 +1,159 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `drawing.rs`
+
+use ::{
+    Drawing,
+    LineWeight,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+
+// Creates a `Text` entity that displays the value of an attribute or attribute definition.
+macro_rules! attribute_as_text {
+    ($att:expr, $value:expr, $common:expr) => {{
+        let text = Text {
+            thickness: $att.thickness,
+            location: $att.location,
+            text_height: $att.text_height,
+            value: $value.clone(),
+            rotation: $att.rotation,
+            relative_x_scale_factor: $att.relative_x_scale_factor,
+            oblique_angle: $att.oblique_angle,
+            text_style_name: $att.text_style_name.clone(),
+            text_generation_flags: $att.text_generation_flags,
+            horizontal_text_justification: $att.horizontal_text_justification,
+            second_alignment_point: $att.second_alignment_point,
+            normal: $att.normal,
+            vertical_text_justification: $att.vertical_text_justification,
+        };
+        Entity {
+            common: $common.clone(),
+            specific: EntityType::Text(text),
+        }
+    }};
+}
+
+impl Insert {
+    /// Returns the transformation from the coordinates of a block with the specified base point to
+    /// world coordinates for the copy at `row` and `column` of the (MINSERT) array.  The first copy is
+    /// at row 0 and column 0.
+    pub fn block_transformation(&self, base_point: &Point, row: i16, column: i16) -> TransformationMatrix {
+        let array_offset = Vector::new(self.column_spacing * column as f64, self.row_spacing * row as f64, 0.0);
+        TransformationMatrix::from_ocs(&self.extrusion_direction)
+            .multiply(&TransformationMatrix::translation(&self.location.as_vector()))
+            .multiply(&TransformationMatrix::rotation_about_z(self.rotation.to_radians()))
+            .multiply(&TransformationMatrix::translation(&array_offset))
+            .multiply(&TransformationMatrix::scale(self.x_scale_factor, self.y_scale_factor, self.z_scale_factor))
+            .multiply(&TransformationMatrix::translation(&-base_point.as_vector()))
+    }
+}
+
+impl Drawing {
+    /// Returns the entities displayed by an `Insert` in world coordinates.  Nested inserts are
+    /// exploded recursively, every copy of a MINSERT array is produced, and visible attributes (and
+    /// constant attribute definitions in the block) become `Text` entities.  BYBLOCK colors, line
+    /// types, and line weights, and entities on layer "0" take their values from the insert.  A block
+    /// that directly or indirectly references itself is only expanded once, inserts of missing blocks
+    /// produce only their attributes, and any other entity is returned unchanged.  The new entities
+    /// don't have a handle so one will be assigned when the drawing is saved.
+    pub fn explode_insert(&self, entity: &Entity) -> Vec<Entity> {
+        let mut result = vec![];
+        let mut block_stack = vec![];
+        self.explode_insert_into(entity, None, &mut block_stack, &mut result);
+        result
+    }
+    /// Returns all entities in the drawing with every `Insert` replaced by the entities it displays;
+    /// see `explode_insert`.
+    pub fn flattened_entities(&self) -> Vec<Entity> {
+        self.entities.iter().flat_map(|e| self.explode_insert(e)).collect()
+    }
+    fn explode_insert_into(&self, entity: &Entity, matrix: Option<&TransformationMatrix>, block_stack: &mut Vec<String>, result: &mut Vec<Entity>) {
+        let transform = |e: &mut Entity| if let Some(matrix) = matrix {
+            e.transform(matrix);
+        };
+        let insert = match entity.specific {
+            EntityType::Insert(ref insert) => insert,
+            _ => {
+                let mut e = entity.clone();
+                transform(&mut e);
+                result.push(e);
+                return;
+            },
+        };
+
+        // attributes are already positioned in the same coordinate system as the insert
+        for att in insert.attributes.iter().filter(|a| !a.get_is_invisible()) {
+            let mut common = entity.common.clone();
+            common.handle = 0;
+            let mut text = attribute_as_text!(att, att.value, common);
+            transform(&mut text);
+            result.push(text);
+        }
+
+        let block = match self.blocks.iter().find(|b| b.name.eq_ignore_ascii_case(&insert.name)) {
+            Some(block) => block,
+            None => return,
+        };
+        let key = block.name.to_lowercase();
+        if block_stack.contains(&key) {
+            return;
+        }
+
+        block_stack.push(key);
+        for row in 0..insert.row_count.max(1) {
+            for column in 0..insert.column_count.max(1) {
+                let block_matrix = insert.block_transformation(&block.base_point, row, column);
+                let block_matrix = match matrix {
+                    Some(matrix) => matrix.multiply(&block_matrix),
+                    None => block_matrix,
+                };
+                for child in &block.entities {
+                    let mut common = child.common.clone();
+                    resolve_by_block(&mut common, &entity.common);
+                    let child = match child.specific {
+                        EntityType::AttributeDefinition(ref def) => {
+                            // only constant attribute definitions are displayed; the others are replaced by the insert's attributes
+                            if !def.get_is_constant() || def.get_is_invisible() {
+                                continue;
+                            }
+                            attribute_as_text!(def, def.value, common)
+                        },
+                        _ => Entity { common, specific: child.specific.clone() },
+                    };
+                    self.explode_insert_into(&child, Some(&block_matrix), block_stack, result);
+                }
+            }
+        }
+
+        block_stack.pop();
+    }
+}
+
+// Resolves BYBLOCK values of an entity in a block against the inserting entity.
+fn resolve_by_block(common: &mut EntityCommon, insert: &EntityCommon) {
+    if common.color.is_by_block() {
+        common.color = insert.color.clone();
+        common.color_24_bit = insert.color_24_bit;
+        common.color_name = insert.color_name.clone();
+    }
+
+    if common.line_type_name.eq_ignore_ascii_case("BYBLOCK") {
+        common.line_type_name = insert.line_type_name.clone();
+    }
+
+    if common.lineweight_enum_value == LineWeight::by_block().get_raw_value() {
+        common.lineweight_enum_value = insert.lineweight_enum_value;
+    }
+
+    if common.layer == "0" {
+        common.layer = insert.layer.clone();
+    }
+
+    common.handle = 0;
+    common.__owner_handle = insert.__owner_handle;
+    common.is_in_paper_space = insert.is_in_paper_space;
+}
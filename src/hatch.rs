@@ -0,0 +1,574 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::io::Write;
+use enum_primitive::FromPrimitive;
+
+use ::{
+    CodePair,
+    Color,
+    Drawing,
+    DrawingItem,
+    DxfError,
+    DxfResult,
+    Point,
+    Vector,
+};
+
+use code_pair_cursor::CodePairCursor;
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use enums::*;
+use helper_functions::*;
+
+/// A vertex of a polyline `Hatch` boundary path.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HatchPolylineVertex {
+    pub location: Point,
+    pub bulge: f64,
+}
+
+/// An edge of a `Hatch` boundary path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HatchBoundaryEdge {
+    Line {
+        start: Point,
+        end: Point,
+    },
+    CircularArc {
+        center: Point,
+        radius: f64,
+        /// The start angle in degrees.
+        start_angle: f64,
+        /// The end angle in degrees.
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    EllipticArc {
+        center: Point,
+        /// The endpoint of the major axis relative to the center.
+        major_axis: Vector,
+        minor_axis_ratio: f64,
+        /// The start angle in degrees.
+        start_angle: f64,
+        /// The end angle in degrees.
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    Spline {
+        degree: i32,
+        is_rational: bool,
+        is_periodic: bool,
+        knot_values: Vec<f64>,
+        control_points: Vec<Point>,
+        /// The control point weights of a rational spline.
+        weights: Vec<f64>,
+        /// The fit points; only written on R2010 and later.
+        fit_points: Vec<Point>,
+        start_tangent: Vector,
+        end_tangent: Vector,
+    },
+}
+
+/// The geometry of a `Hatch` boundary path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HatchBoundaryPathGeometry {
+    Polyline {
+        is_closed: bool,
+        vertices: Vec<HatchPolylineVertex>,
+    },
+    Edges(Vec<HatchBoundaryEdge>),
+}
+
+/// A boundary path of a `Hatch`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HatchBoundaryPath {
+    /// The boundary path type flags.  The polyline flag is written from `geometry`.
+    pub flags: i32,
+    pub geometry: HatchBoundaryPathGeometry,
+    /// The handles of the entities the boundary was created from.
+    pub source_boundary_handles: Vec<u32>,
+}
+
+impl Default for HatchBoundaryPath {
+    fn default() -> Self {
+        HatchBoundaryPath {
+            flags: 0,
+            geometry: HatchBoundaryPathGeometry::Edges(vec![]),
+            source_boundary_handles: vec![],
+        }
+    }
+}
+
+/// A line family of a `Hatch` pattern.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HatchPatternDefinitionLine {
+    /// The line angle in degrees.
+    pub angle: f64,
+    pub base_point: Point,
+    /// The offset between successive lines of the family.
+    pub offset: Vector,
+    /// The lengths of the dashes; positive values are drawn and negative values are gaps.
+    pub dash_lengths: Vec<f64>,
+}
+
+/// A color of a `Hatch` gradient fill.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HatchGradientColor {
+    pub value: f64,
+    pub color: Color,
+    /// The 24-bit color value, or 0 when not set.
+    pub true_color: i32,
+}
+
+impl Default for HatchGradientColor {
+    fn default() -> Self {
+        HatchGradientColor {
+            value: 0.0,
+            color: Color::by_layer(),
+            true_color: 0,
+        }
+    }
+}
+
+const POLYLINE_PATH_FLAG: i32 = 2;
+
+impl HatchBoundaryPath {
+    pub fn new(geometry: HatchBoundaryPathGeometry) -> Self {
+        HatchBoundaryPath {
+            geometry,
+            .. Default::default()
+        }
+    }
+    pub fn get_is_external(&self) -> bool {
+        self.flags & 1 != 0
+    }
+    pub fn set_is_external(&mut self, val: bool) {
+        self.set_flag(1, val);
+    }
+    pub fn get_is_derived(&self) -> bool {
+        self.flags & 4 != 0
+    }
+    pub fn set_is_derived(&mut self, val: bool) {
+        self.set_flag(4, val);
+    }
+    pub fn get_is_text_box(&self) -> bool {
+        self.flags & 8 != 0
+    }
+    pub fn set_is_text_box(&mut self, val: bool) {
+        self.set_flag(8, val);
+    }
+    pub fn get_is_outermost(&self) -> bool {
+        self.flags & 16 != 0
+    }
+    pub fn set_is_outermost(&mut self, val: bool) {
+        self.set_flag(16, val);
+    }
+    fn set_flag(&mut self, mask: i32, val: bool) {
+        if val {
+            self.flags |= mask;
+        }
+        else {
+            self.flags &= !mask;
+        }
+    }
+}
+
+impl Hatch {
+    /// Returns the entities that the boundary paths were created from.
+    pub fn get_source_boundary_entities<'a>(&self, drawing: &'a Drawing) -> Vec<&'a Entity> {
+        self.boundary_paths.iter()
+            .flat_map(|p| p.source_boundary_handles.iter())
+            .filter_map(|&h| match drawing.get_item_by_handle(h) {
+                Some(DrawingItem::Entity(e)) => Some(e),
+                _ => None,
+            })
+            .collect()
+    }
+    pub(crate) fn read_code_pairs(&mut self, pairs: &[CodePair]) -> DxfResult<()> {
+        let mut reader = CodePairCursor::new(pairs);
+        while let Some(pair) = reader.next() {
+            match pair.code {
+                10 => { self.elevation_point.x = pair.value.assert_f64()?; },
+                20 => { self.elevation_point.y = pair.value.assert_f64()?; },
+                30 => { self.elevation_point.z = pair.value.assert_f64()?; },
+                210 => { self.extrusion_direction.x = pair.value.assert_f64()?; },
+                220 => { self.extrusion_direction.y = pair.value.assert_f64()?; },
+                230 => { self.extrusion_direction.z = pair.value.assert_f64()?; },
+                2 => { self.pattern_name = pair.value.assert_string()?; },
+                70 => { self.is_solid_fill = as_bool(pair.value.assert_i16()?); },
+                71 => { self.is_associative = as_bool(pair.value.assert_i16()?); },
+                91 => {
+                    for _ in 0..pair.value.assert_i32()? {
+                        let path = reader.read_boundary_path()?;
+                        self.boundary_paths.push(path);
+                    }
+                },
+                75 => { self.hatch_style = try_result!(HatchStyle::from_i16(pair.value.assert_i16()?)); },
+                76 => { self.pattern_type = try_result!(HatchPatternType::from_i16(pair.value.assert_i16()?)); },
+                52 => { self.pattern_angle = pair.value.assert_f64()?; },
+                41 => { self.pattern_scale = pair.value.assert_f64()?; },
+                77 => { self.is_pattern_double = as_bool(pair.value.assert_i16()?); },
+                78 => {
+                    for _ in 0..pair.value.assert_i16()? {
+                        let line = reader.read_pattern_definition_line()?;
+                        self.pattern_definition_lines.push(line);
+                    }
+                },
+                47 => { self.pixel_size = pair.value.assert_f64()?; },
+                98 => {
+                    for _ in 0..pair.value.assert_i32()? {
+                        let point = reader.read_point(10)?;
+                        self.seed_points.push(point);
+                    }
+                },
+                450 => { self.is_gradient = pair.value.assert_i64()? != 0; },
+                452 => { self.is_single_color_gradient = pair.value.assert_i64()? != 0; },
+                453 => {
+                    for _ in 0..pair.value.assert_i64()? {
+                        let color = reader.read_gradient_color()?;
+                        self.gradient_colors.push(color);
+                    }
+                },
+                460 => { self.gradient_angle = pair.value.assert_f64()?; },
+                461 => { self.gradient_shift = pair.value.assert_f64()?; },
+                462 => { self.gradient_tint = pair.value.assert_f64()?; },
+                470 => { self.gradient_name = pair.value.assert_string()?; },
+                _ => (), // unknown or reserved code, just ignore
+            }
+        }
+
+        Ok(())
+    }
+    pub(crate) fn write<T>(&self, version: &AcadVersion, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbHatch"))?;
+        write_point(writer, 10, &self.elevation_point, true)?;
+        writer.write_code_pair(&CodePair::new_f64(210, self.extrusion_direction.x))?;
+        writer.write_code_pair(&CodePair::new_f64(220, self.extrusion_direction.y))?;
+        writer.write_code_pair(&CodePair::new_f64(230, self.extrusion_direction.z))?;
+        writer.write_code_pair(&CodePair::new_string(2, &self.pattern_name))?;
+        writer.write_code_pair(&CodePair::new_i16(70, as_i16(self.is_solid_fill)))?;
+        writer.write_code_pair(&CodePair::new_i16(71, as_i16(self.is_associative)))?;
+        writer.write_code_pair(&CodePair::new_i32(91, self.boundary_paths.len() as i32))?;
+        for path in &self.boundary_paths {
+            write_boundary_path(writer, version, path)?;
+        }
+
+        writer.write_code_pair(&CodePair::new_i16(75, self.hatch_style as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(76, self.pattern_type as i16))?;
+        if !self.is_solid_fill {
+            writer.write_code_pair(&CodePair::new_f64(52, self.pattern_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(41, self.pattern_scale))?;
+            writer.write_code_pair(&CodePair::new_i16(77, as_i16(self.is_pattern_double)))?;
+            writer.write_code_pair(&CodePair::new_i16(78, self.pattern_definition_lines.len() as i16))?;
+            for line in &self.pattern_definition_lines {
+                writer.write_code_pair(&CodePair::new_f64(53, line.angle))?;
+                writer.write_code_pair(&CodePair::new_f64(43, line.base_point.x))?;
+                writer.write_code_pair(&CodePair::new_f64(44, line.base_point.y))?;
+                writer.write_code_pair(&CodePair::new_f64(45, line.offset.x))?;
+                writer.write_code_pair(&CodePair::new_f64(46, line.offset.y))?;
+                writer.write_code_pair(&CodePair::new_i16(79, line.dash_lengths.len() as i16))?;
+                for dash_length in &line.dash_lengths {
+                    writer.write_code_pair(&CodePair::new_f64(49, *dash_length))?;
+                }
+            }
+        }
+
+        if self.pixel_size != 0.0 {
+            writer.write_code_pair(&CodePair::new_f64(47, self.pixel_size))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_i32(98, self.seed_points.len() as i32))?;
+        for seed_point in &self.seed_points {
+            write_point(writer, 10, seed_point, false)?;
+        }
+
+        if *version >= AcadVersion::R2004 {
+            writer.write_code_pair(&CodePair::new_i64(450, if self.is_gradient { 1 } else { 0 }))?;
+            writer.write_code_pair(&CodePair::new_i64(451, 0))?;
+            writer.write_code_pair(&CodePair::new_f64(460, self.gradient_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(461, self.gradient_shift))?;
+            writer.write_code_pair(&CodePair::new_i64(452, if self.is_single_color_gradient { 1 } else { 0 }))?;
+            writer.write_code_pair(&CodePair::new_f64(462, self.gradient_tint))?;
+            writer.write_code_pair(&CodePair::new_i64(453, self.gradient_colors.len() as i64))?;
+            for color in &self.gradient_colors {
+                writer.write_code_pair(&CodePair::new_f64(463, color.value))?;
+                writer.write_code_pair(&CodePair::new_i16(63, color.color.get_raw_value()))?;
+                if color.true_color != 0 {
+                    writer.write_code_pair(&CodePair::new_i32(421, color.true_color))?;
+                }
+            }
+
+            writer.write_code_pair(&CodePair::new_string(470, &self.gradient_name))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_point<T>(writer: &mut CodePairWriter<T>, code: i32, point: &Point, write_z: bool) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_f64(code, point.x))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 10, point.y))?;
+    if write_z {
+        writer.write_code_pair(&CodePair::new_f64(code + 20, point.z))?;
+    }
+
+    Ok(())
+}
+
+fn write_boundary_path<T>(writer: &mut CodePairWriter<T>, version: &AcadVersion, path: &HatchBoundaryPath) -> DxfResult<()>
+    where T: Write {
+
+    match path.geometry {
+        HatchBoundaryPathGeometry::Polyline { is_closed, ref vertices } => {
+            let has_bulge = vertices.iter().any(|v| v.bulge != 0.0);
+            writer.write_code_pair(&CodePair::new_i32(92, path.flags | POLYLINE_PATH_FLAG))?;
+            writer.write_code_pair(&CodePair::new_i16(72, as_i16(has_bulge)))?;
+            writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_closed)))?;
+            writer.write_code_pair(&CodePair::new_i32(93, vertices.len() as i32))?;
+            for vertex in vertices {
+                write_point(writer, 10, &vertex.location, false)?;
+                if has_bulge {
+                    writer.write_code_pair(&CodePair::new_f64(42, vertex.bulge))?;
+                }
+            }
+        },
+        HatchBoundaryPathGeometry::Edges(ref edges) => {
+            writer.write_code_pair(&CodePair::new_i32(92, path.flags & !POLYLINE_PATH_FLAG))?;
+            writer.write_code_pair(&CodePair::new_i32(93, edges.len() as i32))?;
+            for edge in edges {
+                write_boundary_edge(writer, version, edge)?;
+            }
+        },
+    }
+
+    writer.write_code_pair(&CodePair::new_i32(97, path.source_boundary_handles.len() as i32))?;
+    for handle in &path.source_boundary_handles {
+        writer.write_code_pair(&CodePair::new_string(330, &as_handle(*handle)))?;
+    }
+
+    Ok(())
+}
+
+fn write_boundary_edge<T>(writer: &mut CodePairWriter<T>, version: &AcadVersion, edge: &HatchBoundaryEdge) -> DxfResult<()>
+    where T: Write {
+
+    match *edge {
+        HatchBoundaryEdge::Line { ref start, ref end } => {
+            writer.write_code_pair(&CodePair::new_i16(72, 1))?;
+            write_point(writer, 10, start, false)?;
+            write_point(writer, 11, end, false)?;
+        },
+        HatchBoundaryEdge::CircularArc { ref center, radius, start_angle, end_angle, is_counter_clockwise } => {
+            writer.write_code_pair(&CodePair::new_i16(72, 2))?;
+            write_point(writer, 10, center, false)?;
+            writer.write_code_pair(&CodePair::new_f64(40, radius))?;
+            writer.write_code_pair(&CodePair::new_f64(50, start_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(51, end_angle))?;
+            writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_counter_clockwise)))?;
+        },
+        HatchBoundaryEdge::EllipticArc { ref center, ref major_axis, minor_axis_ratio, start_angle, end_angle, is_counter_clockwise } => {
+            writer.write_code_pair(&CodePair::new_i16(72, 3))?;
+            write_point(writer, 10, center, false)?;
+            writer.write_code_pair(&CodePair::new_f64(11, major_axis.x))?;
+            writer.write_code_pair(&CodePair::new_f64(21, major_axis.y))?;
+            writer.write_code_pair(&CodePair::new_f64(40, minor_axis_ratio))?;
+            writer.write_code_pair(&CodePair::new_f64(50, start_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(51, end_angle))?;
+            writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_counter_clockwise)))?;
+        },
+        HatchBoundaryEdge::Spline { degree, is_rational, is_periodic, ref knot_values, ref control_points, ref weights, ref fit_points, ref start_tangent, ref end_tangent } => {
+            writer.write_code_pair(&CodePair::new_i16(72, 4))?;
+            writer.write_code_pair(&CodePair::new_i32(94, degree))?;
+            writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_rational)))?;
+            writer.write_code_pair(&CodePair::new_i16(74, as_i16(is_periodic)))?;
+            writer.write_code_pair(&CodePair::new_i32(95, knot_values.len() as i32))?;
+            writer.write_code_pair(&CodePair::new_i32(96, control_points.len() as i32))?;
+            for knot_value in knot_values {
+                writer.write_code_pair(&CodePair::new_f64(40, *knot_value))?;
+            }
+
+            for (i, control_point) in control_points.iter().enumerate() {
+                write_point(writer, 10, control_point, false)?;
+                if is_rational {
+                    writer.write_code_pair(&CodePair::new_f64(42, weights.get(i).cloned().unwrap_or(1.0)))?;
+                }
+            }
+
+            if *version >= AcadVersion::R2010 {
+                writer.write_code_pair(&CodePair::new_i32(97, fit_points.len() as i32))?;
+                for fit_point in fit_points {
+                    write_point(writer, 11, fit_point, false)?;
+                }
+
+                writer.write_code_pair(&CodePair::new_f64(12, start_tangent.x))?;
+                writer.write_code_pair(&CodePair::new_f64(22, start_tangent.y))?;
+                writer.write_code_pair(&CodePair::new_f64(13, end_tangent.x))?;
+                writer.write_code_pair(&CodePair::new_f64(23, end_tangent.y))?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+// The nested structures of a `Hatch` are driven by their counts.
+impl<'a> CodePairCursor<'a> {
+    fn read_boundary_path(&mut self) -> DxfResult<HatchBoundaryPath> {
+        let flags = self.expect(92)?.value.assert_i32()?;
+        let geometry = if flags & POLYLINE_PATH_FLAG != 0 {
+            let mut is_closed = false;
+            while let Some(pair) = self.next_if(&[72, 73]) {
+                if pair.code == 73 {
+                    is_closed = as_bool(pair.value.assert_i16()?);
+                }
+            }
+
+            let mut vertices = vec![];
+            for _ in 0..self.expect(93)?.value.assert_i32()? {
+                let location = self.read_point(10)?;
+                let bulge = match self.next_if(&[42]) {
+                    Some(pair) => pair.value.assert_f64()?,
+                    None => 0.0,
+                };
+                vertices.push(HatchPolylineVertex { location, bulge });
+            }
+
+            HatchBoundaryPathGeometry::Polyline { is_closed, vertices }
+        }
+        else {
+            let mut edges = vec![];
+            for _ in 0..self.expect(93)?.value.assert_i32()? {
+                let edge = self.read_boundary_edge()?;
+                edges.push(edge);
+            }
+
+            HatchBoundaryPathGeometry::Edges(edges)
+        };
+
+        let mut source_boundary_handles = vec![];
+        if self.next_if(&[97]).is_some() {
+            while let Some(pair) = self.next_if(&[330]) {
+                source_boundary_handles.push(as_u32(pair.value.assert_string()?)?);
+            }
+        }
+
+        Ok(HatchBoundaryPath { flags: flags & !POLYLINE_PATH_FLAG, geometry, source_boundary_handles })
+    }
+    fn read_boundary_edge(&mut self) -> DxfResult<HatchBoundaryEdge> {
+        let edge_type = self.expect(72)?;
+        match edge_type.value.assert_i16()? {
+            1 => {
+                let start = self.read_point(10)?;
+                let end = self.read_point(11)?;
+                Ok(HatchBoundaryEdge::Line { start, end })
+            },
+            2 => {
+                let center = self.read_point(10)?;
+                let mut edge = (0.0, 0.0, 360.0, true);
+                while let Some(pair) = self.next_if(&[40, 50, 51, 73]) {
+                    match pair.code {
+                        40 => edge.0 = pair.value.assert_f64()?,
+                        50 => edge.1 = pair.value.assert_f64()?,
+                        51 => edge.2 = pair.value.assert_f64()?,
+                        _ => edge.3 = as_bool(pair.value.assert_i16()?),
+                    }
+                }
+
+                let (radius, start_angle, end_angle, is_counter_clockwise) = edge;
+                Ok(HatchBoundaryEdge::CircularArc { center, radius, start_angle, end_angle, is_counter_clockwise })
+            },
+            3 => {
+                let center = self.read_point(10)?;
+                let major_axis = self.read_vector(11)?;
+                let mut edge = (1.0, 0.0, 360.0, true);
+                while let Some(pair) = self.next_if(&[40, 50, 51, 73]) {
+                    match pair.code {
+                        40 => edge.0 = pair.value.assert_f64()?,
+                        50 => edge.1 = pair.value.assert_f64()?,
+                        51 => edge.2 = pair.value.assert_f64()?,
+                        _ => edge.3 = as_bool(pair.value.assert_i16()?),
+                    }
+                }
+
+                let (minor_axis_ratio, start_angle, end_angle, is_counter_clockwise) = edge;
+                Ok(HatchBoundaryEdge::EllipticArc { center, major_axis, minor_axis_ratio, start_angle, end_angle, is_counter_clockwise })
+            },
+            4 => self.read_spline_edge(),
+            _ => Err(DxfError::UnexpectedCodePair(edge_type.clone(), String::from("expected a HATCH edge type"))),
+        }
+    }
+    fn read_spline_edge(&mut self) -> DxfResult<HatchBoundaryEdge> {
+        let mut degree = 3;
+        let mut is_rational = false;
+        let mut is_periodic = false;
+        let mut knot_values = vec![];
+        let mut control_points = vec![];
+        let mut weights = vec![];
+        let mut fit_points = vec![];
+        let mut start_tangent = Vector::zero();
+        let mut end_tangent = Vector::zero();
+        while let Some(code) = self.peek_code(0) {
+            // code 97 is also the count of source boundary objects of the path, which is followed by
+            // handles, the next path, or the end of the paths; a fit count is followed by fit points,
+            // tangents, the next edge, or the path's own 97
+            if code == 97 && !matches!(self.peek_code(1), Some(11) | Some(12) | Some(13) | Some(72) | Some(97)) {
+                break;
+            }
+
+            match code {
+                94 => degree = self.expect(94)?.value.assert_i32()?,
+                73 => is_rational = as_bool(self.expect(73)?.value.assert_i16()?),
+                74 => is_periodic = as_bool(self.expect(74)?.value.assert_i16()?),
+                95..=97 => { self.next(); },
+                40 => knot_values.push(self.expect(40)?.value.assert_f64()?),
+                10 => control_points.push(self.read_point(10)?),
+                42 => weights.push(self.expect(42)?.value.assert_f64()?),
+                11 => fit_points.push(self.read_point(11)?),
+                12 => start_tangent = self.read_vector(12)?,
+                13 => end_tangent = self.read_vector(13)?,
+                _ => break,
+            }
+        }
+
+        Ok(HatchBoundaryEdge::Spline { degree, is_rational, is_periodic, knot_values, control_points, weights, fit_points, start_tangent, end_tangent })
+    }
+    fn read_pattern_definition_line(&mut self) -> DxfResult<HatchPatternDefinitionLine> {
+        let mut line = HatchPatternDefinitionLine {
+            angle: self.expect(53)?.value.assert_f64()?,
+            .. Default::default()
+        };
+        while let Some(pair) = self.next_if(&[43, 44, 45, 46, 79]) {
+            match pair.code {
+                43 => line.base_point.x = pair.value.assert_f64()?,
+                44 => line.base_point.y = pair.value.assert_f64()?,
+                45 => line.offset.x = pair.value.assert_f64()?,
+                46 => line.offset.y = pair.value.assert_f64()?,
+                _ => {
+                    for _ in 0..pair.value.assert_i16()? {
+                        line.dash_lengths.push(self.expect(49)?.value.assert_f64()?);
+                    }
+                },
+            }
+        }
+
+        Ok(line)
+    }
+    fn read_gradient_color(&mut self) -> DxfResult<HatchGradientColor> {
+        let mut color = HatchGradientColor {
+            value: self.expect(463)?.value.assert_f64()?,
+            .. Default::default()
+        };
+        while let Some(pair) = self.next_if(&[63, 421]) {
+            match pair.code {
+                63 => color.color = Color::from_raw_value(pair.value.assert_i16()?),
+                _ => color.true_color = pair.value.assert_i32()?,
+            }
+        }
+
+        Ok(color)
+    }
+}
@@ -0,0 +1,549 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `entity.rs`
+
+use std::f64;
+use std::f64::consts::PI;
+
+use ::{
+    Drawing,
+    Point,
+    RTree,
+    Segment,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use tessellation::normalize_sweep;
+
+// the number of samples used to bracket the intersections of two conics that can't be solved directly
+const CONIC_SAMPLES: usize = 1024;
+
+// the tessellation tolerance of a spline relative to the requested intersection tolerance
+const SPLINE_TOLERANCE_FACTOR: f64 = 0.1;
+
+/// A point where two entities of a `Drawing` meet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityIntersection {
+    /// The index of the first entity in `Drawing::entities`.
+    pub first: usize,
+    /// The index of the second entity in `Drawing::entities`; always greater than `first`.
+    pub second: usize,
+    pub point: Point,
+}
+
+// A piece of curve geometry in world coordinates.
+#[derive(Clone, Copy, Debug)]
+enum Primitive {
+    // the points `origin + direction * t` for `t` in `[min, max]`
+    Linear { origin: Point, direction: Vector, min: f64, max: f64 },
+    // the points `center + a * cos(t) + b * sin(t)` for `t` in `[start, start + sweep]` where `a` and `b` are perpendicular
+    Conic { center: Point, a: Vector, b: Vector, start: f64, sweep: f64 },
+}
+
+impl Entity {
+    /// Returns the points where the curves of this entity and `other` meet, in world coordinates.
+    ///
+    /// Points closer than `tolerance` are considered to be touching, so tangent curves and curves
+    /// that end just short of each other also intersect, and points within `tolerance` of each other
+    /// are only reported once.  Lines, arcs, circles, rays, and construction lines are intersected
+    /// exactly, ellipses numerically, and splines are first tessellated to within a tenth of
+    /// `tolerance`.  `LwPolyline` and `Polyline` entities are intersected segment by segment along
+    /// their centerlines.  Where curves overlap, only the ends of the overlap are reported and
+    /// coincident circles and ellipses don't report any points.  Any other entity type doesn't
+    /// intersect anything.
+    pub fn intersections(&self, other: &Entity, tolerance: f64) -> Vec<Point> {
+        let mut result = vec![];
+        for p in &primitives(self, tolerance) {
+            for q in &primitives(other, tolerance) {
+                intersect(p, q, tolerance, &mut result);
+            }
+        }
+
+        deduplicate(result, tolerance)
+    }
+    /// Returns the points where a `LwPolyline`, `Polyline`, or `Spline` crosses or touches itself,
+    /// excluding the vertices shared by consecutive segments.  See `intersections` for how
+    /// `tolerance` is applied.  Any other entity type returns an empty collection.
+    pub fn self_intersections(&self, tolerance: f64) -> Vec<Point> {
+        match self.specific {
+            EntityType::LwPolyline(_) | EntityType::Polyline(_) | EntityType::Spline(_) => (),
+            _ => return vec![],
+        }
+
+        let prims = primitives(self, tolerance);
+        let is_closed = match (prims.first(), prims.last()) {
+            (Some(first), Some(last)) if prims.len() > 2 => first.start_point().distance_to(&last.end_point()) <= tolerance,
+            _ => false,
+        };
+        let mut result = vec![];
+        for i in 0..prims.len() {
+            for j in i + 1..prims.len() {
+                let shared_vertex = if j == i + 1 {
+                    Some(prims[i].end_point())
+                }
+                else if is_closed && i == 0 && j == prims.len() - 1 {
+                    Some(prims[i].start_point())
+                }
+                else {
+                    None
+                };
+                let mut points = vec![];
+                intersect(&prims[i], &prims[j], tolerance, &mut points);
+                result.extend(points.into_iter().filter(|p| match shared_vertex {
+                    Some(v) => v.distance_to(p) > tolerance,
+                    None => true,
+                }));
+            }
+        }
+
+        deduplicate(result, tolerance)
+    }
+}
+
+impl Drawing {
+    /// Returns every point where two of the drawing's entities meet, ordered by the indices of the
+    /// entities.  See `Entity::intersections` for how `tolerance` is applied.  `Insert` entities are
+    /// not expanded; use `flattened_entities` first to include the contents of blocks.
+    pub fn intersections(&self, tolerance: f64) -> Vec<EntityIntersection> {
+        let prims = self.entities.iter().map(|e| primitives(e, tolerance)).collect::<Vec<_>>();
+        let mut index = RTree::new();
+        let mut unbounded = vec![];
+        for (i, entity) in self.entities.iter().enumerate() {
+            if prims[i].is_empty() {
+                continue;
+            }
+
+            match entity.bounding_box() {
+                Some(bounds) => index.insert(i, bounds.inflate(tolerance)),
+                None => unbounded.push(i),
+            }
+        }
+
+        let mut pairs = vec![];
+        for (bounds, &i) in index.iter() {
+            pairs.extend(index.crossing(bounds).into_iter().filter(|&&j| j > i).map(|&j| (i, j)));
+        }
+        for &i in &unbounded {
+            pairs.extend(index.iter().map(|(_, &j)| (i.min(j), i.max(j))));
+            pairs.extend(unbounded.iter().filter(|&&j| j > i).map(|&j| (i, j)));
+        }
+        pairs.sort();
+
+        let mut result = vec![];
+        for (first, second) in pairs {
+            let mut points = vec![];
+            for p in &prims[first] {
+                for q in &prims[second] {
+                    intersect(p, q, tolerance, &mut points);
+                }
+            }
+
+            result.extend(deduplicate(points, tolerance).into_iter().map(|point| EntityIntersection { first, second, point }));
+        }
+
+        result
+    }
+}
+
+impl Primitive {
+    fn linear(start: Point, end: Point) -> Primitive {
+        Primitive::Linear { origin: start, direction: end - start, min: 0.0, max: 1.0 }
+    }
+    fn start_point(&self) -> Point {
+        match *self {
+            Primitive::Linear { origin, direction, min, .. } => origin + direction * min,
+            Primitive::Conic { start, .. } => self.conic_point(start),
+        }
+    }
+    fn end_point(&self) -> Point {
+        match *self {
+            Primitive::Linear { origin, direction, max, .. } => origin + direction * max,
+            Primitive::Conic { start, sweep, .. } => self.conic_point(start + sweep),
+        }
+    }
+    fn conic_point(&self, t: f64) -> Point {
+        match *self {
+            Primitive::Conic { center, a, b, .. } => center + a * t.cos() + b * t.sin(),
+            Primitive::Linear { origin, direction, .. } => origin + direction * t,
+        }
+    }
+}
+
+// Converts the entity into curve primitives in world coordinates.
+fn primitives(entity: &Entity, tolerance: f64) -> Vec<Primitive> {
+    match entity.specific {
+        EntityType::Arc(ref arc) => {
+            let start = arc.start_angle.to_radians();
+            vec![circle_primitive(&arc.center, arc.radius, &arc.normal, start, normalize_sweep(arc.end_angle.to_radians() - start))]
+        },
+        EntityType::Circle(ref circle) => vec![circle_primitive(&circle.center, circle.radius, &circle.normal, 0.0, PI * 2.0)],
+        EntityType::Ellipse(ref ellipse) => {
+            let minor = ellipse.normal.normalize().cross(&ellipse.major_axis) * ellipse.minor_axis_ratio;
+            vec![Primitive::Conic {
+                center: ellipse.center,
+                a: ellipse.major_axis,
+                b: minor,
+                start: ellipse.start_parameter,
+                sweep: normalize_sweep(ellipse.end_parameter - ellipse.start_parameter),
+            }]
+        },
+        EntityType::Line(ref line) => vec![Primitive::linear(line.p1, line.p2)],
+        EntityType::LwPolyline(ref poly) => segment_primitives(poly.segments(), &poly.extrusion_direction, entity.common.elevation),
+        EntityType::Polyline(ref poly) => {
+            if poly.get_is_3d_polyline() {
+                segment_primitives(poly.segments(), &Vector::z_axis(), 0.0)
+            }
+            else {
+                segment_primitives(poly.segments(), &poly.normal, poly.location.z)
+            }
+        },
+        EntityType::Ray(ref ray) => vec![Primitive::Linear { origin: ray.start_point, direction: ray.unit_direction_vector, min: 0.0, max: f64::INFINITY }],
+        EntityType::Spline(_) => {
+            entity.tessellate(tolerance * SPLINE_TOLERANCE_FACTOR).iter()
+                .flat_map(|points| points.windows(2).map(|pair| Primitive::linear(pair[0], pair[1])).collect::<Vec<_>>())
+                .collect()
+        },
+        EntityType::XLine(ref xline) => vec![Primitive::Linear { origin: xline.first_point, direction: xline.unit_direction_vector, min: f64::NEG_INFINITY, max: f64::INFINITY }],
+        _ => vec![],
+    }
+    .into_iter()
+    .filter(|p| match *p {
+        Primitive::Linear { direction, .. } => direction.length() > 0.0,
+        Primitive::Conic { a, b, .. } => a.length() > 0.0 && b.length() > 0.0,
+    })
+    .collect()
+}
+
+fn circle_primitive(center: &Point, radius: f64, normal: &Vector, start: f64, sweep: f64) -> Primitive {
+    let (x_axis, y_axis, _) = TransformationMatrix::ocs_axes(normal);
+    Primitive::Conic {
+        center: TransformationMatrix::from_ocs(normal).transform_point(center),
+        a: x_axis * radius,
+        b: y_axis * radius,
+        start,
+        sweep,
+    }
+}
+
+fn segment_primitives<I>(segments: I, normal: &Vector, elevation: f64) -> Vec<Primitive>
+    where I: Iterator<Item=Segment> {
+
+    let to_wcs = TransformationMatrix::from_ocs(normal);
+    let with_elevation = |p: Point| to_wcs.transform_point(&Point::new(p.x, p.y, p.z + elevation));
+    segments.map(|segment| {
+        let sweep = segment.sweep_angle().to_radians();
+        match segment {
+            Segment::Line { start, end, .. } => Primitive::linear(with_elevation(start), with_elevation(end)),
+            Segment::Arc { center, radius, start_angle, .. } => {
+                // conics always run counter-clockwise so clockwise arcs start from their end
+                let start = if sweep < 0.0 { start_angle.to_radians() + sweep } else { start_angle.to_radians() };
+                let center = Point::new(center.x, center.y, center.z + elevation);
+                circle_primitive(&center, radius, normal, start, sweep.abs())
+            },
+        }
+    }).collect()
+}
+
+fn intersect(p: &Primitive, q: &Primitive, tolerance: f64, result: &mut Vec<Point>) {
+    match (*p, *q) {
+        (Primitive::Linear { .. }, Primitive::Linear { .. }) => intersect_linear_linear(p, q, tolerance, result),
+        (Primitive::Linear { .. }, Primitive::Conic { .. }) => intersect_linear_conic(p, q, tolerance, result),
+        (Primitive::Conic { .. }, Primitive::Linear { .. }) => intersect_linear_conic(q, p, tolerance, result),
+        (Primitive::Conic { .. }, Primitive::Conic { .. }) => intersect_conic_conic(p, q, tolerance, result),
+    }
+}
+
+// Returns whether the parameter is within the extent of a linear primitive.
+fn linear_contains(linear: &Primitive, t: f64, tolerance: f64) -> bool {
+    match *linear {
+        Primitive::Linear { direction, min, max, .. } => {
+            let slack = tolerance / direction.length();
+            t >= min - slack && t <= max + slack
+        },
+        _ => false,
+    }
+}
+
+// Returns whether the angle is within the sweep of a conic primitive.
+fn conic_contains(conic: &Primitive, angle: f64, tolerance: f64) -> bool {
+    match *conic {
+        Primitive::Conic { a, b, start, sweep, .. } => {
+            if sweep >= PI * 2.0 - 1.0e-12 {
+                return true;
+            }
+
+            let slack = tolerance / a.length().min(b.length());
+            let offset = (angle - start).rem_euclid(PI * 2.0);
+            offset <= sweep + slack || offset >= PI * 2.0 - slack
+        },
+        _ => false,
+    }
+}
+
+fn conic_normal(conic: &Primitive) -> Vector {
+    match *conic {
+        Primitive::Conic { a, b, .. } => a.cross(&b).normalize(),
+        _ => Vector::z_axis(),
+    }
+}
+
+// Returns the coordinates of the point relative to a conic, where the conic is the unit circle in
+// the first two coordinates and the third is the distance from the conic's plane.
+fn conic_coordinates(conic: &Primitive, p: &Point) -> (f64, f64, f64) {
+    match *conic {
+        Primitive::Conic { center, a, b, .. } => {
+            let offset = *p - center;
+            (offset.dot(&a) / a.dot(&a), offset.dot(&b) / b.dot(&b), offset.dot(&conic_normal(conic)))
+        },
+        _ => (0.0, 0.0, 0.0),
+    }
+}
+
+// Returns the angle of the point on a conic closest to `p` and the distance between them.
+fn closest_on_conic(conic: &Primitive, p: &Point) -> (f64, f64) {
+    let (a, b) = match *conic {
+        Primitive::Conic { a, b, .. } => (a, b),
+        _ => return (0.0, f64::INFINITY),
+    };
+    let (x, y, _) = conic_coordinates(conic, p);
+    let mut angle = y.atan2(x);
+    if (a.length() - b.length()).abs() > 1.0e-12 * a.length() {
+        // the angle is only exact for circles so refine it for ellipses with Newton's method
+        for _ in 0..16 {
+            let offset = conic.conic_point(angle) - *p;
+            let first = a * -angle.sin() + b * angle.cos();
+            let second = a * -angle.cos() + b * -angle.sin();
+            let slope = offset.dot(&first);
+            let curvature = first.dot(&first) + offset.dot(&second);
+            if curvature.abs() < 1.0e-300 {
+                break;
+            }
+
+            let step = slope / curvature;
+            angle -= step;
+            if step.abs() < 1.0e-14 {
+                break;
+            }
+        }
+    }
+
+    (angle, conic.conic_point(angle).distance_to(p))
+}
+
+fn intersect_linear_linear(p: &Primitive, q: &Primitive, tolerance: f64, result: &mut Vec<Point>) {
+    let (o1, d1, min1, max1) = match *p { Primitive::Linear { origin, direction, min, max } => (origin, direction, min, max), _ => return };
+    let (o2, d2, min2, max2) = match *q { Primitive::Linear { origin, direction, min, max } => (origin, direction, min, max), _ => return };
+    let w = o1 - o2;
+    let (a, b, c) = (d1.dot(&d1), d1.dot(&d2), d2.dot(&d2));
+    let (d, e) = (d1.dot(&w), d2.dot(&w));
+    let denominator = a * c - b * b;
+    if denominator <= 1.0e-12 * a * c {
+        // parallel; report the ends of any overlap
+        let offset = o2 - o1;
+        let along = offset.dot(&d1) / a;
+        if (offset - d1 * along).length() > tolerance {
+            return;
+        }
+
+        let scale = b / a;
+        let (s1, s2) = (along + scale * min2, along + scale * max2);
+        let low = min1.max(s1.min(s2));
+        let high = max1.min(s1.max(s2));
+        let slack = tolerance / a.sqrt();
+        if low > high + slack {
+            return;
+        }
+
+        for s in &[low, high] {
+            if s.is_finite() {
+                result.push(o1 + d1 * s.max(min1).min(max1));
+            }
+        }
+
+        return;
+    }
+
+    let s = (b * e - c * d) / denominator;
+    let t = (a * e - b * d) / denominator;
+    let p1 = o1 + d1 * s;
+    let p2 = o2 + d2 * t;
+    if p1.distance_to(&p2) <= tolerance && linear_contains(p, s, tolerance) && linear_contains(q, t, tolerance) {
+        result.push(midpoint(&p1, &p2));
+    }
+}
+
+fn intersect_linear_conic(linear: &Primitive, conic: &Primitive, tolerance: f64, result: &mut Vec<Point>) {
+    let (origin, direction) = match *linear { Primitive::Linear { origin, direction, .. } => (origin, direction), _ => return };
+    let normal = conic_normal(conic);
+    let along_normal = direction.dot(&normal);
+    let mut candidates = vec![];
+    if along_normal.abs() <= 1.0e-12 * direction.length() {
+        // the line is parallel to the conic's plane so solve within the plane
+        let (x0, y0, z0) = conic_coordinates(conic, &origin);
+        if z0.abs() > tolerance {
+            return;
+        }
+
+        let (x1, y1, _) = conic_coordinates(conic, &(origin + direction));
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let qa = dx * dx + dy * dy;
+        let qb = 2.0 * (x0 * dx + y0 * dy);
+        let qc = x0 * x0 + y0 * y0 - 1.0;
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if discriminant >= 0.0 {
+            let root = discriminant.sqrt();
+            candidates.push((-qb - root) / (2.0 * qa));
+            candidates.push((-qb + root) / (2.0 * qa));
+        }
+        else {
+            // the closest approach might still be within the tolerance
+            candidates.push(-qb / (2.0 * qa));
+        }
+    }
+    else {
+        let center = match *conic { Primitive::Conic { center, .. } => center, _ => return };
+        candidates.push((center - origin).dot(&normal) / along_normal);
+    }
+
+    for t in candidates {
+        let point = origin + direction * t;
+        let (angle, distance) = closest_on_conic(conic, &point);
+        if distance <= tolerance && linear_contains(linear, t, tolerance) && conic_contains(conic, angle, tolerance) {
+            result.push(point);
+        }
+    }
+}
+
+fn intersect_conic_conic(p: &Primitive, q: &Primitive, tolerance: f64, result: &mut Vec<Point>) {
+    let (c1, a1, b1, start1, sweep1) = match *p { Primitive::Conic { center, a, b, start, sweep } => (center, a, b, start, sweep), _ => return };
+    let (c2, a2, b2) = match *q { Primitive::Conic { center, a, b, .. } => (center, a, b), _ => return };
+    let (n1, n2) = (conic_normal(p), conic_normal(q));
+    let mut candidates = vec![];
+    if n1.cross(&n2).length() <= 1.0e-12 {
+        if (c2 - c1).dot(&n1).abs() > tolerance {
+            return;
+        }
+
+        let (r1, r2) = (a1.length(), a2.length());
+        let is_circular = |a: &Vector, b: &Vector| (a.length() - b.length()).abs() <= 1.0e-12 * a.length();
+        if is_circular(&a1, &b1) && is_circular(&a2, &b2) {
+            // coplanar circles
+            let offset = c2 - c1;
+            let offset = offset - n1 * offset.dot(&n1);
+            let distance = offset.length();
+            if distance <= 1.0e-12 * r1.max(r2) || distance > r1 + r2 + tolerance || distance < (r1 - r2).abs() - tolerance {
+                return;
+            }
+
+            let x = (distance * distance + r1 * r1 - r2 * r2) / (2.0 * distance);
+            let h = (r1 * r1 - x * x).max(0.0).sqrt();
+            let along = offset * (1.0 / distance);
+            let across = n1.cross(&along);
+            for sign in &[-1.0, 1.0] {
+                candidates.push(c1 + along * x + across * (h * sign));
+            }
+        }
+        else {
+            // coplanar ellipses; bracket the sign changes of the second conic's implicit equation
+            // along the first conic and refine them by bisection
+            let implicit = |t: f64| {
+                let (x, y, _) = conic_coordinates(q, &p.conic_point(t));
+                x * x + y * y - 1.0
+            };
+            let samples = (0..CONIC_SAMPLES + 1).map(|i| {
+                let t = start1 + sweep1 * i as f64 / CONIC_SAMPLES as f64;
+                (t, implicit(t))
+            }).collect::<Vec<_>>();
+            for i in 1..samples.len() {
+                let ((t0, f0), (t1, f1)) = (samples[i - 1], samples[i]);
+                if f0 == 0.0 {
+                    candidates.push(p.conic_point(t0));
+                }
+                else if f0 * f1 < 0.0 {
+                    candidates.push(p.conic_point(bisect(&implicit, t0, t1)));
+                }
+                else if i + 1 < samples.len() && f1.abs() < f0.abs() && f1.abs() <= samples[i + 1].1.abs() && f1 * samples[i + 1].1 > 0.0 {
+                    // a local minimum without a sign change may be a tangency
+                    candidates.push(p.conic_point(minimize(&|t| implicit(t).abs(), t0, samples[i + 1].0)));
+                }
+            }
+        }
+    }
+    else {
+        // the conics are in different planes so they can only meet where the first crosses the second's plane
+        let (ca, cb, cc) = (a1.dot(&n2), b1.dot(&n2), (c1 - c2).dot(&n2));
+        let radius = (ca * ca + cb * cb).sqrt();
+        let cosine = -cc / radius;
+        if cosine.abs() > 1.0 + tolerance / radius {
+            return;
+        }
+
+        let phase = cb.atan2(ca);
+        let angle = cosine.max(-1.0).min(1.0).acos();
+        candidates.push(p.conic_point(phase - angle));
+        candidates.push(p.conic_point(phase + angle));
+    }
+
+    for point in candidates {
+        let (angle1, distance1) = closest_on_conic(p, &point);
+        let (angle2, distance2) = closest_on_conic(q, &point);
+        if distance1 <= tolerance && distance2 <= tolerance && conic_contains(p, angle1, tolerance) && conic_contains(q, angle2, tolerance) {
+            result.push(point);
+        }
+    }
+}
+
+// Finds a root of `f` between `low` and `high`, where `f` has opposite signs.
+fn bisect<F>(f: &F, mut low: f64, mut high: f64) -> f64
+    where F: Fn(f64) -> f64 {
+
+    let low_sign = f(low).signum();
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+        if f(mid).signum() == low_sign {
+            low = mid;
+        }
+        else {
+            high = mid;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+// Finds a local minimum of `f` between `low` and `high` using a golden section search.
+fn minimize<F>(f: &F, mut low: f64, mut high: f64) -> f64
+    where F: Fn(f64) -> f64 {
+
+    let ratio = (5.0f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..64 {
+        let left = high - (high - low) * ratio;
+        let right = low + (high - low) * ratio;
+        if f(left) < f(right) {
+            high = right;
+        }
+        else {
+            low = left;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+fn midpoint(p1: &Point, p2: &Point) -> Point {
+    Point::new((p1.x + p2.x) / 2.0, (p1.y + p2.y) / 2.0, (p1.z + p2.z) / 2.0)
+}
+
+// Removes points within `tolerance` of an earlier point.
+fn deduplicate(points: Vec<Point>, tolerance: f64) -> Vec<Point> {
+    let mut result: Vec<Point> = vec![];
+    for p in points {
+        if !result.iter().any(|r| r.distance_to(&p) <= tolerance) {
+            result.push(p);
+        }
+    }
+
+    result
+}
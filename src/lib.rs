@@ -123,6 +123,7 @@ mod handle_tracker;
 
 mod drawing;
 pub use drawing::Drawing;
+mod flatten;
 
 mod drawing_item;
 pub use drawing_item::{
@@ -130,6 +131,12 @@ pub use drawing_item::{
     DrawingItemMut,
 };
 
+mod dictionary_view;
+pub use dictionary_view::{
+    DictionaryView,
+    DictionaryViewMut,
+};
+
 mod section_geometry_settings;
 pub use section_geometry_settings::SectionGeometrySettings;
 
@@ -153,6 +160,12 @@ pub use point::Point;
 mod vector;
 pub use vector::Vector;
 
+mod bounding_box;
+pub use bounding_box::BoundingBox;
+
+mod rtree;
+pub use rtree::RTree;
+
 mod generated;
 pub mod entities {
     pub use generated::entities::*;
@@ -166,6 +179,7 @@ pub mod objects {
 
 include!("expected_type.rs");
 
+mod code_pair_cursor;
 mod code_pair_iter;
 mod code_pair_writer;
 
@@ -184,8 +198,98 @@ pub use line_weight::LineWeight;
 mod entity;
 pub use entity::LwPolylineVertex;
 
+mod acad_table;
+pub use acad_table::{
+    AcadTableCell,
+    AcadTableCellAttribute,
+    AcadTableCellContent,
+    AcadTableCellOverrides,
+};
+
+mod acis;
+pub use acis::{
+    AcisBody,
+    AcisData,
+    AcisFace,
+    AcisModel,
+    AcisRecord,
+    AcisSurface,
+};
+
+mod contour;
+pub use contour::Contour;
+
+mod dimension;
+mod dimension_text;
+mod dynamic_block;
+
+mod hatch;
+pub use hatch::{
+    HatchBoundaryEdge,
+    HatchBoundaryPath,
+    HatchBoundaryPathGeometry,
+    HatchGradientColor,
+    HatchPatternDefinitionLine,
+    HatchPolylineVertex,
+};
+
+mod intersection;
+pub use intersection::EntityIntersection;
+
+mod measurement;
+pub use measurement::Orientation;
+
+mod mesh;
+mod mleader;
+pub use mleader::{
+    MLeaderArrowheadOverride,
+    MLeaderBlockAttribute,
+    MLeaderBlockContent,
+    MLeaderBreak,
+    MLeaderContextData,
+    MLeaderLeader,
+    MLeaderLine,
+    MLeaderTextContent,
+};
+
+mod polyline_mesh;
+pub use polyline_mesh::{
+    PolyfaceFace,
+    PolyfaceMesh,
+    PolygonMesh,
+};
+
+mod proxy_graphics;
+mod segment;
+pub use segment::Segment;
+
+mod spline;
+mod surface;
+mod tessellation;
+mod transform;
+mod units;
+
+mod mtext;
+pub use mtext::{
+    MTextColor,
+    MTextContent,
+    MTextDocument,
+    MTextFont,
+    MTextFormat,
+    MTextParagraph,
+    MTextRun,
+    MTextStackKind,
+};
+
+mod text_codes;
+pub use text_codes::{
+    DecodedText,
+    TextSpan,
+};
+
 mod object;
 pub use object::{
+    BlockVisibilityState,
     GeoMeshPoint,
     MLineStyleElement,
 };
@@ -0,0 +1,390 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `entity.rs`
+
+use std::f64::consts::PI;
+
+use ::{
+    Point,
+    Segment,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use tessellation::normalize_sweep;
+
+/// The direction a closed curve runs when viewed looking down its normal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+// 5-point Gauss-Legendre abscissae and weights on [-1, 1]
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+// the number of pieces each spline span or ellipse is split into for numerical integration
+const INTEGRATION_PIECES: usize = 16;
+
+// the plane a closed curve lies in; the region's moments are accumulated in its coordinates
+struct Plane {
+    origin: Point,
+    x_axis: Vector,
+    y_axis: Vector,
+}
+
+impl Plane {
+    fn from_ocs(normal: &Vector, elevation: f64) -> Plane {
+        let (x_axis, y_axis, z_axis) = TransformationMatrix::ocs_axes(normal);
+        Plane {
+            origin: Point::origin() + z_axis * elevation,
+            x_axis,
+            y_axis,
+        }
+    }
+    fn to_plane(&self, p: &Point) -> (f64, f64) {
+        let v = *p - self.origin;
+        (v.dot(&self.x_axis), v.dot(&self.y_axis))
+    }
+    fn to_wcs(&self, x: f64, y: f64) -> Point {
+        self.origin + self.x_axis * x + self.y_axis * y
+    }
+}
+
+// the signed area and the first moments of area (the integrals of x and y) of a planar region
+#[derive(Default)]
+struct Moments {
+    area: f64,
+    x: f64,
+    y: f64,
+}
+
+impl Moments {
+    // Adds the contribution of a straight boundary edge via Green's theorem.
+    fn add_line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
+        let cross = x1 * y2 - x2 * y1;
+        self.area += cross / 2.0;
+        self.x += cross * (x1 + x2) / 6.0;
+        self.y += cross * (y1 + y2) / 6.0;
+    }
+    // Adds the contribution of a boundary segment in plane coordinates.
+    fn add_segment(&mut self, segment: &Segment) {
+        let start = segment.start_point();
+        let end = segment.end_point();
+        self.add_line((start.x, start.y), (end.x, end.y));
+        if let Segment::Arc { center, radius, start_angle, .. } = *segment {
+            // the circular segment between the chord and the arc
+            let sweep = segment.sweep_angle().to_radians();
+            let area = radius * radius * (sweep - sweep.sin()) / 2.0;
+            let magnitude = sweep.abs();
+            let distance = 4.0 * radius * (magnitude / 2.0).sin().powi(3) / (3.0 * (magnitude - magnitude.sin()));
+            let mid_angle = start_angle.to_radians() + sweep / 2.0;
+            self.area += area;
+            self.x += area * (center.x + distance * mid_angle.cos());
+            self.y += area * (center.y + distance * mid_angle.sin());
+        }
+    }
+}
+
+impl EntityType {
+    /// Returns the length of a curve, or `None` if the entity isn't a curve.  The length of a closed
+    /// curve is its perimeter.
+    pub fn length(&self) -> Option<f64> {
+        match *self {
+            EntityType::Arc(ref arc) => Some(arc.radius * normalize_sweep((arc.end_angle - arc.start_angle).to_radians())),
+            EntityType::Circle(ref circle) => Some(PI * 2.0 * circle.radius),
+            EntityType::Ellipse(ref ellipse) => Some(ellipse_length(ellipse)),
+            EntityType::Helix(ref helix) => {
+                let axis = helix.axis_vector.normalize();
+                let offset = helix.start_point - helix.axis_base_point;
+                let radial = offset - axis * offset.dot(&axis);
+                let radius = if radial.length() == 0.0 { helix.radius } else { radial.length() };
+                let turn_length = ((PI * 2.0 * radius).powi(2) + helix.turn_height.powi(2)).sqrt();
+                Some(helix.number_of_turns.abs() * turn_length)
+            },
+            EntityType::Leader(ref leader) => Some(polyline_length(&leader.vertices)),
+            EntityType::Line(ref line) => Some(line.p1.distance_to(&line.p2)),
+            EntityType::LwPolyline(ref poly) => Some(poly.segments().map(|s| s.length()).sum()),
+            EntityType::Polyline(ref poly) => {
+                if poly.get_is_3d_polygon_mesh() || poly.get_is_polyface_mesh() {
+                    None
+                }
+                else {
+                    Some(poly.segments().map(|s| s.length()).sum())
+                }
+            },
+            EntityType::Spline(ref spline) => spline_length(spline),
+            _ => None,
+        }
+    }
+    /// Returns the area enclosed by a closed curve, positive if the curve runs counter-clockwise when
+    /// viewed looking down its normal and negative otherwise.  Circles and ellipses are always
+    /// counter-clockwise.  Returns `None` if the entity isn't a closed planar curve; this includes 3D
+    /// polylines and meshes.
+    pub fn signed_area(&self) -> Option<f64> {
+        self.moments(0.0).map(|(moments, _)| moments.area)
+    }
+    /// Returns the unsigned area enclosed by a closed curve.
+    pub fn area(&self) -> Option<f64> {
+        self.signed_area().map(f64::abs)
+    }
+    /// Returns the direction of a closed curve, or `None` if the entity isn't a closed planar curve
+    /// or encloses no area.
+    pub fn orientation(&self) -> Option<Orientation> {
+        match self.signed_area() {
+            Some(area) if area > 0.0 => Some(Orientation::CounterClockwise),
+            Some(area) if area < 0.0 => Some(Orientation::Clockwise),
+            _ => None,
+        }
+    }
+    /// Returns the centroid in world coordinates of the region enclosed by a closed curve.  A
+    /// `LwPolyline` is assumed to be at an elevation of 0; use `Entity::centroid` to include the
+    /// entity's elevation.
+    pub fn centroid(&self) -> Option<Point> {
+        self.centroid_at_elevation(0.0)
+    }
+    fn centroid_at_elevation(&self, elevation: f64) -> Option<Point> {
+        match self.moments(elevation) {
+            Some((ref moments, ref plane)) if moments.area != 0.0 => Some(plane.to_wcs(moments.x / moments.area, moments.y / moments.area)),
+            _ => None,
+        }
+    }
+    // `elevation` is only used by `LwPolyline` which stores it on the entity instead
+    fn moments(&self, elevation: f64) -> Option<(Moments, Plane)> {
+        match *self {
+            EntityType::Circle(ref circle) => {
+                let area = PI * circle.radius * circle.radius;
+                let moments = Moments { area, x: area * circle.center.x, y: area * circle.center.y };
+                Some((moments, Plane::from_ocs(&circle.normal, circle.center.z)))
+            },
+            EntityType::Ellipse(ref ellipse) => {
+                let sweep = normalize_sweep(ellipse.end_parameter - ellipse.start_parameter);
+                if (sweep - PI * 2.0).abs() > 1.0e-9 {
+                    return None;
+                }
+
+                let a = ellipse.major_axis.length();
+                let plane = Plane {
+                    origin: ellipse.center,
+                    x_axis: ellipse.major_axis.normalize(),
+                    y_axis: ellipse.normal.cross(&ellipse.major_axis).normalize(),
+                };
+                Some((Moments { area: PI * a * a * ellipse.minor_axis_ratio, x: 0.0, y: 0.0 }, plane))
+            },
+            EntityType::LwPolyline(ref poly) => {
+                if !is_closed_path(poly.get_is_closed(), poly.vertices.first().map(|v| (v.x, v.y)), poly.vertices.last().map(|v| (v.x, v.y))) {
+                    return None;
+                }
+
+                let mut moments = Moments::default();
+                let mut closed = poly.clone();
+                closed.set_is_closed(true);
+                for segment in closed.segments() {
+                    moments.add_segment(&segment);
+                }
+
+                Some((moments, Plane::from_ocs(&poly.extrusion_direction, elevation)))
+            },
+            EntityType::Polyline(ref poly) => {
+                if poly.get_is_3d_polyline() || poly.get_is_3d_polygon_mesh() || poly.get_is_polyface_mesh() {
+                    return None;
+                }
+
+                let first = poly.vertices.first().map(|v| (v.location.x, v.location.y));
+                let last = poly.vertices.last().map(|v| (v.location.x, v.location.y));
+                if !is_closed_path(poly.get_is_closed(), first, last) {
+                    return None;
+                }
+
+                let mut moments = Moments::default();
+                let mut closed = poly.clone();
+                closed.set_is_closed(true);
+                for segment in closed.segments() {
+                    moments.add_segment(&segment);
+                }
+
+                Some((moments, Plane::from_ocs(&poly.normal, poly.location.z)))
+            },
+            EntityType::Spline(ref spline) => spline_moments(spline),
+            _ => None,
+        }
+    }
+}
+
+impl Entity {
+    /// Returns the length of a curve; see `EntityType::length`.
+    pub fn length(&self) -> Option<f64> {
+        self.specific.length()
+    }
+    /// Returns the signed area enclosed by a closed curve; see `EntityType::signed_area`.
+    pub fn signed_area(&self) -> Option<f64> {
+        self.specific.signed_area()
+    }
+    /// Returns the unsigned area enclosed by a closed curve.
+    pub fn area(&self) -> Option<f64> {
+        self.specific.area()
+    }
+    /// Returns the direction of a closed curve; see `EntityType::orientation`.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.specific.orientation()
+    }
+    /// Returns the centroid in world coordinates of the region enclosed by a closed curve.
+    pub fn centroid(&self) -> Option<Point> {
+        self.specific.centroid_at_elevation(self.common.elevation)
+    }
+    /// Returns the area of the region bounded by the given closed curves where any curve nested
+    /// inside an odd number of the others is a hole and any curve nested inside an even number of
+    /// the others (e.g., an island in a hole) adds to the area.  The curves are assumed to be
+    /// parallel to the XY plane and not to intersect each other.  Returns `None` if any of the
+    /// entities isn't a closed curve.
+    pub fn area_with_holes(boundaries: &[Entity]) -> Option<f64> {
+        let mut areas = vec![];
+        let mut polygons = vec![];
+        for boundary in boundaries {
+            let area = boundary.area()?;
+            let mut centerline = boundary.clone();
+            if let EntityType::LwPolyline(ref mut poly) = centerline.specific {
+                // only the centerline matters, not the outline of wide segments
+                poly.constant_width = 0.0;
+                for v in poly.vertices.iter_mut() {
+                    v.starting_width = 0.0;
+                    v.ending_width = 0.0;
+                }
+            }
+
+            let polygon = centerline.tessellate(area.sqrt() * 1.0e-4).into_iter().next().unwrap_or_default();
+            areas.push(area);
+            polygons.push(polygon);
+        }
+
+        let mut total = 0.0;
+        for (i, area) in areas.iter().enumerate() {
+            let sample = match polygons[i].first() {
+                Some(p) => *p,
+                None => continue,
+            };
+            let depth = polygons.iter().enumerate()
+                .filter(|&(j, polygon)| i != j && is_point_in_polygon(&sample, polygon))
+                .count();
+            if depth % 2 == 0 {
+                total += area;
+            }
+            else {
+                total -= area;
+            }
+        }
+
+        Some(total)
+    }
+}
+
+// Returns whether `p` is inside the closed polygon when projected onto the XY plane.
+pub(crate) fn is_point_in_polygon(p: &Point, polygon: &[Point]) -> bool {
+    let mut inside = false;
+    let n = polygon.len();
+    for i in 0..n {
+        let a = &polygon[i];
+        let b = &polygon[(i + n - 1) % n];
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+fn is_closed_path(is_closed: bool, first: Option<(f64, f64)>, last: Option<(f64, f64)>) -> bool {
+    match (first, last) {
+        (Some(first), Some(last)) => is_closed || first == last,
+        _ => false,
+    }
+}
+
+fn polyline_length(points: &[Point]) -> f64 {
+    points.windows(2).map(|pair| pair[0].distance_to(&pair[1])).sum()
+}
+
+// Integrates `f` over [a, b] by splitting it into `pieces` and applying Gauss-Legendre quadrature to each.
+fn integrate<F>(a: f64, b: f64, pieces: usize, f: F) -> f64
+    where F: Fn(f64) -> f64
+{
+    let width = (b - a) / pieces as f64;
+    (0..pieces).map(|i| {
+        let mid = a + width * (i as f64 + 0.5);
+        GAUSS_LEGENDRE.iter().map(|&(x, w)| w * f(mid + x * width / 2.0)).sum::<f64>() * width / 2.0
+    }).sum()
+}
+
+fn ellipse_length(ellipse: &Ellipse) -> f64 {
+    let major = ellipse.major_axis;
+    let minor = ellipse.normal.normalize().cross(&major) * ellipse.minor_axis_ratio;
+    let start = ellipse.start_parameter;
+    let sweep = normalize_sweep(ellipse.end_parameter - start);
+    integrate(start, start + sweep, INTEGRATION_PIECES * 4, |t| (major * -t.sin() + minor * t.cos()).length())
+}
+
+// Returns a copy of the spline that can be evaluated, interpolating the fit points if necessary.
+fn evaluable_spline(spline: &Spline) -> Option<Spline> {
+    if spline.parameter_range().is_some() {
+        return Some(spline.clone());
+    }
+
+    let mut interpolated = spline.clone();
+    match interpolated.interpolate_fit_points() {
+        Ok(()) if spline.control_points.is_empty() => Some(interpolated),
+        _ => None,
+    }
+}
+
+// Returns the distinct knot intervals of the spline's parameter range.
+fn spline_spans(spline: &Spline) -> Vec<(f64, f64)> {
+    let degree = spline.degree_of_curve as usize;
+    let knots = &spline.knot_values;
+    (degree..spline.control_points.len())
+        .map(|i| (knots[i], knots[i + 1]))
+        .filter(|&(a, b)| b > a)
+        .collect()
+}
+
+fn spline_length(spline: &Spline) -> Option<f64> {
+    let spline = evaluable_spline(spline)?;
+    Some(spline_spans(&spline).iter().map(|&(a, b)| {
+        integrate(a, b, INTEGRATION_PIECES, |u| spline.derivatives_at(u, 1).map(|d| d[1].length()).unwrap_or(0.0))
+    }).sum())
+}
+
+fn spline_moments(spline: &Spline) -> Option<(Moments, Plane)> {
+    let spline = evaluable_spline(spline)?;
+    let (start, end) = spline.parameter_range()?;
+    let first = spline.point_at(start).ok()?;
+    let last = spline.point_at(end).ok()?;
+    if !spline.get_is_closed() && !spline.get_is_periodic() && first.distance_to(&last) > 1.0e-9 {
+        return None;
+    }
+
+    let plane = Plane::from_ocs(&spline.normal, 0.0);
+    let local = |u: f64| {
+        let d = spline.derivatives_at(u, 1).unwrap_or_else(|_| vec![Vector::zero(), Vector::zero()]);
+        let (x, y) = plane.to_plane(&(Point::origin() + d[0]));
+        (x, y, d[1].dot(&plane.x_axis), d[1].dot(&plane.y_axis))
+    };
+
+    // Green's theorem: A = 1/2 (x dy - y dx), Mx = 1/2 x^2 dy, My = -1/2 y^2 dx
+    let mut moments = Moments::default();
+    for (a, b) in spline_spans(&spline) {
+        moments.area += integrate(a, b, INTEGRATION_PIECES, |u| { let (x, y, dx, dy) = local(u); (x * dy - y * dx) / 2.0 });
+        moments.x += integrate(a, b, INTEGRATION_PIECES, |u| { let (x, _, _, dy) = local(u); x * x * dy / 2.0 });
+        moments.y -= integrate(a, b, INTEGRATION_PIECES, |u| { let (_, y, dx, _) = local(u); y * y * dx / 2.0 });
+    }
+
+    // close any remaining gap with a straight edge
+    moments.add_line(plane.to_plane(&last), plane.to_plane(&first));
+    Some((moments, plane))
+}
@@ -0,0 +1,140 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::io::Write;
+
+use ::{
+    CodePair,
+    DxfResult,
+    Point,
+};
+
+use code_pair_cursor::CodePairCursor;
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use helper_functions::*;
+
+impl Mesh {
+    /// Creates a mesh from its vertices and the vertex indices of each face.  The edges are the
+    /// unique sides of the faces and none of them are creased.
+    pub fn new(vertices: Vec<Point>, faces: Vec<Vec<usize>>) -> Self {
+        let mut edges = vec![];
+        for face in &faces {
+            for (i, &start) in face.iter().enumerate() {
+                let end = face[(i + 1) % face.len()];
+                if start != end && !edges.contains(&(start, end)) && !edges.contains(&(end, start)) {
+                    edges.push((start, end));
+                }
+            }
+        }
+
+        let edge_creases = vec![0.0; edges.len()];
+        Mesh {
+            vertices,
+            faces,
+            edges,
+            edge_creases,
+            .. Default::default()
+        }
+    }
+    /// Gets the corners of each face.
+    pub fn get_face_points(&self) -> Vec<Vec<Point>> {
+        self.faces.iter()
+            .map(|f| f.iter().filter_map(|&i| self.vertices.get(i).cloned()).collect())
+            .collect()
+    }
+    pub(crate) fn read_code_pairs(&mut self, pairs: &[CodePair]) -> DxfResult<()> {
+        let mut reader = CodePairCursor::new(pairs);
+        // faces and edges with negative vertex indices are invalid and skipped along with the edge's crease
+        let mut is_edge_valid = vec![];
+        while let Some(pair) = reader.next() {
+            match pair.code {
+                71 => { self.version = pair.value.assert_i16()?; },
+                72 => { self.is_blend_creased = as_bool(pair.value.assert_i16()?); },
+                91 => { self.subdivision_level = pair.value.assert_i32()?; },
+                92 => {
+                    for _ in 0..pair.value.assert_i32()? {
+                        self.vertices.push(reader.read_point(10)?);
+                    }
+                },
+                93 => {
+                    let mut remaining = pair.value.assert_i32()?;
+                    while remaining > 0 {
+                        let corner_count = reader.expect(90)?.value.assert_i32()?;
+                        let mut face = vec![];
+                        for _ in 0..corner_count {
+                            face.push(reader.expect(90)?.value.assert_i32()?);
+                        }
+
+                        if face.iter().all(|&i| i >= 0) {
+                            self.faces.push(face.iter().map(|&i| i as usize).collect());
+                        }
+
+                        remaining -= corner_count + 1;
+                    }
+                },
+                94 => {
+                    for _ in 0..pair.value.assert_i32()? {
+                        let start = reader.expect(90)?.value.assert_i32()?;
+                        let end = reader.expect(90)?.value.assert_i32()?;
+                        is_edge_valid.push(start >= 0 && end >= 0);
+                        if start >= 0 && end >= 0 {
+                            self.edges.push((start as usize, end as usize));
+                        }
+                    }
+                },
+                95 => {
+                    for i in 0..pair.value.assert_i32()? {
+                        let crease = reader.expect(140)?.value.assert_f64()?;
+                        if is_edge_valid.get(i as usize).cloned().unwrap_or(true) {
+                            self.edge_creases.push(crease);
+                        }
+                    }
+
+                    // the property overrides of the sub-entities that follow aren't kept
+                    break;
+                },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(())
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbSubDMesh"))?;
+        writer.write_code_pair(&CodePair::new_i16(71, self.version))?;
+        writer.write_code_pair(&CodePair::new_i16(72, as_i16(self.is_blend_creased)))?;
+        writer.write_code_pair(&CodePair::new_i32(91, self.subdivision_level))?;
+        writer.write_code_pair(&CodePair::new_i32(92, self.vertices.len() as i32))?;
+        for vertex in &self.vertices {
+            writer.write_code_pair(&CodePair::new_f64(10, vertex.x))?;
+            writer.write_code_pair(&CodePair::new_f64(20, vertex.y))?;
+            writer.write_code_pair(&CodePair::new_f64(30, vertex.z))?;
+        }
+
+        let face_list_size = self.faces.iter().map(|f| f.len() + 1).sum::<usize>();
+        writer.write_code_pair(&CodePair::new_i32(93, face_list_size as i32))?;
+        for face in &self.faces {
+            writer.write_code_pair(&CodePair::new_i32(90, face.len() as i32))?;
+            for &index in face {
+                writer.write_code_pair(&CodePair::new_i32(90, index as i32))?;
+            }
+        }
+
+        writer.write_code_pair(&CodePair::new_i32(94, self.edges.len() as i32))?;
+        for &(start, end) in &self.edges {
+            writer.write_code_pair(&CodePair::new_i32(90, start as i32))?;
+            writer.write_code_pair(&CodePair::new_i32(90, end as i32))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_i32(95, self.edge_creases.len() as i32))?;
+        for &crease in &self.edge_creases {
+            writer.write_code_pair(&CodePair::new_f64(140, crease))?;
+        }
+
+        // no sub-entity property overrides
+        writer.write_code_pair(&CodePair::new_i32(90, 0))?;
+        Ok(())
+    }
+}
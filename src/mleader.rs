@@ -0,0 +1,654 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::io::Write;
+use enum_primitive::FromPrimitive;
+
+use ::{
+    CodePair,
+    DxfError,
+    DxfResult,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use code_pair_cursor::CodePairCursor;
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use enums::*;
+use helper_functions::*;
+
+const CONTEXT_DATA_START: &str = "CONTEXT_DATA{";
+const LEADER_START: &str = "LEADER{";
+const LEADER_LINE_START: &str = "LEADER_LINE{";
+const GROUP_END: &str = "}";
+
+/// The MText content of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MLeaderTextContent {
+    pub text: String,
+    pub normal: Vector,
+    pub text_style_handle: u32,
+    pub location: Point,
+    pub direction: Vector,
+    pub rotation: f64,
+    pub boundary_width: f64,
+    pub boundary_height: f64,
+    pub line_spacing_factor: f64,
+    pub line_spacing_style: i16,
+    pub color: i32,
+    pub alignment: i16,
+    pub flow_direction: i16,
+    pub background_color: i32,
+    pub background_scale_factor: f64,
+    pub background_transparency: i32,
+    pub is_background_enabled: bool,
+    pub is_background_mask_fill_on: bool,
+    pub column_type: i16,
+    pub is_text_height_automatic: bool,
+    pub column_width: f64,
+    pub column_gutter: f64,
+    pub is_column_flow_reversed: bool,
+    pub column_sizes: Vec<f64>,
+    pub use_word_break: bool,
+}
+
+impl Default for MLeaderTextContent {
+    fn default() -> Self {
+        MLeaderTextContent {
+            text: String::new(),
+            normal: Vector::z_axis(),
+            text_style_handle: 0,
+            location: Point::origin(),
+            direction: Vector::x_axis(),
+            rotation: 0.0,
+            boundary_width: 0.0,
+            boundary_height: 0.0,
+            line_spacing_factor: 1.0,
+            line_spacing_style: 1,
+            color: 0,
+            alignment: 1,
+            flow_direction: 1,
+            background_color: 0,
+            background_scale_factor: 1.5,
+            background_transparency: 0,
+            is_background_enabled: false,
+            is_background_mask_fill_on: false,
+            column_type: 0,
+            is_text_height_automatic: false,
+            column_width: 0.0,
+            column_gutter: 0.0,
+            is_column_flow_reversed: false,
+            column_sizes: vec![],
+            use_word_break: true,
+        }
+    }
+}
+
+/// The block content of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MLeaderBlockContent {
+    pub block_record_handle: u32,
+    pub normal: Vector,
+    pub location: Point,
+    pub scale: Vector,
+    pub rotation: f64,
+    pub color: i32,
+    pub transformation_matrix: TransformationMatrix,
+}
+
+impl Default for MLeaderBlockContent {
+    fn default() -> Self {
+        MLeaderBlockContent {
+            block_record_handle: 0,
+            normal: Vector::z_axis(),
+            location: Point::origin(),
+            scale: Vector::new(1.0, 1.0, 1.0),
+            rotation: 0.0,
+            color: 0,
+            transformation_matrix: TransformationMatrix::identity(),
+        }
+    }
+}
+
+/// A break in an `MLeader` dogleg or leader line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MLeaderBreak {
+    /// The index of the segment with the break; only used by leader lines.
+    pub index: i32,
+    pub start: Point,
+    pub end: Point,
+}
+
+/// A leader line of an `MLeader` (`LEADER_LINE{`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MLeaderLine {
+    pub vertices: Vec<Point>,
+    pub breaks: Vec<MLeaderBreak>,
+    pub index: i32,
+}
+
+/// A leader root of an `MLeader` (`LEADER{`) with its landing and leader lines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MLeaderLeader {
+    pub has_set_last_leader_line_point: bool,
+    pub has_set_dogleg_vector: bool,
+    /// The connection point of the leader lines.
+    pub last_leader_line_point: Point,
+    pub dogleg_vector: Vector,
+    pub breaks: Vec<MLeaderBreak>,
+    pub branch_index: i32,
+    pub dogleg_length: f64,
+    pub lines: Vec<MLeaderLine>,
+    /// Only written on R2010 and later.
+    pub attachment_direction: TextAttachmentDirection,
+}
+
+impl Default for MLeaderLeader {
+    fn default() -> Self {
+        MLeaderLeader {
+            has_set_last_leader_line_point: false,
+            has_set_dogleg_vector: false,
+            last_leader_line_point: Point::origin(),
+            dogleg_vector: Vector::x_axis(),
+            breaks: vec![],
+            branch_index: 0,
+            dogleg_length: 0.0,
+            lines: vec![],
+            attachment_direction: TextAttachmentDirection::Horizontal,
+        }
+    }
+}
+
+/// The content and geometry of an `MLeader` (`CONTEXT_DATA{`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MLeaderContextData {
+    pub content_scale: f64,
+    pub content_base_point: Point,
+    pub text_height: f64,
+    pub arrowhead_size: f64,
+    pub landing_gap: f64,
+    pub text_left_attachment_type: i16,
+    pub text_right_attachment_type: i16,
+    pub text_alignment_type: i16,
+    pub block_content_connection_type: i16,
+    pub text_content: Option<MLeaderTextContent>,
+    pub block_content: Option<MLeaderBlockContent>,
+    pub plane_origin: Point,
+    pub plane_x_axis: Vector,
+    pub plane_y_axis: Vector,
+    pub is_plane_normal_reversed: bool,
+    pub leaders: Vec<MLeaderLeader>,
+    /// Only written on R2010 and later.
+    pub bottom_text_attachment_direction: BottomTextAttachmentDirection,
+    /// Only written on R2010 and later.
+    pub top_text_attachment_direction: TopTextAttachmentDirection,
+}
+
+impl Default for MLeaderContextData {
+    fn default() -> Self {
+        MLeaderContextData {
+            content_scale: 1.0,
+            content_base_point: Point::origin(),
+            text_height: 1.0,
+            arrowhead_size: 1.0,
+            landing_gap: 0.0,
+            text_left_attachment_type: 1,
+            text_right_attachment_type: 1,
+            text_alignment_type: 0,
+            block_content_connection_type: 0,
+            text_content: None,
+            block_content: None,
+            plane_origin: Point::origin(),
+            plane_x_axis: Vector::x_axis(),
+            plane_y_axis: Vector::y_axis(),
+            is_plane_normal_reversed: false,
+            leaders: vec![],
+            bottom_text_attachment_direction: BottomTextAttachmentDirection::Center,
+            top_text_attachment_direction: TopTextAttachmentDirection::Center,
+        }
+    }
+}
+
+/// An arrowhead that overrides the style's arrowhead for one leader line of an `MLeader`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MLeaderArrowheadOverride {
+    pub index: i32,
+    pub arrowhead_handle: u32,
+}
+
+/// The value of an attribute of the block content of an `MLeader`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MLeaderBlockAttribute {
+    /// The handle of the `AttributeDefinition` in the block.
+    pub attribute_definition_handle: u32,
+    pub index: i16,
+    pub width: f64,
+    pub text: String,
+}
+
+impl MLeader {
+    pub(crate) fn read_code_pairs(&mut self, pairs: &[CodePair]) -> DxfResult<()> {
+        let mut reader = CodePairCursor::new(pairs);
+        while let Some(pair) = reader.next() {
+            match pair.code {
+                270 => { self.version = pair.value.assert_i16()?; },
+                300 => { self.context_data = reader.read_m_leader_context_data()?; },
+                340 => { self.__leader_style_handle = as_u32(pair.value.assert_string()?)?; },
+                90 => { self.property_override_flags = pair.value.assert_i32()?; },
+                170 => { self.leader_line_type = pair.value.assert_i16()?; },
+                91 => { self.leader_line_color = pair.value.assert_i32()?; },
+                341 => { self.__leader_line_type_object_handle = as_u32(pair.value.assert_string()?)?; },
+                171 => { self.leader_line_weight = pair.value.assert_i16()?; },
+                290 => { self.enable_landing = pair.value.assert_bool()?; },
+                291 => { self.enable_dogleg = pair.value.assert_bool()?; },
+                41 => { self.dogleg_length = pair.value.assert_f64()?; },
+                342 => { self.__arrowhead_handle = as_u32(pair.value.assert_string()?)?; },
+                42 => { self.arrowhead_size = pair.value.assert_f64()?; },
+                172 => { self.content_type = pair.value.assert_i16()?; },
+                343 => { self.__text_style_handle = as_u32(pair.value.assert_string()?)?; },
+                173 => { self.text_left_attachment_type = pair.value.assert_i16()?; },
+                95 => { self.text_right_attachment_type = pair.value.assert_i32()?; },
+                174 => { self.text_angle_type = pair.value.assert_i16()?; },
+                175 => { self.text_alignment_type = pair.value.assert_i16()?; },
+                92 => { self.text_color = pair.value.assert_i32()?; },
+                292 => { self.enable_frame_text = pair.value.assert_bool()?; },
+                344 => { self.__block_content_handle = as_u32(pair.value.assert_string()?)?; },
+                93 => { self.block_content_color = pair.value.assert_i32()?; },
+                10 => { self.block_content_scale.x = pair.value.assert_f64()?; },
+                20 => { self.block_content_scale.y = pair.value.assert_f64()?; },
+                30 => { self.block_content_scale.z = pair.value.assert_f64()?; },
+                43 => { self.block_content_rotation = pair.value.assert_f64()?; },
+                176 => { self.block_content_connection_type = pair.value.assert_i16()?; },
+                293 => { self.enable_annotation_scale = pair.value.assert_bool()?; },
+                94 => { self.arrowhead_overrides.push(MLeaderArrowheadOverride { index: pair.value.assert_i32()?, arrowhead_handle: 0 }); },
+                345 => { vec_last!(self.arrowhead_overrides).arrowhead_handle = as_u32(pair.value.assert_string()?)?; },
+                330 => { self.block_attributes.push(MLeaderBlockAttribute { attribute_definition_handle: as_u32(pair.value.assert_string()?)?, .. Default::default() }); },
+                177 => { vec_last!(self.block_attributes).index = pair.value.assert_i16()?; },
+                44 => { vec_last!(self.block_attributes).width = pair.value.assert_f64()?; },
+                302 => { vec_last!(self.block_attributes).text = pair.value.assert_string()?; },
+                294 => { self.is_text_direction_negative = pair.value.assert_bool()?; },
+                178 => { self.text_align_in_ipe = pair.value.assert_i16()?; },
+                179 => { self.text_attachment_point = pair.value.assert_i16()?; },
+                45 => { self.scale = pair.value.assert_f64()?; },
+                271 => { self.text_attachment_direction = try_result!(TextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                272 => { self.bottom_text_attachment_direction = try_result!(BottomTextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                273 => { self.top_text_attachment_direction = try_result!(TopTextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(())
+    }
+    pub(crate) fn write<T>(&self, version: &AcadVersion, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbMLeader"))?;
+        writer.write_code_pair(&CodePair::new_i16(270, self.version))?;
+        write_context_data(writer, version, &self.context_data)?;
+        writer.write_code_pair(&CodePair::new_string(340, &as_handle(self.__leader_style_handle)))?;
+        writer.write_code_pair(&CodePair::new_i32(90, self.property_override_flags))?;
+        writer.write_code_pair(&CodePair::new_i16(170, self.leader_line_type))?;
+        writer.write_code_pair(&CodePair::new_i32(91, self.leader_line_color))?;
+        writer.write_code_pair(&CodePair::new_string(341, &as_handle(self.__leader_line_type_object_handle)))?;
+        writer.write_code_pair(&CodePair::new_i16(171, self.leader_line_weight))?;
+        writer.write_code_pair(&CodePair::new_bool(290, self.enable_landing))?;
+        writer.write_code_pair(&CodePair::new_bool(291, self.enable_dogleg))?;
+        writer.write_code_pair(&CodePair::new_f64(41, self.dogleg_length))?;
+        if self.__arrowhead_handle != 0 {
+            writer.write_code_pair(&CodePair::new_string(342, &as_handle(self.__arrowhead_handle)))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_f64(42, self.arrowhead_size))?;
+        writer.write_code_pair(&CodePair::new_i16(172, self.content_type))?;
+        writer.write_code_pair(&CodePair::new_string(343, &as_handle(self.__text_style_handle)))?;
+        writer.write_code_pair(&CodePair::new_i16(173, self.text_left_attachment_type))?;
+        writer.write_code_pair(&CodePair::new_i32(95, self.text_right_attachment_type))?;
+        writer.write_code_pair(&CodePair::new_i16(174, self.text_angle_type))?;
+        writer.write_code_pair(&CodePair::new_i16(175, self.text_alignment_type))?;
+        writer.write_code_pair(&CodePair::new_i32(92, self.text_color))?;
+        writer.write_code_pair(&CodePair::new_bool(292, self.enable_frame_text))?;
+        if self.__block_content_handle != 0 {
+            writer.write_code_pair(&CodePair::new_string(344, &as_handle(self.__block_content_handle)))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_i32(93, self.block_content_color))?;
+        writer.write_code_pair(&CodePair::new_f64(10, self.block_content_scale.x))?;
+        writer.write_code_pair(&CodePair::new_f64(20, self.block_content_scale.y))?;
+        writer.write_code_pair(&CodePair::new_f64(30, self.block_content_scale.z))?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.block_content_rotation))?;
+        writer.write_code_pair(&CodePair::new_i16(176, self.block_content_connection_type))?;
+        writer.write_code_pair(&CodePair::new_bool(293, self.enable_annotation_scale))?;
+        for arrowhead_override in &self.arrowhead_overrides {
+            writer.write_code_pair(&CodePair::new_i32(94, arrowhead_override.index))?;
+            writer.write_code_pair(&CodePair::new_string(345, &as_handle(arrowhead_override.arrowhead_handle)))?;
+        }
+
+        for attribute in &self.block_attributes {
+            writer.write_code_pair(&CodePair::new_string(330, &as_handle(attribute.attribute_definition_handle)))?;
+            writer.write_code_pair(&CodePair::new_i16(177, attribute.index))?;
+            writer.write_code_pair(&CodePair::new_f64(44, attribute.width))?;
+            writer.write_code_pair(&CodePair::new_string(302, &attribute.text))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_bool(294, self.is_text_direction_negative))?;
+        writer.write_code_pair(&CodePair::new_i16(178, self.text_align_in_ipe))?;
+        writer.write_code_pair(&CodePair::new_i16(179, self.text_attachment_point))?;
+        writer.write_code_pair(&CodePair::new_f64(45, self.scale))?;
+        if *version >= AcadVersion::R2010 {
+            writer.write_code_pair(&CodePair::new_i16(271, self.text_attachment_direction as i16))?;
+            writer.write_code_pair(&CodePair::new_i16(272, self.bottom_text_attachment_direction as i16))?;
+            writer.write_code_pair(&CodePair::new_i16(273, self.top_text_attachment_direction as i16))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_point<T>(writer: &mut CodePairWriter<T>, code: i32, x: f64, y: f64, z: f64) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_f64(code, x))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 10, y))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 20, z))?;
+    Ok(())
+}
+
+fn write_context_data<T>(writer: &mut CodePairWriter<T>, version: &AcadVersion, context: &MLeaderContextData) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_str(300, CONTEXT_DATA_START))?;
+    writer.write_code_pair(&CodePair::new_f64(40, context.content_scale))?;
+    let p = &context.content_base_point;
+    write_point(writer, 10, p.x, p.y, p.z)?;
+    writer.write_code_pair(&CodePair::new_f64(41, context.text_height))?;
+    writer.write_code_pair(&CodePair::new_f64(140, context.arrowhead_size))?;
+    writer.write_code_pair(&CodePair::new_f64(145, context.landing_gap))?;
+    writer.write_code_pair(&CodePair::new_i16(174, context.text_left_attachment_type))?;
+    writer.write_code_pair(&CodePair::new_i16(175, context.text_right_attachment_type))?;
+    writer.write_code_pair(&CodePair::new_i16(176, context.text_alignment_type))?;
+    writer.write_code_pair(&CodePair::new_i16(177, context.block_content_connection_type))?;
+    writer.write_code_pair(&CodePair::new_bool(290, context.text_content.is_some()))?;
+    if let Some(ref text) = context.text_content {
+        writer.write_code_pair(&CodePair::new_string(304, &text.text))?;
+        write_point(writer, 11, text.normal.x, text.normal.y, text.normal.z)?;
+        writer.write_code_pair(&CodePair::new_string(340, &as_handle(text.text_style_handle)))?;
+        write_point(writer, 12, text.location.x, text.location.y, text.location.z)?;
+        write_point(writer, 13, text.direction.x, text.direction.y, text.direction.z)?;
+        writer.write_code_pair(&CodePair::new_f64(42, text.rotation))?;
+        writer.write_code_pair(&CodePair::new_f64(43, text.boundary_width))?;
+        writer.write_code_pair(&CodePair::new_f64(44, text.boundary_height))?;
+        writer.write_code_pair(&CodePair::new_f64(45, text.line_spacing_factor))?;
+        writer.write_code_pair(&CodePair::new_i16(170, text.line_spacing_style))?;
+        writer.write_code_pair(&CodePair::new_i32(90, text.color))?;
+        writer.write_code_pair(&CodePair::new_i16(171, text.alignment))?;
+        writer.write_code_pair(&CodePair::new_i16(172, text.flow_direction))?;
+        writer.write_code_pair(&CodePair::new_i32(91, text.background_color))?;
+        writer.write_code_pair(&CodePair::new_f64(141, text.background_scale_factor))?;
+        writer.write_code_pair(&CodePair::new_i32(92, text.background_transparency))?;
+        writer.write_code_pair(&CodePair::new_bool(291, text.is_background_enabled))?;
+        writer.write_code_pair(&CodePair::new_bool(292, text.is_background_mask_fill_on))?;
+        writer.write_code_pair(&CodePair::new_i16(173, text.column_type))?;
+        writer.write_code_pair(&CodePair::new_bool(293, text.is_text_height_automatic))?;
+        writer.write_code_pair(&CodePair::new_f64(142, text.column_width))?;
+        writer.write_code_pair(&CodePair::new_f64(143, text.column_gutter))?;
+        writer.write_code_pair(&CodePair::new_bool(294, text.is_column_flow_reversed))?;
+        for column_size in &text.column_sizes {
+            writer.write_code_pair(&CodePair::new_f64(144, *column_size))?;
+        }
+
+        writer.write_code_pair(&CodePair::new_bool(295, text.use_word_break))?;
+    }
+
+    writer.write_code_pair(&CodePair::new_bool(296, context.block_content.is_some()))?;
+    if let Some(ref block) = context.block_content {
+        writer.write_code_pair(&CodePair::new_string(341, &as_handle(block.block_record_handle)))?;
+        write_point(writer, 14, block.normal.x, block.normal.y, block.normal.z)?;
+        write_point(writer, 15, block.location.x, block.location.y, block.location.z)?;
+        write_point(writer, 16, block.scale.x, block.scale.y, block.scale.z)?;
+        writer.write_code_pair(&CodePair::new_f64(46, block.rotation))?;
+        writer.write_code_pair(&CodePair::new_i32(93, block.color))?;
+        for value in block.transformation_matrix.get_values() {
+            writer.write_code_pair(&CodePair::new_f64(47, value))?;
+        }
+    }
+
+    let p = &context.plane_origin;
+    write_point(writer, 110, p.x, p.y, p.z)?;
+    let v = &context.plane_x_axis;
+    write_point(writer, 111, v.x, v.y, v.z)?;
+    let v = &context.plane_y_axis;
+    write_point(writer, 112, v.x, v.y, v.z)?;
+    writer.write_code_pair(&CodePair::new_bool(297, context.is_plane_normal_reversed))?;
+    for leader in &context.leaders {
+        write_leader(writer, version, leader)?;
+    }
+
+    if *version >= AcadVersion::R2010 {
+        writer.write_code_pair(&CodePair::new_i16(272, context.bottom_text_attachment_direction as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(273, context.top_text_attachment_direction as i16))?;
+    }
+
+    writer.write_code_pair(&CodePair::new_str(301, GROUP_END))?;
+    Ok(())
+}
+
+fn write_leader<T>(writer: &mut CodePairWriter<T>, version: &AcadVersion, leader: &MLeaderLeader) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_str(302, LEADER_START))?;
+    writer.write_code_pair(&CodePair::new_bool(290, leader.has_set_last_leader_line_point))?;
+    writer.write_code_pair(&CodePair::new_bool(291, leader.has_set_dogleg_vector))?;
+    let p = &leader.last_leader_line_point;
+    write_point(writer, 10, p.x, p.y, p.z)?;
+    let v = &leader.dogleg_vector;
+    write_point(writer, 11, v.x, v.y, v.z)?;
+    for leader_break in &leader.breaks {
+        write_point(writer, 12, leader_break.start.x, leader_break.start.y, leader_break.start.z)?;
+        write_point(writer, 13, leader_break.end.x, leader_break.end.y, leader_break.end.z)?;
+    }
+
+    writer.write_code_pair(&CodePair::new_i32(90, leader.branch_index))?;
+    writer.write_code_pair(&CodePair::new_f64(40, leader.dogleg_length))?;
+    for line in &leader.lines {
+        writer.write_code_pair(&CodePair::new_str(304, LEADER_LINE_START))?;
+        for vertex in &line.vertices {
+            write_point(writer, 10, vertex.x, vertex.y, vertex.z)?;
+        }
+
+        for line_break in &line.breaks {
+            writer.write_code_pair(&CodePair::new_i32(90, line_break.index))?;
+            write_point(writer, 11, line_break.start.x, line_break.start.y, line_break.start.z)?;
+            write_point(writer, 12, line_break.end.x, line_break.end.y, line_break.end.z)?;
+        }
+
+        writer.write_code_pair(&CodePair::new_i32(91, line.index))?;
+        writer.write_code_pair(&CodePair::new_str(305, GROUP_END))?;
+    }
+
+    if *version >= AcadVersion::R2010 {
+        writer.write_code_pair(&CodePair::new_i16(271, leader.attachment_direction as i16))?;
+    }
+
+    writer.write_code_pair(&CodePair::new_str(303, GROUP_END))?;
+    Ok(())
+}
+
+// The `{`-bracketed groups of an `MLeader` end at their closing code.
+impl<'a> CodePairCursor<'a> {
+    fn read_m_leader_context_data(&mut self) -> DxfResult<MLeaderContextData> {
+        let mut context = MLeaderContextData::default();
+        while let Some(pair) = self.next() {
+            match pair.code {
+                301 => break,
+                40 => { context.content_scale = pair.value.assert_f64()?; },
+                10 => { context.content_base_point.x = pair.value.assert_f64()?; },
+                20 => { context.content_base_point.y = pair.value.assert_f64()?; },
+                30 => { context.content_base_point.z = pair.value.assert_f64()?; },
+                41 => { context.text_height = pair.value.assert_f64()?; },
+                140 => { context.arrowhead_size = pair.value.assert_f64()?; },
+                145 => { context.landing_gap = pair.value.assert_f64()?; },
+                174 => { context.text_left_attachment_type = pair.value.assert_i16()?; },
+                175 => { context.text_right_attachment_type = pair.value.assert_i16()?; },
+                176 => { context.text_alignment_type = pair.value.assert_i16()?; },
+                177 => { context.block_content_connection_type = pair.value.assert_i16()?; },
+                290 if pair.value.assert_bool()? => { context.text_content = Some(self.read_m_leader_text_content()?); },
+                296 if pair.value.assert_bool()? => { context.block_content = Some(self.read_m_leader_block_content()?); },
+                110 => { context.plane_origin.x = pair.value.assert_f64()?; },
+                120 => { context.plane_origin.y = pair.value.assert_f64()?; },
+                130 => { context.plane_origin.z = pair.value.assert_f64()?; },
+                111 => { context.plane_x_axis.x = pair.value.assert_f64()?; },
+                121 => { context.plane_x_axis.y = pair.value.assert_f64()?; },
+                131 => { context.plane_x_axis.z = pair.value.assert_f64()?; },
+                112 => { context.plane_y_axis.x = pair.value.assert_f64()?; },
+                122 => { context.plane_y_axis.y = pair.value.assert_f64()?; },
+                132 => { context.plane_y_axis.z = pair.value.assert_f64()?; },
+                297 => { context.is_plane_normal_reversed = pair.value.assert_bool()?; },
+                302 => { context.leaders.push(self.read_m_leader_leader()?); },
+                272 => { context.bottom_text_attachment_direction = try_result!(BottomTextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                273 => { context.top_text_attachment_direction = try_result!(TopTextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(context)
+    }
+    fn read_m_leader_text_content(&mut self) -> DxfResult<MLeaderTextContent> {
+        let mut text = MLeaderTextContent::default();
+        // the block content flag follows the text content
+        while let Some(code) = self.peek_code(0) {
+            if code == 296 || code == 301 {
+                break;
+            }
+
+            let pair = self.next().unwrap();
+            match pair.code {
+                304 => { text.text = pair.value.assert_string()?; },
+                11 => { text.normal.x = pair.value.assert_f64()?; },
+                21 => { text.normal.y = pair.value.assert_f64()?; },
+                31 => { text.normal.z = pair.value.assert_f64()?; },
+                340 => { text.text_style_handle = as_u32(pair.value.assert_string()?)?; },
+                12 => { text.location.x = pair.value.assert_f64()?; },
+                22 => { text.location.y = pair.value.assert_f64()?; },
+                32 => { text.location.z = pair.value.assert_f64()?; },
+                13 => { text.direction.x = pair.value.assert_f64()?; },
+                23 => { text.direction.y = pair.value.assert_f64()?; },
+                33 => { text.direction.z = pair.value.assert_f64()?; },
+                42 => { text.rotation = pair.value.assert_f64()?; },
+                43 => { text.boundary_width = pair.value.assert_f64()?; },
+                44 => { text.boundary_height = pair.value.assert_f64()?; },
+                45 => { text.line_spacing_factor = pair.value.assert_f64()?; },
+                170 => { text.line_spacing_style = pair.value.assert_i16()?; },
+                90 => { text.color = pair.value.assert_i32()?; },
+                171 => { text.alignment = pair.value.assert_i16()?; },
+                172 => { text.flow_direction = pair.value.assert_i16()?; },
+                91 => { text.background_color = pair.value.assert_i32()?; },
+                141 => { text.background_scale_factor = pair.value.assert_f64()?; },
+                92 => { text.background_transparency = pair.value.assert_i32()?; },
+                291 => { text.is_background_enabled = pair.value.assert_bool()?; },
+                292 => { text.is_background_mask_fill_on = pair.value.assert_bool()?; },
+                173 => { text.column_type = pair.value.assert_i16()?; },
+                293 => { text.is_text_height_automatic = pair.value.assert_bool()?; },
+                142 => { text.column_width = pair.value.assert_f64()?; },
+                143 => { text.column_gutter = pair.value.assert_f64()?; },
+                294 => { text.is_column_flow_reversed = pair.value.assert_bool()?; },
+                144 => { text.column_sizes.push(pair.value.assert_f64()?); },
+                295 => { text.use_word_break = pair.value.assert_bool()?; },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(text)
+    }
+    fn read_m_leader_block_content(&mut self) -> DxfResult<MLeaderBlockContent> {
+        let mut block = MLeaderBlockContent::default();
+        let mut matrix_values = vec![];
+        // the plane of the leaders follows the block content
+        while let Some(code) = self.peek_code(0) {
+            if code == 110 || code == 301 {
+                break;
+            }
+
+            let pair = self.next().unwrap();
+            match pair.code {
+                341 => { block.block_record_handle = as_u32(pair.value.assert_string()?)?; },
+                14 => { block.normal.x = pair.value.assert_f64()?; },
+                24 => { block.normal.y = pair.value.assert_f64()?; },
+                34 => { block.normal.z = pair.value.assert_f64()?; },
+                15 => { block.location.x = pair.value.assert_f64()?; },
+                25 => { block.location.y = pair.value.assert_f64()?; },
+                35 => { block.location.z = pair.value.assert_f64()?; },
+                16 => { block.scale.x = pair.value.assert_f64()?; },
+                26 => { block.scale.y = pair.value.assert_f64()?; },
+                36 => { block.scale.z = pair.value.assert_f64()?; },
+                46 => { block.rotation = pair.value.assert_f64()?; },
+                93 => { block.color = pair.value.assert_i32()?; },
+                47 => { matrix_values.push(pair.value.assert_f64()?); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        if !matrix_values.is_empty() {
+            block.transformation_matrix.from_vec(&matrix_values);
+        }
+
+        Ok(block)
+    }
+    fn read_m_leader_leader(&mut self) -> DxfResult<MLeaderLeader> {
+        let mut leader = MLeaderLeader::default();
+        while let Some(pair) = self.next() {
+            match pair.code {
+                303 => break,
+                290 => { leader.has_set_last_leader_line_point = pair.value.assert_bool()?; },
+                291 => { leader.has_set_dogleg_vector = pair.value.assert_bool()?; },
+                10 => { leader.last_leader_line_point.x = pair.value.assert_f64()?; },
+                20 => { leader.last_leader_line_point.y = pair.value.assert_f64()?; },
+                30 => { leader.last_leader_line_point.z = pair.value.assert_f64()?; },
+                11 => { leader.dogleg_vector.x = pair.value.assert_f64()?; },
+                21 => { leader.dogleg_vector.y = pair.value.assert_f64()?; },
+                31 => { leader.dogleg_vector.z = pair.value.assert_f64()?; },
+                12 => {
+                    leader.breaks.push(MLeaderBreak::default());
+                    vec_last!(leader.breaks).start.x = pair.value.assert_f64()?;
+                },
+                22 => { vec_last!(leader.breaks).start.y = pair.value.assert_f64()?; },
+                32 => { vec_last!(leader.breaks).start.z = pair.value.assert_f64()?; },
+                13 => { vec_last!(leader.breaks).end.x = pair.value.assert_f64()?; },
+                23 => { vec_last!(leader.breaks).end.y = pair.value.assert_f64()?; },
+                33 => { vec_last!(leader.breaks).end.z = pair.value.assert_f64()?; },
+                90 => { leader.branch_index = pair.value.assert_i32()?; },
+                40 => { leader.dogleg_length = pair.value.assert_f64()?; },
+                304 => { leader.lines.push(self.read_m_leader_line()?); },
+                271 => { leader.attachment_direction = try_result!(TextAttachmentDirection::from_i16(pair.value.assert_i16()?)); },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(leader)
+    }
+    fn read_m_leader_line(&mut self) -> DxfResult<MLeaderLine> {
+        let mut line = MLeaderLine::default();
+        while let Some(pair) = self.next() {
+            match pair.code {
+                305 => break,
+                10 => {
+                    line.vertices.push(Point::origin());
+                    vec_last!(line.vertices).x = pair.value.assert_f64()?;
+                },
+                20 => { vec_last!(line.vertices).y = pair.value.assert_f64()?; },
+                30 => { vec_last!(line.vertices).z = pair.value.assert_f64()?; },
+                90 => { line.breaks.push(MLeaderBreak { index: pair.value.assert_i32()?, .. Default::default() }); },
+                11 => { vec_last!(line.breaks).start.x = pair.value.assert_f64()?; },
+                21 => { vec_last!(line.breaks).start.y = pair.value.assert_f64()?; },
+                31 => { vec_last!(line.breaks).start.z = pair.value.assert_f64()?; },
+                12 => { vec_last!(line.breaks).end.x = pair.value.assert_f64()?; },
+                22 => { vec_last!(line.breaks).end.y = pair.value.assert_f64()?; },
+                32 => { vec_last!(line.breaks).end.z = pair.value.assert_f64()?; },
+                91 => { line.index = pair.value.assert_i32()?; },
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(line)
+    }
+}
@@ -0,0 +1,547 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::Color;
+
+use entities::MText;
+use text_codes::{
+    decode_special_character,
+    is_percent_escaped_before,
+};
+
+// The longest string AutoCAD writes in a single `MText` text chunk.
+const MTEXT_CHUNK_LENGTH: usize = 250;
+
+/// The font of a run of `MText`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MTextFont {
+    /// The font family of a TrueType font (`\f`) or the file name of an SHX font (`\F`).
+    pub name: String,
+    /// Whether the font is an SHX font.
+    pub is_shx: bool,
+    pub is_bold: bool,
+    pub is_italic: bool,
+    /// The Windows character set of a TrueType font.
+    pub character_set: i32,
+    /// The pitch and family of a TrueType font.
+    pub pitch_and_family: i32,
+}
+
+/// The color of a run of `MText`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MTextColor {
+    /// An indexed color from a `\C` code.
+    Index(Color),
+    /// A 24-bit color value exactly as written in a `\c` code.
+    TrueColor(i32),
+}
+
+/// The character formatting of a run of `MText`.  Values that are `None` or `false` are inherited
+/// from the `MText` entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MTextFormat {
+    /// The font from a `\f` or `\F` code.
+    pub font: Option<MTextFont>,
+    /// The absolute text height from a `\H` code.
+    pub height: Option<f64>,
+    /// The factor applied to the inherited text height from a `\H...x` code.
+    pub relative_height: Option<f64>,
+    /// The color from a `\C` or `\c` code.
+    pub color: Option<MTextColor>,
+    /// Underlining from `\L` and `\l` codes.
+    pub is_underlined: bool,
+    /// Overlining from `\O` and `\o` codes.
+    pub is_overlined: bool,
+    /// Strike-through from `\K` and `\k` codes.
+    pub is_struck_through: bool,
+    /// The width factor from a `\W` code.
+    pub width_factor: Option<f64>,
+    /// The oblique angle in degrees from a `\Q` code.
+    pub oblique_angle: Option<f64>,
+    /// The character spacing factor from a `\T` code.
+    pub tracking: Option<f64>,
+    /// The vertical alignment from a `\A` code; 0 is bottom, 1 is center, and 2 is top.
+    pub vertical_alignment: Option<i16>,
+}
+
+/// The separator of a stacked `MText` fraction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MTextStackKind {
+    /// A horizontal fraction bar, written with `/`.
+    Horizontal,
+    /// A diagonal fraction bar, written with `#`.
+    Diagonal,
+    /// Tolerance-style stacking without a bar, written with `^`.
+    Tolerance,
+}
+
+/// The content of a run of `MText`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MTextContent {
+    Text(String),
+    /// A stacked fraction from a `\S` code.
+    Stack {
+        upper: String,
+        lower: String,
+        kind: MTextStackKind,
+    },
+}
+
+/// A piece of `MText` content with uniform formatting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MTextRun {
+    pub format: MTextFormat,
+    pub content: MTextContent,
+}
+
+/// A paragraph of `MText`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MTextParagraph {
+    /// Whether the paragraph starts a new column (`\N`).
+    pub starts_new_column: bool,
+    /// The paragraph properties (indents, tab stops, and alignment) exactly as written in a `\p`
+    /// code, without the code and the terminating `;`.
+    pub properties: String,
+    pub runs: Vec<MTextRun>,
+}
+
+/// The structure of the inline formatting codes of `MText` as paragraphs of formatted runs.
+///
+/// Special characters are decoded: `%%c`, `%%d`, and `%%p` become `⌀`, `°`, and `±`, `%%nnn` the
+/// character with the decimal code `nnn`, `\~` a non-breaking space, and `\U+nnnn` the Unicode
+/// character.  `\M+` multi-byte codes can't be decoded without the drawing's code page and are
+/// dropped, as are unknown codes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MTextDocument {
+    pub paragraphs: Vec<MTextParagraph>,
+}
+
+impl MTextDocument {
+    /// Parses the formatting codes of `MText` content.
+    pub fn parse(text: &str) -> MTextDocument {
+        MTextParser::new(text).parse()
+    }
+    /// Writes the document as `MText` content.  Each run with formatting is wrapped in its own
+    /// `{}` group and characters with a special meaning are escaped.
+    pub fn to_mtext(&self) -> String {
+        let mut result = String::new();
+        for (i, paragraph) in self.paragraphs.iter().enumerate() {
+            if i > 0 {
+                result.push_str(if paragraph.starts_new_column { "\\N" } else { "\\P" });
+            }
+
+            if !paragraph.properties.is_empty() {
+                result.push_str(&format!("\\p{};", paragraph.properties));
+            }
+
+            for (j, run) in paragraph.runs.iter().enumerate() {
+                let codes = format_codes(&run.format);
+                // unformatted runs are written back to back
+                let is_followed_by_text = codes.is_empty() && paragraph.runs.get(j + 1).map_or(false, |next| {
+                    format_codes(&next.format).is_empty() && match next.content {
+                        MTextContent::Text(_) => true,
+                        _ => false,
+                    }
+                });
+                if !codes.is_empty() {
+                    result.push('{');
+                    result.push_str(&codes);
+                }
+
+                match run.content {
+                    MTextContent::Text(ref text) => result.push_str(&escape_text(text, is_followed_by_text)),
+                    MTextContent::Stack { ref upper, ref lower, kind } => {
+                        let separator = match kind {
+                            MTextStackKind::Horizontal => '/',
+                            MTextStackKind::Diagonal => '#',
+                            MTextStackKind::Tolerance => '^',
+                        };
+                        result.push_str(&format!("\\S{}{}{};", escape_stack(upper), separator, escape_stack(lower)));
+                    },
+                }
+
+                if !codes.is_empty() {
+                    result.push('}');
+                }
+            }
+        }
+
+        result
+    }
+    /// The text without formatting where paragraphs are separated by `\n` and stacked fractions
+    /// are written as `upper/lower`.
+    pub fn plain_text(&self) -> String {
+        self.paragraphs.iter().map(|paragraph| {
+            paragraph.runs.iter().map(|run| match run.content {
+                MTextContent::Text(ref text) => text.clone(),
+                MTextContent::Stack { ref upper, ref lower, .. } => format!("{}/{}", upper, lower),
+            }).collect::<String>()
+        }).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl MText {
+    /// The complete content of the `MText`, which is split across `extended_text` and `text`.
+    pub fn full_text(&self) -> String {
+        let mut result = self.extended_text.concat();
+        result.push_str(&self.text);
+        result
+    }
+    /// Sets the complete content of the `MText`, splitting it across `extended_text` and `text` the
+    /// way AutoCAD does.
+    pub fn set_full_text(&mut self, value: &str) {
+        let chars = value.chars().collect::<Vec<_>>();
+        let mut chunks = chars.chunks(MTEXT_CHUNK_LENGTH).map(|c| c.iter().collect::<String>()).collect::<Vec<_>>();
+        self.text = chunks.pop().unwrap_or_default();
+        self.extended_text = chunks;
+    }
+    /// Parses the formatting codes of the complete content.
+    pub fn document(&self) -> MTextDocument {
+        MTextDocument::parse(&self.full_text())
+    }
+    /// Replaces the content with the specified document.
+    pub fn set_document(&mut self, document: &MTextDocument) {
+        self.set_full_text(&document.to_mtext());
+    }
+    /// The content without formatting codes; see `MTextDocument::plain_text`.
+    pub fn plain_text(&self) -> String {
+        self.document().plain_text()
+    }
+}
+
+struct MTextParser {
+    chars: Vec<char>,
+    position: usize,
+    paragraphs: Vec<MTextParagraph>,
+    paragraph: MTextParagraph,
+    format: MTextFormat,
+    groups: Vec<MTextFormat>,
+    text: String,
+}
+
+impl MTextParser {
+    fn new(text: &str) -> Self {
+        MTextParser {
+            chars: text.chars().collect(),
+            position: 0,
+            paragraphs: vec![],
+            paragraph: MTextParagraph::default(),
+            format: MTextFormat::default(),
+            groups: vec![],
+            text: String::new(),
+        }
+    }
+    fn parse(mut self) -> MTextDocument {
+        while let Some(c) = self.next() {
+            match c {
+                '\\' => self.parse_code(),
+                '{' => {
+                    self.flush();
+                    self.groups.push(self.format.clone());
+                },
+                '}' => {
+                    self.flush();
+                    if let Some(format) = self.groups.pop() {
+                        self.format = format;
+                    }
+                },
+                '%' if self.peek() == Some('%') => self.parse_special_character(),
+                _ => self.text.push(c),
+            }
+        }
+
+        self.new_paragraph(false);
+        MTextDocument { paragraphs: self.paragraphs }
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.get(self.position).cloned();
+        self.position += 1;
+        c
+    }
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+    // Reads the argument of a code up to the terminating `;`.
+    fn argument(&mut self) -> String {
+        let mut result = String::new();
+        while let Some(c) = self.next() {
+            if c == ';' {
+                break;
+            }
+
+            result.push(c);
+        }
+
+        result
+    }
+    fn parse_code(&mut self) {
+        let code = match self.next() {
+            Some(c) => c,
+            None => return,
+        };
+        match code {
+            'P' | 'X' => self.new_paragraph(false),
+            'N' => self.new_paragraph(true),
+            '~' => self.text.push('\u{a0}'),
+            '\\' | '{' | '}' => self.text.push(code),
+            'L' => self.set_format(|f| f.is_underlined = true),
+            'l' => self.set_format(|f| f.is_underlined = false),
+            'O' => self.set_format(|f| f.is_overlined = true),
+            'o' => self.set_format(|f| f.is_overlined = false),
+            'K' => self.set_format(|f| f.is_struck_through = true),
+            'k' => self.set_format(|f| f.is_struck_through = false),
+            'f' | 'F' => {
+                let font = parse_font(&self.argument(), code == 'F');
+                self.set_format(|f| f.font = Some(font));
+            },
+            'H' => {
+                let argument = self.argument();
+                if argument.ends_with('x') || argument.ends_with('X') {
+                    if let Ok(factor) = argument[..argument.len() - 1].trim().parse::<f64>() {
+                        self.set_format(|f| match f.height {
+                            Some(height) => f.height = Some(height * factor),
+                            None => f.relative_height = Some(f.relative_height.unwrap_or(1.0) * factor),
+                        });
+                    }
+                }
+                else if let Ok(height) = argument.trim().parse::<f64>() {
+                    self.set_format(|f| {
+                        f.height = Some(height);
+                        f.relative_height = None;
+                    });
+                }
+            },
+            'C' => {
+                if let Ok(index) = self.argument().trim().parse::<i16>() {
+                    self.set_format(|f| f.color = Some(MTextColor::Index(Color::from_raw_value(index))));
+                }
+            },
+            'c' => {
+                if let Ok(value) = self.argument().trim().parse::<i32>() {
+                    self.set_format(|f| f.color = Some(MTextColor::TrueColor(value)));
+                }
+            },
+            'W' => {
+                let value = parse_factor(&self.argument());
+                self.set_format(|f| f.width_factor = value);
+            },
+            'Q' => {
+                let value = parse_factor(&self.argument());
+                self.set_format(|f| f.oblique_angle = value);
+            },
+            'T' => {
+                let value = parse_factor(&self.argument());
+                self.set_format(|f| f.tracking = value);
+            },
+            'A' => {
+                let value = self.argument().trim().parse::<i16>().ok();
+                self.set_format(|f| f.vertical_alignment = value);
+            },
+            'p' => self.paragraph.properties = self.argument(),
+            'S' => self.parse_stack(),
+            'U' if self.peek() == Some('+') => {
+                let digits = self.chars.iter().skip(self.position + 1).take(4).collect::<String>();
+                match u32::from_str_radix(&digits, 16).ok().and_then(::std::char::from_u32) {
+                    Some(c) if digits.len() == 4 => {
+                        self.position += 5;
+                        self.text.push(c);
+                    },
+                    _ => self.text.push_str("\\U"),
+                }
+            },
+            // multi-byte characters need the drawing's code page
+            'M' if self.peek() == Some('+') => self.position = (self.position + 6).min(self.chars.len()),
+            _ => (),
+        }
+    }
+    fn parse_special_character(&mut self) {
+        // the first `%` has already been read
+        match decode_special_character(&self.chars, self.position - 1) {
+            Some((c, length)) => {
+                self.position += length - 1;
+                self.text.push(c);
+            },
+            None => {
+                self.position += 1;
+                self.text.push_str("%%");
+            },
+        }
+    }
+    fn parse_stack(&mut self) {
+        let mut upper = String::new();
+        let mut lower = String::new();
+        let mut kind = None;
+        while let Some(c) = self.next() {
+            let c = match c {
+                ';' => break,
+                '\\' => match self.next() {
+                    Some(escaped) => escaped,
+                    None => break,
+                },
+                '/' | '#' | '^' if kind.is_none() => {
+                    kind = Some(match c {
+                        '/' => MTextStackKind::Horizontal,
+                        '#' => MTextStackKind::Diagonal,
+                        _ => MTextStackKind::Tolerance,
+                    });
+                    continue;
+                },
+                c => c,
+            };
+            if kind.is_none() { upper.push(c) } else { lower.push(c) }
+        }
+
+        self.flush();
+        self.paragraph.runs.push(MTextRun {
+            format: self.format.clone(),
+            content: MTextContent::Stack { upper, lower, kind: kind.unwrap_or(MTextStackKind::Horizontal) },
+        });
+    }
+    fn set_format<F>(&mut self, change: F)
+        where F: FnOnce(&mut MTextFormat) {
+
+        self.flush();
+        change(&mut self.format);
+    }
+    fn new_paragraph(&mut self, starts_new_column: bool) {
+        self.flush();
+        let paragraph = ::std::mem::take(&mut self.paragraph);
+        self.paragraphs.push(paragraph);
+        self.paragraph.starts_new_column = starts_new_column;
+    }
+    // Adds the pending text as a run, merging it with the previous run if that has the same format.
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let text = ::std::mem::take(&mut self.text);
+        if let Some(&mut MTextRun { ref format, content: MTextContent::Text(ref mut previous) }) = self.paragraph.runs.last_mut() {
+            if *format == self.format {
+                previous.push_str(&text);
+                return;
+            }
+        }
+
+        self.paragraph.runs.push(MTextRun {
+            format: self.format.clone(),
+            content: MTextContent::Text(text),
+        });
+    }
+}
+
+// Parses the argument of a `\f` or `\F` code, e.g., `Arial|b1|i0|c0|p34`.
+fn parse_font(argument: &str, is_shx: bool) -> MTextFont {
+    let mut parts = argument.split('|');
+    let mut font = MTextFont {
+        name: String::from(parts.next().unwrap_or("")),
+        is_shx,
+        .. Default::default()
+    };
+    for part in parts {
+        let value = part.get(1..).and_then(|v| v.parse::<i32>().ok()).unwrap_or(0);
+        match part.chars().next() {
+            Some('b') => font.is_bold = value != 0,
+            Some('i') => font.is_italic = value != 0,
+            Some('c') => font.character_set = value,
+            Some('p') => font.pitch_and_family = value,
+            _ => (),
+        }
+    }
+
+    font
+}
+
+// Parses the argument of a code like `\W0.8;` or `\W0.8x;`.
+fn parse_factor(argument: &str) -> Option<f64> {
+    argument.trim().trim_end_matches(|c| c == 'x' || c == 'X').parse().ok()
+}
+
+fn format_codes(format: &MTextFormat) -> String {
+    let mut codes = String::new();
+    if let Some(ref font) = format.font {
+        if font.is_shx {
+            codes.push_str(&format!("\\F{};", font.name));
+        }
+        else {
+            codes.push_str(&format!("\\f{}|b{}|i{}|c{}|p{};", font.name, font.is_bold as i32, font.is_italic as i32, font.character_set, font.pitch_and_family));
+        }
+    }
+
+    if let Some(height) = format.height {
+        codes.push_str(&format!("\\H{};", height));
+    }
+
+    if let Some(factor) = format.relative_height {
+        codes.push_str(&format!("\\H{}x;", factor));
+    }
+
+    match format.color {
+        Some(MTextColor::Index(ref color)) => codes.push_str(&format!("\\C{};", color.get_raw_value())),
+        Some(MTextColor::TrueColor(value)) => codes.push_str(&format!("\\c{};", value)),
+        None => (),
+    }
+
+    if let Some(width_factor) = format.width_factor {
+        codes.push_str(&format!("\\W{};", width_factor));
+    }
+
+    if let Some(oblique_angle) = format.oblique_angle {
+        codes.push_str(&format!("\\Q{};", oblique_angle));
+    }
+
+    if let Some(tracking) = format.tracking {
+        codes.push_str(&format!("\\T{};", tracking));
+    }
+
+    if let Some(alignment) = format.vertical_alignment {
+        codes.push_str(&format!("\\A{};", alignment));
+    }
+
+    if format.is_underlined {
+        codes.push_str("\\L");
+    }
+
+    if format.is_overlined {
+        codes.push_str("\\O");
+    }
+
+    if format.is_struck_through {
+        codes.push_str("\\K");
+    }
+
+    codes
+}
+
+fn escape_text(text: &str, is_followed_by_text: bool) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '{' => result.push_str("\\{"),
+            '}' => result.push_str("\\}"),
+            '\n' => result.push_str("\\P"),
+            '\u{a0}' => result.push_str("\\~"),
+            '\u{2300}' => result.push_str("%%c"),
+            '\u{b0}' => result.push_str("%%d"),
+            '\u{b1}' => result.push_str("%%p"),
+            '%' if chars.peek().map_or(is_followed_by_text, |&next| is_percent_escaped_before(next)) => result.push_str("%%%"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+fn escape_stack(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        if c == '/' || c == '#' || c == '^' || c == ';' || c == '\\' {
+            result.push('\\');
+        }
+
+        result.push(c);
+    }
+
+    result
+}
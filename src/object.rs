@@ -15,8 +15,12 @@ use self::chrono::Duration;
 
 use ::{
     CodePair,
+    CodePairValue,
     Color,
     DataTableValue,
+    Drawing,
+    DrawingItem,
+    DrawingItemMut,
     DxfError,
     DxfResult,
     Point,
@@ -26,12 +30,44 @@ use ::{
 };
 
 use code_pair_writer::CodePairWriter;
+use entities::*;
 use enums::*;
 use handle_tracker::HandleTracker;
 use objects::*;
 use helper_functions::*;
 
 
+//------------------------------------------------------------------------------
+//                                                          BlockVisibilityState
+//------------------------------------------------------------------------------
+/// A named visibility state of a dynamic block and the entities it shows.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockVisibilityState {
+    pub name: String,
+    #[doc(hidden)]
+    pub __entities_handle: Vec<u32>,
+}
+
+impl BlockVisibilityState {
+    pub fn new(name: String) -> Self {
+        BlockVisibilityState {
+            name: name,
+            __entities_handle: vec![],
+        }
+    }
+    pub fn get_entities<'a>(&self, drawing: &'a Drawing) -> Vec<&'a Entity> {
+        self.__entities_handle.iter().filter_map(|&h| {
+            match drawing.get_item_by_handle(h) {
+                Some(DrawingItem::Entity(val)) => Some(val),
+                _ => None,
+            }
+        }).collect()
+    }
+    pub fn add_entities<'a>(&mut self, item: &'a mut Entity, drawing: &'a mut Drawing) {
+        self.__entities_handle.push(drawing.assign_and_get_handle(&mut DrawingItemMut::Entity(item)));
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                  GeoMeshPoint
 //------------------------------------------------------------------------------
@@ -99,6 +135,89 @@ impl VbaProject {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                                 XRecordObject
+//------------------------------------------------------------------------------
+// codes used for a record holding a single typed value
+const XRECORD_STRING: i32 = 1;
+const XRECORD_POINT_X: i32 = 10;
+const XRECORD_POINT_Y: i32 = 20;
+const XRECORD_POINT_Z: i32 = 30;
+const XRECORD_DOUBLE: i32 = 40;
+const XRECORD_INTEGER: i32 = 90;
+const XRECORD_HANDLE: i32 = 340;
+
+impl XRecordObject {
+    /// Gets the first string value (code 1).
+    pub fn get_string(&self) -> Option<String> {
+        self.get_value(XRECORD_STRING)?.assert_string().ok()
+    }
+    /// Sets the first string value (code 1), adding it if there isn't one.  Other values are kept.
+    pub fn set_string(&mut self, value: &str) {
+        self.set_value(CodePair::new_str(XRECORD_STRING, value), None);
+    }
+    /// Gets the first real value (code 40).
+    pub fn get_f64(&self) -> Option<f64> {
+        self.get_value(XRECORD_DOUBLE)?.assert_f64().ok()
+    }
+    /// Sets the first real value (code 40), adding it if there isn't one.  Other values are kept.
+    pub fn set_f64(&mut self, value: f64) {
+        self.set_value(CodePair::new_f64(XRECORD_DOUBLE, value), None);
+    }
+    /// Gets the first integer value (code 90).
+    pub fn get_i32(&self) -> Option<i32> {
+        self.get_value(XRECORD_INTEGER)?.assert_i32().ok()
+    }
+    /// Sets the first integer value (code 90), adding it if there isn't one.  Other values are kept.
+    pub fn set_i32(&mut self, value: i32) {
+        self.set_value(CodePair::new_i32(XRECORD_INTEGER, value), None);
+    }
+    /// Gets the first point (codes 10, 20, and 30).  A missing Z value is treated as 0.0.
+    pub fn get_point(&self) -> Option<Point> {
+        let x = self.get_value(XRECORD_POINT_X)?.assert_f64().ok()?;
+        let y = self.get_value(XRECORD_POINT_Y)?.assert_f64().ok()?;
+        let z = match self.get_value(XRECORD_POINT_Z) {
+            Some(value) => value.assert_f64().ok()?,
+            None => 0.0,
+        };
+        Some(Point::new(x, y, z))
+    }
+    /// Sets the first point (codes 10, 20, and 30), adding any missing coordinates.  Other values
+    /// are kept.
+    pub fn set_point(&mut self, value: &Point) {
+        let x = self.set_value(CodePair::new_f64(XRECORD_POINT_X, value.x), None);
+        let y = self.set_value(CodePair::new_f64(XRECORD_POINT_Y, value.y), Some(x));
+        self.set_value(CodePair::new_f64(XRECORD_POINT_Z, value.z), Some(y));
+    }
+    /// Gets the first hard pointer (code 340).
+    pub fn get_handle(&self) -> Option<u32> {
+        as_u32(self.get_value(XRECORD_HANDLE)?.assert_string().ok()?).ok()
+    }
+    /// Sets the first hard pointer (code 340) to the specified handle, adding it if there isn't one.
+    /// Other values are kept.
+    pub fn set_handle(&mut self, handle: u32) {
+        self.set_value(CodePair::new_string(XRECORD_HANDLE, &as_handle(handle)), None);
+    }
+    fn get_value(&self, code: i32) -> Option<&CodePairValue> {
+        self.data_pairs.iter().find(|p| p.code == code).map(|p| &p.value)
+    }
+    // Replaces the first pair with the same code or, if there isn't one, inserts the pair after the
+    // specified index or at the end.  Returns the index of the pair.
+    fn set_value(&mut self, pair: CodePair, after: Option<usize>) -> usize {
+        match self.data_pairs.iter().position(|p| p.code == pair.code) {
+            Some(index) => {
+                self.data_pairs[index] = pair;
+                index
+            },
+            None => {
+                let index = after.map_or(self.data_pairs.len(), |i| i + 1);
+                self.data_pairs.insert(index, pair);
+                index
+            },
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                  ObjectCommon
 //------------------------------------------------------------------------------
@@ -263,6 +382,38 @@ impl Object {
         where I: Iterator<Item = DxfResult<CodePair>> {
 
         match self.specific {
+            ObjectType::BlockLinearParameter(ref mut param) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    if !param.apply_code_pair(&pair)? {
+                        self.common.apply_individual_pair(&pair, iter)?;
+                    }
+                }
+            },
+            ObjectType::BlockLookupParameter(ref mut param) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    if !param.apply_code_pair(&pair)? {
+                        self.common.apply_individual_pair(&pair, iter)?;
+                    }
+                }
+            },
+            ObjectType::BlockRotationParameter(ref mut param) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    if !param.apply_code_pair(&pair)? {
+                        self.common.apply_individual_pair(&pair, iter)?;
+                    }
+                }
+            },
+            ObjectType::BlockVisibilityParameter(ref mut param) => {
+                loop {
+                    let pair = next_pair!(iter);
+                    if !param.apply_code_pair(&pair)? {
+                        self.common.apply_individual_pair(&pair, iter)?;
+                    }
+                }
+            },
             ObjectType::DataTable(ref mut data) => {
                 let mut read_column_count = false;
                 let mut read_row_count = false;
@@ -930,6 +1081,10 @@ impl Object {
         where T: Write {
 
         match self.specific {
+            ObjectType::BlockLinearParameter(ref param) => { param.write(writer)?; },
+            ObjectType::BlockLookupParameter(ref param) => { param.write(writer)?; },
+            ObjectType::BlockRotationParameter(ref param) => { param.write(writer)?; },
+            ObjectType::BlockVisibilityParameter(ref param) => { param.write(writer)?; },
             ObjectType::DataTable(ref data) => {
                 writer.write_code_pair(&CodePair::new_str(100, "AcDbDataTable"))?;
                 writer.write_code_pair(&CodePair::new_i16(70, data.field))?;
@@ -1,13 +1,19 @@
 // Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
 
+use std::ops::{
+    Add,
+    Sub,
+};
+
 use ::{
     CodePair,
     DxfError,
     DxfResult,
+    Vector,
 };
 
 /// Represents a simple point in Cartesian space.
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Point {
     /// The X value of the point.
     pub x: f64,
@@ -30,6 +36,14 @@ impl Point {
     pub fn origin() -> Point {
         Point::new(0.0, 0.0, 0.0)
     }
+    /// Returns the distance between this point and `other`.
+    pub fn distance_to(&self, other: &Point) -> f64 {
+        (*other - *self).length()
+    }
+    /// Returns the vector from the origin to this point.
+    pub fn as_vector(&self) -> Vector {
+        Vector::new(self.x, self.y, self.z)
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.value.assert_f64()?,
@@ -41,3 +55,24 @@ impl Point {
         Ok(())
     }
 }
+
+impl Add<Vector> for Point {
+    type Output = Point;
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.x, self.y + v.y, self.z + v.z)
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+    fn sub(self, v: Vector) -> Point {
+        Point::new(self.x - v.x, self.y - v.y, self.z - v.z)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::Point;
+
+use entities::*;
+
+/// A face of a `PolyfaceMesh`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolyfaceFace {
+    /// The indices into `PolyfaceMesh::vertices` of the face's corners.  A `Polyline` stores at most
+    /// four corners per face.
+    pub vertex_indices: Vec<usize>,
+    /// Whether the edge from each corner to the next is hidden, in the same order as
+    /// `vertex_indices`.
+    pub invisible_edges: Vec<bool>,
+}
+
+/// The vertices and faces of a `Polyline` flagged as a polyface mesh.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolyfaceMesh {
+    pub vertices: Vec<Point>,
+    pub faces: Vec<PolyfaceFace>,
+}
+
+/// The M×N grid of vertices of a `Polyline` flagged as a 3D polygon mesh.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolygonMesh {
+    pub m_vertex_count: usize,
+    pub n_vertex_count: usize,
+    /// The vertices in rows of `n_vertex_count`.
+    pub vertices: Vec<Point>,
+    /// Whether the last row connects back to the first.
+    pub is_closed_in_m_direction: bool,
+    /// Whether the last column connects back to the first.
+    pub is_closed_in_n_direction: bool,
+}
+
+impl PolyfaceFace {
+    /// Creates a face with all edges visible.
+    pub fn new(vertex_indices: Vec<usize>) -> Self {
+        let invisible_edges = vec![false; vertex_indices.len()];
+        PolyfaceFace {
+            vertex_indices,
+            invisible_edges,
+        }
+    }
+}
+
+impl PolygonMesh {
+    /// Gets the vertex at row `m` and column `n`.
+    pub fn get_vertex(&self, m: usize, n: usize) -> Option<&Point> {
+        if m < self.m_vertex_count && n < self.n_vertex_count {
+            self.vertices.get(m * self.n_vertex_count + n)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl Polyline {
+    /// Returns the vertices and faces of a polyface mesh, or `None` if the polyline isn't one.
+    /// Corners that refer to missing vertices are skipped.
+    pub fn as_polyface(&self) -> Option<PolyfaceMesh> {
+        if !self.get_is_polyface_mesh() {
+            return None;
+        }
+
+        // locations are flagged as both mesh and polyface vertices, faces only as the latter
+        let vertices = self.vertices.iter()
+            .filter(|v| v.get_is_3d_polygon_mesh())
+            .map(|v| v.location)
+            .collect::<Vec<_>>();
+        let faces = self.vertices.iter()
+            .filter(|v| !v.get_is_3d_polygon_mesh())
+            .map(|v| {
+                let mut face = PolyfaceFace::default();
+                let indices = [v.polyface_mesh_vertex_index1, v.polyface_mesh_vertex_index2, v.polyface_mesh_vertex_index3, v.polyface_mesh_vertex_index4];
+                // the indices are 1-based and a negative index hides the edge starting at that corner
+                for &index in indices.iter().filter(|&&i| i != 0 && ((i as i64).abs() as usize) <= vertices.len()) {
+                    face.vertex_indices.push((index as i64).abs() as usize - 1);
+                    face.invisible_edges.push(index < 0);
+                }
+
+                face
+            })
+            .collect();
+        Some(PolyfaceMesh { vertices, faces })
+    }
+    /// Returns the grid of a 3D polygon mesh, or `None` if the polyline isn't one or has fewer
+    /// vertices than its M×N size.
+    pub fn as_polygon_mesh(&self) -> Option<PolygonMesh> {
+        let m = self.polygon_mesh_m_vertex_count.max(0) as usize;
+        let n = self.polygon_mesh_n_vertex_count.max(0) as usize;
+        if !self.get_is_3d_polygon_mesh() || self.vertices.len() < m * n {
+            return None;
+        }
+
+        Some(PolygonMesh {
+            m_vertex_count: m,
+            n_vertex_count: n,
+            vertices: self.vertices.iter().take(m * n).map(|v| v.location).collect(),
+            is_closed_in_m_direction: self.get_is_closed(),
+            is_closed_in_n_direction: self.get_is_polygon_mesh_closed_in_n_direction(),
+        })
+    }
+    /// Creates a polyline flagged as a polyface mesh.  Only the first four corners of each face are
+    /// kept.
+    pub fn from_polyface(mesh: &PolyfaceMesh) -> Self {
+        let mut poly = Polyline::default();
+        poly.set_is_polyface_mesh(true);
+        poly.polygon_mesh_m_vertex_count = mesh.vertices.len() as i32;
+        poly.polygon_mesh_n_vertex_count = mesh.faces.len() as i32;
+        for location in &mesh.vertices {
+            let mut vertex = Vertex::new(*location);
+            vertex.set_is_3d_polygon_mesh(true);
+            vertex.set_is_polyface_mesh_vertex(true);
+            poly.vertices.push(vertex);
+        }
+
+        for face in &mesh.faces {
+            let mut indices = face.vertex_indices.iter().enumerate().take(4).map(|(i, &index)| {
+                let index = index as i32 + 1;
+                if face.invisible_edges.get(i).cloned().unwrap_or(false) { -index } else { index }
+            });
+            let mut vertex = Vertex::default();
+            vertex.set_is_polyface_mesh_vertex(true);
+            vertex.polyface_mesh_vertex_index1 = indices.next().unwrap_or(0);
+            vertex.polyface_mesh_vertex_index2 = indices.next().unwrap_or(0);
+            vertex.polyface_mesh_vertex_index3 = indices.next().unwrap_or(0);
+            vertex.polyface_mesh_vertex_index4 = indices.next().unwrap_or(0);
+            poly.vertices.push(vertex);
+        }
+
+        poly
+    }
+    /// Creates a polyline flagged as a 3D polygon mesh.
+    pub fn from_polygon_mesh(mesh: &PolygonMesh) -> Self {
+        let mut poly = Polyline::default();
+        poly.set_is_3d_polygon_mesh(true);
+        poly.set_is_closed(mesh.is_closed_in_m_direction);
+        poly.set_is_polygon_mesh_closed_in_n_direction(mesh.is_closed_in_n_direction);
+        poly.polygon_mesh_m_vertex_count = mesh.m_vertex_count as i32;
+        poly.polygon_mesh_n_vertex_count = mesh.n_vertex_count as i32;
+        for location in &mesh.vertices {
+            let mut vertex = Vertex::new(*location);
+            vertex.set_is_3d_polygon_mesh(true);
+            poly.vertices.push(vertex);
+        }
+
+        poly
+    }
+}
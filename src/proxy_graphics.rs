@@ -0,0 +1,506 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate byteorder;
+use self::byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+
+use ::{
+    Color,
+    Drawing,
+    DxfError,
+    DxfResult,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use helper_functions::parse_hex_string;
+use polyline_mesh::{
+    PolyfaceFace,
+    PolyfaceMesh,
+    PolygonMesh,
+};
+
+// command types
+const CIRCLE: i32 = 2;
+const CIRCLE_3P: i32 = 3;
+const CIRCULAR_ARC: i32 = 4;
+const CIRCULAR_ARC_3P: i32 = 5;
+const POLYLINE: i32 = 6;
+const POLYGON: i32 = 7;
+const MESH: i32 = 8;
+const SHELL: i32 = 9;
+const TEXT: i32 = 10;
+const TEXT2: i32 = 11;
+const XLINE: i32 = 12;
+const RAY: i32 = 13;
+const SUBENT_COLOR: i32 = 14;
+const SUBENT_LAYER: i32 = 16;
+const SUBENT_LINETYPE: i32 = 18;
+const SUBENT_TRUECOLOR: i32 = 22;
+const SUBENT_LNWEIGHT: i32 = 23;
+const SUBENT_THICKNESS: i32 = 25;
+const PUSH_MODEL_XFORM: i32 = 29;
+const POP_MODEL_XFORM: i32 = 31;
+const POLYLINE_WITH_NORMALS: i32 = 32;
+const UNICODE_TEXT: i32 = 34;
+const UNICODE_TEXT2: i32 = 35;
+
+// special line type indices
+const LINE_TYPE_BY_LAYER: i32 = 32767;
+const LINE_TYPE_BY_BLOCK: i32 = 32766;
+
+// the high byte of a true color value
+const TRUE_COLOR_BY_LAYER: i32 = 0xC0;
+const TRUE_COLOR_BY_BLOCK: i32 = 0xC1;
+const TRUE_COLOR_RGB: i32 = 0xC2;
+const TRUE_COLOR_INDEX: i32 = 0xC3;
+
+impl Drawing {
+    /// Returns the entities drawn by the proxy graphics of `entity`; that's the graphics data of a
+    /// `ProxyEntity` or the `preview_image_data` of any other entity.  The entities start with the
+    /// color, layer, and line type of `entity` and take any attribute changes in the stream after
+    /// that.  Layers and line types are referenced by their index in the drawing's tables.
+    pub fn proxy_graphics_entities(&self, entity: &Entity) -> DxfResult<Vec<Entity>> {
+        let mut data = vec![];
+        match entity.specific {
+            EntityType::ProxyEntity(ref proxy) => {
+                // the entity data is also written as 310 pairs so only the declared size is used
+                for line in &proxy.graphics_data_string {
+                    if data.len() >= proxy.graphics_data_size.max(0) as usize {
+                        break;
+                    }
+
+                    parse_hex_string(line, &mut data)?;
+                }
+
+                data.truncate(proxy.graphics_data_size.max(0) as usize);
+            },
+            _ => {
+                for line in &entity.common.preview_image_data {
+                    parse_hex_string(line, &mut data)?;
+                }
+            },
+        }
+
+        let mut decoder = ProxyGraphicsDecoder::new(self);
+        decoder.color = entity.common.color.clone();
+        decoder.color_24_bit = entity.common.color_24_bit;
+        decoder.layer_name = entity.common.layer.clone();
+        decoder.line_type_name = entity.common.line_type_name.clone();
+        decoder.lineweight_enum_value = entity.common.lineweight_enum_value;
+        decoder.decode(&data)
+    }
+    /// Returns the entities drawn by a binary proxy graphics stream.  The entities are BYLAYER on
+    /// layer "0" until the stream changes that.
+    pub fn decode_proxy_graphics(&self, data: &[u8]) -> DxfResult<Vec<Entity>> {
+        ProxyGraphicsDecoder::new(self).decode(data)
+    }
+}
+
+struct ProxyGraphicsDecoder<'a> {
+    drawing: &'a Drawing,
+    color: Color,
+    color_24_bit: i32,
+    layer_name: String,
+    line_type_name: String,
+    lineweight_enum_value: i16,
+    thickness: f64,
+    matrices: Vec<TransformationMatrix>,
+    entities: Vec<Entity>,
+}
+
+impl<'a> ProxyGraphicsDecoder<'a> {
+    fn new(drawing: &'a Drawing) -> Self {
+        ProxyGraphicsDecoder {
+            drawing,
+            color: Color::by_layer(),
+            color_24_bit: 0,
+            layer_name: String::from("0"),
+            line_type_name: String::from("BYLAYER"),
+            lineweight_enum_value: 0,
+            thickness: 0.0,
+            matrices: vec![],
+            entities: vec![],
+        }
+    }
+    fn decode(mut self, data: &[u8]) -> DxfResult<Vec<Entity>> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // the stream starts with its total size and the number of commands
+        let mut reader = ProxyGraphicsReader::new(data);
+        let _size = reader.read_i32()?;
+        let count = reader.read_i32()?;
+        let mut offset = reader.offset;
+        for _ in 0..count {
+            if offset >= data.len() {
+                break;
+            }
+
+            // each command has its size, including this header, and its type
+            let mut header = ProxyGraphicsReader::new(&data[offset..]);
+            let size = header.read_i32()?;
+            let command = header.read_i32()?;
+            if size < 8 || offset + size as usize > data.len() {
+                return Err(DxfError::InvalidBinaryFile);
+            }
+
+            let mut reader = ProxyGraphicsReader::new(&data[offset + 8..offset + size as usize]);
+            self.read_command(command, &mut reader)?;
+            offset += size as usize;
+        }
+
+        Ok(self.entities)
+    }
+    fn read_command(&mut self, command: i32, reader: &mut ProxyGraphicsReader) -> DxfResult<()> {
+        match command {
+            CIRCLE => {
+                let center = reader.read_point()?;
+                let radius = reader.read_f64()?;
+                let normal = reader.read_vector()?;
+                let mut circle = Circle::new(to_ocs(&center, &normal), radius);
+                circle.normal = normal;
+                self.add(EntityType::Circle(circle));
+            },
+            CIRCLE_3P => {
+                let (p1, p2, p3) = (reader.read_point()?, reader.read_point()?, reader.read_point()?);
+                if let Some((center, normal)) = circumcenter(&p1, &p2, &p3) {
+                    let mut circle = Circle::new(to_ocs(&center, &normal), center.distance_to(&p1));
+                    circle.normal = normal;
+                    self.add(EntityType::Circle(circle));
+                }
+            },
+            CIRCULAR_ARC => {
+                let center = reader.read_point()?;
+                let radius = reader.read_f64()?;
+                let normal = reader.read_vector()?;
+                let start_vector = reader.read_vector()?;
+                let sweep = reader.read_f64()?.to_degrees();
+                let start = ocs_angle(&start_vector, &normal);
+                // arcs are always counterclockwise about their normal
+                let (start, end) = if sweep < 0.0 { (start + sweep, start) } else { (start, start + sweep) };
+                let mut arc = Arc::new(to_ocs(&center, &normal), radius, start, end);
+                arc.normal = normal;
+                self.add(EntityType::Arc(arc));
+            },
+            CIRCULAR_ARC_3P => {
+                let (p1, p2, p3) = (reader.read_point()?, reader.read_point()?, reader.read_point()?);
+                match circumcenter(&p1, &p2, &p3) {
+                    Some((center, normal)) => {
+                        // the normal makes the points counterclockwise
+                        let start = ocs_angle(&(p1 - center), &normal);
+                        let end = ocs_angle(&(p3 - center), &normal);
+                        let mut arc = Arc::new(to_ocs(&center, &normal), center.distance_to(&p1), start, end);
+                        arc.normal = normal;
+                        self.add(EntityType::Arc(arc));
+                    },
+                    None => self.add(EntityType::Line(Line::new(p1, p3))),
+                }
+            },
+            POLYLINE | POLYLINE_WITH_NORMALS => {
+                let points = reader.read_points()?;
+                self.add_polyline(points, false);
+            },
+            POLYGON => {
+                let points = reader.read_points()?;
+                self.add_polyline(points, true);
+            },
+            MESH => {
+                let rows = reader.read_i32()?.max(0) as usize;
+                let columns = reader.read_i32()?.max(0) as usize;
+                let mut vertices = vec![];
+                for _ in 0..rows * columns {
+                    vertices.push(reader.read_point()?);
+                }
+
+                // the optional edge and face data that follows isn't needed
+                let mesh = PolygonMesh {
+                    m_vertex_count: rows,
+                    n_vertex_count: columns,
+                    vertices,
+                    is_closed_in_m_direction: false,
+                    is_closed_in_n_direction: false,
+                };
+                self.add(EntityType::Polyline(Polyline::from_polygon_mesh(&mesh)));
+            },
+            SHELL => {
+                let vertices = reader.read_points()?;
+                let entry_count = reader.read_i32()?.max(0) as usize;
+                let mut entries = vec![];
+                for _ in 0..entry_count {
+                    entries.push(reader.read_i32()?);
+                }
+
+                // each face is its vertex count followed by that many indices; a negative count is a hole
+                let mut faces = vec![];
+                let mut entries = entries.into_iter();
+                while let Some(count) = entries.next() {
+                    let indices = entries.by_ref()
+                        .take((count as i64).abs() as usize)
+                        .map(|i| i.max(0) as usize)
+                        .collect::<Vec<_>>();
+                    if count < 0 || indices.iter().any(|&i| i >= vertices.len()) {
+                        continue;
+                    }
+
+                    // a polyface face has at most four corners so larger faces become a fan of triangles
+                    if indices.len() <= 4 {
+                        faces.push(PolyfaceFace::new(indices));
+                    }
+                    else {
+                        for i in 1..indices.len() - 1 {
+                            let mut face = PolyfaceFace::new(vec![indices[0], indices[i], indices[i + 1]]);
+                            face.invisible_edges = vec![i != 1, false, i != indices.len() - 2];
+                            faces.push(face);
+                        }
+                    }
+                }
+
+                let mesh = PolyfaceMesh { vertices, faces };
+                self.add(EntityType::Polyline(Polyline::from_polyface(&mesh)));
+            },
+            TEXT | UNICODE_TEXT => {
+                let (location, normal, direction) = (reader.read_point()?, reader.read_vector()?, reader.read_vector()?);
+                let text_height = reader.read_f64()?;
+                let relative_x_scale_factor = reader.read_f64()?;
+                let oblique_angle = reader.read_f64()?;
+                let value = if command == UNICODE_TEXT { reader.read_unicode_string()? } else { reader.read_string()? };
+                let mut text = Text::default();
+                text.location = to_ocs(&location, &normal);
+                text.normal = normal;
+                text.rotation = ocs_angle(&direction, &normal);
+                text.text_height = text_height;
+                text.relative_x_scale_factor = relative_x_scale_factor;
+                text.oblique_angle = oblique_angle.to_degrees();
+                text.value = value;
+                self.add(EntityType::Text(text));
+            },
+            TEXT2 | UNICODE_TEXT2 => {
+                let (location, normal, direction) = (reader.read_point()?, reader.read_vector()?, reader.read_vector()?);
+                let value = if command == UNICODE_TEXT2 { reader.read_unicode_string()? } else { reader.read_string()? };
+                let _ignore_length = reader.read_i32()?;
+                let _is_raw = reader.read_i32()?;
+                let text_height = reader.read_f64()?;
+                let relative_x_scale_factor = reader.read_f64()?;
+                let oblique_angle = reader.read_f64()?;
+                let _tracking = reader.read_f64()?;
+                let is_backwards = reader.read_i32()? != 0;
+                let is_upside_down = reader.read_i32()? != 0;
+                // the remaining vertical, underline, overline, and font values aren't needed
+                let mut text = Text::default();
+                text.location = to_ocs(&location, &normal);
+                text.normal = normal;
+                text.rotation = ocs_angle(&direction, &normal);
+                text.text_height = text_height;
+                text.relative_x_scale_factor = relative_x_scale_factor;
+                text.oblique_angle = oblique_angle.to_degrees();
+                text.value = value;
+                text.set_is_text_backwards(is_backwards);
+                text.set_is_text_upside_down(is_upside_down);
+                self.add(EntityType::Text(text));
+            },
+            XLINE => {
+                let (p1, p2) = (reader.read_point()?, reader.read_point()?);
+                let mut xline = XLine::default();
+                xline.first_point = p1;
+                xline.unit_direction_vector = (p2 - p1).normalize();
+                self.add(EntityType::XLine(xline));
+            },
+            RAY => {
+                let (p1, p2) = (reader.read_point()?, reader.read_point()?);
+                let mut ray = Ray::default();
+                ray.start_point = p1;
+                ray.unit_direction_vector = (p2 - p1).normalize();
+                self.add(EntityType::Ray(ray));
+            },
+            SUBENT_COLOR => {
+                self.color = Color::from_raw_value(reader.read_i32()? as i16);
+                self.color_24_bit = 0;
+            },
+            SUBENT_LAYER => {
+                let index = reader.read_i32()?;
+                if let Some(layer) = self.drawing.layers.get(index.max(0) as usize) {
+                    self.layer_name = layer.name.clone();
+                }
+            },
+            SUBENT_LINETYPE => {
+                match reader.read_i32()? {
+                    LINE_TYPE_BY_LAYER => self.line_type_name = String::from("BYLAYER"),
+                    LINE_TYPE_BY_BLOCK => self.line_type_name = String::from("BYBLOCK"),
+                    index => if let Some(line_type) = self.drawing.line_types.get(index.max(0) as usize) {
+                        self.line_type_name = line_type.name.clone();
+                    },
+                }
+            },
+            SUBENT_TRUECOLOR => {
+                let value = reader.read_i32()?;
+                match (value >> 24) & 0xFF {
+                    TRUE_COLOR_BY_LAYER => { self.color = Color::by_layer(); self.color_24_bit = 0; },
+                    TRUE_COLOR_BY_BLOCK => { self.color = Color::by_block(); self.color_24_bit = 0; },
+                    TRUE_COLOR_RGB => self.color_24_bit = value & 0xFF_FFFF,
+                    TRUE_COLOR_INDEX => { self.color = Color::from_raw_value((value & 0xFF) as i16); self.color_24_bit = 0; },
+                    _ => (),
+                }
+            },
+            SUBENT_LNWEIGHT => self.lineweight_enum_value = reader.read_i32()? as i16,
+            SUBENT_THICKNESS => self.thickness = reader.read_f64()?,
+            PUSH_MODEL_XFORM => {
+                let mut values = vec![];
+                for _ in 0..16 {
+                    values.push(reader.read_f64()?);
+                }
+
+                let mut matrix = TransformationMatrix::default();
+                matrix.from_vec(&values);
+                let matrix = match self.matrices.last() {
+                    Some(current) => current.multiply(&matrix),
+                    None => matrix,
+                };
+                self.matrices.push(matrix);
+            },
+            POP_MODEL_XFORM => { self.matrices.pop(); },
+            // markers, fills, clipping, and the other attributes don't produce entities
+            _ => (),
+        }
+
+        Ok(())
+    }
+    fn add_polyline(&mut self, points: Vec<Point>, is_closed: bool) {
+        if points.len() == 2 && !is_closed {
+            self.add(EntityType::Line(Line::new(points[0], points[1])));
+            return;
+        }
+
+        let mut poly = Polyline::default();
+        poly.set_is_3d_polyline(true);
+        poly.set_is_closed(is_closed);
+        for point in points {
+            let mut vertex = Vertex::new(point);
+            vertex.set_is_3d_polyline_vertex(true);
+            poly.vertices.push(vertex);
+        }
+
+        self.add(EntityType::Polyline(poly));
+    }
+    fn add(&mut self, specific: EntityType) {
+        let mut entity = Entity::new(specific);
+        entity.common.color = self.color.clone();
+        entity.common.color_24_bit = self.color_24_bit;
+        entity.common.layer = self.layer_name.clone();
+        entity.common.line_type_name = self.line_type_name.clone();
+        entity.common.lineweight_enum_value = self.lineweight_enum_value;
+        match entity.specific {
+            EntityType::Arc(ref mut arc) => arc.thickness = self.thickness,
+            EntityType::Circle(ref mut circle) => circle.thickness = self.thickness,
+            EntityType::Line(ref mut line) => line.thickness = self.thickness,
+            EntityType::Text(ref mut text) => text.thickness = self.thickness,
+            _ => (),
+        }
+
+        if let Some(matrix) = self.matrices.last() {
+            entity.transform(matrix);
+        }
+
+        self.entities.push(entity);
+    }
+}
+
+struct ProxyGraphicsReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ProxyGraphicsReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ProxyGraphicsReader {
+            data,
+            offset: 0,
+        }
+    }
+    fn read_bytes(&mut self, length: usize) -> DxfResult<&'a [u8]> {
+        if self.offset + length > self.data.len() {
+            return Err(DxfError::UnexpectedEndOfInput);
+        }
+
+        let bytes = &self.data[self.offset..self.offset + length];
+        self.offset += length;
+        Ok(bytes)
+    }
+    fn read_i32(&mut self) -> DxfResult<i32> {
+        Ok(LittleEndian::read_i32(self.read_bytes(4)?))
+    }
+    fn read_f64(&mut self) -> DxfResult<f64> {
+        Ok(LittleEndian::read_f64(self.read_bytes(8)?))
+    }
+    fn read_point(&mut self) -> DxfResult<Point> {
+        Ok(Point::new(self.read_f64()?, self.read_f64()?, self.read_f64()?))
+    }
+    fn read_vector(&mut self) -> DxfResult<Vector> {
+        Ok(Vector::new(self.read_f64()?, self.read_f64()?, self.read_f64()?))
+    }
+    // Reads a vertex count followed by that many points.
+    fn read_points(&mut self) -> DxfResult<Vec<Point>> {
+        let count = self.read_i32()?;
+        let mut points = vec![];
+        for _ in 0..count {
+            points.push(self.read_point()?);
+        }
+
+        Ok(points)
+    }
+    // Reads a null-terminated string that's padded to a multiple of 4 bytes.
+    fn read_string(&mut self) -> DxfResult<String> {
+        let start = self.offset;
+        let length = self.data[start..].iter().position(|&b| b == 0).ok_or(DxfError::UnexpectedEndOfInput)?;
+        let value = String::from_utf8_lossy(&self.data[start..start + length]).into_owned();
+        self.skip_padded(length + 1)?;
+        Ok(value)
+    }
+    // Reads a null-terminated UTF-16 string that's padded to a multiple of 4 bytes.
+    fn read_unicode_string(&mut self) -> DxfResult<String> {
+        let start = self.offset;
+        let units = self.data[start..].chunks(2)
+            .take_while(|c| c.len() == 2 && (c[0] != 0 || c[1] != 0))
+            .map(LittleEndian::read_u16)
+            .collect::<Vec<_>>();
+        self.skip_padded((units.len() + 1) * 2)?;
+        Ok(String::from_utf16_lossy(&units))
+    }
+    fn skip_padded(&mut self, length: usize) -> DxfResult<()> {
+        // the padding of the last value is sometimes left off
+        let padded = ((length + 3) / 4 * 4).min(self.data.len() - self.offset);
+        self.read_bytes(padded.max(length))?;
+        Ok(())
+    }
+}
+
+// Returns the center of the circle through three points and the normal that makes them counterclockwise.
+fn circumcenter(p1: &Point, p2: &Point, p3: &Point) -> Option<(Point, Vector)> {
+    let a = *p1 - *p3;
+    let b = *p2 - *p3;
+    let axb = a.cross(&b);
+    let length_squared = axb.dot(&axb);
+    if length_squared == 0.0 {
+        return None;
+    }
+
+    let offset = (b * a.dot(&a) - a * b.dot(&b)).cross(&axb) * (0.5 / length_squared);
+    Some((*p3 + offset, axb.normalize()))
+}
+
+fn to_ocs(p: &Point, normal: &Vector) -> Point {
+    let (x, y, z) = TransformationMatrix::ocs_axes(normal);
+    let v = p.as_vector();
+    Point::new(v.dot(&x), v.dot(&y), v.dot(&z))
+}
+
+// The angle in degrees of a direction in the OCS of the normal.
+fn ocs_angle(direction: &Vector, normal: &Vector) -> f64 {
+    let (x, y, _) = TransformationMatrix::ocs_axes(normal);
+    direction.dot(&y).atan2(direction.dot(&x)).to_degrees()
+}
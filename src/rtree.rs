@@ -0,0 +1,439 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use ::{
+    BoundingBox,
+    Drawing,
+    DrawingItemMut,
+    Point,
+};
+
+use entities::*;
+
+// the most entries a node can hold before it's split
+const MAX_NODE_ENTRIES: usize = 8;
+
+// the fewest entries a node other than the root can hold before it's dissolved
+const MIN_NODE_ENTRIES: usize = 3;
+
+/// A spatial index that stores items by their bounding boxes and answers window, distance, and
+/// nearest-neighbor queries without checking every item.
+///
+/// Queries only consider the bounding boxes, so e.g., a diagonal line is "near" every point inside
+/// its box.  Callers that need exact answers should refine the candidates against the actual
+/// geometry.  Use `Drawing::spatial_index` to index the entities of a drawing by handle and
+/// `insert`/`remove` to keep the index up to date as entities are added and removed.
+#[derive(Clone, Debug)]
+pub struct RTree<T> {
+    root: Node<T>,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+enum Node<T> {
+    Leaf(Vec<(BoundingBox, T)>),
+    Branch(Vec<(BoundingBox, Node<T>)>),
+}
+
+impl<T> Default for RTree<T> {
+    fn default() -> Self {
+        RTree::new()
+    }
+}
+
+impl<T> RTree<T> {
+    /// Creates a new, empty `RTree`.
+    pub fn new() -> Self {
+        RTree {
+            root: Node::Leaf(vec![]),
+            len: 0,
+        }
+    }
+    /// The number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns whether the tree has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// The bounding box of all items in the tree, or `None` if the tree is empty.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        self.root.bounds()
+    }
+    /// Adds an item with the specified bounding box.
+    pub fn insert(&mut self, item: T, bounds: BoundingBox) {
+        self.len += 1;
+        self.insert_entry(bounds, item);
+    }
+    /// Removes every item for which `predicate` returns `true` and returns how many were removed.
+    /// Every item in the tree is visited.
+    pub fn remove_where<F>(&mut self, predicate: F) -> usize
+        where F: Fn(&T) -> bool {
+
+        let mut orphans = vec![];
+        let removed = self.root.remove_where(&predicate, &mut orphans);
+        self.len -= removed;
+
+        // collapse the root while it only has a single child
+        loop {
+            let only_child = match self.root {
+                Node::Branch(ref mut children) if children.len() == 1 => children.pop().map(|(_, child)| child),
+                Node::Branch(ref children) if children.is_empty() => Some(Node::Leaf(vec![])),
+                _ => None,
+            };
+            match only_child {
+                Some(child) => self.root = child,
+                None => break,
+            }
+        }
+
+        for (bounds, item) in orphans {
+            self.insert_entry(bounds, item);
+        }
+
+        removed
+    }
+    /// Returns every item whose bounding box is entirely inside `window`.
+    pub fn within(&self, window: &BoundingBox) -> Vec<&T> {
+        let mut result = vec![];
+        self.root.search(&|b| b.intersects(window), &|b| window.contains(b), &mut result);
+        result
+    }
+    /// Returns every item whose bounding box is inside or crosses `window`.
+    pub fn crossing(&self, window: &BoundingBox) -> Vec<&T> {
+        let mut result = vec![];
+        self.root.search(&|b| b.intersects(window), &|b| b.intersects(window), &mut result);
+        result
+    }
+    /// Returns every item whose bounding box is no farther than `radius` from `point`.
+    pub fn within_distance(&self, point: &Point, radius: f64) -> Vec<&T> {
+        let mut result = vec![];
+        let is_close = |b: &BoundingBox| b.distance_to_point(point) <= radius;
+        self.root.search(&is_close, &is_close, &mut result);
+        result
+    }
+    /// Returns up to `count` items ordered by the distance from `point` to their bounding boxes,
+    /// closest first.
+    pub fn nearest(&self, point: &Point, count: usize) -> Vec<&T> {
+        let mut result = vec![];
+        let mut queue = BinaryHeap::new();
+        queue.push(QueueEntry { distance: 0.0, item: QueueItem::Node(&self.root) });
+        while let Some(QueueEntry { item, .. }) = queue.pop() {
+            if result.len() >= count {
+                break;
+            }
+
+            match item {
+                QueueItem::Item(item) => result.push(item),
+                QueueItem::Node(Node::Leaf(entries)) => {
+                    for (bounds, item) in entries {
+                        queue.push(QueueEntry { distance: bounds.distance_to_point(point), item: QueueItem::Item(item) });
+                    }
+                },
+                QueueItem::Node(Node::Branch(children)) => {
+                    for (bounds, child) in children {
+                        queue.push(QueueEntry { distance: bounds.distance_to_point(point), item: QueueItem::Node(child) });
+                    }
+                },
+            }
+        }
+
+        result
+    }
+    /// Returns an iterator over every item in the tree and its bounding box in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item=(&BoundingBox, &T)> {
+        let mut entries = vec![];
+        self.root.collect_entries(&mut entries);
+        entries.into_iter()
+    }
+    fn insert_entry(&mut self, bounds: BoundingBox, item: T) {
+        if let Some(sibling) = self.root.insert(bounds, item) {
+            // the root was split so the tree grows by one level
+            let old_root = ::std::mem::replace(&mut self.root, Node::Branch(vec![]));
+            let old_bounds = old_root.bounds().unwrap_or(bounds);
+            self.root = Node::Branch(vec![(old_bounds, old_root), sibling]);
+        }
+    }
+}
+
+impl<T: PartialEq> RTree<T> {
+    /// Removes every copy of `item` and returns whether any were removed.
+    pub fn remove(&mut self, item: &T) -> bool {
+        self.remove_where(|i| i == item) > 0
+    }
+}
+
+impl<T> Node<T> {
+    fn bounds(&self) -> Option<BoundingBox> {
+        match *self {
+            Node::Leaf(ref entries) => union_all(entries.iter().map(|e| &e.0)),
+            Node::Branch(ref children) => union_all(children.iter().map(|c| &c.0)),
+        }
+    }
+    // Adds the entry below this node and returns the new sibling if this node had to be split.
+    fn insert(&mut self, bounds: BoundingBox, item: T) -> Option<(BoundingBox, Node<T>)> {
+        match *self {
+            Node::Leaf(ref mut entries) => {
+                entries.push((bounds, item));
+                if entries.len() > MAX_NODE_ENTRIES {
+                    let sibling = split(entries);
+                    return Some((union_all(sibling.iter().map(|e| &e.0)).unwrap(), Node::Leaf(sibling)));
+                }
+            },
+            Node::Branch(ref mut children) => {
+                let index = choose_child(children, &bounds);
+                children[index].0 = children[index].0.union(&bounds);
+                if let Some(sibling) = children[index].1.insert(bounds, item) {
+                    children[index].0 = children[index].1.bounds().unwrap();
+                    children.push(sibling);
+                    if children.len() > MAX_NODE_ENTRIES {
+                        let sibling = split(children);
+                        return Some((union_all(sibling.iter().map(|c| &c.0)).unwrap(), Node::Branch(sibling)));
+                    }
+                }
+            },
+        }
+
+        None
+    }
+    // Removes matching items and moves the items of underfull nodes into `orphans` so they can be
+    // reinserted.  Returns the number of matching items that were removed.
+    fn remove_where<F>(&mut self, predicate: &F, orphans: &mut Vec<(BoundingBox, T)>) -> usize
+        where F: Fn(&T) -> bool {
+
+        match *self {
+            Node::Leaf(ref mut entries) => {
+                let count = entries.len();
+                entries.retain(|e| !predicate(&e.1));
+                count - entries.len()
+            },
+            Node::Branch(ref mut children) => {
+                let mut removed = 0;
+                let mut i = 0;
+                while i < children.len() {
+                    let child_removed = children[i].1.remove_where(predicate, orphans);
+                    if child_removed > 0 {
+                        removed += child_removed;
+                        if children[i].1.entry_count() < MIN_NODE_ENTRIES {
+                            let (_, child) = children.remove(i);
+                            child.into_entries(orphans);
+                            continue;
+                        }
+
+                        children[i].0 = children[i].1.bounds().unwrap();
+                    }
+
+                    i += 1;
+                }
+
+                removed
+            },
+        }
+    }
+    fn entry_count(&self) -> usize {
+        match *self {
+            Node::Leaf(ref entries) => entries.len(),
+            Node::Branch(ref children) => children.len(),
+        }
+    }
+    fn into_entries(self, result: &mut Vec<(BoundingBox, T)>) {
+        match self {
+            Node::Leaf(entries) => result.extend(entries),
+            Node::Branch(children) => {
+                for (_, child) in children {
+                    child.into_entries(result);
+                }
+            },
+        }
+    }
+    fn collect_entries<'a>(&'a self, result: &mut Vec<(&'a BoundingBox, &'a T)>) {
+        match *self {
+            Node::Leaf(ref entries) => result.extend(entries.iter().map(|(b, i)| (b, i))),
+            Node::Branch(ref children) => {
+                for (_, child) in children {
+                    child.collect_entries(result);
+                }
+            },
+        }
+    }
+    // Visits every node whose bounds pass `descend` and returns the items whose bounds pass `accept`.
+    fn search<'a, D, A>(&'a self, descend: &D, accept: &A, result: &mut Vec<&'a T>)
+        where D: Fn(&BoundingBox) -> bool,
+              A: Fn(&BoundingBox) -> bool {
+
+        match *self {
+            Node::Leaf(ref entries) => {
+                result.extend(entries.iter().filter(|e| accept(&e.0)).map(|e| &e.1));
+            },
+            Node::Branch(ref children) => {
+                for (bounds, child) in children {
+                    if descend(bounds) {
+                        child.search(descend, accept, result);
+                    }
+                }
+            },
+        }
+    }
+}
+
+enum QueueItem<'a, T: 'a> {
+    Node(&'a Node<T>),
+    Item(&'a T),
+}
+
+struct QueueEntry<'a, T: 'a> {
+    distance: f64,
+    item: QueueItem<'a, T>,
+}
+
+impl<'a, T> PartialEq for QueueEntry<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T> Eq for QueueEntry<'a, T> {}
+
+impl<'a, T> PartialOrd for QueueEntry<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> Ord for QueueEntry<'a, T> {
+    // `BinaryHeap` is a max-heap so closer entries must compare greater; at equal distances items
+    // are returned before nodes are expanded
+    fn cmp(&self, other: &Self) -> Ordering {
+        let is_item = |e: &Self| match e.item { QueueItem::Item(_) => 1, QueueItem::Node(_) => 0 };
+        other.distance.partial_cmp(&self.distance).unwrap_or(Ordering::Equal)
+            .then_with(|| is_item(self).cmp(&is_item(other)))
+    }
+}
+
+fn union_all<'a, I>(boxes: I) -> Option<BoundingBox>
+    where I: Iterator<Item=&'a BoundingBox> {
+
+    boxes.fold(None, |acc, b| Some(match acc { Some(acc) => b.union(&acc), None => *b }))
+}
+
+// A measure of the space covered by a box that's still useful for boxes that are flat in one or
+// more dimensions, which is common for 2D drawings.
+fn cost(bounds: &BoundingBox) -> (f64, f64) {
+    let size = bounds.size();
+    (size.x * size.y + size.y * size.z + size.z * size.x, size.x + size.y + size.z)
+}
+
+fn enlargement(bounds: &BoundingBox, addition: &BoundingBox) -> (f64, f64) {
+    let before = cost(bounds);
+    let after = cost(&bounds.union(addition));
+    (after.0 - before.0, after.1 - before.1)
+}
+
+// Picks the child whose bounds need the least enlargement to include the new entry.
+fn choose_child<T>(children: &[(BoundingBox, Node<T>)], bounds: &BoundingBox) -> usize {
+    let mut best = 0;
+    let mut best_key = None;
+    for (i, (child_bounds, _)) in children.iter().enumerate() {
+        let growth = enlargement(child_bounds, bounds);
+        let key = (growth.0, growth.1, cost(child_bounds).0);
+        if best_key.map_or(true, |b| key < b) {
+            best = i;
+            best_key = Some(key);
+        }
+    }
+
+    best
+}
+
+// Splits the overflowing entries in two using the quadratic split algorithm; the entries that
+// remain in `entries` stay in the current node and the returned entries form its new sibling.
+fn split<N>(entries: &mut Vec<(BoundingBox, N)>) -> Vec<(BoundingBox, N)> {
+    // pick the two entries that would waste the most space if grouped together as the seeds
+    let (mut seed1, mut seed2, mut worst) = (0, 1, None);
+    for i in 0..entries.len() {
+        for j in i + 1..entries.len() {
+            let waste = cost(&entries[i].0.union(&entries[j].0)).0 - cost(&entries[i].0).0 - cost(&entries[j].0).0;
+            if worst.map_or(true, |w| waste > w) {
+                seed1 = i;
+                seed2 = j;
+                worst = Some(waste);
+            }
+        }
+    }
+
+    let mut remaining = ::std::mem::take(entries);
+    let second_seed = remaining.remove(seed2);
+    let first_seed = remaining.remove(seed1);
+    let mut first_bounds = first_seed.0;
+    let mut second_bounds = second_seed.0;
+    entries.push(first_seed);
+    let mut second = vec![second_seed];
+    while let Some(entry) = remaining.pop() {
+        // make sure both groups end up with the minimum number of entries
+        let to_first = if entries.len() + remaining.len() < MIN_NODE_ENTRIES {
+            true
+        }
+        else if second.len() + remaining.len() < MIN_NODE_ENTRIES {
+            false
+        }
+        else {
+            enlargement(&first_bounds, &entry.0) <= enlargement(&second_bounds, &entry.0)
+        };
+        if to_first {
+            first_bounds = first_bounds.union(&entry.0);
+            entries.push(entry);
+        }
+        else {
+            second_bounds = second_bounds.union(&entry.0);
+            second.push(entry);
+        }
+    }
+
+    second
+}
+
+impl Drawing {
+    /// Returns the bounding box of the entity in world coordinates.  Unlike `Entity::bounding_box`,
+    /// this includes `Insert` entities by bounding the contents of their blocks.
+    pub fn entity_bounding_box(&self, entity: &Entity) -> Option<BoundingBox> {
+        match entity.specific {
+            EntityType::Insert(_) => {
+                let boxes = self.explode_insert(entity).iter().filter_map(|e| e.bounding_box()).collect::<Vec<_>>();
+                union_all(boxes.iter())
+            },
+            _ => entity.bounding_box(),
+        }
+    }
+    /// Creates a spatial index of the drawing's entities keyed by their handles.  Entities that don't
+    /// have a handle are assigned one first and entities without a bounding box are not indexed.
+    pub fn spatial_index(&mut self) -> RTree<u32> {
+        let mut entities = ::std::mem::take(&mut self.entities);
+        let index = self.index_entities(&mut entities);
+        self.entities = entities;
+        index
+    }
+    /// Creates a spatial index of the entities in the block with the specified name, in block
+    /// coordinates, keyed by their handles.  Entities that don't have a handle are assigned one first
+    /// and entities without a bounding box are not indexed.  Returns `None` if there is no such block.
+    pub fn block_spatial_index(&mut self, block_name: &str) -> Option<RTree<u32>> {
+        let block_index = self.blocks.iter().position(|b| b.name.eq_ignore_ascii_case(block_name))?;
+        let mut entities = ::std::mem::take(&mut self.blocks[block_index].entities);
+        let index = self.index_entities(&mut entities);
+        self.blocks[block_index].entities = entities;
+        Some(index)
+    }
+    fn index_entities(&mut self, entities: &mut [Entity]) -> RTree<u32> {
+        let mut index = RTree::new();
+        for entity in entities.iter_mut() {
+            let handle = self.assign_and_get_handle(&mut DrawingItemMut::Entity(entity));
+            if let Some(bounds) = self.entity_bounding_box(entity) {
+                index.insert(handle, bounds);
+            }
+        }
+
+        index
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::{
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+use tessellation::bulge_to_arc;
+
+/// A single straight or circular piece of a `LwPolyline` or `Polyline`.
+///
+/// Points are in the object coordinate system (OCS) of the owning polyline and angles are in
+/// degrees, measured counter-clockwise from the OCS x-axis.  Widths vary linearly from the start
+/// of the segment to the end.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Line {
+        start: Point,
+        end: Point,
+        starting_width: f64,
+        ending_width: f64,
+    },
+    Arc {
+        start: Point,
+        end: Point,
+        center: Point,
+        radius: f64,
+        /// The angle of `start` around `center`.
+        start_angle: f64,
+        /// The angle of `end` around `center`.
+        end_angle: f64,
+        /// Whether the arc runs counter-clockwise from `start` to `end`.
+        is_counter_clockwise: bool,
+        starting_width: f64,
+        ending_width: f64,
+    },
+}
+
+impl Segment {
+    /// Creates the segment between two polyline vertices where `bulge` is the tangent of a quarter
+    /// of the included angle; a positive bulge is counter-clockwise.
+    pub fn from_bulge(start: Point, end: Point, bulge: f64, starting_width: f64, ending_width: f64) -> Segment {
+        if bulge == 0.0 || start == end {
+            return Segment::Line { start, end, starting_width, ending_width };
+        }
+
+        let (center, radius, start_angle, sweep) = bulge_to_arc(&start, &end, bulge);
+        let center = Point::new(center.x, center.y, start.z);
+        Segment::Arc {
+            start,
+            end,
+            center,
+            radius,
+            start_angle: normalize_degrees(start_angle.to_degrees()),
+            end_angle: normalize_degrees((start_angle + sweep).to_degrees()),
+            is_counter_clockwise: sweep > 0.0,
+            starting_width,
+            ending_width,
+        }
+    }
+    /// The first point of the segment.
+    pub fn start_point(&self) -> Point {
+        match *self {
+            Segment::Line { start, .. } | Segment::Arc { start, .. } => start,
+        }
+    }
+    /// The last point of the segment.
+    pub fn end_point(&self) -> Point {
+        match *self {
+            Segment::Line { end, .. } | Segment::Arc { end, .. } => end,
+        }
+    }
+    /// The signed angle in degrees swept by an arc segment, positive when counter-clockwise, or 0
+    /// for a line segment.
+    pub fn sweep_angle(&self) -> f64 {
+        match *self {
+            Segment::Line { .. } => 0.0,
+            Segment::Arc { start_angle, end_angle, is_counter_clockwise, .. } => {
+                let sweep = normalize_degrees(end_angle - start_angle);
+                let sweep = if sweep == 0.0 { 360.0 } else { sweep };
+                if is_counter_clockwise { sweep } else { sweep - 360.0 }
+            },
+        }
+    }
+    /// The length of the segment.
+    pub fn length(&self) -> f64 {
+        match *self {
+            Segment::Line { start, end, .. } => start.distance_to(&end),
+            Segment::Arc { radius, .. } => radius * self.sweep_angle().abs().to_radians(),
+        }
+    }
+    /// Returns the point at the fraction `t` of the way along the segment, where 0 is the start and
+    /// 1 is the end.
+    pub fn point_at(&self, t: f64) -> Point {
+        match *self {
+            Segment::Line { start, end, .. } => start + (end - start) * t,
+            Segment::Arc { center, radius, start_angle, .. } => {
+                let angle = (start_angle + self.sweep_angle() * t).to_radians();
+                center + Vector::new(angle.cos(), angle.sin(), 0.0) * radius
+            },
+        }
+    }
+    /// Returns the same segment running from its end to its start.
+    pub fn reversed(&self) -> Segment {
+        match *self {
+            Segment::Line { start, end, starting_width, ending_width } => Segment::Line { start: end, end: start, starting_width: ending_width, ending_width: starting_width },
+            Segment::Arc { start, end, center, radius, start_angle, end_angle, is_counter_clockwise, starting_width, ending_width } => Segment::Arc {
+                start: end,
+                end: start,
+                center,
+                radius,
+                start_angle: end_angle,
+                end_angle: start_angle,
+                is_counter_clockwise: !is_counter_clockwise,
+                starting_width: ending_width,
+                ending_width: starting_width,
+            },
+        }
+    }
+    /// Returns the width at the fraction `t` of the way along the segment.
+    pub fn width_at(&self, t: f64) -> f64 {
+        match *self {
+            Segment::Line { starting_width, ending_width, .. } | Segment::Arc { starting_width, ending_width, .. } => {
+                starting_width + (ending_width - starting_width) * t
+            },
+        }
+    }
+}
+
+impl LwPolyline {
+    /// Returns the line and arc segments between consecutive vertices, including the segment from
+    /// the last vertex back to the first if the polyline is closed.  Zero-length segments are skipped
+    /// and vertices without a width use the `constant_width`.  Points are at an elevation of 0; the
+    /// actual elevation is stored in the entity's `common.elevation`.
+    pub fn segments<'a>(&'a self) -> impl Iterator<Item=Segment> + 'a {
+        let vertices = &self.vertices;
+        segment_indices(vertices.len(), self.get_is_closed()).filter_map(move |(i, j)| {
+            let (v1, v2) = (&vertices[i], &vertices[j]);
+            let (starting_width, ending_width) = if v1.starting_width == 0.0 && v1.ending_width == 0.0 {
+                (self.constant_width, self.constant_width)
+            }
+            else {
+                (v1.starting_width, v1.ending_width)
+            };
+            let start = Point::new(v1.x, v1.y, 0.0);
+            let end = Point::new(v2.x, v2.y, 0.0);
+            if start == end {
+                None
+            }
+            else {
+                Some(Segment::from_bulge(start, end, v1.bulge, starting_width, ending_width))
+            }
+        })
+    }
+}
+
+impl Polyline {
+    /// Returns the line and arc segments between consecutive vertices, including the segment from
+    /// the last vertex back to the first if the polyline is closed.  Zero-length segments and spline
+    /// frame control points are skipped and vertices without a width use the default widths.  Like
+    /// `LwPolyline::segments`, points are at an elevation of 0; the actual elevation is the Z value of
+    /// `location`.  3D polylines only produce lines in world coordinates and polygon and polyface
+    /// meshes don't produce any segments.
+    pub fn segments<'a>(&'a self) -> impl Iterator<Item=Segment> + 'a {
+        let is_mesh = self.get_is_3d_polygon_mesh() || self.get_is_polyface_mesh();
+        let is_3d = self.get_is_3d_polyline();
+        let vertices = self.vertices.iter()
+            .filter(|v| !is_mesh && !v.get_is_spline_frame_control_point())
+            .collect::<Vec<_>>();
+        segment_indices(vertices.len(), self.get_is_closed()).filter_map(move |(i, j)| {
+            let (v1, v2) = (vertices[i], vertices[j]);
+            if is_3d {
+                return if v1.location == v2.location {
+                    None
+                }
+                else {
+                    Some(Segment::Line { start: v1.location, end: v2.location, starting_width: 0.0, ending_width: 0.0 })
+                };
+            }
+
+            let (starting_width, ending_width) = if v1.starting_width == 0.0 && v1.ending_width == 0.0 {
+                (self.default_starting_width, self.default_ending_width)
+            }
+            else {
+                (v1.starting_width, v1.ending_width)
+            };
+            let start = Point::new(v1.location.x, v1.location.y, 0.0);
+            let end = Point::new(v2.location.x, v2.location.y, 0.0);
+            if start == end {
+                None
+            }
+            else {
+                Some(Segment::from_bulge(start, end, v1.bulge, starting_width, ending_width))
+            }
+        })
+    }
+}
+
+impl Entity {
+    /// Splits a `LwPolyline` or `Polyline` into separate `Line` and `Arc` entities that share this
+    /// entity's `common` values.  Lines are converted to world coordinates and arcs keep the
+    /// polyline's normal.  Widths are discarded.  The new entities don't have a handle so one will be
+    /// assigned when the drawing is saved.  Returns `None` for any other entity type.
+    pub fn explode_polyline(&self) -> Option<Vec<Entity>> {
+        let (segments, normal, thickness, elevation) = match self.specific {
+            EntityType::LwPolyline(ref poly) => (poly.segments().collect::<Vec<_>>(), poly.extrusion_direction, poly.thickness, self.common.elevation),
+            EntityType::Polyline(ref poly) if poly.get_is_3d_polyline() => (poly.segments().collect(), Vector::z_axis(), 0.0, 0.0),
+            EntityType::Polyline(ref poly) => (poly.segments().collect(), poly.normal, poly.thickness, poly.location.z),
+            _ => return None,
+        };
+
+        let mut common = self.common.clone();
+        common.handle = 0;
+        common.elevation = 0.0;
+        let to_wcs = TransformationMatrix::from_ocs(&normal);
+        let with_elevation = |p: Point| Point::new(p.x, p.y, p.z + elevation);
+        Some(segments.into_iter().map(|segment| {
+            let specific = match segment {
+                Segment::Line { start, end, .. } => {
+                    let mut line = Line::new(to_wcs.transform_point(&with_elevation(start)), to_wcs.transform_point(&with_elevation(end)));
+                    line.thickness = thickness;
+                    line.extrusion_direction = normal;
+                    EntityType::Line(line)
+                },
+                Segment::Arc { center, radius, start_angle, end_angle, is_counter_clockwise, .. } => {
+                    // arc entities always run counter-clockwise
+                    let (start_angle, end_angle) = if is_counter_clockwise { (start_angle, end_angle) } else { (end_angle, start_angle) };
+                    let mut arc = Arc::new(with_elevation(center), radius, start_angle, end_angle);
+                    arc.thickness = thickness;
+                    arc.normal = normal;
+                    EntityType::Arc(arc)
+                },
+            };
+            Entity {
+                common: common.clone(),
+                specific,
+            }
+        }).collect())
+    }
+}
+
+// Returns the index pairs of the vertices at either end of each segment.
+fn segment_indices(vertex_count: usize, is_closed: bool) -> impl Iterator<Item=(usize, usize)> {
+    let segment_count = match vertex_count {
+        0 | 1 => 0,
+        _ if is_closed => vertex_count,
+        _ => vertex_count - 1,
+    };
+    (0..segment_count).map(move |i| (i, (i + 1) % vertex_count))
+}
+
+fn normalize_degrees(angle: f64) -> f64 {
+    let angle = angle % 360.0;
+    let angle = if angle < 0.0 { angle + 360.0 } else { angle };
+    // snap values that are within rounding error of a full turn
+    if (360.0 - angle).abs() < 1.0e-12 || angle.abs() < 1.0e-12 { 0.0 } else { angle }
+}
@@ -0,0 +1,458 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `entity.rs`
+
+use ::{
+    DxfError,
+    DxfResult,
+    Point,
+    Vector,
+};
+
+use entities::Spline;
+
+// a control point in homogeneous form, i.e., with the location pre-multiplied by the weight
+#[derive(Clone, Copy)]
+struct WeightedPoint {
+    location: Vector,
+    weight: f64,
+}
+
+impl WeightedPoint {
+    fn lerp(&self, other: &WeightedPoint, alpha: f64) -> WeightedPoint {
+        WeightedPoint {
+            location: self.location * (1.0 - alpha) + other.location * alpha,
+            weight: self.weight * (1.0 - alpha) + other.weight * alpha,
+        }
+    }
+}
+
+impl Spline {
+    /// Returns the range of parameter values over which the spline is defined, or `None` if the
+    /// degree, knot values, control points, and weights don't describe a valid curve.
+    pub fn parameter_range(&self) -> Option<(f64, f64)> {
+        match self.validate() {
+            Ok(degree) => Some((self.knot_values[degree], self.knot_values[self.control_points.len()])),
+            Err(_) => None,
+        }
+    }
+    /// Evaluates the point on the spline at the parameter `u`.
+    pub fn point_at(&self, u: f64) -> DxfResult<Point> {
+        let degree = self.validate_parameter(u)?;
+        Ok(Point::origin() + self.evaluate(degree, u, 0)[0])
+    }
+    /// Evaluates the point on the spline and its first `count` derivatives at the parameter `u`.
+    /// The first item in the result is the point itself, the second is the first derivative, etc.
+    /// Derivatives higher than the spline's degree are zero.
+    pub fn derivatives_at(&self, u: f64, count: usize) -> DxfResult<Vec<Vector>> {
+        let degree = self.validate_parameter(u)?;
+        Ok(self.evaluate(degree, u, count))
+    }
+    /// Returns the unit tangent direction of the spline at the parameter `u`.
+    pub fn tangent_at(&self, u: f64) -> DxfResult<Vector> {
+        Ok(self.derivatives_at(u, 1)?[1].normalize())
+    }
+    /// Inserts the knot `u` into the spline `times` times without changing the shape of the curve.
+    /// The resulting multiplicity of the knot can't exceed the spline's degree.
+    pub fn insert_knot(&mut self, u: f64, times: usize) -> DxfResult<()> {
+        let degree = self.validate()?;
+        let (start, end) = (self.knot_values[degree], self.knot_values[self.control_points.len()]);
+        if u <= start || u >= end {
+            return Err(DxfError::InvalidSpline(format!("the knot {} must be strictly inside the parameter range [{}, {}]", u, start, end)));
+        }
+
+        let multiplicity = self.knot_values.iter().filter(|&&k| k == u).count();
+        if multiplicity + times > degree {
+            return Err(DxfError::InvalidSpline(format!("the knot {} would have a multiplicity greater than the degree {}", u, degree)));
+        }
+
+        if times == 0 {
+            return Ok(());
+        }
+
+        // Boehm's algorithm; see The NURBS Book, A5.1
+        let (p, s, r) = (degree, multiplicity, times);
+        let k = self.find_span(degree, u);
+        let old_knots = &self.knot_values;
+        let old_points = self.weighted_points();
+        let mut knots = Vec::with_capacity(old_knots.len() + r);
+        knots.extend_from_slice(&old_knots[..k + 1]);
+        knots.extend((0..r).map(|_| u));
+        knots.extend_from_slice(&old_knots[k + 1..]);
+
+        let mut points = Vec::with_capacity(old_points.len() + r);
+        points.extend_from_slice(&old_points[..k + 1 - p]);
+        points.extend((0..r + p - 1 - s).map(|_| old_points[0]));
+        points.extend_from_slice(&old_points[k - s..]);
+
+        let mut temp = old_points[k - p..k - s + 1].to_vec();
+        for j in 1..r + 1 {
+            let l = k - p + j;
+            for i in 0..p - j - s + 1 {
+                let alpha = (u - old_knots[l + i]) / (old_knots[i + k + 1] - old_knots[l + i]);
+                temp[i] = temp[i].lerp(&temp[i + 1], alpha);
+            }
+
+            points[l] = temp[0];
+            points[k + r - j - s] = temp[p - j - s];
+        }
+
+        let l = k - p + r;
+        if l + 1 < k - s {
+            points[l + 1..k - s].copy_from_slice(&temp[1..k - s - l]);
+        }
+
+        self.knot_values = knots;
+        self.set_weighted_points(&points);
+        Ok(())
+    }
+    /// Inserts each of the specified knots into the spline once without changing the shape of the curve.
+    /// If any knot can't be inserted the spline is left unchanged.
+    pub fn refine_knots(&mut self, knots: &[f64]) -> DxfResult<()> {
+        let mut knots = knots.to_vec();
+        knots.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        let mut refined = self.clone();
+        for u in knots {
+            refined.insert_knot(u, 1)?;
+        }
+
+        self.knot_values = refined.knot_values;
+        self.control_points = refined.control_points;
+        self.weights = refined.weights;
+        Ok(())
+    }
+    /// Computes the knot values and control points of a non-rational spline that passes through
+    /// all of the fit points using global interpolation with chord-length parameterization.  If a
+    /// start or end tangent is specified and the degree is at least 2, the curve is also constrained
+    /// to those tangent directions.  The degree is reduced if there are too few fit points to support
+    /// it.  The fit points are left unchanged.
+    pub fn interpolate_fit_points(&mut self) -> DxfResult<()> {
+        if self.degree_of_curve < 1 {
+            return Err(DxfError::InvalidSpline(String::from("the degree must be at least 1")));
+        }
+
+        let fit_points = &self.fit_points;
+        if fit_points.len() < 2 {
+            return Err(DxfError::InvalidSpline(String::from("at least 2 fit points are required")));
+        }
+
+        // chord-length parameterization
+        let n = fit_points.len() - 1;
+        let mut parameters = vec![0.0];
+        for i in 1..n + 1 {
+            let distance = fit_points[i - 1].distance_to(&fit_points[i]);
+            if distance == 0.0 {
+                return Err(DxfError::InvalidSpline(format!("fit points {} and {} are coincident", i - 1, i)));
+            }
+
+            let last = parameters[i - 1];
+            parameters.push(last + distance);
+        }
+
+        let total = parameters[n];
+        let start_tangent = self.start_tangent.as_vector();
+        let end_tangent = self.end_tangent.as_vector();
+        let has_tangents = self.degree_of_curve >= 2 && (start_tangent.length() > 0.0 || end_tangent.length() > 0.0);
+        let degree = (self.degree_of_curve as usize).min(if has_tangents { n + 2 } else { n });
+
+        let count = if has_tangents { n + 3 } else { n + 1 };
+        let mut knots = vec![0.0; degree + 1];
+        let first_interior = if has_tangents { 0 } else { 1 };
+        let last_interior = count - degree - 1 + first_interior;
+        for j in first_interior..last_interior {
+            knots.push(parameters[j..j + degree].iter().sum::<f64>() / degree as f64);
+        }
+        knots.extend((0..degree + 1).map(|_| total));
+
+        let basis_spline = Spline {
+            degree_of_curve: degree as i32,
+            knot_values: knots.clone(),
+            control_points: vec![Point::origin(); count],
+            .. Default::default()
+        };
+        let mut matrix = vec![vec![0.0; count]; count];
+        let mut values = vec![Vector::zero(); count];
+        let mut add_row = |row: usize, u: f64, value: Vector| {
+            if u == 0.0 {
+                matrix[row][0] = 1.0;
+            }
+            else if u == total {
+                matrix[row][count - 1] = 1.0;
+            }
+            else {
+                let span = basis_spline.find_span(degree, u);
+                let basis = basis_function_derivatives(&knots, degree, span, u, 0);
+                for (j, b) in basis[0].iter().enumerate() {
+                    matrix[row][span - degree + j] = *b;
+                }
+            }
+
+            values[row] = value;
+        };
+
+        if has_tangents {
+            let direction = |tangent: Vector, chord: Vector| if tangent.length() > 0.0 { tangent.normalize() } else { chord.normalize() };
+            let start_direction = direction(start_tangent, fit_points[1] - fit_points[0]);
+            let end_direction = direction(end_tangent, fit_points[n] - fit_points[n - 1]);
+            add_row(0, 0.0, fit_points[0].as_vector());
+            for k in 1..n {
+                add_row(k + 1, parameters[k], fit_points[k].as_vector());
+            }
+            add_row(count - 1, total, fit_points[n].as_vector());
+
+            // the end derivatives are expressed as differences of the first and last two control points
+            matrix[1][0] = -1.0;
+            matrix[1][1] = 1.0;
+            values[1] = start_direction * (knots[degree + 1] / degree as f64);
+            matrix[count - 2][count - 2] = -1.0;
+            matrix[count - 2][count - 1] = 1.0;
+            values[count - 2] = end_direction * ((total - knots[knots.len() - degree - 2]) / degree as f64);
+        }
+        else {
+            for k in 0..n + 1 {
+                add_row(k, parameters[k], fit_points[k].as_vector());
+            }
+        }
+
+        let control_points = solve_linear_system(matrix, values)?;
+        self.degree_of_curve = degree as i32;
+        self.knot_values = knots;
+        self.control_points = control_points.iter().map(|v| Point::origin() + *v).collect();
+        self.weights.clear();
+        self.set_is_rational(false);
+        Ok(())
+    }
+    // Evaluates the point at `u` assuming the spline has already been validated.
+    pub(crate) fn evaluate_point(&self, degree: usize, u: f64) -> Point {
+        Point::origin() + self.evaluate(degree, u, 0)[0]
+    }
+    // Ensures the spline can be evaluated and returns its degree.
+    fn validate(&self) -> DxfResult<usize> {
+        if self.degree_of_curve < 1 {
+            return Err(DxfError::InvalidSpline(String::from("the degree must be at least 1")));
+        }
+
+        let degree = self.degree_of_curve as usize;
+        let count = self.control_points.len();
+        if count <= degree {
+            return Err(DxfError::InvalidSpline(format!("a spline of degree {} requires at least {} control points", degree, degree + 1)));
+        }
+
+        if self.knot_values.len() != count + degree + 1 {
+            return Err(DxfError::InvalidSpline(format!("expected {} knot values but found {}", count + degree + 1, self.knot_values.len())));
+        }
+
+        if self.knot_values.windows(2).any(|pair| pair[1] < pair[0]) {
+            return Err(DxfError::InvalidSpline(String::from("the knot values must be non-decreasing")));
+        }
+
+        if self.knot_values[count] <= self.knot_values[degree] {
+            return Err(DxfError::InvalidSpline(String::from("the parameter range is empty")));
+        }
+
+        if !self.weights.is_empty() {
+            if self.weights.len() != count {
+                return Err(DxfError::InvalidSpline(format!("expected {} weights but found {}", count, self.weights.len())));
+            }
+
+            if self.weights.iter().any(|&w| w <= 0.0) {
+                return Err(DxfError::InvalidSpline(String::from("the weights must be positive")));
+            }
+        }
+
+        Ok(degree)
+    }
+    fn validate_parameter(&self, u: f64) -> DxfResult<usize> {
+        let degree = self.validate()?;
+        let (start, end) = (self.knot_values[degree], self.knot_values[self.control_points.len()]);
+        if u < start || u > end {
+            return Err(DxfError::InvalidSpline(format!("the parameter {} is outside of the range [{}, {}]", u, start, end)));
+        }
+
+        Ok(degree)
+    }
+    // Returns the index of the knot span containing `u`; see The NURBS Book, A2.1.
+    fn find_span(&self, degree: usize, u: f64) -> usize {
+        let knots = &self.knot_values;
+        let n = self.control_points.len() - 1;
+        if u >= knots[n + 1] {
+            return n;
+        }
+
+        if u <= knots[degree] {
+            return degree;
+        }
+
+        let mut low = degree;
+        let mut high = n + 1;
+        let mut mid = (low + high) / 2;
+        while u < knots[mid] || u >= knots[mid + 1] {
+            if u < knots[mid] {
+                high = mid;
+            }
+            else {
+                low = mid;
+            }
+
+            mid = (low + high) / 2;
+        }
+
+        mid
+    }
+    fn weighted_points(&self) -> Vec<WeightedPoint> {
+        self.control_points.iter().enumerate().map(|(i, p)| {
+            let weight = if self.weights.is_empty() { 1.0 } else { self.weights[i] };
+            WeightedPoint { location: p.as_vector() * weight, weight }
+        }).collect()
+    }
+    fn set_weighted_points(&mut self, points: &[WeightedPoint]) {
+        let had_weights = !self.weights.is_empty();
+        self.control_points = points.iter().map(|p| Point::origin() + p.location * (1.0 / p.weight)).collect();
+        self.weights = if had_weights { points.iter().map(|p| p.weight).collect() } else { vec![] };
+    }
+    // Evaluates the curve and its derivatives at `u` from the derivatives of the homogeneous curve;
+    // see The NURBS Book, A3.2 and A4.2.
+    fn evaluate(&self, degree: usize, u: f64, count: usize) -> Vec<Vector> {
+        let span = self.find_span(degree, u);
+        let basis = basis_function_derivatives(&self.knot_values, degree, span, u, count);
+        let points = self.weighted_points();
+        let homogeneous = basis.iter().map(|row| {
+            row.iter().enumerate().fold((Vector::zero(), 0.0), |(location, weight), (j, b)| {
+                let p = &points[span - degree + j];
+                (location + p.location * *b, weight + p.weight * *b)
+            })
+        }).collect::<Vec<_>>();
+
+        let mut result: Vec<Vector> = Vec::with_capacity(count + 1);
+        for k in 0..count + 1 {
+            if k >= homogeneous.len() {
+                result.push(Vector::zero());
+                continue;
+            }
+
+            let mut v = homogeneous[k].0;
+            for i in 1..k + 1 {
+                v = v - result[k - i] * (binomial(k, i) * homogeneous[i].1);
+            }
+
+            result.push(v * (1.0 / homogeneous[0].1));
+        }
+
+        result
+    }
+}
+
+// Computes the non-zero basis functions and their first `count` derivatives at `u` for the knot
+// span `span`; `result[k][j]` is the k-th derivative of basis function `span - degree + j`.  See
+// The NURBS Book, A2.3.
+fn basis_function_derivatives(knots: &[f64], degree: usize, span: usize, u: f64, count: usize) -> Vec<Vec<f64>> {
+    let p = degree;
+    let count = count.min(p);
+    let mut ndu = vec![vec![0.0; p + 1]; p + 1];
+    let mut left = vec![0.0; p + 1];
+    let mut right = vec![0.0; p + 1];
+    ndu[0][0] = 1.0;
+    for j in 1..p + 1 {
+        left[j] = u - knots[span + 1 - j];
+        right[j] = knots[span + j] - u;
+        let mut saved = 0.0;
+        for r in 0..j {
+            ndu[j][r] = right[r + 1] + left[j - r];
+            let temp = ndu[r][j - 1] / ndu[j][r];
+            ndu[r][j] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+
+        ndu[j][j] = saved;
+    }
+
+    let mut result = vec![vec![0.0; p + 1]; count + 1];
+    for j in 0..p + 1 {
+        result[0][j] = ndu[j][p];
+    }
+
+    let mut a = vec![vec![0.0; p + 1]; 2];
+    for r in 0..p + 1 {
+        let (mut s1, mut s2) = (0, 1);
+        a[0][0] = 1.0;
+        for k in 1..count + 1 {
+            let mut d = 0.0;
+            let rk = r as isize - k as isize;
+            let pk = p - k;
+            if rk >= 0 {
+                a[s2][0] = a[s1][0] / ndu[pk + 1][rk as usize];
+                d = a[s2][0] * ndu[rk as usize][pk];
+            }
+
+            let j1 = if rk >= -1 { 1 } else { (-rk) as usize };
+            let j2 = if r as isize - 1 <= pk as isize { k - 1 } else { p - r };
+            for j in j1..j2 + 1 {
+                let index = (rk + j as isize) as usize;
+                a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][index];
+                d += a[s2][j] * ndu[index][pk];
+            }
+
+            if r <= pk {
+                a[s2][k] = -a[s1][k - 1] / ndu[pk + 1][r];
+                d += a[s2][k] * ndu[r][pk];
+            }
+
+            result[k][r] = d;
+            ::std::mem::swap(&mut s1, &mut s2);
+        }
+    }
+
+    let mut factor = p as f64;
+    for (k, row) in result.iter_mut().enumerate().skip(1) {
+        for value in row.iter_mut() {
+            *value *= factor;
+        }
+
+        factor *= (p - k) as f64;
+    }
+
+    result
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+// Solves `matrix * x = values` by Gaussian elimination with partial pivoting.
+fn solve_linear_system(mut matrix: Vec<Vec<f64>>, mut values: Vec<Vector>) -> DxfResult<Vec<Vector>> {
+    let n = values.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().partial_cmp(&matrix[b][col].abs()).unwrap_or(::std::cmp::Ordering::Equal)).unwrap_or(col);
+        if matrix[pivot][col].abs() < 1.0e-12 {
+            return Err(DxfError::InvalidSpline(String::from("the fit points can't be interpolated")));
+        }
+
+        matrix.swap(col, pivot);
+        values.swap(col, pivot);
+        let pivot_row = matrix[col].clone();
+        for row in col + 1..n {
+            let factor = matrix[row][col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+
+            for (c, value) in matrix[row].iter_mut().enumerate().skip(col) {
+                *value -= factor * pivot_row[c];
+            }
+
+            values[row] = values[row] - values[col] * factor;
+        }
+    }
+
+    let mut result = vec![Vector::zero(); n];
+    for row in (0..n).rev() {
+        let mut value = values[row];
+        for c in row + 1..n {
+            value = value - result[c] * matrix[row][c];
+        }
+
+        result[row] = value * (1.0 / matrix[row][row]);
+    }
+
+    Ok(result)
+}
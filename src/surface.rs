@@ -0,0 +1,379 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use std::io::Write;
+use enum_primitive::FromPrimitive;
+
+use ::{
+    CodePair,
+    DxfError,
+    DxfResult,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use acis::{
+    read_modeler_data,
+    write_modeler_data,
+};
+use code_pair_cursor::CodePairCursor;
+use code_pair_writer::CodePairWriter;
+use entities::*;
+use enums::*;
+use helper_functions::*;
+
+// The number of bytes written in a single binary data pair.
+const BINARY_CHUNK_LENGTH: usize = 128;
+
+// Reads the pairs of the `AcDbModelerGeometry`, `AcDbSurface`, and surface-specific subclasses,
+// passing the pairs of the last to `apply_surface_code_pair`; and writes them in the same order.
+macro_rules! impl_surface {
+    ($typ:ty, $subclass:expr) => {
+        impl $typ {
+            pub(crate) fn read_code_pairs(&mut self, pairs: &[CodePair]) -> DxfResult<()> {
+                let mut reader = CodePairCursor::new(pairs);
+                let mut subclass = "AcDbModelerGeometry";
+                while let Some(pair) = reader.next() {
+                    match (subclass, pair.code) {
+                        (_, 100) => { subclass = if pair.value.assert_string()? == $subclass { $subclass } else { "AcDbSurface" }; },
+                        ("AcDbModelerGeometry", 70) => { self.format_version_number = pair.value.assert_i16()?; },
+                        ("AcDbModelerGeometry", 1) | ("AcDbModelerGeometry", 3) => { read_modeler_data(pair, &mut self.custom_data, &mut self.custom_data2)?; },
+                        ("AcDbSurface", 71) => { self.u_isoline_count = pair.value.assert_i16()?; },
+                        ("AcDbSurface", 72) => { self.v_isoline_count = pair.value.assert_i16()?; },
+                        ($subclass, _) => { self.apply_surface_code_pair(pair, &mut reader)?; },
+                        _ => (), // unknown code, just ignore
+                    }
+                }
+
+                Ok(())
+            }
+            pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+                where T: Write {
+
+                writer.write_code_pair(&CodePair::new_str(100, "AcDbModelerGeometry"))?;
+                writer.write_code_pair(&CodePair::new_i16(70, self.format_version_number))?;
+                write_modeler_data(&self.custom_data, &self.custom_data2, writer)?;
+                writer.write_code_pair(&CodePair::new_str(100, "AcDbSurface"))?;
+                writer.write_code_pair(&CodePair::new_i16(71, self.u_isoline_count))?;
+                writer.write_code_pair(&CodePair::new_i16(72, self.v_isoline_count))?;
+                writer.write_code_pair(&CodePair::new_str(100, $subclass))?;
+                self.write_surface_code_pairs(writer)
+            }
+        }
+    };
+}
+
+impl_surface!(ExtrudedSurface, "AcDbExtrudedSurface");
+impl_surface!(LoftedSurface, "AcDbLoftedSurface");
+impl_surface!(PlaneSurface, "AcDbPlaneSurface");
+impl_surface!(RevolvedSurface, "AcDbRevolvedSurface");
+impl_surface!(SweptSurface, "AcDbSweptSurface");
+
+//------------------------------------------------------------------------------
+//                                                               ExtrudedSurface
+//------------------------------------------------------------------------------
+impl ExtrudedSurface {
+    fn apply_surface_code_pair(&mut self, pair: &CodePair, reader: &mut CodePairCursor) -> DxfResult<()> {
+        match pair.code {
+            90 => {
+                self.class_id = pair.value.assert_i32()?;
+                self.sweep_entity_data = reader.read_binary_data()?;
+            },
+            10 => { self.sweep_vector.x = pair.value.assert_f64()?; },
+            20 => { self.sweep_vector.y = pair.value.assert_f64()?; },
+            30 => { self.sweep_vector.z = pair.value.assert_f64()?; },
+            40 => { self.extruded_entity_transform = reader.read_transformation_matrix(pair)?; },
+            42 => { self.draft_angle = pair.value.assert_f64()?; },
+            43 => { self.draft_start_distance = pair.value.assert_f64()?; },
+            44 => { self.draft_end_distance = pair.value.assert_f64()?; },
+            45 => { self.twist_angle = pair.value.assert_f64()?; },
+            48 => { self.scale_factor = pair.value.assert_f64()?; },
+            49 => { self.align_angle = pair.value.assert_f64()?; },
+            46 => { self.sweep_entity_transform = reader.read_transformation_matrix(pair)?; },
+            47 => { self.path_entity_transform = reader.read_transformation_matrix(pair)?; },
+            290 => { self.is_solid = pair.value.assert_bool()?; },
+            70 => { self.sweep_alignment = try_result!(SweepAlignment::from_i16(pair.value.assert_i16()?)); },
+            292 => { self.is_start_aligned = pair.value.assert_bool()?; },
+            293 => { self.is_banked = pair.value.assert_bool()?; },
+            294 => { self.is_base_point_set = pair.value.assert_bool()?; },
+            295 => { self.is_sweep_entity_transform_computed = pair.value.assert_bool()?; },
+            296 => { self.is_path_entity_transform_computed = pair.value.assert_bool()?; },
+            11 => { self.twist_reference_vector.x = pair.value.assert_f64()?; },
+            21 => { self.twist_reference_vector.y = pair.value.assert_f64()?; },
+            31 => { self.twist_reference_vector.z = pair.value.assert_f64()?; },
+            _ => (), // unknown code, just ignore
+        }
+
+        Ok(())
+    }
+    fn write_surface_code_pairs<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_i32(90, self.class_id))?;
+        write_binary_data(writer, &self.sweep_entity_data)?;
+        write_vector(writer, 10, &self.sweep_vector)?;
+        write_transformation_matrix(writer, 40, &self.extruded_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_f64(42, self.draft_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.draft_start_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(44, self.draft_end_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(45, self.twist_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(48, self.scale_factor))?;
+        writer.write_code_pair(&CodePair::new_f64(49, self.align_angle))?;
+        write_transformation_matrix(writer, 46, &self.sweep_entity_transform)?;
+        write_transformation_matrix(writer, 47, &self.path_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_bool(290, self.is_solid))?;
+        writer.write_code_pair(&CodePair::new_i16(70, self.sweep_alignment as i16))?;
+        writer.write_code_pair(&CodePair::new_bool(292, self.is_start_aligned))?;
+        writer.write_code_pair(&CodePair::new_bool(293, self.is_banked))?;
+        writer.write_code_pair(&CodePair::new_bool(294, self.is_base_point_set))?;
+        writer.write_code_pair(&CodePair::new_bool(295, self.is_sweep_entity_transform_computed))?;
+        writer.write_code_pair(&CodePair::new_bool(296, self.is_path_entity_transform_computed))?;
+        write_vector(writer, 11, &self.twist_reference_vector)?;
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                 LoftedSurface
+//------------------------------------------------------------------------------
+impl LoftedSurface {
+    fn apply_surface_code_pair(&mut self, pair: &CodePair, reader: &mut CodePairCursor) -> DxfResult<()> {
+        match pair.code {
+            40 => { self.loft_entity_transform = reader.read_transformation_matrix(pair)?; },
+            70 => { self.normal_mode = try_result!(LoftedObjectNormalMode::from_i16(pair.value.assert_i16()?)); },
+            41 => { self.start_draft_angle = pair.value.assert_f64()?; },
+            42 => { self.end_draft_angle = pair.value.assert_f64()?; },
+            43 => { self.start_draft_magnitude = pair.value.assert_f64()?; },
+            44 => { self.end_draft_magnitude = pair.value.assert_f64()?; },
+            290 => { self.is_arc_length_parameterized = pair.value.assert_bool()?; },
+            291 => { self.has_no_twist = pair.value.assert_bool()?; },
+            292 => { self.is_direction_aligned = pair.value.assert_bool()?; },
+            293 => { self.create_simple_surfaces = pair.value.assert_bool()?; },
+            294 => { self.create_closed_surface = pair.value.assert_bool()?; },
+            295 => { self.is_solid = pair.value.assert_bool()?; },
+            296 => { self.create_ruled_surface = pair.value.assert_bool()?; },
+            297 => { self.is_virtual_guide = pair.value.assert_bool()?; },
+            _ => (), // unknown code, just ignore
+        }
+
+        Ok(())
+    }
+    fn write_surface_code_pairs<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        write_transformation_matrix(writer, 40, &self.loft_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_i16(70, self.normal_mode as i16))?;
+        writer.write_code_pair(&CodePair::new_f64(41, self.start_draft_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(42, self.end_draft_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.start_draft_magnitude))?;
+        writer.write_code_pair(&CodePair::new_f64(44, self.end_draft_magnitude))?;
+        writer.write_code_pair(&CodePair::new_bool(290, self.is_arc_length_parameterized))?;
+        writer.write_code_pair(&CodePair::new_bool(291, self.has_no_twist))?;
+        writer.write_code_pair(&CodePair::new_bool(292, self.is_direction_aligned))?;
+        writer.write_code_pair(&CodePair::new_bool(293, self.create_simple_surfaces))?;
+        writer.write_code_pair(&CodePair::new_bool(294, self.create_closed_surface))?;
+        writer.write_code_pair(&CodePair::new_bool(295, self.is_solid))?;
+        writer.write_code_pair(&CodePair::new_bool(296, self.create_ruled_surface))?;
+        writer.write_code_pair(&CodePair::new_bool(297, self.is_virtual_guide))?;
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                  PlaneSurface
+//------------------------------------------------------------------------------
+impl PlaneSurface {
+    fn apply_surface_code_pair(&mut self, _pair: &CodePair, _reader: &mut CodePairCursor) -> DxfResult<()> {
+        // the plane is entirely described by the modeler geometry
+        Ok(())
+    }
+    fn write_surface_code_pairs<T>(&self, _writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                               RevolvedSurface
+//------------------------------------------------------------------------------
+impl RevolvedSurface {
+    fn apply_surface_code_pair(&mut self, pair: &CodePair, reader: &mut CodePairCursor) -> DxfResult<()> {
+        match pair.code {
+            90 => {
+                self.revolve_entity_id = pair.value.assert_i32()?;
+                self.revolve_entity_data = reader.read_binary_data()?;
+            },
+            10 => { self.axis_point.x = pair.value.assert_f64()?; },
+            20 => { self.axis_point.y = pair.value.assert_f64()?; },
+            30 => { self.axis_point.z = pair.value.assert_f64()?; },
+            11 => { self.axis_vector.x = pair.value.assert_f64()?; },
+            21 => { self.axis_vector.y = pair.value.assert_f64()?; },
+            31 => { self.axis_vector.z = pair.value.assert_f64()?; },
+            40 => { self.revolve_angle = pair.value.assert_f64()?; },
+            41 => { self.start_angle = pair.value.assert_f64()?; },
+            42 => { self.revolve_entity_transform = reader.read_transformation_matrix(pair)?; },
+            43 => { self.draft_angle = pair.value.assert_f64()?; },
+            44 => { self.start_draft_distance = pair.value.assert_f64()?; },
+            45 => { self.end_draft_distance = pair.value.assert_f64()?; },
+            46 => { self.twist_angle = pair.value.assert_f64()?; },
+            290 => { self.is_solid = pair.value.assert_bool()?; },
+            291 => { self.is_close_to_axis = pair.value.assert_bool()?; },
+            _ => (), // unknown code, just ignore
+        }
+
+        Ok(())
+    }
+    fn write_surface_code_pairs<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_i32(90, self.revolve_entity_id))?;
+        write_binary_data(writer, &self.revolve_entity_data)?;
+        write_point(writer, 10, &self.axis_point)?;
+        write_vector(writer, 11, &self.axis_vector)?;
+        writer.write_code_pair(&CodePair::new_f64(40, self.revolve_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(41, self.start_angle))?;
+        write_transformation_matrix(writer, 42, &self.revolve_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.draft_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(44, self.start_draft_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(45, self.end_draft_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(46, self.twist_angle))?;
+        writer.write_code_pair(&CodePair::new_bool(290, self.is_solid))?;
+        writer.write_code_pair(&CodePair::new_bool(291, self.is_close_to_axis))?;
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                  SweptSurface
+//------------------------------------------------------------------------------
+impl SweptSurface {
+    fn apply_surface_code_pair(&mut self, pair: &CodePair, reader: &mut CodePairCursor) -> DxfResult<()> {
+        match pair.code {
+            90 => {
+                // the sweep entity is always followed by the path entity
+                self.sweep_entity_id = pair.value.assert_i32()?;
+                self.sweep_entity_data = reader.read_binary_data()?;
+                self.path_entity_id = reader.expect(90)?.value.assert_i32()?;
+                self.path_entity_data = reader.read_binary_data()?;
+            },
+            40 => { self.sweep_entity_transform = reader.read_transformation_matrix(pair)?; },
+            41 => { self.path_entity_transform = reader.read_transformation_matrix(pair)?; },
+            42 => { self.draft_angle = pair.value.assert_f64()?; },
+            43 => { self.draft_start_distance = pair.value.assert_f64()?; },
+            44 => { self.draft_end_distance = pair.value.assert_f64()?; },
+            45 => { self.twist_angle = pair.value.assert_f64()?; },
+            48 => { self.scale_factor = pair.value.assert_f64()?; },
+            49 => { self.align_angle = pair.value.assert_f64()?; },
+            46 => { self.computed_sweep_entity_transform = reader.read_transformation_matrix(pair)?; },
+            47 => { self.computed_path_entity_transform = reader.read_transformation_matrix(pair)?; },
+            290 => { self.is_solid = pair.value.assert_bool()?; },
+            70 => { self.sweep_alignment = try_result!(SweepAlignment::from_i16(pair.value.assert_i16()?)); },
+            292 => { self.is_start_aligned = pair.value.assert_bool()?; },
+            293 => { self.is_banked = pair.value.assert_bool()?; },
+            294 => { self.is_base_point_set = pair.value.assert_bool()?; },
+            295 => { self.is_sweep_entity_transform_computed = pair.value.assert_bool()?; },
+            296 => { self.is_path_entity_transform_computed = pair.value.assert_bool()?; },
+            11 => { self.twist_reference_vector.x = pair.value.assert_f64()?; },
+            21 => { self.twist_reference_vector.y = pair.value.assert_f64()?; },
+            31 => { self.twist_reference_vector.z = pair.value.assert_f64()?; },
+            _ => (), // unknown code, just ignore
+        }
+
+        Ok(())
+    }
+    fn write_surface_code_pairs<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+        where T: Write {
+
+        writer.write_code_pair(&CodePair::new_i32(90, self.sweep_entity_id))?;
+        write_binary_data(writer, &self.sweep_entity_data)?;
+        writer.write_code_pair(&CodePair::new_i32(90, self.path_entity_id))?;
+        write_binary_data(writer, &self.path_entity_data)?;
+        write_transformation_matrix(writer, 40, &self.sweep_entity_transform)?;
+        write_transformation_matrix(writer, 41, &self.path_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_f64(42, self.draft_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.draft_start_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(44, self.draft_end_distance))?;
+        writer.write_code_pair(&CodePair::new_f64(45, self.twist_angle))?;
+        writer.write_code_pair(&CodePair::new_f64(48, self.scale_factor))?;
+        writer.write_code_pair(&CodePair::new_f64(49, self.align_angle))?;
+        write_transformation_matrix(writer, 46, &self.computed_sweep_entity_transform)?;
+        write_transformation_matrix(writer, 47, &self.computed_path_entity_transform)?;
+        writer.write_code_pair(&CodePair::new_bool(290, self.is_solid))?;
+        writer.write_code_pair(&CodePair::new_i16(70, self.sweep_alignment as i16))?;
+        writer.write_code_pair(&CodePair::new_bool(292, self.is_start_aligned))?;
+        writer.write_code_pair(&CodePair::new_bool(293, self.is_banked))?;
+        writer.write_code_pair(&CodePair::new_bool(294, self.is_base_point_set))?;
+        writer.write_code_pair(&CodePair::new_bool(295, self.is_sweep_entity_transform_computed))?;
+        writer.write_code_pair(&CodePair::new_bool(296, self.is_path_entity_transform_computed))?;
+        write_vector(writer, 11, &self.twist_reference_vector)?;
+        Ok(())
+    }
+}
+
+fn write_point<T>(writer: &mut CodePairWriter<T>, code: i32, point: &Point) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_f64(code, point.x))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 10, point.y))?;
+    writer.write_code_pair(&CodePair::new_f64(code + 20, point.z))?;
+    Ok(())
+}
+
+fn write_vector<T>(writer: &mut CodePairWriter<T>, code: i32, vector: &Vector) -> DxfResult<()>
+    where T: Write {
+
+    write_point(writer, code, &Point::new(vector.x, vector.y, vector.z))
+}
+
+fn write_transformation_matrix<T>(writer: &mut CodePairWriter<T>, code: i32, matrix: &TransformationMatrix) -> DxfResult<()>
+    where T: Write {
+
+    for value in matrix.get_values() {
+        writer.write_code_pair(&CodePair::new_f64(code, value))?;
+    }
+
+    Ok(())
+}
+
+// Writes the size of the data followed by its hex strings.
+fn write_binary_data<T>(writer: &mut CodePairWriter<T>, data: &[u8]) -> DxfResult<()>
+    where T: Write {
+
+    writer.write_code_pair(&CodePair::new_i32(90, data.len() as i32))?;
+    for chunk in data.chunks(BINARY_CHUNK_LENGTH) {
+        let line = chunk.iter().map(|b| format!("{:02X}", b)).collect::<String>();
+        writer.write_code_pair(&CodePair::new_string(310, &line))?;
+    }
+
+    Ok(())
+}
+
+impl<'a> CodePairCursor<'a> {
+    // Reads the optional size of the binary data and the hex strings that follow it.
+    fn read_binary_data(&mut self) -> DxfResult<Vec<u8>> {
+        let mut data = vec![];
+        if self.next_if(&[90]).is_some() {
+            while let Some(pair) = self.next_if(&[310]) {
+                parse_hex_string(&pair.value.assert_string()?, &mut data)?;
+            }
+        }
+
+        Ok(data)
+    }
+    // Reads the 16 values of a matrix written with the same code as the first pair.
+    fn read_transformation_matrix(&mut self, first: &CodePair) -> DxfResult<TransformationMatrix> {
+        let mut values = vec![first.value.assert_f64()?];
+        while let Some(pair) = self.next_if(&[first.code]) {
+            values.push(pair.value.assert_f64()?);
+        }
+
+        if values.len() != 16 {
+            return Err(DxfError::UnexpectedCodePair(first.clone(), String::from("expected 16 matrix values")));
+        }
+
+        let mut matrix = TransformationMatrix::identity();
+        matrix.from_vec(&values);
+        Ok(matrix)
+    }
+}
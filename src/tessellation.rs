@@ -0,0 +1,407 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `entity.rs`
+
+use std::f64::consts::PI;
+
+use ::{
+    LwPolylineVertex,
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use entities::*;
+
+// the most segments any single curve will be split into, regardless of the requested tolerance
+const MAX_CURVE_SEGMENTS: usize = 4096;
+
+// the deepest a spline span will be recursively subdivided
+const MAX_SPLINE_DEPTH: usize = 12;
+
+impl Entity {
+    /// Approximates the entity as a series of polylines in world coordinates.
+    ///
+    /// Curves are split into straight segments such that no segment deviates from the true curve by more than
+    /// `tolerance`.  Closed curves return a polyline whose last point equals its first.  Wide `LwPolyline` and
+    /// `Polyline` segments return their closed outlines instead of their centerlines.  Entities without a finite
+    /// curve representation (e.g., `Text`, `Ray`) return an empty collection.
+    ///
+    /// A `tolerance` of zero or less (or NaN) requests the finest approximation allowed: 4096 segments per curve
+    /// and the deepest subdivision of splines.  Pass a tolerance larger than the curve for the coarsest one.
+    pub fn tessellate(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        let tolerance = if tolerance > 0.0 { tolerance } else { 0.0 };
+        match self.specific {
+            EntityType::Arc(ref arc) => {
+                let start = arc.start_angle.to_radians();
+                let sweep = normalize_sweep(arc.end_angle.to_radians() - start);
+                let points = arc_points(&arc.center, arc.radius, start, sweep, tolerance);
+                vec![to_wcs(&arc.normal, points)]
+            },
+            EntityType::Circle(ref circle) => {
+                let mut points = arc_points(&circle.center, circle.radius, 0.0, PI * 2.0, tolerance);
+                let first = points[0];
+                *points.last_mut().unwrap() = first; // ensure the loop is exactly closed
+                vec![to_wcs(&circle.normal, points)]
+            },
+            EntityType::Ellipse(ref ellipse) => vec![tessellate_ellipse(ellipse, tolerance)],
+            EntityType::Helix(ref helix) => vec![tessellate_helix(helix, tolerance)],
+            EntityType::Leader(ref leader) => {
+                if leader.vertices.len() < 2 { vec![] } else { vec![leader.vertices.clone()] }
+            },
+            EntityType::Line(ref line) => vec![vec![line.p1, line.p2]],
+            EntityType::LwPolyline(ref poly) => {
+                let vertices = poly.vertices.iter().map(|v| {
+                    let mut v = *v;
+                    if v.starting_width == 0.0 && v.ending_width == 0.0 {
+                        v.starting_width = poly.constant_width;
+                        v.ending_width = poly.constant_width;
+                    }
+                    v
+                }).collect::<Vec<_>>();
+                tessellate_bulged_vertices(&vertices, poly.get_is_closed(), self.common.elevation, tolerance)
+                    .into_iter()
+                    .map(|p| to_wcs(&poly.extrusion_direction, p))
+                    .collect()
+            },
+            EntityType::MLine(ref mline) => tessellate_mline(mline),
+            EntityType::Polyline(ref poly) => tessellate_polyline(poly, tolerance),
+            EntityType::Spline(ref spline) => {
+                let points = tessellate_spline(spline, tolerance);
+                if points.len() < 2 { vec![] } else { vec![points] }
+            },
+            _ => vec![],
+        }
+    }
+}
+
+// Returns the number of segments required to approximate an arc of the given radius and sweep (in radians).
+// A tolerance of zero or less gives the most segments allowed.
+pub(crate) fn arc_segment_count(radius: f64, sweep: f64, tolerance: f64) -> usize {
+    let radius = radius.abs();
+    let sweep = sweep.abs();
+    if radius == 0.0 || sweep == 0.0 {
+        return 1;
+    }
+
+    let max_step = if tolerance <= 0.0 {
+        0.0
+    }
+    else if tolerance < radius {
+        2.0 * (1.0 - tolerance / radius).acos()
+    }
+    else {
+        PI / 2.0
+    };
+    let count = if max_step > 0.0 { (sweep / max_step).ceil() as usize } else { MAX_CURVE_SEGMENTS };
+    count.max(1).min(MAX_CURVE_SEGMENTS)
+}
+
+// Returns the points along a circular arc in the XY plane at the center's Z value.  Angles are in radians.
+pub(crate) fn arc_points(center: &Point, radius: f64, start: f64, sweep: f64, tolerance: f64) -> Vec<Point> {
+    let count = arc_segment_count(radius, sweep, tolerance);
+    (0..count + 1).map(|i| {
+        let angle = start + sweep * i as f64 / count as f64;
+        Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin(), center.z)
+    }).collect()
+}
+
+// Maps a sweep angle in radians to the range (0, 2PI].
+pub(crate) fn normalize_sweep(sweep: f64) -> f64 {
+    let mut sweep = sweep % (PI * 2.0);
+    if sweep <= 0.0 {
+        sweep += PI * 2.0;
+    }
+    sweep
+}
+
+// Converts points from the OCS defined by `normal` into the WCS.
+pub(crate) fn to_wcs(normal: &Vector, points: Vec<Point>) -> Vec<Point> {
+    if *normal == Vector::z_axis() {
+        return points;
+    }
+
+    let matrix = TransformationMatrix::from_ocs(normal);
+    points.iter().map(|p| matrix.transform_point(p)).collect()
+}
+
+// Returns the center, radius, start angle, and signed sweep of the arc described by a bulge between two points.
+pub(crate) fn bulge_to_arc(p1: &Point, p2: &Point, bulge: f64) -> (Point, f64, f64, f64) {
+    let chord = Vector::new(p2.x - p1.x, p2.y - p1.y, 0.0);
+    let chord_length = chord.length();
+    let sweep = 4.0 * bulge.atan();
+    let radius = chord_length * (1.0 + bulge * bulge) / (4.0 * bulge.abs());
+
+    // distance from the chord midpoint to the center, positive to the left of the chord for a positive bulge
+    let sagitta_offset = radius - chord_length * bulge.abs() / 2.0;
+    let left = Vector::new(-chord.y, chord.x, 0.0).normalize();
+    let direction = if bulge > 0.0 { 1.0 } else { -1.0 };
+    let mid = Point::new((p1.x + p2.x) / 2.0, (p1.y + p2.y) / 2.0, p1.z);
+    let center = mid + left * (sagitta_offset * direction);
+    let start = (p1.y - center.y).atan2(p1.x - center.x);
+    (center, radius, start, sweep)
+}
+
+// Samples a single bulged segment; each sample is the point, the unit vector to the left of the direction of
+// travel, and the fraction of the way along the segment.
+fn bulged_segment_samples(p1: &Point, p2: &Point, bulge: f64, tolerance: f64) -> Vec<(Point, Vector, f64)> {
+    if bulge == 0.0 || p1 == p2 {
+        let direction = (*p2 - *p1).normalize();
+        let left = Vector::new(-direction.y, direction.x, 0.0);
+        return vec![(*p1, left, 0.0), (*p2, left, 1.0)];
+    }
+
+    let (center, radius, start, sweep) = bulge_to_arc(p1, p2, bulge);
+    let count = arc_segment_count(radius, sweep, tolerance);
+    (0..count + 1).map(|i| {
+        let t = i as f64 / count as f64;
+        let angle = start + sweep * t;
+        let radial = Vector::new(angle.cos(), angle.sin(), 0.0);
+        let point = if i == count { *p2 } else { center + radial * radius };
+
+        // the left side is toward the center on counter-clockwise arcs
+        let left = if sweep > 0.0 { -radial } else { radial };
+        (point, left, t)
+    }).collect()
+}
+
+// Tessellates a set of 2D vertices with bulges and widths in OCS.
+fn tessellate_bulged_vertices(vertices: &[LwPolylineVertex], is_closed: bool, elevation: f64, tolerance: f64) -> Vec<Vec<Point>> {
+    if vertices.is_empty() {
+        return vec![];
+    }
+
+    let segment_count = if is_closed { vertices.len() } else { vertices.len() - 1 };
+    let is_wide = vertices.iter().take(segment_count).any(|v| v.starting_width != 0.0 || v.ending_width != 0.0);
+    let mut centerline = vec![Point::new(vertices[0].x, vertices[0].y, elevation)];
+    let mut outlines = vec![];
+    for i in 0..segment_count {
+        let v1 = &vertices[i];
+        let v2 = &vertices[(i + 1) % vertices.len()];
+        let p1 = Point::new(v1.x, v1.y, elevation);
+        let p2 = Point::new(v2.x, v2.y, elevation);
+        let samples = bulged_segment_samples(&p1, &p2, v1.bulge, tolerance);
+        if is_wide {
+            let half_width = |t: f64| (v1.starting_width + (v1.ending_width - v1.starting_width) * t) / 2.0;
+            let mut outline = samples.iter().map(|&(p, left, t)| p + left * half_width(t)).collect::<Vec<_>>();
+            outline.extend(samples.iter().rev().map(|&(p, left, t)| p - left * half_width(t)));
+            let first = outline[0];
+            outline.push(first);
+            outlines.push(outline);
+        }
+        else {
+            centerline.extend(samples.into_iter().skip(1).map(|(p, _, _)| p));
+        }
+    }
+
+    if is_wide {
+        outlines
+    }
+    else if centerline.len() < 2 {
+        vec![]
+    }
+    else {
+        vec![centerline]
+    }
+}
+
+fn tessellate_polyline(poly: &Polyline, tolerance: f64) -> Vec<Vec<Point>> {
+    if poly.get_is_polyface_mesh() {
+        let locations = poly.vertices.iter()
+            .filter(|v| v.get_is_3d_polygon_mesh())
+            .map(|v| v.location)
+            .collect::<Vec<_>>();
+        let mut result = vec![];
+        for face in poly.vertices.iter().filter(|v| !v.get_is_3d_polygon_mesh()) {
+            let indices = [face.polyface_mesh_vertex_index1, face.polyface_mesh_vertex_index2, face.polyface_mesh_vertex_index3, face.polyface_mesh_vertex_index4];
+            let indices = indices.iter().cloned().filter(|&i| i != 0 && ((i as i64).abs() as usize) <= locations.len()).collect::<Vec<_>>();
+            for (i, &index) in indices.iter().enumerate() {
+                // a negative index marks the edge starting at that vertex as invisible
+                if index > 0 && indices.len() > 1 {
+                    let next = indices[(i + 1) % indices.len()];
+                    result.push(vec![locations[index as usize - 1], locations[(next as i64).abs() as usize - 1]]);
+                }
+            }
+        }
+        return result;
+    }
+
+    if poly.get_is_3d_polygon_mesh() {
+        let m = poly.polygon_mesh_m_vertex_count.max(0) as usize;
+        let n = poly.polygon_mesh_n_vertex_count.max(0) as usize;
+        if m == 0 || n == 0 || poly.vertices.len() < m * n {
+            return vec![];
+        }
+
+        let at = |row: usize, col: usize| poly.vertices[row * n + col].location;
+        let mut result = vec![];
+        for row in 0..m {
+            let mut line = (0..n).map(|col| at(row, col)).collect::<Vec<_>>();
+            if poly.get_is_polygon_mesh_closed_in_n_direction() {
+                line.push(at(row, 0));
+            }
+            result.push(line);
+        }
+        for col in 0..n {
+            let mut line = (0..m).map(|row| at(row, col)).collect::<Vec<_>>();
+            if poly.get_is_closed() {
+                line.push(at(0, col));
+            }
+            result.push(line);
+        }
+        return result;
+    }
+
+    // frame control points of spline-fit polylines aren't part of the displayed curve
+    let vertices = poly.vertices.iter()
+        .filter(|v| !v.get_is_spline_frame_control_point())
+        .collect::<Vec<_>>();
+    if poly.get_is_3d_polyline() {
+        let mut points = vertices.iter().map(|v| v.location).collect::<Vec<_>>();
+        if poly.get_is_closed() && !points.is_empty() {
+            let first = points[0];
+            points.push(first);
+        }
+        return if points.len() < 2 { vec![] } else { vec![points] };
+    }
+
+    let vertices = vertices.iter().map(|v| {
+        let (starting_width, ending_width) = if v.starting_width == 0.0 && v.ending_width == 0.0 {
+            (poly.default_starting_width, poly.default_ending_width)
+        }
+        else {
+            (v.starting_width, v.ending_width)
+        };
+        LwPolylineVertex {
+            x: v.location.x,
+            y: v.location.y,
+            starting_width,
+            ending_width,
+            bulge: v.bulge,
+            .. Default::default()
+        }
+    }).collect::<Vec<_>>();
+    tessellate_bulged_vertices(&vertices, poly.get_is_closed(), poly.location.z, tolerance)
+        .into_iter()
+        .map(|p| to_wcs(&poly.normal, p))
+        .collect()
+}
+
+fn tessellate_ellipse(ellipse: &Ellipse, tolerance: f64) -> Vec<Point> {
+    let major = ellipse.major_axis;
+    let minor = ellipse.normal.normalize().cross(&major) * ellipse.minor_axis_ratio;
+    let start = ellipse.start_parameter;
+    let sweep = normalize_sweep(ellipse.end_parameter - start);
+    let count = arc_segment_count(major.length(), sweep, tolerance);
+    (0..count + 1).map(|i| {
+        let t = start + sweep * i as f64 / count as f64;
+        ellipse.center + major * t.cos() + minor * t.sin()
+    }).collect()
+}
+
+fn tessellate_helix(helix: &Helix, tolerance: f64) -> Vec<Point> {
+    let axis = helix.axis_vector.normalize();
+    let offset = helix.start_point - helix.axis_base_point;
+    let radial = offset - axis * offset.dot(&axis);
+    let radial = if radial.length() == 0.0 { Vector::x_axis() * helix.radius } else { radial };
+    let radius = radial.length();
+    let sweep = PI * 2.0 * helix.number_of_turns;
+    let direction = if helix.is_right_handed { 1.0 } else { -1.0 };
+    let count = arc_segment_count(radius, sweep, tolerance);
+    let tangent = axis.cross(&radial);
+    (0..count + 1).map(|i| {
+        let angle = sweep * i as f64 / count as f64;
+        let rotated = radial * angle.cos() + tangent * (angle.sin() * direction);
+        helix.axis_base_point + rotated + axis * (helix.turn_height * angle / (PI * 2.0))
+    }).collect()
+}
+
+fn tessellate_mline(mline: &MLine) -> Vec<Vec<Point>> {
+    if mline.vertices.len() < 2 {
+        return vec![];
+    }
+
+    let close = |mut points: Vec<Point>| {
+        if mline.get_is_closed() {
+            let first = points[0];
+            points.push(first);
+        }
+        points
+    };
+
+    // each vertex stores a group of parameters per style element, the first of which is the offset along the miter
+    let element_count = mline.style_element_count.max(0) as usize;
+    let group_size = mline.vertices.len() * element_count;
+    if element_count == 0 || mline.miter_directions.len() != mline.vertices.len() || mline.parameters.is_empty() || mline.parameters.len() % group_size != 0 {
+        return vec![close(mline.vertices.clone())];
+    }
+
+    let parameters_per_element = mline.parameters.len() / group_size;
+    (0..element_count).map(|element| {
+        close(mline.vertices.iter().enumerate().map(|(i, v)| {
+            let offset = mline.parameters[(i * element_count + element) * parameters_per_element];
+            *v + mline.miter_directions[i] * offset
+        }).collect())
+    }).collect()
+}
+
+fn tessellate_spline(spline: &Spline, tolerance: f64) -> Vec<Point> {
+    if spline.control_points.is_empty() && spline.fit_points.len() > 1 {
+        let mut interpolated = spline.clone();
+        if interpolated.interpolate_fit_points().is_ok() {
+            return tessellate_spline(&interpolated, tolerance);
+        }
+    }
+
+    if spline.parameter_range().is_none() {
+        // not enough information to evaluate the curve; fall back to the defining points
+        return if spline.control_points.is_empty() { spline.fit_points.clone() } else { spline.control_points.clone() };
+    }
+
+    let degree = spline.degree_of_curve as usize;
+    let control_points = &spline.control_points;
+    let knots = &spline.knot_values;
+    let mut points = vec![spline.evaluate_point(degree, knots[degree])];
+    for span in degree..control_points.len() {
+        let (u0, u1) = (knots[span], knots[span + 1]);
+        if u1 <= u0 {
+            continue;
+        }
+
+        // start with a few pieces per span so s-curves within a single span aren't missed
+        let pieces = 4;
+        for i in 0..pieces {
+            let a = u0 + (u1 - u0) * i as f64 / pieces as f64;
+            let b = u0 + (u1 - u0) * (i + 1) as f64 / pieces as f64;
+            subdivide_spline(spline, degree, a, b, tolerance, 0, &mut points);
+        }
+    }
+
+    points
+}
+
+fn subdivide_spline(spline: &Spline, degree: usize, u0: f64, u1: f64, tolerance: f64, depth: usize, points: &mut Vec<Point>) {
+    let start = points[points.len() - 1];
+    let end = spline.evaluate_point(degree, u1);
+    let mid_u = (u0 + u1) / 2.0;
+    let mid = spline.evaluate_point(degree, mid_u);
+    if depth < MAX_SPLINE_DEPTH && distance_to_segment(&mid, &start, &end) > tolerance {
+        subdivide_spline(spline, degree, u0, mid_u, tolerance, depth + 1, points);
+        subdivide_spline(spline, degree, mid_u, u1, tolerance, depth + 1, points);
+    }
+    else {
+        points.push(end);
+    }
+}
+
+// Returns the shortest distance from `p` to the segment between `a` and `b`.
+pub(crate) fn distance_to_segment(p: &Point, a: &Point, b: &Point) -> f64 {
+    let ab = *b - *a;
+    let length_squared = ab.dot(&ab);
+    if length_squared == 0.0 {
+        return p.distance_to(a);
+    }
+
+    let t = ((*p - *a).dot(&ab) / length_squared).max(0.0).min(1.0);
+    p.distance_to(&(*a + ab * t))
+}
@@ -0,0 +1,197 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use entities::*;
+use enums::AcadVersion;
+
+/// A run of `Text` characters that share the same underline and overline state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    /// Whether the span is underlined, toggled by `%%u`.
+    pub is_underlined: bool,
+    /// Whether the span is overlined, toggled by `%%o`.
+    pub is_overlined: bool,
+}
+
+/// The value of a `Text`, `Attribute`, or `AttributeDefinition` with its control codes decoded.
+///
+/// `%%c`, `%%d`, and `%%p` become `⌀`, `°`, and `±`, `%%%` becomes `%`, `%%nnn` becomes the
+/// character with the decimal code `nnn`, and `\U+nnnn` the Unicode character.  The `%%u` and
+/// `%%o` toggles split the text into spans.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecodedText {
+    pub spans: Vec<TextSpan>,
+}
+
+impl DecodedText {
+    /// Decodes the control codes of a text value.
+    pub fn decode(value: &str) -> DecodedText {
+        let chars = value.chars().collect::<Vec<_>>();
+        let mut spans = vec![];
+        let mut current = TextSpan::default();
+        let mut position = 0;
+        while position < chars.len() {
+            if chars[position] == '%' && chars.get(position + 1) == Some(&'%') {
+                match chars.get(position + 2) {
+                    Some('u') | Some('U') | Some('o') | Some('O') => {
+                        let is_underline = chars[position + 2].eq_ignore_ascii_case(&'u');
+                        let mut next = TextSpan {
+                            text: String::new(),
+                            is_underlined: current.is_underlined != is_underline,
+                            is_overlined: current.is_overlined == is_underline,
+                        };
+                        ::std::mem::swap(&mut current, &mut next);
+                        if !next.text.is_empty() {
+                            spans.push(next);
+                        }
+
+                        position += 3;
+                        continue;
+                    },
+                    _ => (),
+                }
+            }
+
+            match decode_special_character(&chars, position) {
+                Some((c, length)) => {
+                    current.text.push(c);
+                    position += length;
+                },
+                None => {
+                    current.text.push(chars[position]);
+                    position += 1;
+                },
+            }
+        }
+
+        if !current.text.is_empty() {
+            spans.push(current);
+        }
+
+        DecodedText { spans }
+    }
+    /// Encodes the text as a value for a drawing of the specified version.  Versions before
+    /// R2007 can't store Unicode so characters outside of ASCII are written as `\U+nnnn`.
+    pub fn encode(&self, version: AcadVersion) -> String {
+        let mut result = String::new();
+        let mut is_underlined = false;
+        let mut is_overlined = false;
+        for (i, span) in self.spans.iter().enumerate() {
+            if span.is_underlined != is_underlined {
+                result.push_str("%%u");
+                is_underlined = span.is_underlined;
+            }
+
+            if span.is_overlined != is_overlined {
+                result.push_str("%%o");
+                is_overlined = span.is_overlined;
+            }
+
+            let mut chars = span.text.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\u{2300}' => result.push_str("%%c"),
+                    '\u{b0}' => result.push_str("%%d"),
+                    '\u{b1}' => result.push_str("%%p"),
+                    '%' if match chars.peek() {
+                        Some(&next) => is_percent_escaped_before(next),
+                        None => i + 1 < self.spans.len(),
+                    } => result.push_str("%%%"),
+                    // `\U+` would start a Unicode character
+                    '\\' if chars.peek() == Some(&'U') => result.push_str("\\U+005C"),
+                    _ if !c.is_ascii() && version < AcadVersion::R2007 => {
+                        let mut units = [0; 2];
+                        for unit in c.encode_utf16(&mut units) {
+                            result.push_str(&format!("\\U+{:04X}", unit));
+                        }
+                    },
+                    _ => result.push(c),
+                }
+            }
+        }
+
+        result
+    }
+    /// The text without control codes or underline and overline information.
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+}
+
+// Whether a `%` followed by the character has to be written as `%%%`; otherwise the two would start a control
+// code because `%`, `\u{2300}`, `\u{b0}`, and `\u{b1}` are all written starting with `%`.
+pub(crate) fn is_percent_escaped_before(next: char) -> bool {
+    next == '%' || next == '\u{2300}' || next == '\u{b0}' || next == '\u{b1}'
+}
+
+// Decodes the `%%` special character or `\U+nnnn` escape at the specified position, returning
+// the character and the length of the code.
+pub(crate) fn decode_special_character(chars: &[char], position: usize) -> Option<(char, usize)> {
+    match (chars.get(position), chars.get(position + 1)) {
+        (Some('%'), Some('%')) => match chars.get(position + 2) {
+            Some('c') | Some('C') => Some(('\u{2300}', 3)),
+            Some('d') | Some('D') => Some(('\u{b0}', 3)),
+            Some('p') | Some('P') => Some(('\u{b1}', 3)),
+            Some('%') => Some(('%', 3)),
+            _ => {
+                let digits = chars.iter().skip(position + 2).take(3).collect::<String>();
+                if digits.len() == 3 && digits.chars().all(|c| c.is_ascii_digit()) {
+                    // the code page of the font isn't known, so codes are treated as Latin-1
+                    digits.parse::<u32>().ok().and_then(::std::char::from_u32).map(|c| (c, 5))
+                }
+                else {
+                    None
+                }
+            },
+        },
+        (Some('\\'), Some('U')) if chars.get(position + 2) == Some(&'+') => {
+            let digits = chars.iter().skip(position + 3).take(4).collect::<String>();
+            if digits.len() != 4 {
+                return None;
+            }
+
+            let unit = u32::from_str_radix(&digits, 16).ok()?;
+            if (0xD800..0xDC00).contains(&unit) {
+                // a surrogate pair written as two escapes
+                let low = chars.iter().skip(position + 7).take(7).collect::<String>();
+                if !low.starts_with("\\U+") {
+                    return None;
+                }
+
+                let low = u32::from_str_radix(&low[3..], 16).ok()?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return None;
+                }
+
+                ::std::char::from_u32(0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)).map(|c| (c, 14))
+            }
+            else {
+                ::std::char::from_u32(unit).map(|c| (c, 7))
+            }
+        },
+        _ => None,
+    }
+}
+
+macro_rules! impl_text_codes {
+    ($typ:ty) => {
+        impl $typ {
+            /// The `value` with its control codes decoded.
+            pub fn decoded_value(&self) -> DecodedText {
+                DecodedText::decode(&self.value)
+            }
+            /// Sets the `value` from decoded text, encoded for a drawing of the specified version.
+            pub fn set_decoded_value(&mut self, value: &DecodedText, version: AcadVersion) {
+                self.value = value.encode(version);
+            }
+            /// The `value` without control codes; see `DecodedText::plain_text`.
+            pub fn plain_text(&self) -> String {
+                self.decoded_value().plain_text()
+            }
+        }
+    };
+}
+
+impl_text_codes!(Text);
+impl_text_codes!(Attribute);
+impl_text_codes!(AttributeDefinition);
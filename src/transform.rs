@@ -0,0 +1,693 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+// other implementation is in `entity.rs`
+
+use std::f64::consts::PI;
+
+use ::{
+    Point,
+    TransformationMatrix,
+    Vector,
+};
+
+use acad_table::*;
+use entities::*;
+use hatch::*;
+use mleader::*;
+use tessellation::normalize_sweep;
+
+// Maps points, angles, and lengths from one object coordinate system (OCS) through a
+// transformation into the OCS defined by the transformed normal.
+struct OcsMap<'a> {
+    matrix: &'a TransformationMatrix,
+    old_axes: (Vector, Vector, Vector),
+    new_axes: (Vector, Vector, Vector),
+}
+
+impl<'a> OcsMap<'a> {
+    fn new(matrix: &'a TransformationMatrix, normal: &Vector) -> OcsMap<'a> {
+        let old_axes = TransformationMatrix::ocs_axes(normal);
+        let x = matrix.transform_vector(&old_axes.0);
+        let y = matrix.transform_vector(&old_axes.1);
+        let new_normal = x.cross(&y);
+        let new_normal = if new_normal.length() == 0.0 { matrix.transform_vector(&old_axes.2) } else { new_normal };
+        OcsMap {
+            matrix,
+            old_axes,
+            new_axes: TransformationMatrix::ocs_axes(&new_normal),
+        }
+    }
+    fn normal(&self) -> Vector {
+        self.new_axes.2
+    }
+    fn to_new_ocs(&self, v: &Vector) -> Point {
+        Point::new(v.dot(&self.new_axes.0), v.dot(&self.new_axes.1), v.dot(&self.new_axes.2))
+    }
+    fn point(&self, p: &Point) -> Point {
+        let (x, y, z) = self.old_axes;
+        let wcs = Point::origin() + x * p.x + y * p.y + z * p.z;
+        self.to_new_ocs(&self.matrix.transform_point(&wcs).as_vector())
+    }
+    fn vector(&self, v: &Vector) -> Vector {
+        let (x, y, z) = self.old_axes;
+        self.to_new_ocs(&self.matrix.transform_vector(&(x * v.x + y * v.y + z * v.z))).as_vector()
+    }
+    // Maps a 2D point at the given elevation, dropping the new elevation.
+    fn planar_point(&self, p: &Point, elevation: f64) -> Point {
+        let p = self.point(&Point::new(p.x, p.y, elevation));
+        Point::new(p.x, p.y, 0.0)
+    }
+    fn direction(&self, degrees: f64) -> Vector {
+        let angle = degrees.to_radians();
+        self.matrix.transform_vector(&(self.old_axes.0 * angle.cos() + self.old_axes.1 * angle.sin()))
+    }
+    // Maps an angle in degrees measured in the old OCS to the new OCS.
+    fn angle(&self, degrees: f64) -> f64 {
+        let d = self.to_new_ocs(&self.direction(degrees));
+        d.y.atan2(d.x).to_degrees()
+    }
+    // Maps an angle in degrees measured from the direction `base` in the old OCS to one measured from
+    // the mapped direction.
+    fn relative_angle(&self, base: f64, degrees: f64) -> f64 {
+        if degrees == 0.0 {
+            return 0.0;
+        }
+
+        let from = self.to_new_ocs(&self.direction(base));
+        let to = self.to_new_ocs(&self.direction(base + degrees));
+        (from.x * to.y - from.y * to.x).atan2(from.x * to.x + from.y * to.y).to_degrees()
+    }
+    // The scale applied to lengths in the direction of the specified angle.
+    fn scale_along(&self, degrees: f64) -> f64 {
+        self.direction(degrees).length()
+    }
+    // The scale applied to lengths along the normal.
+    fn normal_scale(&self) -> f64 {
+        self.matrix.transform_vector(&self.old_axes.2).length()
+    }
+    // The average scale applied to lengths in the plane.
+    fn scale(&self) -> f64 {
+        self.direction(0.0).cross(&self.direction(90.0)).length().sqrt()
+    }
+    // Whether circles stay circles.
+    fn is_conformal(&self) -> bool {
+        let x = self.direction(0.0);
+        let y = self.direction(90.0);
+        let tolerance = 1.0e-9 * x.length().max(y.length());
+        (x.length() - y.length()).abs() <= tolerance && x.dot(&y).abs() <= tolerance * x.length().max(y.length())
+    }
+}
+
+macro_rules! transform_text {
+    ($text:expr, $matrix:expr) => {{
+        let map = OcsMap::new($matrix, &$text.normal);
+        let height_scale = map.scale_along($text.rotation + 90.0);
+        if height_scale != 0.0 {
+            $text.relative_x_scale_factor *= map.scale_along($text.rotation) / height_scale;
+        }
+        $text.text_height *= height_scale;
+        $text.thickness *= map.normal_scale();
+        $text.location = map.point(&$text.location);
+        $text.second_alignment_point = map.point(&$text.second_alignment_point);
+        $text.rotation = map.angle($text.rotation);
+        $text.normal = map.normal();
+    }};
+}
+
+macro_rules! transform_dimension_base {
+    ($dim:expr, $matrix:expr) => {{
+        let map = OcsMap::new($matrix, &$dim.normal);
+        // the text rotation is measured from the dimension's horizontal direction
+        $dim.text_rotation_angle = map.relative_angle($dim.horizontal_direction_angle, $dim.text_rotation_angle);
+        $dim.horizontal_direction_angle = map.angle($dim.horizontal_direction_angle);
+        $dim.definition_point_1 = $matrix.transform_point(&$dim.definition_point_1);
+        $dim.text_mid_point = $matrix.transform_point(&$dim.text_mid_point);
+        $dim.normal = transform_direction($matrix, &$dim.normal);
+    }};
+}
+
+impl Entity {
+    /// Applies the transformation to the entity's geometry.  Circles and arcs become ellipses if the
+    /// transformation doesn't preserve their shape; bulges, text, and other planar entities are only
+    /// exact for transformations that don't scale non-uniformly within their plane.  Entities that
+    /// store opaque geometry (e.g., `Solid3D`, `Region`, OLE frames, and underlays) are left
+    /// unchanged, and surfaces only transform their creation parameters and not their ACIS data.
+    pub fn transform(&mut self, matrix: &TransformationMatrix) {
+        let replacement = match self.specific {
+            EntityType::Arc(ref arc) => Some(transform_circular(matrix, &arc.center, arc.radius, &arc.normal, arc.start_angle, arc.end_angle, arc.thickness)),
+            EntityType::Circle(ref circle) => match transform_circular(matrix, &circle.center, circle.radius, &circle.normal, 0.0, 360.0, circle.thickness) {
+                EntityType::Arc(arc) => {
+                    let mut new_circle = Circle::new(arc.center, arc.radius);
+                    new_circle.normal = arc.normal;
+                    new_circle.thickness = arc.thickness;
+                    Some(EntityType::Circle(new_circle))
+                },
+                e => Some(e),
+            },
+            EntityType::Ellipse(ref mut ellipse) => {
+                let minor = ellipse.normal.normalize().cross(&ellipse.major_axis) * ellipse.minor_axis_ratio;
+                let is_full = (normalize_sweep(ellipse.end_parameter - ellipse.start_parameter) - PI * 2.0).abs() < 1.0e-12;
+                let new_ellipse = ellipse_from_conjugate_diameters(
+                    matrix.transform_point(&ellipse.center),
+                    matrix.transform_vector(&ellipse.major_axis),
+                    matrix.transform_vector(&minor),
+                    ellipse.start_parameter,
+                    ellipse.end_parameter,
+                    is_full);
+                *ellipse = new_ellipse;
+                None
+            },
+            _ => None,
+        };
+        if let Some(specific) = replacement {
+            self.specific = specific;
+            return;
+        }
+
+        match self.specific {
+            EntityType::AcadTable(ref mut table) => {
+                let horizontal = matrix.transform_vector(&table.horizontal_direction.normalize());
+                let vertical = matrix.transform_vector(&Vector::z_axis().cross(&table.horizontal_direction).normalize());
+                let vertical_scale = vertical.length();
+                scale_all(&mut table.column_widths, horizontal.length());
+                scale_all(&mut table.row_heights, vertical_scale);
+                for cell in table.cells.iter_mut() {
+                    if let Some(ref mut text_height) = cell.overrides.text_height {
+                        *text_height *= vertical_scale;
+                    }
+                    if let AcadTableCellContent::Block { ref mut scale, .. } = cell.content {
+                        *scale *= vertical_scale;
+                    }
+                }
+                table.insertion_point = matrix.transform_point(&table.insertion_point);
+                table.horizontal_direction = horizontal.normalize();
+            },
+            EntityType::AttributeDefinition(ref mut att) => transform_text!(att, matrix),
+            EntityType::Attribute(ref mut att) => transform_text!(att, matrix),
+            EntityType::RotatedDimension(ref mut dim) => {
+                let map = OcsMap::new(matrix, &dim.dimension_base.normal);
+                dim.rotation_angle = map.angle(dim.rotation_angle);
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.insertion_point = matrix.transform_point(&dim.insertion_point);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+            },
+            EntityType::RadialDimension(ref mut dim) => {
+                dim.leader_length *= OcsMap::new(matrix, &dim.dimension_base.normal).scale();
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+            },
+            EntityType::DiameterDimension(ref mut dim) => {
+                dim.leader_length *= OcsMap::new(matrix, &dim.dimension_base.normal).scale();
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+            },
+            EntityType::AngularThreePointDimension(ref mut dim) => {
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                dim.definition_point_4 = matrix.transform_point(&dim.definition_point_4);
+                dim.definition_point_5 = matrix.transform_point(&dim.definition_point_5);
+            },
+            EntityType::OrdinateDimension(ref mut dim) => {
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+            },
+            EntityType::AngularTwoLineDimension(ref mut dim) => {
+                // the arc point is in the OCS
+                dim.arc_definition_point = OcsMap::new(matrix, &dim.dimension_base.normal).point(&dim.arc_definition_point);
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                dim.definition_point_4 = matrix.transform_point(&dim.definition_point_4);
+            },
+            EntityType::ArcDimension(ref mut dim) => {
+                let map = OcsMap::new(matrix, &dim.dimension_base.normal);
+                dim.start_angle = map.angle(dim.start_angle.to_degrees()).to_radians();
+                dim.end_angle = map.angle(dim.end_angle.to_degrees()).to_radians();
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                dim.arc_center = matrix.transform_point(&dim.arc_center);
+                dim.leader_point_1 = matrix.transform_point(&dim.leader_point_1);
+                dim.leader_point_2 = matrix.transform_point(&dim.leader_point_2);
+            },
+            EntityType::LargeRadialDimension(ref mut dim) => {
+                transform_dimension_base!(dim.dimension_base, matrix);
+                dim.chord_point = matrix.transform_point(&dim.chord_point);
+                dim.override_center = matrix.transform_point(&dim.override_center);
+                dim.jog_point = matrix.transform_point(&dim.jog_point);
+            },
+            EntityType::ExtrudedSurface(ref mut surface) => {
+                let draft_scale = matrix.transform_vector(&surface.sweep_vector.normalize()).length();
+                surface.draft_start_distance *= draft_scale;
+                surface.draft_end_distance *= draft_scale;
+                surface.sweep_vector = matrix.transform_vector(&surface.sweep_vector);
+                surface.twist_reference_vector = matrix.transform_vector(&surface.twist_reference_vector);
+                surface.extruded_entity_transform = matrix.multiply(&surface.extruded_entity_transform);
+                surface.sweep_entity_transform = matrix.multiply(&surface.sweep_entity_transform);
+                surface.path_entity_transform = matrix.multiply(&surface.path_entity_transform);
+            },
+            EntityType::Face3D(ref mut face) => {
+                face.first_corner = matrix.transform_point(&face.first_corner);
+                face.second_corner = matrix.transform_point(&face.second_corner);
+                face.third_corner = matrix.transform_point(&face.third_corner);
+                face.fourth_corner = matrix.transform_point(&face.fourth_corner);
+            },
+            EntityType::Hatch(ref mut hatch) => {
+                let map = OcsMap::new(matrix, &hatch.extrusion_direction);
+                let scale = map.scale();
+                let elevation = hatch.elevation_point.z;
+                for path in hatch.boundary_paths.iter_mut() {
+                    match path.geometry {
+                        HatchBoundaryPathGeometry::Polyline { ref mut vertices, .. } => {
+                            for v in vertices.iter_mut() {
+                                v.location = map.planar_point(&v.location, elevation);
+                            }
+                        },
+                        HatchBoundaryPathGeometry::Edges(ref mut edges) => {
+                            for edge in edges.iter_mut() {
+                                *edge = transform_hatch_edge(&map, edge, elevation);
+                            }
+                        },
+                    }
+                }
+                for line in hatch.pattern_definition_lines.iter_mut() {
+                    let dash_scale = map.scale_along(line.angle);
+                    scale_all(&mut line.dash_lengths, dash_scale);
+                    line.angle = map.angle(line.angle);
+                    line.base_point = map.planar_point(&line.base_point, elevation);
+                    line.offset = map.vector(&line.offset);
+                }
+                hatch.pattern_angle = map.angle(hatch.pattern_angle);
+                hatch.pattern_scale *= scale;
+                hatch.pixel_size *= scale;
+                hatch.seed_points = hatch.seed_points.iter().map(|p| map.planar_point(p, elevation)).collect();
+                hatch.gradient_angle = map.angle(hatch.gradient_angle.to_degrees()).to_radians();
+                hatch.elevation_point = Point::new(0.0, 0.0, map.point(&Point::new(0.0, 0.0, elevation)).z);
+                hatch.extrusion_direction = map.normal();
+            },
+            EntityType::LoftedSurface(ref mut surface) => {
+                let scale = length_scale(matrix);
+                surface.start_draft_magnitude *= scale;
+                surface.end_draft_magnitude *= scale;
+                surface.loft_entity_transform = matrix.multiply(&surface.loft_entity_transform);
+            },
+            EntityType::Helix(ref mut helix) => {
+                let axis_scale = matrix.transform_vector(&helix.axis_vector.normalize()).length();
+                let (x, y, z) = (matrix.transform_vector(&Vector::x_axis()), matrix.transform_vector(&Vector::y_axis()), matrix.transform_vector(&Vector::z_axis()));
+                if x.dot(&y.cross(&z)) < 0.0 {
+                    helix.is_right_handed = !helix.is_right_handed;
+                }
+                helix.axis_base_point = matrix.transform_point(&helix.axis_base_point);
+                helix.start_point = matrix.transform_point(&helix.start_point);
+                helix.axis_vector = matrix.transform_vector(&helix.axis_vector);
+                helix.radius *= OcsMap::new(matrix, &helix.axis_vector).scale();
+                helix.turn_height *= axis_scale;
+            },
+            EntityType::Image(ref mut image) => {
+                image.location = matrix.transform_point(&image.location);
+                image.u_vector = matrix.transform_vector(&image.u_vector);
+                image.v_vector = matrix.transform_vector(&image.v_vector);
+            },
+            EntityType::Insert(ref mut insert) => {
+                let map = OcsMap::new(matrix, &insert.extrusion_direction);
+                insert.x_scale_factor *= map.scale_along(insert.rotation);
+                insert.y_scale_factor *= map.scale_along(insert.rotation + 90.0);
+                insert.z_scale_factor *= map.normal_scale();
+                insert.column_spacing *= map.scale_along(insert.rotation);
+                insert.row_spacing *= map.scale_along(insert.rotation + 90.0);
+                insert.location = map.point(&insert.location);
+                insert.rotation = map.angle(insert.rotation);
+                insert.extrusion_direction = map.normal();
+                for att in insert.attributes.iter_mut() {
+                    transform_text!(att, matrix);
+                }
+            },
+            EntityType::Leader(ref mut leader) => {
+                let scale = OcsMap::new(matrix, &leader.normal).scale();
+                leader.text_annotation_height *= scale;
+                leader.text_annotation_width *= scale;
+                leader.vertices = leader.vertices.iter().map(|p| matrix.transform_point(p)).collect();
+                leader.normal = transform_direction(matrix, &leader.normal);
+                leader.right = transform_direction(matrix, &leader.right);
+                leader.block_offset = matrix.transform_vector(&leader.block_offset);
+                leader.annotation_offset = matrix.transform_vector(&leader.annotation_offset);
+            },
+            EntityType::Light(ref mut light) => {
+                light.position = matrix.transform_point(&light.position);
+                light.target_location = matrix.transform_point(&light.target_location);
+            },
+            EntityType::Line(ref mut line) => {
+                line.thickness *= matrix.transform_vector(&line.extrusion_direction.normalize()).length();
+                line.p1 = matrix.transform_point(&line.p1);
+                line.p2 = matrix.transform_point(&line.p2);
+                line.extrusion_direction = transform_direction(matrix, &line.extrusion_direction);
+            },
+            EntityType::LwPolyline(ref mut poly) => {
+                let map = OcsMap::new(matrix, &poly.extrusion_direction);
+                let scale = map.scale();
+                let elevation = self.common.elevation;
+                poly.constant_width *= scale;
+                poly.thickness *= map.normal_scale();
+                for v in poly.vertices.iter_mut() {
+                    let p = map.point(&Point::new(v.x, v.y, elevation));
+                    v.x = p.x;
+                    v.y = p.y;
+                    v.starting_width *= scale;
+                    v.ending_width *= scale;
+                }
+                self.common.elevation = map.point(&Point::new(0.0, 0.0, elevation)).z;
+                poly.extrusion_direction = map.normal();
+            },
+            EntityType::MLine(ref mut mline) => {
+                mline.scale_factor *= OcsMap::new(matrix, &mline.normal).scale();
+                mline.start_point = matrix.transform_point(&mline.start_point);
+                mline.vertices = mline.vertices.iter().map(|p| matrix.transform_point(p)).collect();
+                mline.segment_directions = mline.segment_directions.iter().map(|v| transform_direction(matrix, v)).collect();
+                mline.miter_directions = mline.miter_directions.iter().map(|v| transform_direction(matrix, v)).collect();
+                mline.normal = transform_direction(matrix, &mline.normal);
+            },
+            EntityType::MLeader(ref mut mleader) => {
+                let ctx = &mut mleader.context_data;
+                let normal = ctx.plane_x_axis.cross(&ctx.plane_y_axis);
+                let normal = if normal.length() == 0.0 { Vector::z_axis() } else { normal };
+                let map = OcsMap::new(matrix, &normal);
+                let scale = map.scale();
+                mleader.dogleg_length *= scale;
+                mleader.arrowhead_size *= scale;
+                mleader.block_content_scale = mleader.block_content_scale * scale;
+                mleader.block_content_rotation = map.angle(mleader.block_content_rotation.to_degrees()).to_radians();
+                for att in mleader.block_attributes.iter_mut() {
+                    att.width *= scale;
+                }
+                ctx.text_height *= scale;
+                ctx.arrowhead_size *= scale;
+                ctx.landing_gap *= scale;
+                ctx.content_base_point = matrix.transform_point(&ctx.content_base_point);
+                ctx.plane_origin = matrix.transform_point(&ctx.plane_origin);
+                ctx.plane_x_axis = transform_direction(matrix, &ctx.plane_x_axis);
+                ctx.plane_y_axis = transform_direction(matrix, &ctx.plane_y_axis);
+                if let Some(ref mut text) = ctx.text_content {
+                    text.boundary_width *= scale;
+                    text.boundary_height *= scale;
+                    text.column_width *= scale;
+                    text.column_gutter *= scale;
+                    scale_all(&mut text.column_sizes, scale);
+                    text.rotation = map.angle(text.rotation.to_degrees()).to_radians();
+                    text.location = matrix.transform_point(&text.location);
+                    text.direction = transform_direction(matrix, &text.direction);
+                    text.normal = transform_direction(matrix, &text.normal);
+                }
+                if let Some(ref mut block) = ctx.block_content {
+                    block.scale = block.scale * scale;
+                    block.rotation = map.angle(block.rotation.to_degrees()).to_radians();
+                    block.location = matrix.transform_point(&block.location);
+                    block.normal = transform_direction(matrix, &block.normal);
+                    block.transformation_matrix = matrix.multiply(&block.transformation_matrix);
+                }
+                for leader in ctx.leaders.iter_mut() {
+                    leader.dogleg_length *= scale;
+                    leader.last_leader_line_point = matrix.transform_point(&leader.last_leader_line_point);
+                    leader.dogleg_vector = transform_direction(matrix, &leader.dogleg_vector);
+                    transform_mleader_breaks(matrix, &mut leader.breaks);
+                    for line in leader.lines.iter_mut() {
+                        line.vertices = line.vertices.iter().map(|p| matrix.transform_point(p)).collect();
+                        transform_mleader_breaks(matrix, &mut line.breaks);
+                    }
+                }
+            },
+            EntityType::Mesh(ref mut mesh) => {
+                mesh.vertices = mesh.vertices.iter().map(|p| matrix.transform_point(p)).collect();
+            },
+            EntityType::MText(ref mut mtext) => {
+                let normal = mtext.extrusion_direction.normalize();
+                let x_axis = if mtext.x_axis_direction.length() == 0.0 { Vector::x_axis() } else { mtext.x_axis_direction.normalize() };
+                let y_axis = normal.cross(&x_axis);
+                let new_x = matrix.transform_vector(&x_axis);
+                let new_y = matrix.transform_vector(&y_axis);
+                let height_scale = new_y.length();
+                mtext.initial_text_height *= height_scale;
+                mtext.reference_rectangle_width *= new_x.length();
+                mtext.horizontal_width *= new_x.length();
+                mtext.vertical_height *= height_scale;
+                mtext.insertion_point = matrix.transform_point(&mtext.insertion_point);
+                mtext.x_axis_direction = new_x.normalize();
+                mtext.extrusion_direction = new_x.cross(&new_y).normalize();
+                let (ocs_x, ocs_y, _) = TransformationMatrix::ocs_axes(&mtext.extrusion_direction);
+                mtext.rotation_angle = mtext.x_axis_direction.dot(&ocs_y).atan2(mtext.x_axis_direction.dot(&ocs_x));
+            },
+            EntityType::ModelPoint(ref mut point) => {
+                point.location = matrix.transform_point(&point.location);
+                point.extrusion_direction = transform_direction(matrix, &point.extrusion_direction);
+            },
+            EntityType::Polyline(ref mut poly) => {
+                if poly.get_is_3d_polyline() || poly.get_is_3d_polygon_mesh() || poly.get_is_polyface_mesh() {
+                    for v in poly.vertices.iter_mut() {
+                        v.location = matrix.transform_point(&v.location);
+                    }
+                }
+                else {
+                    let map = OcsMap::new(matrix, &poly.normal);
+                    let scale = map.scale();
+                    let elevation = poly.location.z;
+                    poly.default_starting_width *= scale;
+                    poly.default_ending_width *= scale;
+                    poly.thickness *= map.normal_scale();
+                    for v in poly.vertices.iter_mut() {
+                        let p = map.point(&Point::new(v.location.x, v.location.y, elevation));
+                        v.location = Point::new(p.x, p.y, 0.0);
+                        v.starting_width *= scale;
+                        v.ending_width *= scale;
+                    }
+                    poly.location = Point::new(0.0, 0.0, map.point(&Point::new(0.0, 0.0, elevation)).z);
+                    poly.normal = map.normal();
+                }
+            },
+            EntityType::RevolvedSurface(ref mut surface) => {
+                let draft_scale = matrix.transform_vector(&surface.axis_vector.normalize()).length();
+                surface.start_draft_distance *= draft_scale;
+                surface.end_draft_distance *= draft_scale;
+                surface.axis_point = matrix.transform_point(&surface.axis_point);
+                surface.axis_vector = matrix.transform_vector(&surface.axis_vector);
+                surface.revolve_entity_transform = matrix.multiply(&surface.revolve_entity_transform);
+            },
+            EntityType::Ray(ref mut ray) => {
+                ray.start_point = matrix.transform_point(&ray.start_point);
+                ray.unit_direction_vector = transform_direction(matrix, &ray.unit_direction_vector);
+            },
+            EntityType::Shape(ref mut shape) => {
+                let map = OcsMap::new(matrix, &shape.extrusion_direction);
+                let height_scale = map.scale_along(shape.rotation_angle + 90.0);
+                if height_scale != 0.0 {
+                    shape.relative_x_scale_factor *= map.scale_along(shape.rotation_angle) / height_scale;
+                }
+                shape.size *= height_scale;
+                shape.thickness *= map.normal_scale();
+                shape.location = map.point(&shape.location);
+                shape.rotation_angle = map.angle(shape.rotation_angle);
+                shape.extrusion_direction = map.normal();
+            },
+            EntityType::Solid(ref mut solid) => {
+                let map = OcsMap::new(matrix, &solid.extrusion_direction);
+                solid.thickness *= map.normal_scale();
+                solid.first_corner = map.point(&solid.first_corner);
+                solid.second_corner = map.point(&solid.second_corner);
+                solid.third_corner = map.point(&solid.third_corner);
+                solid.fourth_corner = map.point(&solid.fourth_corner);
+                solid.extrusion_direction = map.normal();
+            },
+            EntityType::Spline(ref mut spline) => {
+                spline.control_points = spline.control_points.iter().map(|p| matrix.transform_point(p)).collect();
+                spline.fit_points = spline.fit_points.iter().map(|p| matrix.transform_point(p)).collect();
+                spline.start_tangent = Point::origin() + matrix.transform_vector(&spline.start_tangent.as_vector());
+                spline.end_tangent = Point::origin() + matrix.transform_vector(&spline.end_tangent.as_vector());
+                spline.normal = transform_direction(matrix, &spline.normal);
+            },
+            EntityType::SweptSurface(ref mut surface) => {
+                let scale = length_scale(matrix);
+                surface.draft_start_distance *= scale;
+                surface.draft_end_distance *= scale;
+                surface.twist_reference_vector = matrix.transform_vector(&surface.twist_reference_vector);
+                surface.sweep_entity_transform = matrix.multiply(&surface.sweep_entity_transform);
+                surface.path_entity_transform = matrix.multiply(&surface.path_entity_transform);
+                surface.computed_sweep_entity_transform = matrix.multiply(&surface.computed_sweep_entity_transform);
+                surface.computed_path_entity_transform = matrix.multiply(&surface.computed_path_entity_transform);
+            },
+            EntityType::Text(ref mut text) => transform_text!(text, matrix),
+            EntityType::Tolerance(ref mut tolerance) => {
+                tolerance.insertion_point = matrix.transform_point(&tolerance.insertion_point);
+                tolerance.direction_vector = transform_direction(matrix, &tolerance.direction_vector);
+                tolerance.extrusion_direction = transform_direction(matrix, &tolerance.extrusion_direction);
+            },
+            EntityType::Trace(ref mut trace) => {
+                let map = OcsMap::new(matrix, &trace.extrusion_direction);
+                trace.thickness *= map.normal_scale();
+                trace.first_corner = map.point(&trace.first_corner);
+                trace.second_corner = map.point(&trace.second_corner);
+                trace.third_corner = map.point(&trace.third_corner);
+                trace.fourth_corner = map.point(&trace.fourth_corner);
+                trace.extrusion_direction = map.normal();
+            },
+            EntityType::Vertex(ref mut vertex) => vertex.location = matrix.transform_point(&vertex.location),
+            EntityType::Viewport(ref mut viewport) => {
+                // the frame stays axis-aligned in paper space and the model space view isn't changed
+                viewport.width *= matrix.transform_vector(&Vector::x_axis()).length();
+                viewport.height *= matrix.transform_vector(&Vector::y_axis()).length();
+                viewport.center = matrix.transform_point(&viewport.center);
+            },
+            EntityType::Wipeout(ref mut wipeout) => {
+                wipeout.location = matrix.transform_point(&wipeout.location);
+                wipeout.u_vector = matrix.transform_vector(&wipeout.u_vector);
+                wipeout.v_vector = matrix.transform_vector(&wipeout.v_vector);
+            },
+            EntityType::XLine(ref mut xline) => {
+                xline.first_point = matrix.transform_point(&xline.first_point);
+                xline.unit_direction_vector = transform_direction(matrix, &xline.unit_direction_vector);
+            },
+            _ => (),
+        }
+    }
+}
+
+fn transform_direction(matrix: &TransformationMatrix, v: &Vector) -> Vector {
+    matrix.transform_vector(v).normalize()
+}
+
+// The average scale applied to lengths in any direction.
+fn length_scale(matrix: &TransformationMatrix) -> f64 {
+    let (x, y, z) = (matrix.transform_vector(&Vector::x_axis()), matrix.transform_vector(&Vector::y_axis()), matrix.transform_vector(&Vector::z_axis()));
+    x.dot(&y.cross(&z)).abs().cbrt()
+}
+
+fn scale_all(values: &mut [f64], factor: f64) {
+    for v in values.iter_mut() {
+        *v *= factor;
+    }
+}
+
+fn transform_mleader_breaks(matrix: &TransformationMatrix, breaks: &mut [MLeaderBreak]) {
+    for b in breaks.iter_mut() {
+        b.start = matrix.transform_point(&b.start);
+        b.end = matrix.transform_point(&b.end);
+    }
+}
+
+// Transforms a `Hatch` boundary edge whose points are in the OCS described by `map`.  Clockwise arcs store the
+// negated angles of the arc.
+fn transform_hatch_edge(map: &OcsMap, edge: &HatchBoundaryEdge, elevation: f64) -> HatchBoundaryEdge {
+    match *edge {
+        HatchBoundaryEdge::Line { ref start, ref end } => HatchBoundaryEdge::Line {
+            start: map.planar_point(start, elevation),
+            end: map.planar_point(end, elevation),
+        },
+        HatchBoundaryEdge::CircularArc { ref center, radius, start_angle, end_angle, is_counter_clockwise } => {
+            let center = map.planar_point(center, elevation);
+            if map.is_conformal() {
+                let direction = if is_counter_clockwise { 1.0 } else { -1.0 };
+                let new_start = direction * map.angle(direction * start_angle);
+                HatchBoundaryEdge::CircularArc {
+                    center,
+                    radius: radius * map.scale_along(0.0),
+                    start_angle: new_start,
+                    // keep the sweep so full circles stay full
+                    end_angle: new_start + end_angle - start_angle,
+                    is_counter_clockwise,
+                }
+            }
+            else {
+                transform_hatch_elliptic_arc(map, center, Vector::new(radius, 0.0, 0.0), Vector::new(0.0, radius, 0.0), start_angle, end_angle, is_counter_clockwise)
+            }
+        },
+        HatchBoundaryEdge::EllipticArc { ref center, ref major_axis, minor_axis_ratio, start_angle, end_angle, is_counter_clockwise } => {
+            let minor_axis = Vector::new(-major_axis.y, major_axis.x, 0.0) * minor_axis_ratio;
+            transform_hatch_elliptic_arc(map, map.planar_point(center, elevation), *major_axis, minor_axis, start_angle, end_angle, is_counter_clockwise)
+        },
+        HatchBoundaryEdge::Spline { degree, is_rational, is_periodic, ref knot_values, ref control_points, ref weights, ref fit_points, ref start_tangent, ref end_tangent } => HatchBoundaryEdge::Spline {
+            degree,
+            is_rational,
+            is_periodic,
+            knot_values: knot_values.clone(),
+            control_points: control_points.iter().map(|p| map.planar_point(p, elevation)).collect(),
+            weights: weights.clone(),
+            fit_points: fit_points.iter().map(|p| map.planar_point(p, elevation)).collect(),
+            start_tangent: map.vector(start_tangent),
+            end_tangent: map.vector(end_tangent),
+        },
+    }
+}
+
+// Transforms the elliptic `Hatch` edge `center + a cos(t) + b sin(t)`; `center` is already transformed and the angles
+// are in degrees.
+fn transform_hatch_elliptic_arc(map: &OcsMap, center: Point, a: Vector, b: Vector, start_angle: f64, end_angle: f64, is_counter_clockwise: bool) -> HatchBoundaryEdge {
+    // a clockwise edge runs along the mirrored ellipse, which keeps its direction through the transformation
+    let b = if is_counter_clockwise { b } else { -b };
+    let start = start_angle.to_radians();
+    let end = end_angle.to_radians();
+    let is_full = (normalize_sweep(end - start) - PI * 2.0).abs() < 1.0e-12;
+    let ellipse = ellipse_from_conjugate_diameters(center, map.vector(&a), map.vector(&b), start, end, is_full);
+    HatchBoundaryEdge::EllipticArc {
+        center: ellipse.center,
+        major_axis: ellipse.major_axis,
+        minor_axis_ratio: ellipse.minor_axis_ratio,
+        start_angle: ellipse.start_parameter.to_degrees(),
+        end_angle: ellipse.end_parameter.to_degrees(),
+        is_counter_clockwise,
+    }
+}
+
+// Transforms a circle or arc, returning an arc if it remains circular or an ellipse if not.  Angles
+// are in degrees.
+fn transform_circular(matrix: &TransformationMatrix, center: &Point, radius: f64, normal: &Vector, start_angle: f64, end_angle: f64, thickness: f64) -> EntityType {
+    let map = OcsMap::new(matrix, normal);
+    if map.is_conformal() {
+        let mut arc = Arc::new(map.point(center), radius * map.scale_along(0.0), map.angle(start_angle), map.angle(end_angle));
+        arc.normal = map.normal();
+        arc.thickness = thickness * map.normal_scale();
+        return EntityType::Arc(arc);
+    }
+
+    let (x, y, z) = map.old_axes;
+    let wcs_center = Point::origin() + x * center.x + y * center.y + z * center.z;
+    let sweep = normalize_sweep((end_angle - start_angle).to_radians());
+    let start = start_angle.to_radians();
+    EntityType::Ellipse(ellipse_from_conjugate_diameters(
+        matrix.transform_point(&wcs_center),
+        matrix.transform_vector(&(x * radius)),
+        matrix.transform_vector(&(y * radius)),
+        start,
+        start + sweep,
+        (sweep - PI * 2.0).abs() < 1.0e-12))
+}
+
+// Creates the ellipse `center + a cos(t) + b sin(t)` for `t` from `start` to `end` where `a` and `b`
+// are conjugate semi-diameters that aren't necessarily perpendicular.
+fn ellipse_from_conjugate_diameters(center: Point, a: Vector, b: Vector, start: f64, end: f64, is_full: bool) -> Ellipse {
+    // the parameter offset of the major axis maximizes |a cos(t) + b sin(t)|
+    let mut offset = 0.5 * (2.0 * a.dot(&b)).atan2(a.dot(&a) - b.dot(&b));
+    let mut major = a * offset.cos() + b * offset.sin();
+    let mut minor = a * -offset.sin() + b * offset.cos();
+    if minor.length() > major.length() {
+        let old_major = major;
+        major = minor;
+        minor = -old_major;
+        offset += PI / 2.0;
+    }
+
+    let (start_parameter, end_parameter) = if is_full {
+        (0.0, PI * 2.0)
+    }
+    else {
+        let new_start = (start - offset).rem_euclid(PI * 2.0);
+        (new_start, (new_start + normalize_sweep(end - start)).rem_euclid(PI * 2.0))
+    };
+    Ellipse {
+        center,
+        major_axis: major,
+        normal: a.cross(&b).normalize(),
+        minor_axis_ratio: if major.length() == 0.0 { 1.0 } else { minor.length() / major.length() },
+        start_parameter,
+        end_parameter,
+    }
+}
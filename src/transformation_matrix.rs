@@ -1,5 +1,10 @@
 // Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
 
+use ::{
+    Point,
+    Vector,
+};
+
 /// Applies a transformation to a point.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct TransformationMatrix {
@@ -32,6 +37,82 @@ impl TransformationMatrix {
             .. Default::default()
         }
     }
+    /// Creates a matrix that moves points by `offset`.
+    pub fn translation(offset: &Vector) -> Self {
+        TransformationMatrix {
+            m14: offset.x,
+            m24: offset.y,
+            m34: offset.z,
+            .. TransformationMatrix::identity()
+        }
+    }
+    /// Creates a matrix that scales points about the origin.
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        TransformationMatrix {
+            m11: x,
+            m22: y,
+            m33: z,
+            m44: 1.0,
+            .. Default::default()
+        }
+    }
+    /// Creates a matrix that rotates points about the Z axis by `angle` radians.
+    pub fn rotation_about_z(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        TransformationMatrix {
+            m11: cos,
+            m12: -sin,
+            m21: sin,
+            m22: cos,
+            .. TransformationMatrix::identity()
+        }
+    }
+    /// Creates a matrix that converts points from the object coordinate system (OCS) defined by `normal` to the
+    /// world coordinate system (WCS) using the arbitrary axis algorithm.
+    pub fn from_ocs(normal: &Vector) -> Self {
+        let (x_axis, y_axis, z_axis) = TransformationMatrix::ocs_axes(normal);
+        TransformationMatrix {
+            m11: x_axis.x,
+            m21: x_axis.y,
+            m31: x_axis.z,
+            m12: y_axis.x,
+            m22: y_axis.y,
+            m32: y_axis.z,
+            m13: z_axis.x,
+            m23: z_axis.y,
+            m33: z_axis.z,
+            m44: 1.0,
+            .. Default::default()
+        }
+    }
+    /// Returns the matrix that applies `other` and then `self`.
+    pub fn multiply(&self, other: &TransformationMatrix) -> Self {
+        let a = self.get_values();
+        let b = other.get_values();
+        let mut values = vec![0.0; 16];
+        for row in 0..4 {
+            for col in 0..4 {
+                values[row * 4 + col] = (0..4).map(|i| a[row * 4 + i] * b[i * 4 + col]).sum();
+            }
+        }
+        let mut result = TransformationMatrix::default();
+        result.from_vec(&values);
+        result
+    }
+    /// Applies the transformation to a point.
+    pub fn transform_point(&self, p: &Point) -> Point {
+        Point::new(
+            self.m11 * p.x + self.m12 * p.y + self.m13 * p.z + self.m14,
+            self.m21 * p.x + self.m22 * p.y + self.m23 * p.z + self.m24,
+            self.m31 * p.x + self.m32 * p.y + self.m33 * p.z + self.m34)
+    }
+    /// Applies the transformation to a direction vector, ignoring any translation.
+    pub fn transform_vector(&self, v: &Vector) -> Vector {
+        Vector::new(
+            self.m11 * v.x + self.m12 * v.y + self.m13 * v.z,
+            self.m21 * v.x + self.m22 * v.y + self.m23 * v.z,
+            self.m31 * v.x + self.m32 * v.y + self.m33 * v.z)
+    }
 }
 
 // internal visibility only
@@ -94,6 +175,17 @@ impl TransformationMatrix {
 
 // private implementation
 impl TransformationMatrix {
+    pub(crate) fn ocs_axes(normal: &Vector) -> (Vector, Vector, Vector) {
+        let z_axis = normal.normalize();
+        let x_axis = if z_axis.x.abs() < 1.0 / 64.0 && z_axis.y.abs() < 1.0 / 64.0 {
+            Vector::y_axis().cross(&z_axis).normalize()
+        }
+        else {
+            Vector::z_axis().cross(&z_axis).normalize()
+        };
+        let y_axis = z_axis.cross(&x_axis).normalize();
+        (x_axis, y_axis, z_axis)
+    }
     fn get_value_or_default(values: &Vec<f64>, index: usize) -> f64 {
         if values.len() > index {
             values[index]
@@ -0,0 +1,312 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+use ::{
+    Drawing,
+    Point,
+    TransformationMatrix,
+};
+
+use entities::*;
+use enums::*;
+
+impl Units {
+    /// The length of one unit in meters, or `None` for `Unitless`.
+    pub fn meters(&self) -> Option<f64> {
+        Some(match *self {
+            Units::Unitless => return None,
+            Units::Inches => 0.0254,
+            Units::Feet => 0.3048,
+            Units::Miles => 1609.344,
+            Units::Millimeters => 1.0e-3,
+            Units::Centimeters => 1.0e-2,
+            Units::Meters => 1.0,
+            Units::Kilometers => 1.0e3,
+            Units::Microinches => 2.54e-8,
+            Units::Mils => 2.54e-5,
+            Units::Yards => 0.9144,
+            Units::Angstroms => 1.0e-10,
+            Units::Nanometers => 1.0e-9,
+            Units::Microns => 1.0e-6,
+            Units::Decimeters => 0.1,
+            Units::Decameters => 10.0,
+            Units::Hectometers => 100.0,
+            Units::Gigameters => 1.0e9,
+            Units::AstronomicalUnits => 149_597_870_700.0,
+            Units::LightYears => 9_460_730_472_580_800.0,
+            Units::Parsecs => 3.085_677_581_491_367e16,
+        })
+    }
+    /// Returns the factor that converts a length in these units into `other` units, e.g., 25.4 from
+    /// `Inches` to `Millimeters`.  Returns 1 if either is `Unitless`.
+    pub fn scale_factor_to(&self, other: Units) -> f64 {
+        match (self.meters(), other.meters()) {
+            (Some(from), Some(to)) if from != to => from / to,
+            _ => 1.0,
+        }
+    }
+    // Returns the measurement system the units belong to, if any.
+    fn drawing_units(&self) -> Option<DrawingUnits> {
+        match *self {
+            Units::Inches | Units::Feet | Units::Miles | Units::Microinches | Units::Mils | Units::Yards => Some(DrawingUnits::English),
+            Units::Millimeters | Units::Centimeters | Units::Meters | Units::Kilometers | Units::Angstroms | Units::Nanometers |
+                Units::Microns | Units::Decimeters | Units::Decameters | Units::Hectometers | Units::Gigameters => Some(DrawingUnits::Metric),
+            _ => None,
+        }
+    }
+}
+
+impl Drawing {
+    /// The units of the drawing's geometry as specified by `$INSUNITS`.  If that is `Unitless`,
+    /// drawings that use the English measurement system (`$MEASUREMENT`) are assumed to be in inches
+    /// and metric drawings in millimeters.
+    pub fn units(&self) -> Units {
+        match (self.header.default_drawing_units, self.header.drawing_units) {
+            (Units::Unitless, DrawingUnits::English) => Units::Inches,
+            (Units::Unitless, DrawingUnits::Metric) => Units::Millimeters,
+            (units, _) => units,
+        }
+    }
+    /// Returns the factor by which the contents of the named block are scaled when inserted into the
+    /// drawing because the block record specifies different units than the drawing.  Returns 1 if
+    /// the block doesn't specify units or there's no such block record.
+    pub fn insert_units_scale(&self, block_name: &str) -> f64 {
+        match self.block_records.iter().find(|r| r.name.eq_ignore_ascii_case(block_name)) {
+            Some(record) if record.insertion_units != Units::Unitless => record.insertion_units.scale_factor_to(self.units()),
+            _ => 1.0,
+        }
+    }
+    /// Converts the drawing from its current units (see `units`) into `units`.
+    ///
+    /// All entities and the blocks in the drawing's units are scaled, along with the text heights
+    /// of text styles, the distances of dimension styles, the pattern lengths of line types, the
+    /// views, viewports, and UCSs, and the distances in the header.  Blocks whose block records
+    /// specify other units are left alone because they're scaled when inserted.  `$INSUNITS` and, for
+    /// metric and English units, `$MEASUREMENT` are then updated.  Objects are not converted.
+    pub fn convert_units(&mut self, units: Units) {
+        let old_units = self.units();
+        let factor = old_units.scale_factor_to(units);
+        if factor != 1.0 {
+            let matrix = TransformationMatrix::scale(factor, factor, factor);
+            for entity in self.entities.iter_mut() {
+                scale_entity(entity, &matrix, factor);
+            }
+
+            for block in self.blocks.iter_mut() {
+                let block_units = self.block_records.iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(&block.name))
+                    .map(|r| r.insertion_units)
+                    .unwrap_or(Units::Unitless);
+                if block_units != Units::Unitless && block_units != old_units {
+                    continue;
+                }
+
+                block.base_point = scale_point(&block.base_point, factor);
+                for entity in block.entities.iter_mut() {
+                    scale_entity(entity, &matrix, factor);
+                }
+            }
+
+            self.scale_tables(factor);
+            self.scale_header(factor);
+        }
+
+        for record in self.block_records.iter_mut() {
+            if record.insertion_units == old_units {
+                record.insertion_units = units;
+            }
+        }
+
+        self.header.default_drawing_units = units;
+        if let Some(drawing_units) = units.drawing_units() {
+            self.header.drawing_units = drawing_units;
+        }
+    }
+    fn scale_tables(&mut self, factor: f64) {
+        for dim_style in self.dim_styles.iter_mut() {
+            dim_style.dimensioning_arrow_size *= factor;
+            dim_style.dimension_extension_line_offset *= factor;
+            dim_style.dimension_line_increment *= factor;
+            dim_style.dimension_extension_line_extension *= factor;
+            dim_style.dimension_distance_rounding_value *= factor;
+            dim_style.dimension_line_extension *= factor;
+            dim_style.dimension_plus_tolerance *= factor;
+            dim_style.dimension_minus_tolerance *= factor;
+            dim_style.dimensioning_text_height *= factor;
+            dim_style.center_mark_size *= factor;
+            dim_style.dimensioning_tick_size *= factor;
+            dim_style.dimension_line_gap *= factor;
+            // alternate units are still shown in the same units
+            dim_style.alternate_dimensioning_scale_factor /= factor;
+        }
+
+        for line_type in self.line_types.iter_mut() {
+            line_type.total_pattern_length *= factor;
+            scale_all(&mut line_type.dash_dot_space_lengths, factor);
+            scale_all(&mut line_type.x_offsets, factor);
+            scale_all(&mut line_type.y_offsets, factor);
+        }
+
+        for style in self.styles.iter_mut() {
+            style.text_height *= factor;
+            style.last_height_used *= factor;
+        }
+
+        for ucs in self.ucss.iter_mut() {
+            ucs.origin = scale_point(&ucs.origin, factor);
+            ucs.orthographic_origin = scale_point(&ucs.orthographic_origin, factor);
+            ucs.elevation *= factor;
+        }
+
+        for view in self.views.iter_mut() {
+            view.view_height *= factor;
+            view.view_width *= factor;
+            view.view_center_point = scale_point(&view.view_center_point, factor);
+            view.target_point = scale_point(&view.target_point, factor);
+            view.front_clipping_plane *= factor;
+            view.back_clipping_plane *= factor;
+            view.ucs_origin = scale_point(&view.ucs_origin, factor);
+            view.ucs_elevation *= factor;
+        }
+
+        for view_port in self.view_ports.iter_mut() {
+            view_port.view_center = scale_point(&view_port.view_center, factor);
+            view_port.snap_base_point = scale_point(&view_port.snap_base_point, factor);
+            view_port.snap_spacing = view_port.snap_spacing * factor;
+            view_port.grid_spacing = view_port.grid_spacing * factor;
+            view_port.target_view_point = scale_point(&view_port.target_view_point, factor);
+            view_port.view_height *= factor;
+            view_port.front_clipping_plane *= factor;
+            view_port.back_clipping_plane *= factor;
+            view_port.ucs_origin = scale_point(&view_port.ucs_origin, factor);
+            view_port.ucs_elevation *= factor;
+        }
+    }
+    fn scale_header(&mut self, factor: f64) {
+        let header = &mut self.header;
+        for p in &mut [
+            &mut header.insertion_base,
+            &mut header.minimum_drawing_extents,
+            &mut header.maximum_drawing_extents,
+            &mut header.minimum_drawing_limits,
+            &mut header.maximum_drawing_limits,
+            &mut header.ucs_origin,
+            &mut header.ucs_origin_top,
+            &mut header.ucs_origin_bottom,
+            &mut header.ucs_origin_left,
+            &mut header.ucs_origin_right,
+            &mut header.ucs_origin_front,
+            &mut header.ucs_origin_back,
+            &mut header.paperspace_ucs_origin,
+            &mut header.paperspace_ucs_origin_top,
+            &mut header.paperspace_ucs_origin_bottom,
+            &mut header.paperspace_ucs_origin_left,
+            &mut header.paperspace_ucs_origin_right,
+            &mut header.paperspace_ucs_origin_front,
+            &mut header.paperspace_ucs_origin_back,
+            &mut header.paperspace_insertion_base,
+            &mut header.paperspace_minimum_drawing_extents,
+            &mut header.paperspace_maximum_drawing_extents,
+            &mut header.paperspace_minimum_drawing_limits,
+            &mut header.paperspace_maximum_drawing_limits,
+            &mut header.snap_base_point,
+            &mut header.view_center,
+        ] {
+            **p = scale_point(p, factor);
+        }
+
+        for v in &mut [&mut header.grid_spacing, &mut header.snap_spacing, &mut header.axis_tick_spacing] {
+            **v = **v * factor;
+        }
+
+        for d in &mut [
+            &mut header.default_text_height,
+            &mut header.trace_width,
+            &mut header.dimensioning_arrow_size,
+            &mut header.dimension_extension_line_offset,
+            &mut header.dimension_line_increment,
+            &mut header.dimension_distance_rounding_value,
+            &mut header.dimension_line_extension,
+            &mut header.dimension_extension_line_extension,
+            &mut header.dimension_plus_tolerance,
+            &mut header.dimension_minus_tolerance,
+            &mut header.dimensioning_text_height,
+            &mut header.center_mark_size,
+            &mut header.dimensioning_tick_size,
+            &mut header.dimension_line_gap,
+            &mut header.dimension_line_fixed_length,
+            &mut header.sketch_record_increment,
+            &mut header.fillet_radius,
+            &mut header.elevation,
+            &mut header.paperspace_elevation,
+            &mut header.thickness,
+            &mut header.first_chamfer_distance,
+            &mut header.second_chamfer_distance,
+            &mut header.chamfer_length,
+            &mut header.default_polyline_width,
+            &mut header.camera_height,
+            &mut header.step_size_in_walk_or_fly_mode,
+            &mut header.last_poly_solid_width,
+            &mut header.last_poly_solid_height,
+            &mut header.shadow_plane_z_offset,
+            &mut header.view_height,
+        ] {
+            **d *= factor;
+        }
+
+        header.alternate_dimensioning_scale_factor /= factor;
+
+        // a negative point display size is a percentage of the screen size
+        if header.point_display_size > 0.0 {
+            header.point_display_size *= factor;
+        }
+    }
+}
+
+// Scales the geometry of an entity for a change of units.
+fn scale_entity(entity: &mut Entity, matrix: &TransformationMatrix, factor: f64) {
+    let insert_scale = match entity.specific {
+        EntityType::Insert(ref insert) => Some((insert.x_scale_factor, insert.y_scale_factor, insert.z_scale_factor)),
+        _ => None,
+    };
+    entity.transform(matrix);
+    match entity.specific {
+        EntityType::Insert(ref mut insert) => {
+            // the block's contents are converted separately
+            if let Some((x, y, z)) = insert_scale {
+                insert.x_scale_factor = x;
+                insert.y_scale_factor = y;
+                insert.z_scale_factor = z;
+            }
+        },
+        EntityType::RotatedDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::RadialDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::DiameterDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::OrdinateDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::ArcDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::LargeRadialDimension(ref mut dim) => dim.dimension_base.actual_measurement *= factor,
+        EntityType::Viewport(ref mut viewport) => {
+            // the model space view is in the same units
+            viewport.view_center = scale_point(&viewport.view_center, factor);
+            viewport.snap_base_point = scale_point(&viewport.snap_base_point, factor);
+            viewport.snap_spacing = viewport.snap_spacing * factor;
+            viewport.grid_spacing = viewport.grid_spacing * factor;
+            viewport.view_target = scale_point(&viewport.view_target, factor);
+            viewport.view_height *= factor;
+            viewport.front_clipping_plane *= factor;
+            viewport.back_clipping_plane *= factor;
+            viewport.ucs_origin = scale_point(&viewport.ucs_origin, factor);
+            viewport.elevation *= factor;
+        },
+        _ => (),
+    }
+}
+
+fn scale_point(p: &Point, factor: f64) -> Point {
+    Point::new(p.x * factor, p.y * factor, p.z * factor)
+}
+
+fn scale_all(values: &mut [f64], factor: f64) {
+    for v in values.iter_mut() {
+        *v *= factor;
+    }
+}
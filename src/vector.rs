@@ -1,5 +1,12 @@
 // Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
 
+use std::ops::{
+    Add,
+    Mul,
+    Neg,
+    Sub,
+};
+
 use ::{
     CodePair,
     DxfError,
@@ -7,7 +14,7 @@ use ::{
 };
 
 /// Represents a simple vector in Cartesian space.
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Vector {
     /// The X component of the vector.
     pub x: f64,
@@ -42,6 +49,31 @@ impl Vector {
     pub fn z_axis() -> Vector {
         Vector::new(0.0, 0.0, 1.0)
     }
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    /// Returns a vector with the same direction and a length of 1.  A zero vector is returned unchanged.
+    pub fn normalize(&self) -> Vector {
+        let length = self.length();
+        if length == 0.0 {
+            *self
+        }
+        else {
+            Vector::new(self.x / length, self.y / length, self.z / length)
+        }
+    }
+    /// Returns the dot product of this vector and `other`.
+    pub fn dot(&self, other: &Vector) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    /// Returns the cross product of this vector and `other`.
+    pub fn cross(&self, other: &Vector) -> Vector {
+        Vector::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x)
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.value.assert_f64()?,
@@ -53,3 +85,31 @@ impl Vector {
         Ok(())
     }
 }
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vector {
+    type Output = Vector;
+    fn mul(self, scale: f64) -> Vector {
+        Vector::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::enums::*;
+
+mod test_helpers;
+use test_helpers::helpers::*;
+
+// a cylinder with a planar top standing on the origin, and a second body with a single vertex
+const SAT_TEXT: &str = "400 12 2 0
+8 AutoCAD 11 ACIS 4.0.0 24 Tue Oct 22 10:13:02 2002
+1 9.9999999999999995e-007 1e-010
+body $-1 $1 $-1 $-1 #
+lump $-1 $-1 $2 $0 #
+shell $-1 $-1 $-1 $3 $-1 $1 #
+face $-1 $4 $7 $2 $-1 $5 forward single #
+face $-1 $-1 $-1 $2 $-1 $6 reversed single #
+plane-surface $-1 0 0 10 0 0 1 1 0 0 forward_v I I I I #
+cone-surface $-1 0 0 0 0 0 1 5 0 0 1 I I 0 1 5 forward I I I I #
+vertex $-1 $-1 $8 #
+point $-1 5 0 10 #
+point $-1 -5 0 0 #
+body $-1 $11 $-1 $-1 #
+vertex $-1 $-1 $9 #
+End-of-ACIS-data";
+
+#[test]
+fn decode_obfuscated_sat_lines() {
+    let data = AcisData::from_lines(&[String::from("koo nm m"), String::from("9><:, &r")]);
+    assert_eq!(AcisData::Sat(String::from("400 12 2\nfaces y-")), data);
+    assert_eq!(vec!["koo nm m", "9><:, &r"], data.to_lines());
+}
+
+#[test]
+fn round_trip_sat_through_solid() {
+    let mut solid = Solid3D::default();
+    solid.set_acis_data(&AcisData::Sat(String::from(SAT_TEXT)));
+    assert!(solid.custom_data[0].starts_with("koo"));
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2000;
+    drawing.entities.push(Entity::new(EntityType::Solid3D(solid)));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    match round_tripped.entities[0].specific {
+        EntityType::Solid3D(ref solid) => assert_eq!(AcisData::Sat(String::from(SAT_TEXT)), solid.get_acis_data()),
+        _ => panic!("expected a 3d solid"),
+    }
+}
+
+#[test]
+fn round_trip_sat_lines_longer_than_a_code_pair() {
+    let long_record = format!("point $-1 {} 0 0 #", "1".repeat(600));
+    let text = format!("400 12 2 0\n8 AutoCAD 11 ACIS 4.0.0 24 Tue Oct 22 10:13:02 2002\n1 1e-006 1e-010\n{}\nEnd-of-ACIS-data", long_record);
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2007;
+    let mut solid = Solid3D::default();
+    solid.set_acis_data(&AcisData::Sat(text.clone()));
+    drawing.entities.push(Entity::new(EntityType::Solid3D(solid)));
+    let mut surface = PlaneSurface::default();
+    surface.set_acis_data(&AcisData::Sat(text.clone()));
+    drawing.entities.push(Entity::new(EntityType::PlaneSurface(surface)));
+
+    // the long line is split into a code 1 pair and code 3 continuations
+    let written = to_test_string(&drawing);
+    assert!(written.lines().all(|l| l.len() <= 255));
+    assert!(written.contains("\r\n  3\r\n"));
+
+    let round_tripped = parse_drawing(&written);
+    let data = match round_tripped.entities[0].specific {
+        EntityType::Solid3D(ref solid) => solid.get_acis_data(),
+        _ => panic!("expected a 3d solid"),
+    };
+    assert_eq!(AcisData::Sat(text.clone()), data);
+    match round_tripped.entities[1].specific {
+        EntityType::PlaneSurface(ref surface) => assert_eq!(AcisData::Sat(text), surface.get_acis_data()),
+        _ => panic!("expected a plane surface"),
+    }
+    assert_eq!(1, data.parse_model().unwrap().unwrap().records.len());
+}
+
+#[test]
+fn plain_sat_and_sab_lines() {
+    assert_eq!(AcisData::Sat(String::from("700 0 1 0")), AcisData::from_lines(&[String::from("700 0 1 0")]));
+
+    let mut bytes = b"ACIS BinaryFile".to_vec();
+    bytes.extend((0..200).map(|b| b as u8));
+    let mut region = Region::default();
+    region.set_acis_data(&AcisData::Sab(bytes.clone()));
+    assert_eq!(2, region.custom_data.len());
+    assert!(region.custom_data[0].starts_with("4143495320"));
+    assert_eq!(AcisData::Sab(bytes), region.get_acis_data());
+    assert!(region.get_acis_data().parse_model().is_none());
+}
+
+#[test]
+fn bound_entities_by_their_sat_vertices() {
+    let mut surface = PlaneSurface::default();
+    surface.set_acis_data(&AcisData::Sat(String::from(SAT_TEXT)));
+    let bounds = Entity::new(EntityType::PlaneSurface(surface)).bounding_box().unwrap();
+    assert_eq!((Point::new(-5.0, 0.0, 0.0), Point::new(5.0, 0.0, 10.0)), (bounds.min, bounds.max));
+
+    let mut solid = Solid3D::default();
+    solid.set_acis_data(&AcisData::Sab(b"ACIS BinaryFile".to_vec()));
+    assert_eq!(None, Entity::new(EntityType::Solid3D(solid)).bounding_box());
+}
+
+#[test]
+fn parse_sat_bodies_faces_and_surfaces() {
+    let model = AcisModel::parse(SAT_TEXT).unwrap();
+    assert_eq!(400, model.version);
+    assert_eq!(12, model.records.len());
+    assert_eq!("cone-surface", model.records[6].name);
+
+    let bodies = model.get_bodies();
+    assert_eq!(2, bodies.len());
+    assert_eq!(vec![
+        AcisFace {
+            surface: AcisSurface::Plane { origin: Point::new(0.0, 0.0, 10.0), normal: Vector::z_axis() },
+            is_reversed: false,
+        },
+        AcisFace {
+            surface: AcisSurface::Cylinder { center: Point::origin(), axis: Vector::z_axis(), radius: 5.0 },
+            is_reversed: true,
+        },
+    ], bodies[0].faces);
+    let bounds = bodies[0].bounding_box.unwrap();
+    assert_eq!((Point::new(5.0, 0.0, 10.0), Point::new(5.0, 0.0, 10.0)), (bounds.min, bounds.max));
+    assert!(bodies[1].faces.is_empty());
+    assert_eq!(Point::new(-5.0, 0.0, 0.0), bodies[1].bounding_box.unwrap().min);
+
+    let bounds = model.get_bounding_box().unwrap();
+    assert_eq!((Point::new(-5.0, 0.0, 0.0), Point::new(5.0, 0.0, 10.0)), (bounds.min, bounds.max));
+}
+
+#[test]
+fn parse_sat_version_7_records() {
+    let model = AcisModel::parse(vec![
+        "700 0 1 0",
+        "@33 Autodesk AutoCAD Version 18.0",
+        "1 9.9999999999999995e-007 1e-010",
+        "-0 body $-1 -1 $-1 $1 $-1 $-1 #",
+        "-1 point $-1 -1 $-1 1.5 2.5 3.5 #",
+    ].join("\n").as_str()).unwrap();
+    assert_eq!(vec!["body", "point"], model.records.iter().map(|r| r.name.as_str()).collect::<Vec<_>>());
+    assert_eq!(Point::new(1.5, 2.5, 3.5), model.get_bodies()[0].bounding_box.unwrap().max);
+}
@@ -402,3 +402,76 @@ fn round_trip_blocks() {
         _ => panic!("expected a circle"),
     }
 }
+
+#[test]
+fn resolve_dynamic_block_references() {
+    fn dictionary(handle: u32, owner: u32, entries: Vec<(&str, u32)>) -> objects::Object {
+        let mut dict = objects::Dictionary::default();
+        for (key, value) in entries {
+            dict.value_handles.insert(String::from(key), value);
+        }
+        let mut obj = objects::Object::new(objects::ObjectType::Dictionary(dict));
+        obj.common.handle = handle;
+        obj.common.__owner_handle = owner;
+        obj
+    }
+    fn extension_dictionary(handle: u32) -> ExtensionGroup {
+        ExtensionGroup {
+            application_name: String::from("ACAD_XDICTIONARY"),
+            items: vec![ExtensionGroupItem::CodePair(CodePair::new_string(360, &format!("{:X}", handle)))],
+        }
+    }
+
+    let mut drawing = Drawing::default();
+    for (handle, name) in vec![(0x10, "DOOR-36"), (0x20, "*U5"), (0x30, "*U6")] {
+        let mut block = Block::default();
+        block.name = String::from(name);
+        drawing.blocks.push(block);
+        let mut record = tables::BlockRecord::default();
+        record.handle = handle;
+        record.name = String::from(name);
+        drawing.block_records.push(record);
+    }
+
+    // the definition owns an evaluation graph with a parameter
+    drawing.block_records[0].extension_data_groups.push(extension_dictionary(0x100));
+    drawing.objects.push(dictionary(0x100, 0x10, vec![("ACAD_ENHANCEDBLOCK", 0x101)]));
+    let mut visibility = objects::BlockVisibilityParameter::default();
+    visibility.states.push(BlockVisibilityState::new(String::from("Open")));
+    let mut parameter = objects::Object::new(objects::ObjectType::BlockVisibilityParameter(visibility));
+    parameter.common.__owner_handle = 0x101;
+    drawing.objects.push(parameter);
+
+    // one anonymous copy points back through XData, the other through the insert's extension dictionary
+    drawing.block_records[1].x_data.push(XData {
+        application_name: String::from("AcDbBlockRepBTag"),
+        items: vec![XDataItem::Integer(1), XDataItem::Handle(0x10)],
+    });
+    drawing.objects.push(dictionary(0x200, 0x40, vec![("AcDbBlockRepresentation", 0x201)]));
+    drawing.objects.push(dictionary(0x201, 0x200, vec![("AcDbRepData", 0x202)]));
+    let mut data = objects::Object::new(objects::ObjectType::BlockRepresentationData(objects::BlockRepresentationData::default()));
+    data.common.handle = 0x202;
+    if let objects::ObjectType::BlockRepresentationData(ref mut data) = data.specific {
+        data.__block_record_handle = 0x10;
+    }
+    drawing.objects.push(data);
+
+    let insert = |name: &str| {
+        let mut insert = Insert::default();
+        insert.name = String::from(name);
+        Entity::new(EntityType::Insert(insert))
+    };
+    let mut second = insert("*U6");
+    second.common.extension_data_groups.push(extension_dictionary(0x200));
+    for entity in vec![insert("DOOR-36"), insert("*U5"), second] {
+        assert_eq!(Some(String::from("DOOR-36")), drawing.effective_block_name(&entity));
+        assert_eq!("DOOR-36", drawing.dynamic_block_definition(&entity).unwrap().name);
+        assert_eq!(1, drawing.dynamic_block_parameters(&entity).len());
+    }
+
+    let plain = insert("*U6");
+    assert_eq!(Some(String::from("*U6")), drawing.effective_block_name(&plain));
+    assert!(drawing.dynamic_block_definition(&plain).is_none());
+    assert!(drawing.dynamic_block_parameters(&plain).is_empty());
+    assert_eq!(None, drawing.effective_block_name(&Entity::new(EntityType::Line(Line::default()))));
+}
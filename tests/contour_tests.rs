@@ -0,0 +1,103 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+use std::f64::consts::PI;
+
+fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> Entity {
+    Entity::new(EntityType::Line(Line::new(Point::new(x1, y1, 0.0), Point::new(x2, y2, 0.0))))
+}
+
+fn arc(x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) -> Entity {
+    Entity::new(EntityType::Arc(Arc::new(Point::new(x, y, 0.0), radius, start_angle, end_angle)))
+}
+
+// a 10x10 square drawn clockwise with shuffled and reversed lines and small gaps
+fn square_lines() -> Vec<Entity> {
+    vec![
+        line(10.0, 10.0, 10.0, 0.0005),
+        line(0.0, 0.0, 0.0, 10.0),
+        line(10.0, 0.0, 0.0, 0.0),
+        line(10.0, 10.0, 0.0, 10.0),
+    ]
+}
+
+#[test]
+fn chain_shuffled_lines_into_closed_contour() {
+    let mut drawing = Drawing::default();
+    drawing.entities = square_lines();
+    let contours = drawing.chain_contours(0.001);
+    assert_eq!(1, contours.len());
+    let contour = &contours[0];
+    assert!(contour.is_closed);
+    assert_eq!(4, contour.segments.len());
+    let mut indices = contour.entity_indices.clone();
+    indices.sort();
+    assert_eq!(vec![0, 1, 2, 3], indices);
+    assert_eq!(Some(Orientation::CounterClockwise), contour.orientation());
+    assert!((contour.signed_area().unwrap() - 100.0).abs() < 0.01);
+    for pair in contour.segments.windows(2) {
+        assert!(pair[0].end_point().distance_to(&pair[1].start_point()) <= 0.001);
+    }
+}
+
+#[test]
+fn open_chain_is_not_closed() {
+    let mut drawing = Drawing::default();
+    drawing.entities = vec![line(1.0, 0.0, 2.0, 0.0), line(0.0, 0.0, 1.0, 0.0), line(5.0, 5.0, 6.0, 6.0)];
+    let contours = drawing.chain_contours(1.0e-9);
+    assert_eq!(2, contours.len());
+    assert!(!contours[0].is_closed);
+    assert_eq!(2, contours[0].segments.len());
+    assert_eq!(None, contours[0].signed_area());
+    assert_eq!(vec![2], contours[1].entity_indices);
+}
+
+#[test]
+fn nested_contours_are_oriented() {
+    let mut drawing = Drawing::default();
+    drawing.entities = square_lines();
+    drawing.entities.push(Entity::new(EntityType::Circle(Circle::new(Point::new(5.0, 5.0, 0.0), 2.0))));
+    drawing.entities.push(Entity::new(EntityType::Circle(Circle::new(Point::new(5.0, 5.0, 0.0), 1.0))));
+    let contours = drawing.chain_contours(0.001);
+    assert_eq!(3, contours.len());
+    assert_eq!((0, None), (contours[0].depth, contours[0].parent));
+    assert_eq!((1, Some(0)), (contours[1].depth, contours[1].parent));
+    assert_eq!((2, Some(1)), (contours[2].depth, contours[2].parent));
+    assert_eq!(Some(Orientation::CounterClockwise), contours[0].orientation());
+    assert_eq!(Some(Orientation::Clockwise), contours[1].orientation());
+    assert_eq!(Some(Orientation::CounterClockwise), contours[2].orientation());
+}
+
+#[test]
+fn join_slot_into_polyline() {
+    // a slot made of two lines and two semicircles, with an unrelated line in the middle of the list
+    let mut drawing = Drawing::default();
+    drawing.entities = vec![
+        line(0.0, 0.0, 10.0, 0.0),
+        arc(10.0, 1.0, 1.0, 270.0, 90.0),
+        line(20.0, 20.0, 30.0, 30.0),
+        line(10.0, 2.0, 0.0, 2.0),
+        arc(0.0, 1.0, 1.0, 90.0, 270.0),
+    ];
+    drawing.entities[1].common.layer = String::from("profile");
+    drawing.entities[0].common.layer = String::from("profile");
+    assert_eq!(1, drawing.join_contours(1.0e-9));
+    assert_eq!(2, drawing.entities.len());
+    assert_eq!("profile", drawing.entities[0].common.layer);
+    match drawing.entities[0].specific {
+        EntityType::LwPolyline(ref poly) => {
+            assert!(poly.get_is_closed());
+            assert_eq!(4, poly.vertices.len());
+            assert_eq!(2, poly.vertices.iter().filter(|v| (v.bulge.abs() - 1.0).abs() < 1.0e-9).count());
+        },
+        _ => panic!("expected a polyline"),
+    }
+    assert!((drawing.entities[0].area().unwrap() - (20.0 + PI)).abs() < 1.0e-9);
+    match drawing.entities[1].specific {
+        EntityType::Line(_) => (),
+        _ => panic!("expected the unrelated line to remain"),
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::enums::*;
+use self::dxf::tables::*;
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1.0e-9, "expected {} but got {}", expected, actual);
+}
+
+fn drawing_with_style() -> Drawing {
+    let mut drawing = Drawing::default();
+    let mut style = DimStyle::default();
+    style.name = String::from("STANDARD");
+    style.dimension_unit_tolerance_decimal_places = 2;
+    style.angular_dimension_precision = 0;
+    drawing.dim_styles.push(style);
+    drawing
+}
+
+fn texts(entities: &[Entity]) -> Vec<String> {
+    entities.iter().filter_map(|e| match e.specific {
+        EntityType::MText(ref mtext) => Some(mtext.text.clone()),
+        _ => None,
+    }).collect()
+}
+
+fn count<F>(entities: &[Entity], predicate: F) -> usize
+    where F: Fn(&EntityType) -> bool {
+
+    entities.iter().filter(|e| predicate(&e.specific)).count()
+}
+
+#[test]
+fn generate_aligned_dimension_block() {
+    let mut drawing = drawing_with_style();
+    let mut dim = RotatedDimension::default();
+    dim.dimension_base.dimension_type = DimensionType::Aligned;
+    dim.definition_point_2 = Point::new(0.0, 0.0, 0.0);
+    dim.definition_point_3 = Point::new(10.0, 0.0, 0.0);
+    dim.dimension_base.definition_point_1 = Point::new(10.0, 2.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::RotatedDimension(dim)));
+
+    assert_eq!(1, drawing.generate_dimension_blocks());
+    let base = drawing.entities[0].dimension_base().unwrap().clone();
+    assert_eq!("*D1", base.block_name);
+    assert_close(10.0, base.actual_measurement);
+    assert_eq!(Point::new(5.0, 2.0, 0.0), base.text_mid_point);
+
+    let block = drawing.blocks.iter().find(|b| b.name == "*D1").unwrap();
+    assert!(block.get_is_anonymous());
+    assert!(drawing.block_records.iter().any(|r| r.name == "*D1"));
+    assert!(drawing.layers.iter().any(|l| l.name == "Defpoints"));
+    assert_eq!(vec![String::from("10.00")], texts(&block.entities));
+    assert_eq!(2, count(&block.entities, |e| match *e { EntityType::Solid(_) => true, _ => false }));
+    assert_eq!(3, count(&block.entities, |e| match *e { EntityType::ModelPoint(_) => true, _ => false }));
+    // two extension lines and the dimension line broken around the text
+    assert_eq!(4, count(&block.entities, |e| match *e { EntityType::Line(_) => true, _ => false }));
+    let extension_line = block.entities.iter().filter_map(|e| match e.specific {
+        EntityType::Line(ref line) => Some(line.clone()),
+        _ => None,
+    }).next().unwrap();
+    assert_close(0.0625, extension_line.p1.y);
+    assert_close(2.18, extension_line.p2.y);
+}
+
+#[test]
+fn generate_dimension_blocks_in_blocks() {
+    let mut drawing = drawing_with_style();
+    let mut dim = RotatedDimension::default();
+    dim.dimension_base.dimension_type = DimensionType::Aligned;
+    dim.definition_point_3 = Point::new(4.0, 0.0, 0.0);
+    dim.dimension_base.definition_point_1 = Point::new(4.0, 1.0, 0.0);
+    let mut block = Block::default();
+    block.name = String::from("part");
+    block.entities.push(Entity::new(EntityType::RotatedDimension(dim)));
+    drawing.blocks.push(block);
+
+    assert_eq!(1, drawing.generate_dimension_blocks());
+    let base = drawing.blocks[0].entities[0].dimension_base().unwrap().clone();
+    assert_eq!("*D1", base.block_name);
+    assert_close(4.0, base.actual_measurement);
+    let generated = drawing.blocks.iter().find(|b| b.name == "*D1").unwrap();
+    assert_eq!(vec![String::from("4.00")], texts(&generated.entities));
+
+    // regenerating reuses the block
+    assert_eq!(1, drawing.generate_dimension_blocks());
+    assert_eq!(2, drawing.blocks.len());
+}
+
+#[test]
+fn rotated_dimension_measures_along_rotation() {
+    let mut drawing = drawing_with_style();
+    drawing.dim_styles[0].dimensioning_suffix = String::from("<> mm");
+    drawing.dim_styles[0].text_above_dimension_line = true;
+    let mut dim = RotatedDimension::default();
+    dim.dimension_base.dimension_type = DimensionType::RotatedHorizontalOrVertical;
+    dim.definition_point_2 = Point::new(0.0, 0.0, 0.0);
+    dim.definition_point_3 = Point::new(3.0, 4.0, 0.0);
+    dim.dimension_base.definition_point_1 = Point::new(0.0, 6.0, 0.0);
+    dim.rotation_angle = 90.0;
+    drawing.entities.push(Entity::new(EntityType::RotatedDimension(dim)));
+
+    let entities = drawing.dimension_block_entities(&drawing.entities[0]).unwrap();
+    assert_eq!(vec![String::from("4.00 mm")], texts(&entities));
+    assert_eq!(1, drawing.generate_dimension_blocks());
+    let base = drawing.entities[0].dimension_base().unwrap();
+    assert_close(4.0, base.actual_measurement);
+    // the text reads upward and sits to the left of the vertical dimension line
+    assert_close(2.0, base.text_mid_point.y);
+    assert!(base.text_mid_point.x < 0.0);
+}
+
+#[test]
+fn rotated_dimension_text_angle_is_in_radians() {
+    let drawing = drawing_with_style();
+    let mut dim = RotatedDimension::default();
+    dim.dimension_base.dimension_type = DimensionType::Aligned;
+    dim.definition_point_2 = Point::new(0.0, 0.0, 0.0);
+    dim.definition_point_3 = Point::new(0.0, 10.0, 0.0);
+    dim.dimension_base.definition_point_1 = Point::new(-2.0, 10.0, 0.0);
+    let entity = Entity::new(EntityType::RotatedDimension(dim));
+
+    let entities = drawing.dimension_block_entities(&entity).unwrap();
+    let mtext = entities.iter().filter_map(|e| match e.specific {
+        EntityType::MText(ref mtext) => Some(mtext.clone()),
+        _ => None,
+    }).next().unwrap();
+    assert_close(::std::f64::consts::FRAC_PI_2, mtext.rotation_angle);
+    assert_close(1.0, mtext.x_axis_direction.y);
+}
+
+#[test]
+fn radial_diameter_and_angular_text() {
+    let mut drawing = drawing_with_style();
+    let mut radial = RadialDimension::default();
+    radial.dimension_base.dimension_type = DimensionType::Radius;
+    radial.dimension_base.definition_point_1 = Point::new(1.0, 1.0, 0.0);
+    radial.definition_point_2 = Point::new(4.0, 5.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::RadialDimension(radial)));
+
+    let mut diameter = DiameterDimension::default();
+    diameter.dimension_base.dimension_type = DimensionType::Diameter;
+    diameter.dimension_base.definition_point_1 = Point::new(-2.0, 0.0, 0.0);
+    diameter.definition_point_2 = Point::new(2.0, 0.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::DiameterDimension(diameter)));
+
+    let mut angular = AngularThreePointDimension::default();
+    angular.dimension_base.dimension_type = DimensionType::AngularThreePoint;
+    angular.definition_point_2 = Point::new(5.0, 0.0, 0.0);
+    angular.definition_point_3 = Point::new(0.0, 5.0, 0.0);
+    angular.definition_point_4 = Point::origin();
+    angular.dimension_base.definition_point_1 = Point::new(-3.0, -3.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::AngularThreePointDimension(angular)));
+
+    assert_eq!(3, drawing.generate_dimension_blocks());
+    let names = drawing.entities.iter().map(|e| e.dimension_base().unwrap().block_name.clone()).collect::<Vec<_>>();
+    assert_eq!(vec!["*D1", "*D2", "*D3"], names);
+    let block_text = |name: &str| texts(&drawing.blocks.iter().find(|b| b.name == name).unwrap().entities);
+    assert_eq!(vec![String::from("R5.00")], block_text("*D1"));
+    assert_eq!(vec![String::from("%%c4.00")], block_text("*D2"));
+    // the dimension arc is on the reflex side of the angle
+    assert_eq!(vec![String::from("270%%d")], block_text("*D3"));
+    assert_close(270.0, drawing.entities[2].dimension_base().unwrap().actual_measurement);
+}
+
+#[test]
+fn regenerate_dimension_reuses_block_and_applies_text_override() {
+    let mut drawing = drawing_with_style();
+    let mut dim = RotatedDimension::default();
+    dim.dimension_base.dimension_type = DimensionType::Aligned;
+    dim.definition_point_3 = Point::new(2.5, 0.0, 0.0);
+    dim.dimension_base.text = String::from("<> typ.");
+    drawing.entities.push(Entity::new(EntityType::RotatedDimension(dim)));
+    drawing.generate_dimension_blocks();
+
+    match drawing.entities[0].specific {
+        EntityType::RotatedDimension(ref mut dim) => {
+            dim.definition_point_3 = Point::new(3.5, 0.0, 0.0);
+        },
+        _ => panic!("expected a dimension"),
+    }
+    drawing.generate_dimension_blocks();
+    assert_eq!(1, drawing.blocks.iter().filter(|b| b.name.starts_with("*D")).count());
+    assert_eq!(vec![String::from("3.50 typ.")], texts(&drawing.blocks[0].entities));
+
+    drawing.entities[0].dimension_base_mut().unwrap().text = String::from(" ");
+    drawing.generate_dimension_blocks();
+    assert!(texts(&drawing.blocks[0].entities).is_empty());
+}
+
+#[test]
+fn missing_dim_style_uses_header_values() {
+    let mut drawing = Drawing::default();
+    drawing.header.dimensioning_text_height = 2.5;
+    drawing.header.dimension_unit_tolerance_decimal_places = 1;
+    let style = drawing.resolve_dim_style("");
+    assert_eq!("STANDARD", style.name);
+    assert_close(2.5, style.dimensioning_text_height);
+
+    let mut dim = DiameterDimension::default();
+    dim.definition_point_2 = Point::new(0.0, 0.0, 0.0);
+    dim.dimension_base.definition_point_1 = Point::new(0.0, 12.0, 0.0);
+    let entities = drawing.dimension_block_entities(&Entity::new(EntityType::DiameterDimension(dim))).unwrap();
+    let heights = entities.iter().filter_map(|e| match e.specific {
+        EntityType::MText(ref mtext) => Some((mtext.text.clone(), mtext.initial_text_height)),
+        _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(vec![(String::from("%%c12.0"), 2.5)], heights);
+}
+
+fn style(precision: i16) -> DimStyle {
+    let mut style = DimStyle::default();
+    style.dimension_unit_tolerance_decimal_places = precision;
+    style
+}
+
+#[test]
+fn dimension_measurements() {
+    let mut drawing = drawing_with_style();
+    let mut rotated = RotatedDimension::default();
+    rotated.definition_point_3 = Point::new(3.0, 4.0, 0.0);
+    rotated.dimension_base.dimension_type = DimensionType::RotatedHorizontalOrVertical;
+    let mut ordinate = OrdinateDimension::default();
+    ordinate.dimension_base.definition_point_1 = Point::new(1.0, 1.0, 0.0);
+    ordinate.definition_point_2 = Point::new(4.5, 7.0, 0.0);
+    ordinate.dimension_base.is_ordinate_x_type = true;
+    let mut angular = AngularThreePointDimension::default();
+    angular.definition_point_2 = Point::new(1.0, 0.0, 0.0);
+    angular.definition_point_3 = Point::new(1.0, 1.0, 0.0);
+    angular.dimension_base.definition_point_1 = Point::new(2.0, 0.5, 0.0);
+    angular.dimension_base.text = String::from("approx. <>");
+    drawing.entities.push(Entity::new(EntityType::RotatedDimension(rotated)));
+    drawing.entities.push(Entity::new(EntityType::OrdinateDimension(ordinate)));
+    drawing.entities.push(Entity::new(EntityType::AngularThreePointDimension(angular)));
+    drawing.entities.push(Entity::new(EntityType::Line(Line::default())));
+
+    assert_close(3.0, drawing.entities[0].dimension_measurement().unwrap());
+    assert_close(3.5, drawing.entities[1].dimension_measurement().unwrap());
+    assert_close(45.0, drawing.entities[2].dimension_measurement().unwrap());
+    assert_eq!(None, drawing.entities[3].dimension_measurement());
+    assert_eq!(Some(String::from("3.00")), drawing.dimension_text(&drawing.entities[0]));
+    assert_eq!(Some(String::from("approx. 45%%d")), drawing.dimension_text(&drawing.entities[2]));
+    assert_eq!(None, drawing.dimension_text(&drawing.entities[3]));
+}
+
+#[test]
+fn two_line_arc_length_and_jogged_radius_measurements() {
+    let drawing = drawing_with_style();
+    let mut angular = AngularTwoLineDimension::default();
+    angular.definition_point_2 = Point::new(2.0, 0.0, 0.0);
+    angular.definition_point_3 = Point::new(5.0, 0.0, 0.0);
+    angular.definition_point_4 = Point::new(1.0, 1.0, 0.0);
+    angular.dimension_base.definition_point_1 = Point::new(3.0, 3.0, 0.0);
+    angular.arc_definition_point = Point::new(4.0, 1.0, 0.0);
+    let mut arc = ArcDimension::default();
+    arc.definition_point_2 = Point::new(2.0, 0.0, 0.0);
+    arc.definition_point_3 = Point::new(0.0, 2.0, 0.0);
+    arc.end_angle = ::std::f64::consts::PI / 2.0;
+    let mut jogged = LargeRadialDimension::default();
+    jogged.dimension_base.definition_point_1 = Point::new(0.0, -40.0, 0.0);
+    jogged.chord_point = Point::new(0.0, 10.0, 0.0);
+    let angular = Entity::new(EntityType::AngularTwoLineDimension(angular));
+    let arc = Entity::new(EntityType::ArcDimension(arc));
+    let jogged = Entity::new(EntityType::LargeRadialDimension(jogged));
+
+    assert_close(45.0, angular.dimension_measurement().unwrap());
+    assert_close(::std::f64::consts::PI, arc.dimension_measurement().unwrap());
+    assert_close(50.0, jogged.dimension_measurement().unwrap());
+    assert_eq!(Some(String::from("45%%d")), drawing.dimension_text(&angular));
+    assert_eq!(Some(String::from("R50.00")), drawing.dimension_text(&jogged));
+}
+
+#[test]
+fn generate_two_line_arc_length_and_jogged_radius_blocks() {
+    let mut drawing = drawing_with_style();
+    let mut angular = AngularTwoLineDimension::default();
+    angular.definition_point_2 = Point::new(2.0, 0.0, 0.0);
+    angular.definition_point_3 = Point::new(5.0, 0.0, 0.0);
+    angular.definition_point_4 = Point::new(1.0, 1.0, 0.0);
+    angular.dimension_base.definition_point_1 = Point::new(3.0, 3.0, 0.0);
+    angular.arc_definition_point = Point::new(4.0, 1.0, 0.0);
+    let mut arc = ArcDimension::default();
+    arc.dimension_base.definition_point_1 = Point::new(3.0, 0.0, 0.0);
+    arc.definition_point_2 = Point::new(2.0, 0.0, 0.0);
+    arc.definition_point_3 = Point::new(0.0, 2.0, 0.0);
+    arc.end_angle = ::std::f64::consts::PI / 2.0;
+    let mut jogged = LargeRadialDimension::default();
+    jogged.dimension_base.definition_point_1 = Point::new(0.0, -40.0, 0.0);
+    jogged.chord_point = Point::new(0.0, 10.0, 0.0);
+    jogged.override_center = Point::new(2.0, 0.0, 0.0);
+    jogged.jog_point = Point::new(1.0, 5.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::AngularTwoLineDimension(angular)));
+    drawing.entities.push(Entity::new(EntityType::ArcDimension(arc)));
+    drawing.entities.push(Entity::new(EntityType::LargeRadialDimension(jogged)));
+
+    assert_eq!(3, drawing.generate_dimension_blocks());
+    let blocks = drawing.entities.iter()
+        .map(|e| drawing.blocks.iter().find(|b| b.name == e.dimension_base().unwrap().block_name).unwrap())
+        .collect::<Vec<_>>();
+    let arcs = |block: &Block| block.entities.iter().filter_map(|e| match e.specific {
+        EntityType::Arc(ref arc) => Some(arc.clone()),
+        _ => None,
+    }).collect::<Vec<_>>();
+
+    // the angle between the lines is drawn around their intersection through the arc point
+    assert_eq!(vec![String::from("45%%d")], texts(&blocks[0].entities));
+    let angular_arcs = arcs(blocks[0]);
+    assert_eq!(1, angular_arcs.len());
+    assert_eq!(Point::origin(), angular_arcs[0].center);
+    assert_close(17f64.sqrt(), angular_arcs[0].radius);
+    assert_close(45.0, angular_arcs[0].end_angle);
+
+    // the dimension arc passes through the text side point and the extension lines start on the measured arc
+    assert_eq!(vec![String::from("3.14")], texts(&blocks[1].entities));
+    let length_arcs = arcs(blocks[1]);
+    assert_eq!(1, length_arcs.len());
+    assert_close(3.0, length_arcs[0].radius);
+    assert_close(90.0, length_arcs[0].end_angle);
+    assert_close(::std::f64::consts::PI, drawing.entities[1].dimension_base().unwrap().actual_measurement);
+
+    // the dimension line is jogged between the displayed center and the arrowhead
+    assert_eq!(vec![String::from("R50.00")], texts(&blocks[2].entities));
+    assert_eq!(4, count(&blocks[2].entities, |e| match *e { EntityType::Line(_) => true, _ => false }));
+    assert_eq!(1, count(&blocks[2].entities, |e| match *e { EntityType::Solid(_) => true, _ => false }));
+    assert_close(50.0, drawing.entities[2].dimension_base().unwrap().actual_measurement);
+}
+
+#[test]
+fn format_decimal_distances() {
+    let mut style = style(3);
+    style.dimension_linear_measurement_scale_factor = 2.0;
+    assert_eq!("1.250", style.format_distance(0.625));
+    style.dimension_unit_zero_suppression = UnitZeroSuppression::SuppressLeadingAndTrailingZeros;
+    assert_eq!("1.25", style.format_distance(0.625));
+    assert_eq!(".2", style.format_distance(0.1));
+    style.dimension_unit_zero_suppression = UnitZeroSuppression::SuppressTrailingZeros;
+    assert_eq!("4", style.format_distance(2.0));
+    style.dimension_distance_rounding_value = 0.5;
+    style.dimension_decilam_separator_char = ',';
+    assert_eq!("2,5", style.format_distance(1.2));
+    style.dimensioning_suffix = String::from("~<> mm");
+    assert_eq!("~2,5 mm", style.format_distance(1.2));
+}
+
+#[test]
+fn format_other_linear_units() {
+    let mut style = style(4);
+    style.dimension_non_angular_units = NonAngularUnits::Architectural;
+    assert_eq!("1'-2 1/2\"", style.format_distance(14.5));
+    assert_eq!("3/16\"", style.format_distance(0.1875));
+    assert_eq!("2'", style.format_distance(24.01));
+    style.dimension_unit_zero_suppression = UnitZeroSuppression::IncludeZeroFeetAndZeroInches;
+    assert_eq!("2'-0\"", style.format_distance(24.01));
+    style.dimension_non_angular_units = NonAngularUnits::Engineering;
+    style.dimension_unit_tolerance_decimal_places = 2;
+    assert_eq!("1'-0.50\"", style.format_distance(12.5));
+    style.dimension_non_angular_units = NonAngularUnits::Fractional;
+    style.dimension_unit_tolerance_decimal_places = 3;
+    assert_eq!("2 3/8", style.format_distance(2.37));
+    style.dimension_non_angular_units = NonAngularUnits::Scientific;
+    style.dimension_unit_tolerance_decimal_places = 2;
+    assert_eq!("1.25E+03", style.format_distance(1250.0));
+    assert_eq!("9.99E-03", style.format_distance(0.00999));
+}
+
+#[test]
+fn format_tolerances_and_alternate_units() {
+    let mut style = style(2);
+    style.dimension_tolerace_decimal_places = 2;
+    style.generate_dimension_tolerances = true;
+    style.dimension_plus_tolerance = 0.1;
+    style.dimension_minus_tolerance = 0.1;
+    assert_eq!("10.00%%p0.10", style.format_distance(10.0));
+    style.dimension_minus_tolerance = 0.05;
+    style.dimension_tolerance_displace_scale_factor = 0.7;
+    assert_eq!("10.00{\\H0.7x;\\S+0.10^-0.05;}", style.format_distance(10.0));
+    style.generate_dimension_tolerances = false;
+    style.generate_dimension_limits = true;
+    assert_eq!("{\\H0.7x;\\S10.10^9.95;}", style.format_distance(10.0));
+    style.generate_dimension_limits = false;
+    style.use_alternate_dimensioning = true;
+    style.alternate_dimensioning_scale_factor = 25.4;
+    style.alternate_dimensioning_decimal_places = 1;
+    style.alternate_dimensioning_suffix = String::from("<> mm");
+    assert_eq!("2.00 [50.8 mm]", style.format_distance(2.0));
+}
+
+#[test]
+fn format_angles() {
+    let mut style = style(2);
+    style.angular_dimension_precision = 2;
+    assert_eq!("45.50%%d", style.format_angle(45.5));
+    style.dimension_angle_zero_suppression = UnitZeroSuppression::IncludeZeroFeetAndSuppressZeroInches;
+    assert_eq!("45.5%%d", style.format_angle(45.5));
+    style.dimensioning_angle_format = AngleFormat::DegreesMinutesSeconds;
+    style.angular_dimension_precision = 4;
+    assert_eq!("45%%d30'15\"", style.format_angle(45.504_166_666));
+    style.angular_dimension_precision = 1;
+    assert_eq!("45%%d30'", style.format_angle(45.5));
+    style.dimensioning_angle_format = AngleFormat::Radians;
+    style.dimension_angle_zero_suppression = UnitZeroSuppression::SuppressZeroFeetAndZeroInches;
+    style.angular_dimension_precision = 3;
+    assert_eq!("1.571r", style.format_angle(90.0));
+    style.dimensioning_angle_format = AngleFormat::Gradians;
+    assert_eq!("100.000g", style.format_angle(90.0));
+}
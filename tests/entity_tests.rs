@@ -191,8 +191,9 @@ fn read_field_with_multiples_specific() {
     let ent = read_entity("3DSOLID", vec!["1", "one-1", "1", "one-2", "3", "three-1", "3", "three-2"].join("\r\n"));
     match ent.specific {
         EntityType::Solid3D(ref solid3d) => {
-            assert_eq!(vec!["one-1", "one-2"], solid3d.custom_data);
-            assert_eq!(vec!["three-1", "three-2"], solid3d.custom_data2);
+            // continuation lines are joined onto the line they follow
+            assert_eq!(vec!["one-1", "one-2three-1three-2"], solid3d.custom_data);
+            assert!(solid3d.custom_data2.is_empty());
         },
         _ => panic!("expected a 3DSOLID"),
     }
@@ -629,6 +630,85 @@ fn write_polyline() {
     ].join("\r\n"));
 }
 
+#[test]
+fn read_polyface_mesh() {
+    let drawing = from_section("ENTITIES", vec![
+        "0", "POLYLINE",
+            "70", "64",
+            "71", "3",
+            "72", "1",
+        "0", "VERTEX",
+            "10", "0.0", "20", "0.0", "30", "0.0",
+            "70", "192",
+        "0", "VERTEX",
+            "10", "1.0", "20", "0.0", "30", "0.0",
+            "70", "192",
+        "0", "VERTEX",
+            "10", "0.0", "20", "1.0", "30", "0.0",
+            "70", "192",
+        "0", "VERTEX",
+            "70", "128",
+            "71", "1",
+            "72", "-2",
+            "73", "3",
+        "0", "SEQEND",
+    ].join("\r\n").as_str());
+    match drawing.entities[0].specific {
+        EntityType::Polyline(ref poly) => {
+            assert_eq!(None, poly.as_polygon_mesh());
+            let mesh = poly.as_polyface().unwrap();
+            assert_eq!(Point::new(0.0, 1.0, 0.0), mesh.vertices[2]);
+            assert_eq!(vec![PolyfaceFace { vertex_indices: vec![0, 1, 2], invisible_edges: vec![false, true, false] }], mesh.faces);
+        },
+        _ => panic!("expected a polyline"),
+    }
+}
+
+#[test]
+fn write_and_round_trip_polyface_and_polygon_meshes() {
+    let mut face = PolyfaceFace::new(vec![0, 1, 2, 3]);
+    face.invisible_edges[3] = true;
+    let polyface = PolyfaceMesh {
+        vertices: vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0), Point::new(0.0, 1.0, 0.0)],
+        faces: vec![face],
+    };
+    let grid = PolygonMesh {
+        m_vertex_count: 2,
+        n_vertex_count: 3,
+        vertices: (0..6).map(|i| Point::new((i % 3) as f64, (i / 3) as f64, 0.0)).collect(),
+        is_closed_in_m_direction: false,
+        is_closed_in_n_direction: true,
+    };
+    assert_eq!(Some(&Point::new(2.0, 1.0, 0.0)), grid.get_vertex(1, 2));
+    assert_eq!(None, grid.get_vertex(2, 0));
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2000;
+    drawing.entities.push(Entity::new(EntityType::Polyline(Polyline::from_polyface(&polyface))));
+    drawing.entities.push(Entity::new(EntityType::Polyline(Polyline::from_polygon_mesh(&grid))));
+    assert_contains(&drawing, vec![
+        " 70", "   128",
+        " 50", "0.0",
+        " 71", "     1",
+        " 72", "     2",
+        " 73", "     3",
+        " 74", "    -4",
+    ].join("\r\n"));
+
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    match (&round_tripped.entities[0].specific, &round_tripped.entities[1].specific) {
+        (&EntityType::Polyline(ref first), &EntityType::Polyline(ref second)) => {
+            assert!(first.get_is_polyface_mesh());
+            assert_eq!((4, 1), (first.polygon_mesh_m_vertex_count, first.polygon_mesh_n_vertex_count));
+            assert_eq!(Some(polyface), first.as_polyface());
+            assert!(second.get_is_3d_polygon_mesh());
+            assert!(second.get_is_polygon_mesh_closed_in_n_direction());
+            assert_eq!(Some(grid), second.as_polygon_mesh());
+            assert_eq!(None, second.as_polyface());
+        },
+        _ => panic!("expected polylines"),
+    }
+}
+
 #[test]
 fn read_lw_polyline_with_no_vertices() {
     let drawing = from_section("ENTITIES", vec![
@@ -758,6 +838,48 @@ fn write_lw_polyline() {
     ].join("\r\n"));
 }
 
+#[test]
+fn read_spline_with_weights() {
+    let ent = read_entity("SPLINE", vec![
+        "40", "0.0", // knot_values
+        "40", "1.0",
+        "41", "1.5", // weights
+        "41", "2.5",
+        "10", "1.0", // control_points[0]
+        "20", "2.0",
+        "30", "0.0",
+        "10", "3.0", // control_points[1]
+        "20", "4.0",
+        "30", "0.0",
+    ].join("\r\n"));
+    match ent.specific {
+        EntityType::Spline(ref spline) => {
+            assert_eq!(vec![0.0, 1.0], spline.knot_values);
+            assert_eq!(vec![1.5, 2.5], spline.weights);
+            assert_eq!(vec![Point::new(1.0, 2.0, 0.0), Point::new(3.0, 4.0, 0.0)], spline.control_points);
+        },
+        _ => panic!("expected a SPLINE"),
+    }
+}
+
+#[test]
+fn write_spline_with_weights() {
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2013;
+    let spline = Spline {
+        knot_values: vec![0.0, 1.0],
+        weights: vec![1.5, 2.5],
+        .. Default::default()
+    };
+    drawing.entities.push(Entity::new(EntityType::Spline(spline)));
+    assert_contains(&drawing, vec![
+        " 40", "0.0",
+        " 40", "1.0",
+        " 41", "1.5",
+        " 41", "2.5",
+    ].join("\r\n"));
+}
+
 #[test]
 fn read_dimension() {
     let ent = read_entity("DIMENSION", vec![
@@ -818,6 +940,124 @@ fn write_dimension() {
     ].join("\r\n"));
 }
 
+#[test]
+fn read_two_line_angular_dimension() {
+    let ent = read_entity("DIMENSION", vec![
+        "10", "0.0", "20", "5.0", "30", "0.0",
+        "70", "2",
+        "100", "AcDb2LineAngularDimension",
+        "13", "1.0", "23", "0.0", "33", "0.0",
+        "14", "5.0", "24", "0.0", "34", "0.0",
+        "15", "0.0", "25", "1.0", "35", "0.0",
+        "16", "2.0", "26", "2.0", "36", "0.0",
+    ].join("\r\n"));
+    match ent.specific {
+        EntityType::AngularTwoLineDimension(ref dim) => {
+            assert_eq!(DimensionType::Angular, dim.dimension_base.dimension_type);
+            assert_eq!(Point::new(0.0, 5.0, 0.0), dim.dimension_base.definition_point_1);
+            assert_eq!(Point::new(5.0, 0.0, 0.0), dim.definition_point_3);
+            assert_eq!(Point::new(0.0, 1.0, 0.0), dim.definition_point_4);
+            assert_eq!(Point::new(2.0, 2.0, 0.0), dim.arc_definition_point);
+        },
+        _ => panic!("expected a two line angular dimension"),
+    }
+}
+
+#[test]
+fn read_arc_dimension() {
+    let ent = read_entity("ARC_DIMENSION", vec![
+        "1", "<>",
+        "70", "8",
+        "100", "AcDbArcDimension",
+        "13", "1.0", "23", "0.0", "33", "0.0",
+        "14", "0.0", "24", "1.0", "34", "0.0",
+        "15", "0.0", "25", "0.0", "35", "0.0",
+        "40", "0.0",
+        "41", "1.5707963267948966",
+        "70", "1",
+        "71", "0",
+    ].join("\r\n"));
+    match ent.specific {
+        EntityType::ArcDimension(ref dim) => {
+            assert_eq!(DimensionType::ArcLength, dim.dimension_base.dimension_type);
+            assert_eq!(Point::new(1.0, 0.0, 0.0), dim.definition_point_2);
+            assert_eq!(Point::origin(), dim.arc_center);
+            assert_eq!(::std::f64::consts::PI / 2.0, dim.end_angle);
+            assert!(dim.is_partial);
+            assert!(!dim.has_leader);
+        },
+        _ => panic!("expected an arc dimension"),
+    }
+}
+
+#[test]
+fn read_large_radial_dimension_type() {
+    let ent = read_entity("LARGE_RADIAL_DIMENSION", vec![
+        "70", "41", // jogged radius with the block referenced by this dimension only
+        "100", "AcDbRadialDimensionLarge",
+        "40", "0.785",
+    ].join("\r\n"));
+    match ent.specific {
+        EntityType::LargeRadialDimension(ref dim) => {
+            assert_eq!(DimensionType::JoggedRadius, dim.dimension_base.dimension_type);
+            assert!(dim.dimension_base.is_block_reference_referenced_by_this_block_only);
+            assert_eq!(0.785, dim.jog_angle);
+        },
+        _ => panic!("expected a large radial dimension"),
+    }
+}
+
+#[test]
+fn read_dimension_with_unknown_type() {
+    let ent = read_entity("DIMENSION", vec![
+        "70", "140", // unknown type 12 with the user defined location flag
+        "100", "AcDbAlignedDimension",
+        "13", "1.0", "23", "2.0", "33", "3.0",
+    ].join("\r\n"));
+    match ent.specific {
+        EntityType::RotatedDimension(ref dim) => {
+            assert_eq!(DimensionBase::default().dimension_type, dim.dimension_base.dimension_type);
+            assert!(dim.dimension_base.is_at_user_defined_location);
+            assert_eq!(Point::new(1.0, 2.0, 3.0), dim.definition_point_2);
+        },
+        _ => panic!("expected a rotated dimension"),
+    }
+}
+
+#[test]
+fn write_and_round_trip_large_radial_dimension() {
+    let dim = LargeRadialDimension {
+        dimension_base: DimensionBase { definition_point_1: Point::new(100.0, 0.0, 0.0), .. Default::default() },
+        chord_point: Point::new(0.0, 0.0, 0.0),
+        override_center: Point::new(10.0, 5.0, 0.0),
+        jog_point: Point::new(5.0, 3.0, 0.0),
+        jog_angle: 0.785,
+    };
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2004;
+    drawing.entities.push(Entity::new(EntityType::LargeRadialDimension(dim.clone())));
+    assert_contains(&drawing, vec!["  0", "LARGE_RADIAL_DIMENSION"].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "100", "AcDbRadialDimensionLarge",
+        " 13", "0.0",
+        " 23", "0.0",
+        " 33", "0.0",
+        " 14", "10.0",
+        " 24", "5.0",
+        " 34", "0.0",
+        " 15", "5.0",
+        " 25", "3.0",
+        " 35", "0.0",
+        " 40", "0.785",
+    ].join("\r\n"));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    assert_eq!(EntityType::LargeRadialDimension(dim), round_tripped.entities[0].specific);
+
+    // not supported before R2004
+    drawing.header.version = AcadVersion::R2000;
+    assert!(!to_test_string(&drawing).contains("LARGE_RADIAL_DIMENSION"));
+}
+
 #[test]
 fn read_extension_data() {
     let ent = read_entity("LINE", vec![
@@ -1022,3 +1262,880 @@ fn write_all_types() {
         }
     }
 }
+
+#[test]
+fn read_hatch() {
+    let drawing = from_section("ENTITIES", vec![
+        "0", "HATCH",
+            "5", "A0",
+            "100", "AcDbEntity",
+            "8", "hatch-layer",
+            "100", "AcDbHatch",
+            "10", "0.0", "20", "0.0", "30", "2.5",
+            "210", "0.0", "220", "0.0", "230", "1.0",
+            "2", "ANSI31",
+            "70", "0",
+            "71", "1",
+            "91", "2",
+                // polyline path
+                "92", "3",
+                "72", "1",
+                "73", "1",
+                "93", "2",
+                "10", "0.0", "20", "0.0", "42", "0.5",
+                "10", "10.0", "20", "0.0", "42", "0.0",
+                "97", "1",
+                "330", "C1",
+                // edge path
+                "92", "16",
+                "93", "4",
+                "72", "1", "10", "0.0", "20", "0.0", "11", "1.0", "21", "0.0",
+                "72", "2", "10", "1.0", "20", "1.0", "40", "1.0", "50", "270.0", "51", "90.0", "73", "1",
+                "72", "3", "10", "0.0", "20", "2.0", "11", "2.0", "21", "0.0", "40", "0.5", "50", "0.0", "51", "180.0", "73", "0",
+                "72", "4", "94", "3", "73", "1", "74", "0", "95", "8", "96", "4",
+                    "40", "0.0", "40", "0.0", "40", "0.0", "40", "0.0", "40", "1.0", "40", "1.0", "40", "1.0", "40", "1.0",
+                    "10", "0.0", "20", "0.0", "42", "1.0",
+                    "10", "1.0", "20", "1.0", "42", "2.0",
+                    "10", "2.0", "20", "1.0", "42", "2.0",
+                    "10", "3.0", "20", "0.0", "42", "1.0",
+                    "97", "0",
+                    "12", "1.0", "22", "0.0",
+                    "13", "0.0", "23", "1.0",
+                "97", "0",
+            "75", "1",
+            "76", "1",
+            "52", "45.0",
+            "41", "2.0",
+            "77", "0",
+            "78", "1",
+                "53", "45.0", "43", "0.0", "44", "0.0", "45", "-2.2", "46", "2.2", "79", "2", "49", "3.0", "49", "-1.5",
+            "47", "0.1",
+            "98", "1",
+                "10", "5.0", "20", "6.0",
+            "450", "1",
+            "451", "0",
+            "460", "0.5",
+            "461", "0.25",
+            "452", "0",
+            "462", "1.0",
+            "453", "2",
+                "463", "0.0", "63", "5", "421", "255",
+                "463", "1.0", "63", "2", "421", "16776960",
+            "470", "LINEAR",
+            "1001", "ACAD",
+            "1000", "some string",
+        "0", "LINE",
+    ].join("\r\n").as_str());
+    assert_eq!(2, drawing.entities.len());
+    let entity = &drawing.entities[0];
+    assert_eq!((0xA0, "hatch-layer"), (entity.common.handle, entity.common.layer.as_str()));
+    assert_eq!(1, entity.common.x_data.len());
+    let hatch = match entity.specific {
+        EntityType::Hatch(ref hatch) => hatch,
+        _ => panic!("expected a HATCH"),
+    };
+    assert_eq!(2.5, hatch.elevation_point.z);
+    assert_eq!(("ANSI31", false, true), (hatch.pattern_name.as_str(), hatch.is_solid_fill, hatch.is_associative));
+    assert_eq!(2, hatch.boundary_paths.len());
+
+    let polyline_path = &hatch.boundary_paths[0];
+    assert!(polyline_path.get_is_external());
+    assert_eq!(vec![0xC1], polyline_path.source_boundary_handles);
+    assert_eq!(HatchBoundaryPathGeometry::Polyline {
+        is_closed: true,
+        vertices: vec![
+            HatchPolylineVertex { location: Point::new(0.0, 0.0, 0.0), bulge: 0.5 },
+            HatchPolylineVertex { location: Point::new(10.0, 0.0, 0.0), bulge: 0.0 },
+        ],
+    }, polyline_path.geometry);
+
+    let edge_path = &hatch.boundary_paths[1];
+    assert!(edge_path.get_is_outermost());
+    match edge_path.geometry {
+        HatchBoundaryPathGeometry::Edges(ref edges) => {
+            assert_eq!(4, edges.len());
+            assert_eq!(HatchBoundaryEdge::Line { start: Point::new(0.0, 0.0, 0.0), end: Point::new(1.0, 0.0, 0.0) }, edges[0]);
+            assert_eq!(HatchBoundaryEdge::CircularArc { center: Point::new(1.0, 1.0, 0.0), radius: 1.0, start_angle: 270.0, end_angle: 90.0, is_counter_clockwise: true }, edges[1]);
+            assert_eq!(HatchBoundaryEdge::EllipticArc { center: Point::new(0.0, 2.0, 0.0), major_axis: Vector::new(2.0, 0.0, 0.0), minor_axis_ratio: 0.5, start_angle: 0.0, end_angle: 180.0, is_counter_clockwise: false }, edges[2]);
+            match edges[3] {
+                HatchBoundaryEdge::Spline { degree, is_rational, ref knot_values, ref control_points, ref weights, ref fit_points, end_tangent, .. } => {
+                    assert_eq!((3, true), (degree, is_rational));
+                    assert_eq!((8, 4, 0), (knot_values.len(), control_points.len(), fit_points.len()));
+                    assert_eq!(vec![1.0, 2.0, 2.0, 1.0], *weights);
+                    assert_eq!(Vector::new(0.0, 1.0, 0.0), end_tangent);
+                },
+                _ => panic!("expected a spline edge"),
+            }
+        },
+        _ => panic!("expected an edge path"),
+    }
+    assert!(edge_path.source_boundary_handles.is_empty());
+
+    assert_eq!((HatchStyle::Outermost, HatchPatternType::Predefined), (hatch.hatch_style, hatch.pattern_type));
+    assert_eq!((45.0, 2.0, 0.1), (hatch.pattern_angle, hatch.pattern_scale, hatch.pixel_size));
+    assert_eq!(vec![HatchPatternDefinitionLine {
+        angle: 45.0,
+        base_point: Point::origin(),
+        offset: Vector::new(-2.2, 2.2, 0.0),
+        dash_lengths: vec![3.0, -1.5],
+    }], hatch.pattern_definition_lines);
+    assert_eq!(vec![Point::new(5.0, 6.0, 0.0)], hatch.seed_points);
+    assert!(hatch.is_gradient);
+    assert_eq!((0.5, 0.25, 1.0, "LINEAR"), (hatch.gradient_angle, hatch.gradient_shift, hatch.gradient_tint, hatch.gradient_name.as_str()));
+    assert_eq!(vec![
+        HatchGradientColor { value: 0.0, color: Color::from_index(5), true_color: 255 },
+        HatchGradientColor { value: 1.0, color: Color::from_index(2), true_color: 0xffff00 },
+    ], hatch.gradient_colors);
+}
+
+#[test]
+fn read_hatch_spline_edge_with_zero_fit_count_before_source_boundaries() {
+    let drawing = from_section("ENTITIES", vec![
+        "0", "HATCH",
+            "100", "AcDbEntity",
+            "100", "AcDbHatch",
+            "10", "0.0", "20", "0.0", "30", "0.0",
+            "210", "0.0", "220", "0.0", "230", "1.0",
+            "2", "SOLID",
+            "70", "1",
+            "71", "1",
+            "91", "2",
+                "92", "1",
+                "93", "1",
+                "72", "4", "94", "1", "73", "0", "74", "0", "95", "4", "96", "2",
+                    "40", "0.0", "40", "0.0", "40", "1.0", "40", "1.0",
+                    "10", "0.0", "20", "0.0",
+                    "10", "1.0", "20", "1.0",
+                    "97", "0",
+                "97", "2",
+                "330", "C1",
+                "330", "C2",
+                "92", "1",
+                "93", "2",
+                "72", "4", "94", "1", "73", "0", "74", "0", "95", "4", "96", "2",
+                    "40", "0.0", "40", "0.0", "40", "1.0", "40", "1.0",
+                    "10", "1.0", "20", "1.0",
+                    "10", "0.0", "20", "0.0",
+                    "97", "0",
+                    "72", "1", "10", "0.0", "20", "0.0", "11", "1.0", "21", "1.0",
+                "97", "0",
+            "75", "0",
+            "76", "1",
+            "98", "0",
+        "0", "LINE",
+    ].join("\r\n").as_str());
+    assert_eq!(2, drawing.entities.len());
+    let hatch = match drawing.entities[0].specific {
+        EntityType::Hatch(ref hatch) => hatch,
+        _ => panic!("expected a HATCH"),
+    };
+    assert_eq!(2, hatch.boundary_paths.len());
+    assert_eq!(vec![0xC1, 0xC2], hatch.boundary_paths[0].source_boundary_handles);
+    match hatch.boundary_paths[0].geometry {
+        HatchBoundaryPathGeometry::Edges(ref edges) => assert_eq!(1, edges.len()),
+        _ => panic!("expected an edge path"),
+    }
+    // a zero fit count can also be followed by the next edge
+    match hatch.boundary_paths[1].geometry {
+        HatchBoundaryPathGeometry::Edges(ref edges) => assert_eq!(2, edges.len()),
+        _ => panic!("expected an edge path"),
+    }
+    assert!(hatch.boundary_paths[1].source_boundary_handles.is_empty());
+}
+
+#[test]
+fn write_and_round_trip_hatch() {
+    let mut hatch = Hatch::default();
+    hatch.is_solid_fill = false;
+    hatch.pattern_name = String::from("USER");
+    hatch.pattern_type = HatchPatternType::UserDefined;
+    hatch.pattern_definition_lines.push(HatchPatternDefinitionLine { angle: 0.0, offset: Vector::new(0.0, 1.0, 0.0), .. Default::default() });
+    hatch.boundary_paths.push(HatchBoundaryPath {
+        flags: 1,
+        geometry: HatchBoundaryPathGeometry::Edges(vec![
+            HatchBoundaryEdge::Line { start: Point::new(0.0, 0.0, 0.0), end: Point::new(2.0, 0.0, 0.0) },
+            HatchBoundaryEdge::Spline {
+                degree: 1,
+                is_rational: false,
+                is_periodic: false,
+                knot_values: vec![0.0, 0.0, 1.0, 1.0],
+                control_points: vec![Point::new(2.0, 0.0, 0.0), Point::new(0.0, 0.0, 0.0)],
+                weights: vec![],
+                fit_points: vec![Point::new(2.0, 0.0, 0.0), Point::new(0.0, 0.0, 0.0)],
+                start_tangent: Vector::new(-1.0, 0.0, 0.0),
+                end_tangent: Vector::new(-1.0, 0.0, 0.0),
+            },
+        ]),
+        source_boundary_handles: vec![0x2A],
+    });
+    hatch.is_gradient = true;
+    hatch.gradient_colors.push(HatchGradientColor { value: 0.0, color: Color::from_index(1), true_color: 0 });
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2013;
+    drawing.entities.push(Entity::new(EntityType::Hatch(hatch.clone())));
+    assert_contains(&drawing, vec![
+        " 91", "        1",
+        " 92", "        1",
+        " 93", "        2",
+        " 72", "     1",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        " 97", "        2",
+        " 11", "2.0",
+        " 21", "0.0",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        " 97", "        1",
+        "330", "2A",
+        " 75", "     0",
+        " 76", "     0",
+        " 52", "0.0",
+        " 41", "1.0",
+        " 77", "     0",
+        " 78", "     1",
+        " 53", "0.0",
+    ].join("\r\n"));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    assert_eq!(EntityType::Hatch(hatch.clone()), round_tripped.entities[0].specific);
+
+    // spline fit data is only written on R2010+ and gradients on R2004+
+    drawing.header.version = AcadVersion::R2000;
+    let text = to_test_string(&drawing);
+    assert!(!text.contains("\r\n 97\r\n        2\r\n"));
+    assert!(!text.contains("\r\n450\r\n"));
+    let round_tripped = parse_drawing(&text);
+    match round_tripped.entities[0].specific {
+        EntityType::Hatch(ref read) => {
+            assert_eq!(vec![0x2A], read.boundary_paths[0].source_boundary_handles);
+            assert!(!read.is_gradient);
+        },
+        _ => panic!("expected a HATCH"),
+    }
+
+    // HATCH isn't supported before R14
+    drawing.header.version = AcadVersion::R13;
+    assert!(!to_test_string(&drawing).contains("HATCH"));
+}
+
+#[test]
+fn read_mleader() {
+    let drawing = from_section("ENTITIES", vec![
+        "0", "MULTILEADER",
+            "5", "B0",
+            "100", "AcDbEntity",
+            "8", "leader-layer",
+            "100", "AcDbMLeader",
+            "270", "2",
+            "300", "CONTEXT_DATA{",
+                "40", "1.0",
+                "10", "5.0", "20", "5.0", "30", "0.0",
+                "41", "0.18",
+                "140", "0.18",
+                "145", "0.09",
+                "174", "1",
+                "175", "1",
+                "176", "0",
+                "177", "0",
+                "290", "1",
+                    "304", "some text",
+                    "11", "0.0", "21", "0.0", "31", "1.0",
+                    "340", "11",
+                    "12", "5.5", "22", "5.1", "32", "0.0",
+                    "13", "1.0", "23", "0.0", "33", "0.0",
+                    "42", "0.0",
+                    "43", "0.0",
+                    "44", "0.0",
+                    "45", "1.0",
+                    "170", "1",
+                    "90", "-1056964608",
+                    "171", "1",
+                    "172", "5",
+                    "91", "-939524096",
+                    "141", "1.5",
+                    "92", "0",
+                    "291", "0",
+                    "292", "0",
+                    "173", "0",
+                    "293", "0",
+                    "142", "0.0",
+                    "143", "0.0",
+                    "294", "0",
+                    "295", "1",
+                "296", "0",
+                "110", "0.0", "120", "0.0", "130", "0.0",
+                "111", "1.0", "121", "0.0", "131", "0.0",
+                "112", "0.0", "122", "1.0", "132", "0.0",
+                "297", "0",
+                "302", "LEADER{",
+                    "290", "1",
+                    "291", "1",
+                    "10", "5.0", "20", "5.0", "30", "0.0",
+                    "11", "1.0", "21", "0.0", "31", "0.0",
+                    "90", "0",
+                    "40", "0.36",
+                    "304", "LEADER_LINE{",
+                        "10", "1.0", "20", "1.0", "30", "0.0",
+                        "10", "3.0", "20", "4.0", "30", "0.0",
+                        "90", "0",
+                        "11", "1.5", "21", "1.5", "31", "0.0",
+                        "12", "2.0", "22", "2.0", "32", "0.0",
+                        "91", "0",
+                    "305", "}",
+                    "271", "0",
+                "303", "}",
+                "272", "9",
+                "273", "9",
+            "301", "}",
+            "340", "12",
+            "90", "6816768",
+            "170", "1",
+            "91", "-1056964608",
+            "341", "14",
+            "171", "-2",
+            "290", "1",
+            "291", "1",
+            "41", "0.36",
+            "42", "0.18",
+            "172", "2",
+            "343", "11",
+            "173", "1",
+            "95", "1",
+            "174", "1",
+            "175", "0",
+            "92", "-1056964608",
+            "292", "0",
+            "93", "-1056964608",
+            "10", "1.0", "20", "1.0", "30", "1.0",
+            "43", "0.0",
+            "176", "0",
+            "293", "0",
+            "94", "0", "345", "15",
+            "330", "16", "177", "1", "44", "0.0", "302", "attribute value",
+            "294", "0",
+            "178", "0",
+            "179", "1",
+            "45", "1.0",
+            "271", "0",
+            "272", "9",
+            "273", "9",
+            "1001", "ACAD",
+            "1000", "some string",
+        "0", "LINE",
+    ].join("\r\n").as_str());
+    assert_eq!(2, drawing.entities.len());
+    let entity = &drawing.entities[0];
+    assert_eq!((0xB0, "leader-layer"), (entity.common.handle, entity.common.layer.as_str()));
+    assert_eq!(1, entity.common.x_data.len());
+    let mleader = match entity.specific {
+        EntityType::MLeader(ref mleader) => mleader,
+        _ => panic!("expected a MULTILEADER"),
+    };
+    assert_eq!((2, 6816768, 0.36), (mleader.version, mleader.property_override_flags, mleader.dogleg_length));
+    assert_eq!(vec![MLeaderArrowheadOverride { index: 0, arrowhead_handle: 0x15 }], mleader.arrowhead_overrides);
+    assert_eq!(vec![MLeaderBlockAttribute {
+        attribute_definition_handle: 0x16,
+        index: 1,
+        width: 0.0,
+        text: String::from("attribute value"),
+    }], mleader.block_attributes);
+    assert_eq!(BottomTextAttachmentDirection::Center, mleader.bottom_text_attachment_direction);
+
+    let context = &mleader.context_data;
+    assert_eq!(Point::new(5.0, 5.0, 0.0), context.content_base_point);
+    assert_eq!((0.18, 0.09), (context.text_height, context.landing_gap));
+    assert!(context.block_content.is_none());
+    let text = context.text_content.as_ref().unwrap();
+    assert_eq!(("some text", 0x11), (text.text.as_str(), text.text_style_handle));
+    assert_eq!(Point::new(5.5, 5.1, 0.0), text.location);
+    assert_eq!((5, 1.5, true), (text.flow_direction, text.background_scale_factor, text.use_word_break));
+    assert_eq!(Vector::y_axis(), context.plane_y_axis);
+
+    assert_eq!(1, context.leaders.len());
+    let leader = &context.leaders[0];
+    assert!(leader.has_set_last_leader_line_point);
+    assert_eq!(Point::new(5.0, 5.0, 0.0), leader.last_leader_line_point);
+    assert_eq!(0.36, leader.dogleg_length);
+    assert_eq!(vec![MLeaderLine {
+        vertices: vec![Point::new(1.0, 1.0, 0.0), Point::new(3.0, 4.0, 0.0)],
+        breaks: vec![MLeaderBreak { index: 0, start: Point::new(1.5, 1.5, 0.0), end: Point::new(2.0, 2.0, 0.0) }],
+        index: 0,
+    }], leader.lines);
+}
+
+#[test]
+fn write_and_round_trip_mleader() {
+    let mut mleader = MLeader::default();
+    mleader.content_type = 1;
+    mleader.context_data.block_content = Some(MLeaderBlockContent {
+        block_record_handle: 0x2A,
+        location: Point::new(4.0, 4.0, 0.0),
+        .. Default::default()
+    });
+    mleader.context_data.leaders.push(MLeaderLeader {
+        last_leader_line_point: Point::new(4.0, 4.0, 0.0),
+        breaks: vec![MLeaderBreak { index: 0, start: Point::new(3.5, 4.0, 0.0), end: Point::new(3.75, 4.0, 0.0) }],
+        lines: vec![MLeaderLine {
+            vertices: vec![Point::new(0.0, 0.0, 0.0)],
+            breaks: vec![],
+            index: 0,
+        }],
+        attachment_direction: TextAttachmentDirection::Vertical,
+        .. Default::default()
+    });
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2010;
+    drawing.entities.push(Entity::new(EntityType::MLeader(mleader.clone())));
+    assert_contains(&drawing, vec![
+        "100", "AcDbMLeader",
+        "270", "     2",
+        "300", "CONTEXT_DATA{",
+        " 40", "1.0",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "296", "1",
+        "341", "2A",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "304", "LEADER_LINE{",
+        " 10", "0.0",
+        " 20", "0.0",
+        " 30", "0.0",
+        " 91", "        0",
+        "305", "}",
+        "271", "     1",
+        "303", "}",
+    ].join("\r\n"));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    assert_eq!(EntityType::MLeader(mleader.clone()), round_tripped.entities[0].specific);
+
+    // the attachment directions are only written on R2010+
+    drawing.header.version = AcadVersion::R2007;
+    let text = to_test_string(&drawing);
+    assert!(!text.contains("\r\n271\r\n"));
+    let round_tripped = parse_drawing(&text);
+    match round_tripped.entities[0].specific {
+        EntityType::MLeader(ref read) => {
+            assert_eq!(TextAttachmentDirection::Horizontal, read.context_data.leaders[0].attachment_direction);
+            assert_eq!(Some(0x2A), read.context_data.block_content.as_ref().map(|b| b.block_record_handle));
+        },
+        _ => panic!("expected a MULTILEADER"),
+    }
+
+    // MULTILEADER isn't supported before R2007
+    drawing.header.version = AcadVersion::R2004;
+    assert!(!to_test_string(&drawing).contains("MULTILEADER"));
+}
+
+#[test]
+fn read_acad_table() {
+    let drawing = from_section("ENTITIES", vec![
+        "0", "ACAD_TABLE",
+            "5", "C0",
+            "100", "AcDbEntity",
+            "8", "table-layer",
+            "100", "AcDbBlockReference",
+            "2", "*T1",
+            "10", "1.0", "20", "2.0", "30", "0.0",
+            "100", "AcDbTable",
+            "280", "0",
+            "342", "7E",
+            "343", "7F",
+            "11", "1.0", "21", "0.0", "31", "0.0",
+            "90", "22",
+            "91", "2",
+            "92", "2",
+            "93", "0",
+            "94", "0",
+            "95", "0",
+            "96", "0",
+            "141", "0.5", "141", "0.25",
+            "142", "2.5", "142", "1.5",
+            // merged title cell
+            "171", "1", "172", "0", "173", "1", "174", "0", "175", "2", "176", "1", "91", "0", "178", "0", "145", "0.0",
+                "2", "Bill of ", "1", "Materials",
+                "140", "0.25", "64", "1", "91", "3", "289", "0",
+            // the override flags are optional, so this 91 is the unit type
+            "171", "1", "172", "0", "173", "1", "174", "0", "175", "0", "176", "0", "178", "0", "145", "0.0",
+                "1", "",
+                "91", "4",
+            // field cell
+            "171", "1", "172", "0", "173", "0", "174", "0", "175", "1", "176", "1", "91", "0", "178", "0", "145", "0.0",
+                "344", "A1",
+                "1", "42",
+            // block cell
+            "171", "2", "172", "0", "173", "0", "174", "1", "175", "1", "176", "1", "91", "0", "178", "0", "145", "0.0",
+                "340", "B2", "144", "0.5", "179", "1", "331", "B3", "300", "value",
+            // table-level overrides
+            "70", "0",
+            "40", "0.06",
+            "1001", "ACAD",
+            "1000", "some string",
+        "0", "LINE",
+    ].join("\r\n").as_str());
+    assert_eq!(2, drawing.entities.len());
+    let entity = &drawing.entities[0];
+    assert_eq!((0xC0, "table-layer"), (entity.common.handle, entity.common.layer.as_str()));
+    assert_eq!(1, entity.common.x_data.len());
+    let table = match entity.specific {
+        EntityType::AcadTable(ref table) => table,
+        _ => panic!("expected an ACAD_TABLE"),
+    };
+    assert_eq!(("*T1", Point::new(1.0, 2.0, 0.0)), (table.block_name.as_str(), table.insertion_point));
+    assert_eq!((2, 2, 4), (table.row_count(), table.column_count(), table.cells.len()));
+    assert_eq!(vec![
+        vec![String::from("Bill of Materials"), String::new()],
+        vec![String::from("42"), String::new()],
+    ], table.get_text_grid());
+
+    let title = table.get_cell(0, 0).unwrap();
+    assert_eq!((true, 2, 1), (title.is_merged, title.merged_column_count, title.merged_row_count));
+    assert_eq!(AcadTableCellOverrides {
+        text_height: Some(0.25),
+        text_color: Some(Color::from_index(1)),
+        unit_type: Some(3),
+        is_top_border_visible: Some(false),
+        .. Default::default()
+    }, title.overrides);
+    let merged = table.get_cell(0, 1).unwrap();
+    assert_eq!((0, Some(4)), (merged.override_flags, merged.overrides.unit_type));
+    assert_eq!(AcadTableCellContent::Field { field_handle: 0xA1, text: String::from("42") }, table.get_cell(1, 0).unwrap().content);
+    assert_eq!(AcadTableCellContent::Block {
+        block_record_handle: 0xB2,
+        scale: 0.5,
+        attributes: vec![AcadTableCellAttribute { attribute_definition_handle: 0xB3, value: String::from("value") }],
+    }, table.get_cell(1, 1).unwrap().content);
+    assert!(table.get_cell(2, 0).is_none());
+    assert_eq!(vec![CodePair::new_i16(70, 0), CodePair::new_f64(40, 0.06)], table.table_overrides);
+}
+
+#[test]
+fn write_and_round_trip_acad_table() {
+    let mut table = AcadTable::from_text_grid(&[
+        vec!["Part", "Qty"],
+        vec!["Bolt", "4"],
+        vec!["Nut"],
+    ], 0.5, 2.0);
+    assert_eq!(vec![
+        vec!["Part", "Qty"],
+        vec!["Bolt", "4"],
+        vec!["Nut", ""],
+    ], table.get_text_grid());
+    assert!(table.merge_cells(0, 0, 1, 2));
+    assert!(!table.merge_cells(2, 1, 1, 2));
+    table.get_cell_mut(1, 1).unwrap().overrides.alignment = Some(5);
+    let long_text = "x".repeat(300);
+    table.get_cell_mut(2, 1).unwrap().content = AcadTableCellContent::Text(long_text.clone());
+    table.table_overrides = vec![CodePair::new_i16(70, 0), CodePair::new_f64(40, 0.06)];
+
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2004;
+    drawing.entities.push(Entity::new(EntityType::AcadTable(table.clone())));
+    assert_contains(&drawing, vec![
+        " 91", "        3",
+        " 92", "        2",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "171", "     1",
+        "172", "     0",
+        "173", "     1",
+        "174", "     0",
+        "175", "     2",
+        "176", "     1",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "  1", "4",
+        "170", "     5",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "  2", &long_text[..250],
+        "  1", &long_text[250..],
+    ].join("\r\n"));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    assert_eq!(EntityType::AcadTable(table.clone()), round_tripped.entities[0].specific);
+
+    // ACAD_TABLE isn't supported before R2004
+    drawing.header.version = AcadVersion::R2000;
+    assert!(!to_test_string(&drawing).contains("ACAD_TABLE"));
+}
+
+#[test]
+fn write_acad_table_with_merged_count_out_of_range() {
+    let mut table = AcadTable::from_text_grid(&[vec!["a"]], 0.5, 2.0);
+    table.cells[0].merged_column_count = 40000;
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2004;
+    drawing.entities.push(Entity::new(EntityType::AcadTable(table)));
+    let mut buf = vec![];
+    match drawing.save(&mut buf) {
+        Err(DxfError::ValueOutOfRange(_)) => (),
+        _ => panic!("expected the merged column count to be rejected"),
+    }
+}
+
+#[test]
+fn read_viewport() {
+    let vp = read_entity("VIEWPORT", vec![
+        "10", "5.0", "20", "4.0", "30", "0.0",
+        "40", "8.0",
+        "41", "6.0",
+        "68", "2",
+        "69", "3",
+        "12", "50.0", "22", "40.0",
+        "16", "0.0", "26", "0.0", "36", "1.0",
+        "17", "1.0", "27", "2.0", "37", "3.0",
+        "45", "60.0",
+        "51", "15.0",
+        "331", "A1",
+        "331", "A2",
+        "90", "98336",
+        "340", "B1",
+        "281", "3",
+        "71", "1",
+        "110", "1.0", "120", "1.0", "130", "0.0",
+        "345", "C1",
+    ].join("\r\n"));
+    match vp.specific {
+        EntityType::Viewport(ref vp) => {
+            assert_eq!(Point::new(5.0, 4.0, 0.0), vp.center);
+            assert_eq!((8.0, 6.0, 2, 3), (vp.width, vp.height, vp.status, vp.id));
+            assert_eq!(Point::new(50.0, 40.0, 0.0), vp.view_center);
+            assert_eq!(Point::new(1.0, 2.0, 3.0), vp.view_target);
+            assert_eq!((0.1, 15.0), (vp.get_scale(), vp.view_twist_angle));
+            assert_eq!(vec![0xA1, 0xA2], vp.__frozen_layers_handle);
+            assert!(vp.get_has_non_rectangular_clipping());
+            assert!(vp.get_is_ucs_icon_visible());
+            assert!(!vp.get_is_perspective_view());
+            assert_eq!(0xB1, vp.__clipping_boundary_handle);
+            assert_eq!(ViewRenderMode::FlatShaded, vp.render_mode);
+            assert!(vp.has_own_ucs);
+            assert_eq!(Point::new(1.0, 1.0, 0.0), vp.ucs_origin);
+            assert_eq!(0xC1, vp.__ucs_handle);
+        },
+        _ => panic!("expected a viewport"),
+    }
+}
+
+#[test]
+fn write_viewport() {
+    let mut vp = Viewport::new(Point::new(5.0, 4.0, 0.0), 8.0, 6.0, Point::new(50.0, 40.0, 0.0), 0.1);
+    vp.set_is_zoom_locked(true);
+    vp.__frozen_layers_handle.push(0xA1);
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2000;
+    drawing.entities.push(Entity::new(EntityType::Viewport(vp.clone())));
+    assert_contains(&drawing, vec![
+        "100", "AcDbViewport",
+        " 10", "5.0",
+        " 20", "4.0",
+        " 30", "0.0",
+        " 40", "8.0",
+        " 41", "6.0",
+        " 68", "     1",
+        " 69", "     2",
+        " 12", "50.0",
+        " 22", "40.0",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        " 45", "60.0",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "331", "A1",
+        " 90", "    49184",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "281", "     0",
+        " 71", "     0",
+    ].join("\r\n"));
+    let text = to_test_string(&drawing);
+    assert!(!text.contains("\r\n345\r\n")); // only written with its own UCS
+    assert!(!text.contains("\r\n170\r\n")); // R2004+
+    let round_tripped = parse_drawing(&text);
+    assert_eq!(EntityType::Viewport(vp.clone()), round_tripped.entities[0].specific);
+
+    // only the paper space placement is written on R12
+    drawing.header.version = AcadVersion::R12;
+    let text = to_test_string(&drawing);
+    assert!(text.contains("VIEWPORT"));
+    assert!(!text.contains("AcDbViewport"));
+    assert!(!text.contains("\r\n 12\r\n"));
+}
+
+#[test]
+fn viewport_rejects_zero_scale() {
+    let mut vp = Viewport::new(Point::origin(), 8.0, 6.0, Point::origin(), 0.0);
+    assert_eq!(6.0, vp.view_height);
+    assert!(vp.set_scale(0.5));
+    assert_eq!(12.0, vp.view_height);
+    assert!(!vp.set_scale(0.0));
+    assert!(!vp.set_scale(-2.0));
+    assert_eq!(12.0, vp.view_height);
+}
+
+#[test]
+fn read_mesh() {
+    let mesh = read_entity("MESH", vec![
+        "100", "AcDbSubDMesh",
+        "71", "2",
+        "72", "1",
+        "91", "3",
+        "92", "4",
+            "10", "0.0", "20", "0.0", "30", "0.0",
+            "10", "1.0", "20", "0.0", "30", "0.0",
+            "10", "1.0", "20", "1.0", "30", "0.0",
+            "10", "0.0", "20", "1.0", "30", "0.0",
+        "93", "5",
+            "90", "4",
+            "90", "0", "90", "1", "90", "2", "90", "3",
+        "94", "1",
+            "90", "0", "90", "2",
+        "95", "1",
+            "140", "2.5",
+        "90", "0",
+    ].join("\r\n"));
+    match mesh.specific {
+        EntityType::Mesh(ref mesh) => {
+            assert!(mesh.is_blend_creased);
+            assert_eq!(3, mesh.subdivision_level);
+            assert_eq!(Point::new(1.0, 1.0, 0.0), mesh.vertices[2]);
+            assert_eq!(vec![vec![0, 1, 2, 3]], mesh.faces);
+            assert_eq!(vec![(0, 2)], mesh.edges);
+            assert_eq!(vec![2.5], mesh.edge_creases);
+            assert_eq!(Point::new(0.0, 1.0, 0.0), mesh.get_face_points()[0][3]);
+        },
+        _ => panic!("expected a mesh"),
+    }
+}
+
+#[test]
+fn read_mesh_with_negative_indices() {
+    let mesh = read_entity("MESH", vec![
+        "100", "AcDbSubDMesh",
+        "92", "3",
+            "10", "0.0", "20", "0.0", "30", "0.0",
+            "10", "1.0", "20", "0.0", "30", "0.0",
+            "10", "1.0", "20", "1.0", "30", "0.0",
+        "93", "8",
+            "90", "3",
+            "90", "0", "90", "-1", "90", "2",
+            "90", "3",
+            "90", "0", "90", "1", "90", "2",
+        "94", "2",
+            "90", "0", "90", "-2",
+            "90", "1", "90", "2",
+        "95", "2",
+            "140", "1.5",
+            "140", "2.5",
+        "90", "0",
+    ].join("\r\n"));
+    match mesh.specific {
+        EntityType::Mesh(ref mesh) => {
+            assert_eq!(vec![vec![0, 1, 2]], mesh.faces);
+            assert_eq!(vec![(1, 2)], mesh.edges);
+            assert_eq!(vec![2.5], mesh.edge_creases);
+        },
+        _ => panic!("expected a mesh"),
+    }
+}
+
+#[test]
+fn write_and_round_trip_mesh() {
+    let mesh = Mesh::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(0.0, 0.0, 1.0),
+    ], vec![vec![0, 1, 2], vec![0, 1, 3], vec![1, 2, 3], vec![0, 2, 3]]);
+    assert_eq!(6, mesh.edges.len());
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2010;
+    drawing.entities.push(Entity::new(EntityType::Mesh(mesh.clone())));
+    assert_contains(&drawing, vec![
+        "100", "AcDbSubDMesh",
+        " 71", "     2",
+        " 72", "     0",
+        " 91", "        0",
+        " 92", "        4",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        " 93", "       16",
+        " 90", "        3",
+        " 90", "        0",
+        " 90", "        1",
+        " 90", "        2",
+    ].join("\r\n"));
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    assert_eq!(EntityType::Mesh(mesh), round_tripped.entities[0].specific);
+
+    // not supported before R2010
+    drawing.header.version = AcadVersion::R2007;
+    assert!(!to_test_string(&drawing).contains("MESH"));
+}
+
+#[test]
+fn read_swept_surface() {
+    let mut body = vec![
+        "100", "AcDbModelerGeometry",
+        "70", "1",
+        "1", "ACIS data",
+        "100", "AcDbSurface",
+        "71", "4",
+        "72", "5",
+        "100", "AcDbSweptSurface",
+        "90", "1",
+        "90", "2",
+        "310", "0102",
+        "90", "3",
+        "90", "1",
+        "310", "FF",
+    ];
+    for i in 0..16 {
+        body.push("40");
+        body.push(if i % 5 == 0 { "2.0" } else { "0.0" });
+    }
+    body.extend(vec!["70", "2", "293", "1"]);
+    let surface = read_entity("SWEPTSURFACE", body.join("\r\n"));
+    match surface.specific {
+        EntityType::SweptSurface(ref surface) => {
+            assert_eq!(vec![String::from("ACIS data")], surface.custom_data);
+            assert_eq!((4, 5), (surface.u_isoline_count, surface.v_isoline_count));
+            assert_eq!((1, vec![0x01, 0x02]), (surface.sweep_entity_id, surface.sweep_entity_data.clone()));
+            assert_eq!((3, vec![0xFF]), (surface.path_entity_id, surface.path_entity_data.clone()));
+            assert_eq!(Point::new(2.0, 2.0, 2.0), surface.sweep_entity_transform.transform_point(&Point::new(1.0, 1.0, 1.0)));
+            assert_eq!(SweepAlignment::TranslateSweepEntityToPath, surface.sweep_alignment);
+            assert!(surface.is_banked);
+        },
+        _ => panic!("expected a swept surface"),
+    }
+}
+
+#[test]
+fn write_and_round_trip_extruded_surface() {
+    let mut surface = ExtrudedSurface::default();
+    surface.custom_data.push(String::from("ACIS data"));
+    surface.sweep_entity_data = (0..200).map(|i| i as u8).collect();
+    surface.sweep_vector = Vector::new(0.0, 0.0, 5.0);
+    surface.extruded_entity_transform = TransformationMatrix::scale(2.0, 2.0, 2.0);
+    surface.sweep_alignment = SweepAlignment::AlignSweepEntityToPath;
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2007;
+    drawing.entities.push(Entity::new(EntityType::ExtrudedSurface(surface.clone())));
+    assert_contains(&drawing, vec![
+        "100", "AcDbModelerGeometry",
+        " 70", "     1",
+        "  1", "ACIS data",
+        "100", "AcDbSurface",
+        " 71", "     6",
+        " 72", "     6",
+        "100", "AcDbExtrudedSurface",
+        " 90", "        0",
+        " 90", "      200",
+        "310", "000102030405",
+    ].join("\r\n"));
+    let text = to_test_string(&drawing);
+    let round_tripped = parse_drawing(&text);
+    assert_eq!(EntityType::ExtrudedSurface(surface), round_tripped.entities[0].specific);
+
+    // not supported before R2007
+    drawing.header.version = AcadVersion::R2004;
+    assert!(!to_test_string(&drawing).contains("AcDbExtrudedSurface"));
+}
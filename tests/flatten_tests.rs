@@ -0,0 +1,219 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::enums::*;
+
+fn assert_close(expected: &Point, actual: &Point) {
+    assert!(expected.distance_to(actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+fn block(name: &str, base_point: Point, entities: Vec<Entity>) -> Block {
+    let mut block = Block::default();
+    block.name = String::from(name);
+    block.base_point = base_point;
+    block.entities = entities;
+    block
+}
+
+fn insert(name: &str, location: Point) -> Insert {
+    let mut insert = Insert::default();
+    insert.name = String::from(name);
+    insert.location = location;
+    insert
+}
+
+fn line(start: Point, end: Point) -> Entity {
+    Entity::new(EntityType::Line(Line::new(start, end)))
+}
+
+fn line_points(entity: &Entity) -> (Point, Point) {
+    match entity.specific {
+        EntityType::Line(ref line) => (line.p1, line.p2),
+        _ => panic!("expected a line"),
+    }
+}
+
+#[test]
+fn explode_non_insert_returns_entity() {
+    let drawing = Drawing::default();
+    let ent = line(Point::origin(), Point::new(1.0, 0.0, 0.0));
+    let exploded = drawing.explode_insert(&ent);
+    assert_eq!(1, exploded.len());
+    assert_eq!(line_points(&ent), line_points(&exploded[0]));
+}
+
+#[test]
+fn explode_insert_with_translation_rotation_and_scale() {
+    let mut drawing = Drawing::default();
+    drawing.blocks.push(block("b", Point::new(1.0, 0.0, 0.0), vec![line(Point::new(1.0, 0.0, 0.0), Point::new(2.0, 0.0, 0.0))]));
+    let mut ins = insert("B", Point::new(10.0, 10.0, 0.0));
+    ins.rotation = 90.0;
+    ins.x_scale_factor = 2.0;
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(ins)));
+    assert_eq!(1, exploded.len());
+    let (p1, p2) = line_points(&exploded[0]);
+    assert_close(&Point::new(10.0, 10.0, 0.0), &p1);
+    assert_close(&Point::new(10.0, 12.0, 0.0), &p2);
+    assert_eq!(0, exploded[0].common.handle);
+}
+
+#[test]
+fn explode_minsert_produces_every_copy() {
+    let mut drawing = Drawing::default();
+    drawing.blocks.push(block("b", Point::origin(), vec![line(Point::origin(), Point::new(1.0, 0.0, 0.0))]));
+    let mut ins = insert("b", Point::origin());
+    ins.column_count = 3;
+    ins.row_count = 2;
+    ins.column_spacing = 5.0;
+    ins.row_spacing = 7.0;
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(ins)));
+    assert_eq!(6, exploded.len());
+    let starts = exploded.iter().map(|e| line_points(e).0).collect::<Vec<_>>();
+    assert!(starts.contains(&Point::new(10.0, 7.0, 0.0)));
+    assert!(starts.contains(&Point::new(5.0, 0.0, 0.0)));
+}
+
+#[test]
+fn flatten_nested_inserts() {
+    let mut drawing = Drawing::default();
+    drawing.blocks.push(block("inner", Point::origin(), vec![line(Point::origin(), Point::new(1.0, 0.0, 0.0))]));
+    let mut inner = insert("inner", Point::new(1.0, 0.0, 0.0));
+    inner.x_scale_factor = 3.0;
+    drawing.blocks.push(block("outer", Point::origin(), vec![Entity::new(EntityType::Insert(inner))]));
+    drawing.entities.push(Entity::new(EntityType::Insert(insert("outer", Point::new(0.0, 5.0, 0.0)))));
+    drawing.entities.push(line(Point::origin(), Point::new(0.0, 1.0, 0.0)));
+    let flattened = drawing.flattened_entities();
+    assert_eq!(2, flattened.len());
+    let (p1, p2) = line_points(&flattened[0]);
+    assert_close(&Point::new(1.0, 5.0, 0.0), &p1);
+    assert_close(&Point::new(4.0, 5.0, 0.0), &p2);
+}
+
+#[test]
+fn explode_insert_converts_attributes_to_text() {
+    let mut drawing = Drawing::default();
+    let mut constant = AttributeDefinition::default();
+    constant.value = String::from("constant");
+    constant.set_is_constant(true);
+    let mut variable = AttributeDefinition::default();
+    variable.value = String::from("default");
+    drawing.blocks.push(block("b", Point::origin(), vec![
+        Entity::new(EntityType::AttributeDefinition(constant)),
+        Entity::new(EntityType::AttributeDefinition(variable)),
+    ]));
+    let mut ins = insert("b", Point::new(2.0, 0.0, 0.0));
+    let mut visible = Attribute::default();
+    visible.value = String::from("visible");
+    visible.location = Point::new(2.0, 1.0, 0.0);
+    let mut invisible = Attribute::default();
+    invisible.value = String::from("invisible");
+    invisible.set_is_invisible(true);
+    ins.attributes.push(visible);
+    ins.attributes.push(invisible);
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(ins)));
+    let texts = exploded.iter().map(|e| match e.specific {
+        EntityType::Text(ref t) => (t.value.clone(), t.location),
+        _ => panic!("expected text"),
+    }).collect::<Vec<_>>();
+    assert_eq!(vec![
+        (String::from("visible"), Point::new(2.0, 1.0, 0.0)),
+        (String::from("constant"), Point::new(2.0, 0.0, 0.0)),
+    ], texts);
+}
+
+#[test]
+fn explode_insert_resolves_by_block_properties() {
+    let mut drawing = Drawing::default();
+    let mut by_block = line(Point::origin(), Point::new(1.0, 0.0, 0.0));
+    by_block.common.color = Color::by_block();
+    by_block.common.line_type_name = String::from("ByBlock");
+    by_block.common.lineweight_enum_value = LineWeight::by_block().get_raw_value();
+    let mut explicit = line(Point::origin(), Point::new(1.0, 0.0, 0.0));
+    explicit.common.color = Color::from_index(3);
+    explicit.common.layer = String::from("other");
+    drawing.blocks.push(block("b", Point::origin(), vec![by_block, explicit]));
+    let mut ent = Entity::new(EntityType::Insert(insert("b", Point::origin())));
+    ent.common.color = Color::from_index(1);
+    ent.common.line_type_name = String::from("DASHED");
+    ent.common.lineweight_enum_value = 50;
+    ent.common.layer = String::from("insert-layer");
+    let exploded = drawing.explode_insert(&ent);
+    assert_eq!(Color::from_index(1), exploded[0].common.color);
+    assert_eq!("DASHED", exploded[0].common.line_type_name);
+    assert_eq!(50, exploded[0].common.lineweight_enum_value);
+    assert_eq!("insert-layer", exploded[0].common.layer);
+    assert_eq!(Color::from_index(3), exploded[1].common.color);
+    assert_eq!("other", exploded[1].common.layer);
+}
+
+#[test]
+fn explode_recursive_block_terminates() {
+    let mut drawing = Drawing::default();
+    drawing.blocks.push(block("a", Point::origin(), vec![
+        line(Point::origin(), Point::new(1.0, 0.0, 0.0)),
+        Entity::new(EntityType::Insert(insert("B", Point::new(1.0, 0.0, 0.0)))),
+    ]));
+    drawing.blocks.push(block("b", Point::origin(), vec![
+        Entity::new(EntityType::Insert(insert("a", Point::new(1.0, 0.0, 0.0)))),
+    ]));
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(insert("a", Point::origin()))));
+    assert_eq!(1, exploded.len());
+}
+
+#[test]
+fn explode_insert_with_non_uniform_scale_turns_circle_into_ellipse() {
+    let mut drawing = Drawing::default();
+    drawing.blocks.push(block("b", Point::origin(), vec![Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0)))]));
+    let mut ins = insert("b", Point::new(5.0, 0.0, 0.0));
+    ins.x_scale_factor = 2.0;
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(ins)));
+    match exploded[0].specific {
+        EntityType::Ellipse(ref ellipse) => {
+            assert_close(&Point::new(5.0, 0.0, 0.0), &ellipse.center);
+            assert!((ellipse.major_axis.length() - 2.0).abs() < 1.0e-9);
+            assert!((ellipse.minor_axis_ratio - 0.5).abs() < 1.0e-9);
+        },
+        _ => panic!("expected an ellipse"),
+    }
+}
+
+#[test]
+fn explode_insert_rotates_and_scales_dimensions() {
+    let mut drawing = Drawing::default();
+    let mut rotated = RotatedDimension::default();
+    rotated.dimension_base.dimension_type = DimensionType::RotatedHorizontalOrVertical;
+    rotated.definition_point_2 = Point::new(0.0, 0.0, 0.0);
+    rotated.definition_point_3 = Point::new(4.0, 1.0, 0.0);
+    rotated.dimension_base.definition_point_1 = Point::new(0.0, -1.0, 0.0);
+    rotated.dimension_base.horizontal_direction_angle = 10.0;
+    rotated.dimension_base.text_rotation_angle = 15.0;
+    let mut radial = RadialDimension::default();
+    radial.definition_point_2 = Point::new(1.0, 0.0, 0.0);
+    radial.leader_length = 1.5;
+    drawing.blocks.push(block("dims", Point::origin(), vec![
+        Entity::new(EntityType::RotatedDimension(rotated)),
+        Entity::new(EntityType::RadialDimension(radial)),
+    ]));
+    let mut ins = insert("dims", Point::new(10.0, 0.0, 0.0));
+    ins.rotation = 90.0;
+    ins.x_scale_factor = 2.0;
+    ins.y_scale_factor = 2.0;
+    let exploded = drawing.explode_insert(&Entity::new(EntityType::Insert(ins)));
+    assert_eq!(2, exploded.len());
+    match exploded[0].specific {
+        EntityType::RotatedDimension(ref dim) => {
+            assert!((dim.rotation_angle - 90.0).abs() < 1.0e-9, "rotation {}", dim.rotation_angle);
+            assert!((dim.dimension_base.horizontal_direction_angle - 100.0).abs() < 1.0e-9);
+            assert!((dim.dimension_base.text_rotation_angle - 15.0).abs() < 1.0e-9);
+        },
+        _ => panic!("expected a rotated dimension"),
+    }
+    // the dimension still measures along its rotated dimension line
+    assert!((exploded[0].dimension_measurement().unwrap() - 8.0).abs() < 1.0e-9);
+    match exploded[1].specific {
+        EntityType::RadialDimension(ref dim) => assert!((dim.leader_length - 3.0).abs() < 1.0e-9),
+        _ => panic!("expected a radial dimension"),
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+const TOLERANCE: f64 = 1.0e-6;
+
+fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> Entity {
+    Entity::new(EntityType::Line(Line::new(Point::new(x1, y1, 0.0), Point::new(x2, y2, 0.0))))
+}
+
+fn circle(x: f64, y: f64, radius: f64) -> Entity {
+    Entity::new(EntityType::Circle(Circle::new(Point::new(x, y, 0.0), radius)))
+}
+
+fn lw_polyline(points: &[(f64, f64)], is_closed: bool) -> Entity {
+    let mut poly = LwPolyline::default();
+    poly.set_is_closed(is_closed);
+    for &(x, y) in points {
+        let mut vertex = LwPolylineVertex::default();
+        vertex.x = x;
+        vertex.y = y;
+        poly.vertices.push(vertex);
+    }
+    Entity::new(EntityType::LwPolyline(poly))
+}
+
+fn assert_points(expected: &[(f64, f64)], actual: &[Point]) {
+    assert_eq!(expected.len(), actual.len(), "expected {:?} but got {:?}", expected, actual);
+    for &(x, y) in expected {
+        let expected = Point::new(x, y, 0.0);
+        assert!(actual.iter().any(|p| p.distance_to(&expected) < 1.0e-6), "expected {:?} in {:?}", expected, actual);
+    }
+}
+
+#[test]
+fn line_line_intersections() {
+    assert_points(&[(1.0, 1.0)], &line(0.0, 0.0, 2.0, 2.0).intersections(&line(0.0, 2.0, 2.0, 0.0), TOLERANCE));
+    assert_points(&[], &line(0.0, 0.0, 1.0, 1.0).intersections(&line(3.0, 0.0, 2.0, 1.0), TOLERANCE));
+
+    // a gap smaller than the tolerance still counts
+    assert_points(&[(1.0, 0.0)], &line(0.0, 0.0, 0.9999, 0.0).intersections(&line(1.0, -1.0, 1.0, 1.0), 0.001));
+}
+
+#[test]
+fn collinear_overlap_reports_its_ends() {
+    assert_points(&[(1.0, 0.0), (2.0, 0.0)], &line(0.0, 0.0, 2.0, 0.0).intersections(&line(1.0, 0.0, 3.0, 0.0), TOLERANCE));
+}
+
+#[test]
+fn line_circle_intersections() {
+    assert_points(&[(-1.0, 0.0), (1.0, 0.0)], &line(-2.0, 0.0, 2.0, 0.0).intersections(&circle(0.0, 0.0, 1.0), TOLERANCE));
+    assert_points(&[(0.0, 1.0)], &line(-2.0, 1.0, 2.0, 1.0).intersections(&circle(0.0, 0.0, 1.0), TOLERANCE));
+    assert_points(&[], &line(-2.0, 1.1, 2.0, 1.1).intersections(&circle(0.0, 0.0, 1.0), TOLERANCE));
+}
+
+#[test]
+fn arc_only_intersects_within_its_sweep() {
+    let arc = Entity::new(EntityType::Arc(Arc::new(Point::origin(), 1.0, 0.0, 90.0)));
+    assert_points(&[(1.0, 0.0)], &arc.intersections(&line(-2.0, 0.0, 2.0, 0.0), TOLERANCE));
+}
+
+#[test]
+fn circle_circle_and_ellipse_intersections() {
+    let root = 0.75f64.sqrt();
+    assert_points(&[(0.5, root), (0.5, -root)], &circle(0.0, 0.0, 1.0).intersections(&circle(1.0, 0.0, 1.0), TOLERANCE));
+
+    let mut ellipse = Ellipse::default();
+    ellipse.major_axis = Vector::new(2.0, 0.0, 0.0);
+    ellipse.minor_axis_ratio = 0.5;
+    let ellipse = Entity::new(EntityType::Ellipse(ellipse));
+    assert_points(&[(-2.0, 0.0), (2.0, 0.0)], &ellipse.intersections(&line(-3.0, 0.0, 3.0, 0.0), TOLERANCE));
+
+    // x^2/4 + y^2 = 1 and x^2 + y^2 = 2 meet at x^2 = 4/3 and y^2 = 2/3
+    let (x, y) = ((4.0f64 / 3.0).sqrt(), (2.0f64 / 3.0).sqrt());
+    assert_points(&[(x, y), (-x, y), (x, -y), (-x, -y)], &ellipse.intersections(&circle(0.0, 0.0, 2.0f64.sqrt()), TOLERANCE));
+}
+
+#[test]
+fn non_coplanar_circles() {
+    let mut vertical = Circle::new(Point::origin(), 1.0);
+    vertical.normal = Vector::new(0.0, 1.0, 0.0);
+    let vertical = Entity::new(EntityType::Circle(vertical));
+    assert_points(&[(-1.0, 0.0), (1.0, 0.0)], &vertical.intersections(&circle(0.0, 0.0, 1.0), TOLERANCE));
+}
+
+#[test]
+fn polyline_and_spline_intersections() {
+    let square = lw_polyline(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)], true);
+    assert_points(&[(0.0, 1.0), (2.0, 1.0)], &square.intersections(&line(-1.0, 1.0, 3.0, 1.0), TOLERANCE));
+
+    let mut spline = Spline::default();
+    spline.degree_of_curve = 2;
+    spline.control_points = vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 2.0, 0.0), Point::new(2.0, 0.0, 0.0)];
+    spline.knot_values = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+    let spline = Entity::new(EntityType::Spline(spline));
+    // the curve peaks at (1, 1)
+    assert_points(&[(1.0, 1.0)], &spline.intersections(&line(0.0, 1.0, 2.0, 1.0), 1.0e-4));
+}
+
+#[test]
+fn polyline_self_intersections() {
+    let bow_tie = lw_polyline(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)], true);
+    assert_points(&[(1.0, 1.0)], &bow_tie.self_intersections(TOLERANCE));
+    let square = lw_polyline(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)], true);
+    assert_points(&[], &square.self_intersections(TOLERANCE));
+}
+
+#[test]
+fn drawing_intersections_include_unbounded_entities() {
+    let mut drawing = Drawing::default();
+    drawing.entities.push(line(0.0, 0.0, 2.0, 2.0));
+    drawing.entities.push(line(0.0, 2.0, 2.0, 0.0));
+    drawing.entities.push(circle(10.0, 10.0, 1.0));
+    let mut xline = XLine::default();
+    xline.first_point = Point::new(0.0, 10.0, 0.0);
+    xline.unit_direction_vector = Vector::x_axis();
+    drawing.entities.push(Entity::new(EntityType::XLine(xline)));
+    let intersections = drawing.intersections(TOLERANCE);
+    let pairs = intersections.iter().map(|i| (i.first, i.second)).collect::<Vec<_>>();
+    assert_eq!(vec![(0, 1), (2, 3), (2, 3)], pairs);
+    assert_points(&[(1.0, 1.0)], &[intersections[0].point]);
+}
@@ -0,0 +1,171 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+use std::f64::consts::PI;
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1.0e-9, "expected {} but got {}", expected, actual);
+}
+
+fn assert_point_close(expected: Point, actual: Point) {
+    assert!(expected.distance_to(&actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+fn lw_polyline(points: &[(f64, f64, f64)], is_closed: bool) -> Entity {
+    let mut poly = LwPolyline::default();
+    poly.set_is_closed(is_closed);
+    poly.vertices = points.iter().map(|&(x, y, bulge)| LwPolylineVertex { x, y, bulge, .. Default::default() }).collect();
+    Entity::new(EntityType::LwPolyline(poly))
+}
+
+#[test]
+fn line_and_arc_length() {
+    let line = Entity::new(EntityType::Line(Line::new(Point::new(1.0, 1.0, 0.0), Point::new(4.0, 5.0, 0.0))));
+    assert_eq!(Some(5.0), line.length());
+    assert_eq!(None, line.area());
+
+    let arc = Entity::new(EntityType::Arc(Arc::new(Point::origin(), 2.0, 270.0, 0.0)));
+    assert_close(PI, arc.length().unwrap());
+    assert_eq!(None, arc.signed_area());
+}
+
+#[test]
+fn circle_measurements() {
+    let mut circle = Circle::new(Point::new(1.0, 2.0, 3.0), 2.0);
+    circle.normal = Vector::new(0.0, 0.0, -1.0);
+    let ent = Entity::new(EntityType::Circle(circle));
+    assert_close(PI * 4.0, ent.length().unwrap());
+    assert_close(PI * 4.0, ent.signed_area().unwrap());
+    assert_eq!(Some(Orientation::CounterClockwise), ent.orientation());
+
+    // the center is in OCS coordinates
+    assert_point_close(Point::new(-1.0, 2.0, -3.0), ent.centroid().unwrap());
+}
+
+#[test]
+fn ellipse_measurements() {
+    let ellipse = Ellipse {
+        center: Point::new(1.0, 1.0, 0.0),
+        major_axis: Vector::new(3.0, 0.0, 0.0),
+        minor_axis_ratio: 1.0,
+        .. Default::default()
+    };
+    let ent = Entity::new(EntityType::Ellipse(ellipse.clone()));
+    assert_close(PI * 6.0, ent.length().unwrap());
+
+    let ellipse = Ellipse { minor_axis_ratio: 0.5, .. ellipse };
+    let ent = Entity::new(EntityType::Ellipse(ellipse.clone()));
+    assert_close(PI * 3.0 * 1.5, ent.area().unwrap());
+    assert_point_close(Point::new(1.0, 1.0, 0.0), ent.centroid().unwrap());
+
+    // Ramanujan's approximation is very accurate for moderate eccentricities
+    let (a, b) = (3.0f64, 1.5f64);
+    let h = ((a - b) / (a + b)).powi(2);
+    let expected = PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()));
+    assert!((expected - ent.length().unwrap()).abs() < 1.0e-6);
+
+    // partial ellipses don't enclose an area
+    let partial = Ellipse { end_parameter: PI, .. ellipse };
+    assert_eq!(None, Entity::new(EntityType::Ellipse(partial)).area());
+}
+
+#[test]
+fn lw_polyline_measurements() {
+    let ccw = lw_polyline(&[(0.0, 0.0, 0.0), (4.0, 0.0, 0.0), (4.0, 2.0, 0.0), (0.0, 2.0, 0.0)], true);
+    assert_close(12.0, ccw.length().unwrap());
+    assert_close(8.0, ccw.signed_area().unwrap());
+    assert_eq!(Some(Orientation::CounterClockwise), ccw.orientation());
+    assert_point_close(Point::new(2.0, 1.0, 0.0), ccw.centroid().unwrap());
+
+    let cw = lw_polyline(&[(0.0, 0.0, 0.0), (0.0, 2.0, 0.0), (4.0, 2.0, 0.0), (4.0, 0.0, 0.0)], true);
+    assert_close(-8.0, cw.signed_area().unwrap());
+    assert_eq!(Some(Orientation::Clockwise), cw.orientation());
+
+    // open polylines only have a length
+    let open = lw_polyline(&[(0.0, 0.0, 0.0), (4.0, 0.0, 0.0), (4.0, 2.0, 0.0)], false);
+    assert_close(6.0, open.length().unwrap());
+    assert_eq!(None, open.area());
+}
+
+#[test]
+fn lw_polyline_with_bulges() {
+    // a circle of radius 1 made from two semicircles
+    let mut circle = lw_polyline(&[(0.0, 0.0, 1.0), (2.0, 0.0, 1.0)], true);
+    circle.common.elevation = 3.0;
+    assert_close(PI * 2.0, circle.length().unwrap());
+    assert_close(PI, circle.signed_area().unwrap());
+    assert_point_close(Point::new(1.0, 0.0, 3.0), circle.centroid().unwrap());
+
+    // a 2x2 square with a semicircle bulging out of the right side
+    let shape = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 1.0), (2.0, 2.0, 0.0), (0.0, 2.0, 0.0)], true);
+    let half_disk = PI / 2.0;
+    assert_close(4.0 + half_disk, shape.area().unwrap());
+    let expected_x = (4.0 * 1.0 + half_disk * (2.0 + 4.0 / (3.0 * PI))) / (4.0 + half_disk);
+    assert_point_close(Point::new(expected_x, 1.0, 0.0), shape.centroid().unwrap());
+}
+
+#[test]
+fn polyline_explicitly_closed_by_last_vertex() {
+    let mut poly = Polyline::default();
+    for &(x, y) in &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)] {
+        poly.vertices.push(Vertex::new(Point::new(x, y, 0.0)));
+    }
+    let ent = Entity::new(EntityType::Polyline(poly));
+    assert_close(0.5, ent.signed_area().unwrap());
+    assert_point_close(Point::new(2.0 / 3.0, 1.0 / 3.0, 0.0), ent.centroid().unwrap());
+}
+
+#[test]
+fn spline_length() {
+    let spline = Spline {
+        degree_of_curve: 1,
+        knot_values: vec![0.0, 0.0, 1.0, 2.0, 2.0],
+        control_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(3.0, 4.0, 0.0), Point::new(3.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    assert_close(9.0, Entity::new(EntityType::Spline(spline)).length().unwrap());
+}
+
+#[test]
+fn closed_rational_spline_circle() {
+    let w = 0.5f64.sqrt();
+    let mut spline = Spline {
+        degree_of_curve: 2,
+        knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 4.0],
+        weights: vec![1.0, w, 1.0, w, 1.0, w, 1.0, w, 1.0],
+        control_points: vec![
+            Point::new(3.0, 1.0, 0.0),
+            Point::new(3.0, 2.0, 0.0),
+            Point::new(2.0, 2.0, 0.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+            Point::new(3.0, 0.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+        ],
+        .. Default::default()
+    };
+    spline.set_is_rational(true);
+    let ent = Entity::new(EntityType::Spline(spline));
+    assert!((PI * 2.0 - ent.length().unwrap()).abs() < 1.0e-6);
+    assert!((PI - ent.signed_area().unwrap()).abs() < 1.0e-6);
+    assert!(Point::new(2.0, 1.0, 0.0).distance_to(&ent.centroid().unwrap()) < 1.0e-6);
+    assert_eq!(Some(Orientation::CounterClockwise), ent.orientation());
+}
+
+#[test]
+fn area_with_holes() {
+    let outer = lw_polyline(&[(0.0, 0.0, 0.0), (10.0, 0.0, 0.0), (10.0, 10.0, 0.0), (0.0, 10.0, 0.0)], true);
+    let hole = Entity::new(EntityType::Circle(Circle::new(Point::new(5.0, 5.0, 0.0), 1.0)));
+    let island = lw_polyline(&[(4.75, 4.75, 0.0), (5.25, 4.75, 0.0), (5.25, 5.25, 0.0), (4.75, 5.25, 0.0)], true);
+    let separate_hole = lw_polyline(&[(1.0, 1.0, 0.0), (1.0, 2.0, 0.0), (2.0, 2.0, 0.0), (2.0, 1.0, 0.0)], true);
+    let area = Entity::area_with_holes(&[island, outer, hole, separate_hole]).unwrap();
+    assert_close(100.0 - PI + 0.25 - 1.0, area);
+
+    let open = Entity::new(EntityType::Line(Line::default()));
+    assert_eq!(None, Entity::area_with_holes(&[open]));
+}
@@ -0,0 +1,123 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+fn text_runs(document: &MTextDocument) -> Vec<Vec<(String, MTextFormat)>> {
+    document.paragraphs.iter().map(|p| p.runs.iter().map(|r| match r.content {
+        MTextContent::Text(ref text) => (text.clone(), r.format.clone()),
+        MTextContent::Stack { ref upper, ref lower, .. } => (format!("{}|{}", upper, lower), r.format.clone()),
+    }).collect()).collect()
+}
+
+#[test]
+fn parse_paragraphs_and_groups() {
+    let document = MTextDocument::parse("Line 1\\P{\\fArial|b1|i0|c0|p34;\\H2.5;\\C1;Bold red} plain\\Pa\\~b \\{braces\\} \\\\ 100%%d");
+    let runs = text_runs(&document);
+    assert_eq!(3, runs.len());
+    assert_eq!(vec![(String::from("Line 1"), MTextFormat::default())], runs[0]);
+
+    let (ref text, ref format) = runs[1][0];
+    assert_eq!("Bold red", text);
+    let font = format.font.clone().unwrap();
+    assert_eq!(("Arial", true, false, 34), (font.name.as_str(), font.is_bold, font.is_italic, font.pitch_and_family));
+    assert_eq!(Some(2.5), format.height);
+    assert_eq!(Some(MTextColor::Index(Color::from_index(1))), format.color);
+    assert_eq!((String::from(" plain"), MTextFormat::default()), runs[1][1]);
+
+    assert_eq!("a\u{a0}b {braces} \\ 100\u{b0}", runs[2][0].0);
+    assert_eq!("Line 1\nBold red plain\na\u{a0}b {braces} \\ 100\u{b0}", document.plain_text());
+}
+
+#[test]
+fn parse_toggles_stacks_and_relative_heights() {
+    let document = MTextDocument::parse("\\H2;\\Lunder\\l \\Oover\\o {\\H0.5x;half}\\S1/2; \\S+0.1^-0.2;\\U+2205");
+    let runs = &document.paragraphs[0].runs;
+    let formats = runs.iter().map(|r| (r.format.height, r.format.is_underlined, r.format.is_overlined)).collect::<Vec<_>>();
+    assert_eq!(vec![
+        (Some(2.0), true, false),
+        (Some(2.0), false, false),
+        (Some(2.0), false, true),
+        (Some(2.0), false, false),
+        (Some(1.0), false, false),
+        (Some(2.0), false, false),
+        (Some(2.0), false, false),
+        (Some(2.0), false, false),
+        (Some(2.0), false, false),
+    ], formats);
+    assert_eq!(MTextContent::Stack { upper: String::from("1"), lower: String::from("2"), kind: MTextStackKind::Horizontal }, runs[5].content);
+    assert_eq!(MTextContent::Stack { upper: String::from("+0.1"), lower: String::from("-0.2"), kind: MTextStackKind::Tolerance }, runs[7].content);
+    assert_eq!(MTextContent::Text(String::from("\u{2205}")), runs[8].content);
+
+    let relative = MTextDocument::parse("{\\H0.7x;small}");
+    assert_eq!((None, Some(0.7)), (relative.paragraphs[0].runs[0].format.height, relative.paragraphs[0].runs[0].format.relative_height));
+}
+
+#[test]
+fn write_and_round_trip_document() {
+    let mut format = MTextFormat::default();
+    format.is_underlined = true;
+    format.color = Some(MTextColor::TrueColor(0x00ff00));
+    let document = MTextDocument {
+        paragraphs: vec![
+            MTextParagraph {
+                properties: String::from("xqc"),
+                runs: vec![
+                    MTextRun { format: MTextFormat::default(), content: MTextContent::Text(String::from("Price {100%}\\")) },
+                    MTextRun { format: format.clone(), content: MTextContent::Text(String::from("2 ")) },
+                    MTextRun { format, content: MTextContent::Stack { upper: String::from("1"), lower: String::from("4"), kind: MTextStackKind::Diagonal } },
+                ],
+                .. Default::default()
+            },
+            MTextParagraph {
+                starts_new_column: true,
+                runs: vec![MTextRun { format: MTextFormat::default(), content: MTextContent::Text(String::from("%%c isn't a code; \u{b1}1^2")) }],
+                .. Default::default()
+            },
+        ],
+    };
+    let written = document.to_mtext();
+    assert_eq!("\\pxqc;Price \\{100%\\}\\\\{\\c65280;\\L2 }{\\c65280;\\L\\S1#4;}\\N%%%%c isn't a code; %%p1^2", written);
+    assert_eq!(document, MTextDocument::parse(&written));
+}
+
+#[test]
+fn round_trip_percent_before_special_characters() {
+    for text in &["%\u{2300}5", "90%\u{b0}", "%\u{b1}1", "%%", "100%"] {
+        let document = MTextDocument {
+            paragraphs: vec![MTextParagraph {
+                runs: vec![MTextRun { format: MTextFormat::default(), content: MTextContent::Text(String::from(*text)) }],
+                .. Default::default()
+            }],
+        };
+        assert_eq!(document, MTextDocument::parse(&document.to_mtext()), "round-tripping {:?}", text);
+    }
+
+    // unformatted runs are written back to back
+    let document = MTextDocument {
+        paragraphs: vec![MTextParagraph {
+            runs: vec![
+                MTextRun { format: MTextFormat::default(), content: MTextContent::Text(String::from("50%")) },
+                MTextRun { format: MTextFormat::default(), content: MTextContent::Text(String::from("\u{b0}")) },
+            ],
+            .. Default::default()
+        }],
+    };
+    assert_eq!("50%\u{b0}", MTextDocument::parse(&document.to_mtext()).plain_text());
+}
+
+#[test]
+fn mtext_full_text_is_split_into_chunks() {
+    let mut mtext = MText::default();
+    let long = (0..600).map(|i| if i % 2 == 0 { 'a' } else { 'é' }).collect::<String>();
+    mtext.set_full_text(&long);
+    assert_eq!(2, mtext.extended_text.len());
+    assert_eq!(250, mtext.extended_text[0].chars().count());
+    assert_eq!(100, mtext.text.chars().count());
+    assert_eq!(long, mtext.full_text());
+
+    mtext.set_full_text("{\\LNote:} see detail\\P2");
+    assert!(mtext.extended_text.is_empty());
+    assert_eq!("Note: see detail\n2", mtext.plain_text());
+}
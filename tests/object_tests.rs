@@ -509,3 +509,399 @@ fn write_all_types() {
         }
     }
 }
+
+#[test]
+fn read_block_visibility_parameter() {
+    let obj = read_object("BLOCKVISIBILITYPARAMETER", vec![
+        "  5", "1A",
+        "330", "1B",
+        "100", "AcDbEvalExpr",
+        " 90", "4",
+        " 98", "33",
+        " 99", "29",
+        "100", "AcDbBlockElement",
+        "300", "Visibility1",
+        "1071", "0",
+        "100", "AcDbBlockParameter",
+        "280", "1",
+        "281", "0",
+        "100", "AcDbBlock1PtParameter",
+        "1010", "1.0",
+        "1020", "2.0",
+        "1030", "0.0",
+        " 93", "0",
+        "170", "0",
+        "171", "0",
+        "100", "AcDbBlockVisibilityParameter",
+        "281", "1",
+        "301", "Door Swing",
+        "302", "",
+        " 91", "0",
+        " 93", "2",
+        "331", "2A",
+        "331", "2B",
+        " 92", "2",
+        "303", "Left",
+        " 94", "1",
+        "332", "2A",
+        " 95", "0",
+        "303", "Right",
+        " 94", "1",
+        "332", "2B",
+        " 95", "0",
+    ].join("\r\n"));
+    match obj.specific {
+        ObjectType::BlockVisibilityParameter(ref vis) => {
+            assert_eq!(4, vis.node_id);
+            assert_eq!("Visibility1", vis.element_name);
+            assert_eq!(Point::new(1.0, 2.0, 0.0), vis.location);
+            assert_eq!("Door Swing", vis.label);
+            assert_eq!(vec![0x2A, 0x2B], vis.__entities_handle);
+            assert_eq!(vec!["Left", "Right"], vis.states.iter().map(|s| s.name.as_str()).collect::<Vec<_>>());
+            assert_eq!(vec![0x2B], vis.get_state("right").unwrap().__entities_handle);
+        },
+        _ => panic!("expected a visibility parameter"),
+    }
+    assert_eq!(0x1B, obj.common.__owner_handle);
+}
+
+#[test]
+fn write_and_round_trip_block_parameters() {
+    let mut linear = BlockLinearParameter::default();
+    linear.node_id = 2;
+    linear.base_point = Point::new(1.0, 1.0, 0.0);
+    linear.end_point = Point::new(4.0, 5.0, 0.0);
+    let mut rotation = BlockRotationParameter::default();
+    rotation.base_point = Point::new(1.0, 1.0, 0.0);
+    rotation.angle_point = Point::new(1.0, 3.0, 0.0);
+    let mut visibility = BlockVisibilityParameter::default();
+    let mut state = BlockVisibilityState::new(String::from("Open"));
+    state.__entities_handle.push(0x2A);
+    visibility.states.push(state);
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2004;
+    for specific in vec![ObjectType::BlockLinearParameter(linear), ObjectType::BlockRotationParameter(rotation), ObjectType::BlockVisibilityParameter(visibility)] {
+        drawing.objects.push(Object::new(specific));
+    }
+    assert_contains(&drawing, vec![
+        "100", "AcDbBlock2PtParameter",
+        "1010", "1.0",
+        "1020", "1.0",
+        "1030", "0.0",
+        "1011", "4.0",
+    ].join("\r\n"));
+
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    match round_tripped.objects[0].specific {
+        ObjectType::BlockLinearParameter(ref linear) => {
+            assert_eq!(2, linear.node_id);
+            assert_eq!(5.0, linear.distance());
+        },
+        _ => panic!("expected a linear parameter"),
+    }
+    match round_tripped.objects[1].specific {
+        ObjectType::BlockRotationParameter(ref rotation) => assert_eq!(90.0, rotation.angle()),
+        _ => panic!("expected a rotation parameter"),
+    }
+    match round_tripped.objects[2].specific {
+        ObjectType::BlockVisibilityParameter(ref visibility) => assert_eq!(vec![0x2A], visibility.states[0].__entities_handle),
+        _ => panic!("expected a visibility parameter"),
+    }
+}
+
+#[test]
+fn read_named_objects_by_path() {
+    let drawing = from_section("OBJECTS", vec![
+        "  0", "DICTIONARY",
+        "  5", "C",
+        "330", "0",
+        "100", "AcDbDictionary",
+        "  3", "ACAD_LAYOUT",
+        "350", "1A",
+        "  3", "ACAD_GROUP",
+        "350", "1C",
+        "  0", "DICTIONARY",
+        "  5", "1A",
+        "330", "C",
+        "100", "AcDbDictionary",
+        "  3", "Layout1",
+        "350", "1B",
+        "  0", "LAYOUT",
+        "  5", "1B",
+        "330", "1A",
+        "  0", "DICTIONARY",
+        "  5", "1C",
+        "330", "C",
+        "100", "AcDbDictionary",
+    ].join("\r\n").as_str());
+    let named = drawing.named_objects().unwrap();
+    assert_eq!(0xC, named.object().common.handle);
+    assert_eq!(vec!["ACAD_GROUP", "ACAD_LAYOUT"], named.keys());
+    assert_eq!(vec![0x1C, 0x1A], named.iter().map(|(_, o)| o.common.handle).collect::<Vec<_>>());
+    match named.get("ACAD_LAYOUT/Layout1").unwrap().specific {
+        ObjectType::Layout(_) => (),
+        _ => panic!("expected a layout"),
+    }
+    assert!(named.get("ACAD_LAYOUT/Layout2").is_none());
+    assert!(named.get("ACAD_LAYOUT/Layout1/Nested").is_none());
+    assert_eq!(1, named.get_dictionary("ACAD_LAYOUT").unwrap().len());
+    assert!(named.get_dictionary("ACAD_GROUP").unwrap().is_empty());
+}
+
+#[test]
+fn add_and_remove_named_objects() {
+    let mut drawing = Drawing::default();
+    assert!(drawing.named_objects().is_none());
+    let root = drawing.named_objects_mut().handle();
+    assert_ne!(0, root);
+    let (app, record) = {
+        let mut named = drawing.named_objects_mut();
+        assert_eq!(root, named.handle());
+        let mut app = named.get_or_create_dictionary("MYAPP/SETTINGS").unwrap();
+        let record = app.insert("Colors", Object::new(ObjectType::XRecordObject(XRecordObject::default()))).unwrap();
+        (app.handle(), record)
+    };
+    assert_eq!(4, drawing.objects.len());
+    assert_eq!(root, drawing.objects[0].common.handle);
+    let settings = drawing.named_objects().unwrap().get("MYAPP/SETTINGS").unwrap();
+    assert_eq!(app, settings.common.handle);
+    let colors = drawing.named_objects().unwrap().get("MYAPP/SETTINGS/Colors").unwrap();
+    assert_eq!((record, app), (colors.common.handle, colors.common.__owner_handle));
+    assert_eq!(root, drawing.named_objects().unwrap().get("MYAPP").unwrap().common.__owner_handle);
+
+    {
+        let mut named = drawing.named_objects_mut();
+        let mut settings = named.get_dictionary_mut("MYAPP/SETTINGS").unwrap();
+        assert!(settings.insert_reference("Root", root));
+        assert_eq!(root, settings.get("Root").unwrap().common.handle);
+        match settings.get_mut("Colors").unwrap().specific {
+            ObjectType::XRecordObject(ref mut xr) => xr.data_pairs.push(CodePair::new_str(1, "red")),
+            _ => panic!("expected an xrecord"),
+        }
+    }
+    {
+        let mut named = drawing.named_objects_mut();
+        let mut app = named.get_dictionary_mut("MYAPP").unwrap();
+        match app.get_mut("SETTINGS").unwrap().specific {
+            ObjectType::Dictionary(ref mut dict) => dict.is_hard_owner = true,
+            _ => panic!("expected a dictionary"),
+        }
+        // a hard owner can't refer to objects it doesn't own
+        assert!(!app.get_dictionary_mut("SETTINGS").unwrap().insert_reference("Other", root));
+    }
+    assert_contains(&drawing, vec![
+        "  3", "Colors",
+        "360", &format!("{:X}", record),
+    ].join("\r\n"));
+
+    // removing an owned dictionary removes everything it owns but not what it only refers to
+    let removed = drawing.named_objects_mut().remove("MYAPP").unwrap();
+    assert_eq!(root, removed.common.__owner_handle);
+    assert_eq!(1, drawing.objects.len());
+    assert!(drawing.named_objects().unwrap().is_empty());
+    assert!(drawing.named_objects_mut().remove("MYAPP").is_none());
+}
+
+#[test]
+fn change_dictionary_with_default() {
+    let mut drawing = from_section("OBJECTS", vec![
+        "  0", "DICTIONARY",
+        "  5", "C",
+        "330", "0",
+        "100", "AcDbDictionary",
+        "  3", "ACAD_PLOTSTYLENAME",
+        "350", "E",
+        "  0", "ACDBDICTIONARYWDFLT",
+        "  5", "E",
+        "330", "C",
+        "100", "AcDbDictionary",
+        "  3", "Normal",
+        "350", "F",
+        "100", "AcDbDictionaryWithDefault",
+        "340", "F",
+        "  0", "ACDBPLACEHOLDER",
+        "  5", "F",
+        "330", "E",
+    ].join("\r\n").as_str());
+    {
+        let mut named = drawing.named_objects_mut();
+        let mut styles = named.get_dictionary_mut("ACAD_PLOTSTYLENAME").unwrap();
+        assert_eq!(0xE, styles.handle());
+        assert!(styles.insert_reference("Root", 0xC));
+        styles.set_string("Comment", "plot styles");
+        assert!(styles.remove("Normal").is_some());
+    }
+    let named = drawing.named_objects().unwrap();
+    let styles = named.get_dictionary("ACAD_PLOTSTYLENAME").unwrap();
+    assert_eq!(vec!["Comment", "Root"], styles.keys());
+    assert_eq!(Some(String::from("plot styles")), styles.get_string("Comment"));
+    assert_eq!(0xC, styles.get("Root").unwrap().common.handle);
+    assert!(drawing.objects.iter().all(|o| o.common.handle != 0xF));
+    assert!(drawing.named_objects_mut().get_or_create_dictionary("ACAD_PLOTSTYLENAME/Nested").is_some());
+}
+
+#[test]
+fn store_values_in_extension_dictionary() {
+    let mut drawing = Drawing::default();
+    drawing.header.version = AcadVersion::R2004;
+    let mut entity = entities::Entity::new(entities::EntityType::Line(entities::Line::default()));
+    assert!(entity.extension_dictionary(&drawing).is_none());
+    let (xdict, name) = {
+        let mut xdict = entity.get_or_create_extension_dictionary(&mut drawing);
+        let name = xdict.set_string("Name", "pipe").unwrap();
+        xdict.set_f64("Diameter", 2.5);
+        xdict.set_i32("Count", 3);
+        xdict.set_point("Anchor", &Point::new(1.0, 2.0, 3.0));
+        xdict.set_handle("Owner", 0xABC);
+        // updating a value keeps the record
+        assert_eq!(Some(name), xdict.set_string("Name", "duct"));
+        (xdict.handle(), name)
+    };
+    assert_ne!(0, entity.common.handle);
+    assert_eq!(6, drawing.objects.len());
+    assert_eq!(xdict, entity.get_or_create_extension_dictionary(&mut drawing).handle());
+    assert_eq!(6, drawing.objects.len());
+
+    {
+        let view = entity.extension_dictionary(&drawing).unwrap();
+        assert_eq!(entity.common.handle, view.object().common.__owner_handle);
+        assert_eq!(xdict, view.get("Name").unwrap().common.__owner_handle);
+        assert_eq!(name, view.get("Name").unwrap().common.handle);
+        assert_eq!(Some(String::from("duct")), view.get_string("Name"));
+        assert_eq!(Some(2.5), view.get_f64("Diameter"));
+        assert_eq!(Some(3), view.get_i32("Count"));
+        assert_eq!(Some(Point::new(1.0, 2.0, 3.0)), view.get_point("Anchor"));
+        assert_eq!(Some(0xABC), view.get_handle("Owner"));
+        // mismatched types and missing keys
+        assert_eq!(None, view.get_f64("Name"));
+        assert_eq!(None, view.get_string("Missing"));
+    }
+
+    drawing.entities.push(entity);
+    assert_contains(&drawing, vec![
+        "102", "{ACAD_XDICTIONARY",
+        "360", &format!("{:X}", xdict),
+        "102", "}",
+    ].join("\r\n"));
+    assert_contains(&drawing, vec![
+        "  3", "Name",
+        "360", &format!("{:X}", name),
+    ].join("\r\n"));
+
+    let round_tripped = parse_drawing(&to_test_string(&drawing));
+    let view = round_tripped.entities[0].extension_dictionary(&round_tripped).unwrap();
+    assert_eq!(5, view.len());
+    assert_eq!(Some(String::from("duct")), view.get_string("Name"));
+    assert_eq!(Some(Point::new(1.0, 2.0, 3.0)), view.get_point("Anchor"));
+    assert_eq!(Some(0xABC), view.get_handle("Owner"));
+}
+
+#[test]
+fn extension_dictionary_of_item_in_drawing() {
+    let mut drawing = Drawing::default();
+    let mut line = entities::Entity::new(entities::EntityType::Line(entities::Line::default()));
+    line.common.handle = 0x10;
+    drawing.entities.push(line);
+    drawing.header.next_available_handle = 0x11;
+    let line = 0x10;
+    let xdict = {
+        let mut xdict = drawing.get_or_create_extension_dictionary(line).unwrap();
+        xdict.set_string("Name", "pipe");
+        xdict.handle()
+    };
+    assert_eq!(xdict, drawing.get_or_create_extension_dictionary(line).unwrap().handle());
+    assert_eq!(Some(String::from("pipe")), drawing.entities[0].extension_dictionary(&drawing).unwrap().get_string("Name"));
+
+    // objects get extension dictionaries too
+    let root = drawing.named_objects_mut().handle();
+    drawing.get_or_create_extension_dictionary(root).unwrap().set_i32("Count", 3);
+    assert_eq!(Some(3), drawing.objects[0].extension_dictionary(&drawing).unwrap().get_i32("Count"));
+
+    // unknown handles
+    assert!(drawing.get_or_create_extension_dictionary(0).is_none());
+    assert!(drawing.get_or_create_extension_dictionary(0xFFFF).is_none());
+}
+
+#[test]
+fn dictionary_view_of_changed_object() {
+    let mut drawing = Drawing::default();
+    let mut named = drawing.named_objects_mut();
+    let mut app = named.get_or_create_dictionary("MYAPP").unwrap();
+    let handle = app.handle();
+    assert!(app.insert_reference("Self", handle));
+    // the view's handle no longer refers to a dictionary
+    app.get_mut("Self").unwrap().specific = ObjectType::XRecordObject(XRecordObject::default());
+    assert!(app.as_view().is_none());
+    assert!(app.set_string("Name", "pipe").is_none());
+    assert!(app.insert("Other", Object::new(ObjectType::XRecordObject(XRecordObject::default()))).is_none());
+    assert!(!app.insert_reference("Root", handle));
+    assert!(app.remove("Self").is_none());
+    assert!(app.get_or_create_dictionary("Nested").is_none());
+}
+
+#[test]
+fn xrecord_typed_values() {
+    let mut xr = XRecordObject::default();
+    assert_eq!(None, xr.get_string());
+    xr.data_pairs.push(CodePair::new_f64(10, 1.0));
+    xr.data_pairs.push(CodePair::new_f64(20, 2.0));
+    assert_eq!(Some(Point::new(1.0, 2.0, 0.0)), xr.get_point());
+    xr.set_i32(42);
+    assert_eq!(Some(42), xr.get_i32());
+    assert_eq!(Some(Point::new(1.0, 2.0, 0.0)), xr.get_point());
+    xr.set_handle(0x1F);
+    assert_eq!(Some(0x1F), xr.get_handle());
+
+    // setting a value only replaces the first pair with the same code
+    xr.data_pairs.push(CodePair::new_i32(90, 7));
+    xr.set_i32(43);
+    xr.set_point(&Point::new(4.0, 5.0, 6.0));
+    assert_eq!(vec![
+        CodePair::new_f64(10, 4.0),
+        CodePair::new_f64(20, 5.0),
+        CodePair::new_f64(30, 6.0),
+        CodePair::new_i32(90, 43),
+        CodePair::new_str(340, "1F"),
+        CodePair::new_i32(90, 7),
+    ], xr.data_pairs);
+}
+
+#[test]
+fn reuse_extension_dictionary_with_default() {
+    let mut drawing = Drawing::default();
+    let mut line = entities::Entity::new(entities::EntityType::Line(entities::Line::default()));
+    line.common.handle = 0x10;
+    line.common.extension_data_groups.push(ExtensionGroup {
+        application_name: String::from("ACAD_XDICTIONARY"),
+        items: vec![ExtensionGroupItem::CodePair(CodePair::new_str(360, "11"))],
+    });
+    let mut xdict = Object::new(ObjectType::DictionaryWithDefault(DictionaryWithDefault::default()));
+    xdict.common.handle = 0x11;
+    xdict.common.__owner_handle = 0x10;
+    drawing.objects.push(xdict);
+    drawing.header.next_available_handle = 0x12;
+    {
+        let mut xdict = line.get_or_create_extension_dictionary(&mut drawing);
+        assert_eq!(0x11, xdict.handle());
+        xdict.set_string("Name", "pipe");
+    }
+    assert_eq!(2, drawing.objects.len());
+    assert_eq!(Some(String::from("pipe")), line.extension_dictionary(&drawing).unwrap().get_string("Name"));
+
+    // an xdictionary reference to something else the entity owns is replaced
+    let mut circle = entities::Entity::new(entities::EntityType::Circle(entities::Circle::default()));
+    circle.common.handle = 0x20;
+    circle.common.extension_data_groups.push(ExtensionGroup {
+        application_name: String::from("ACAD_XDICTIONARY"),
+        items: vec![ExtensionGroupItem::CodePair(CodePair::new_str(360, "21"))],
+    });
+    let mut record = Object::new(ObjectType::XRecordObject(XRecordObject::default()));
+    record.common.handle = 0x21;
+    record.common.__owner_handle = 0x20;
+    drawing.objects.push(record);
+    drawing.header.next_available_handle = 0x22;
+    let xdict = circle.get_or_create_extension_dictionary(&mut drawing).handle();
+    assert_ne!(0x21, xdict);
+    assert!(drawing.objects.iter().all(|o| o.common.handle != 0x21));
+    assert_eq!(xdict, circle.extension_dictionary(&drawing).unwrap().object().common.handle);
+}
@@ -0,0 +1,183 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::tables::*;
+
+fn assert_close(expected: &Point, actual: &Point) {
+    assert!(expected.distance_to(actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+fn ints(values: &[i32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes().to_vec()).collect()
+}
+
+fn doubles(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes().to_vec()).collect()
+}
+
+fn command(kind: i32, data: Vec<u8>) -> Vec<u8> {
+    let mut bytes = ints(&[data.len() as i32 + 8, kind]);
+    bytes.extend(data);
+    bytes
+}
+
+fn stream(commands: Vec<Vec<u8>>) -> Vec<u8> {
+    let body = commands.concat();
+    let mut bytes = ints(&[body.len() as i32 + 8, commands.len() as i32]);
+    bytes.extend(body);
+    bytes
+}
+
+fn drawing_with_layers(names: &[&str]) -> Drawing {
+    let mut drawing = Drawing::default();
+    for name in names {
+        let mut layer = Layer::default();
+        layer.name = String::from(*name);
+        drawing.layers.push(layer);
+    }
+
+    drawing
+}
+
+#[test]
+fn decode_primitives_with_colors_and_layers() {
+    let drawing = drawing_with_layers(&["0", "walls"]);
+    let mut text = doubles(&[1.0, 2.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 2.5, 1.0, 0.0]);
+    text.extend(b"abc\0");
+    let data = stream(vec![
+        command(2, doubles(&[1.0, 2.0, 0.0, 3.0, 0.0, 0.0, 1.0])),
+        command(14, ints(&[1])),
+        command(16, ints(&[1])),
+        command(4, doubles(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, -::std::f64::consts::FRAC_PI_2])),
+        command(6, [ints(&[2]), doubles(&[0.0, 0.0, 0.0, 1.0, 1.0, 0.0])].concat()),
+        command(7, [ints(&[3]), doubles(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0])].concat()),
+        command(17, doubles(&[0.0])),
+        command(10, text),
+    ]);
+    let entities = drawing.decode_proxy_graphics(&data).unwrap();
+    assert_eq!(5, entities.len());
+
+    assert_eq!("0", entities[0].common.layer);
+    assert_eq!(Color::by_layer(), entities[0].common.color);
+    match entities[0].specific {
+        EntityType::Circle(ref circle) => {
+            assert_eq!(Point::new(1.0, 2.0, 0.0), circle.center);
+            assert_eq!(3.0, circle.radius);
+        },
+        _ => panic!("expected a circle"),
+    }
+
+    for entity in &entities[1..] {
+        assert_eq!("walls", entity.common.layer);
+        assert_eq!(Some(1), entity.common.color.index());
+    }
+
+    match entities[1].specific {
+        // the negative sweep from 90 degrees runs clockwise down to 0
+        EntityType::Arc(ref arc) => assert_eq!((2.0, 0.0, 90.0), (arc.radius, arc.start_angle.round(), arc.end_angle.round())),
+        _ => panic!("expected an arc"),
+    }
+    match entities[2].specific {
+        EntityType::Line(ref line) => assert_eq!(Point::new(1.0, 1.0, 0.0), line.p2),
+        _ => panic!("expected a line"),
+    }
+    match entities[3].specific {
+        EntityType::Polyline(ref poly) => {
+            assert!(poly.get_is_closed());
+            assert_eq!(3, poly.vertices.len());
+        },
+        _ => panic!("expected a polyline"),
+    }
+    match entities[4].specific {
+        EntityType::Text(ref text) => {
+            assert_eq!("abc", text.value);
+            assert_eq!(Point::new(1.0, 2.0, 0.0), text.location);
+            assert_eq!(90.0, text.rotation);
+            assert_eq!(2.5, text.text_height);
+        },
+        _ => panic!("expected text"),
+    }
+}
+
+#[test]
+fn decode_proxy_entity_graphics() {
+    let drawing = drawing_with_layers(&["0"]);
+    let mut text = doubles(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+    text.extend(vec![0x4F, 0x00, 0x4B, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    let translation = doubles(&[
+        1.0, 0.0, 0.0, 10.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ]);
+    // a pentagon face and a triangular hole
+    let shell = [
+        ints(&[5]),
+        doubles(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 3.0, 1.0, 0.0, 1.0, 2.0, 0.0, -1.0, 1.0, 0.0]),
+        ints(&[10, 5, 0, 1, 2, 3, 4, -3, 0, 1, 2]),
+    ].concat();
+    let data = stream(vec![
+        command(22, ints(&[0xC2_00FF00u32 as i32])),
+        command(29, translation),
+        command(5, doubles(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 0.0])),
+        command(31, vec![]),
+        command(34, text),
+        command(9, shell),
+    ]);
+
+    let mut proxy = ProxyEntity::default();
+    proxy.graphics_data_size = data.len() as i32;
+    proxy.graphics_data_string = data.chunks(16)
+        .map(|c| c.iter().map(|b| format!("{:02X}", b)).collect())
+        .collect();
+    // the entity data follows the graphics
+    proxy.graphics_data_string.push(String::from("DEADBEEF"));
+    let mut entity = Entity::new(EntityType::ProxyEntity(proxy));
+    entity.common.layer = String::from("proxies");
+    entity.common.color = Color::from_index(3);
+
+    let entities = drawing.proxy_graphics_entities(&entity).unwrap();
+    assert_eq!(3, entities.len());
+    for entity in &entities {
+        assert_eq!("proxies", entity.common.layer);
+        assert_eq!(Some(3), entity.common.color.index());
+        assert_eq!(0x00FF00, entity.common.color_24_bit);
+    }
+
+    match entities[0].specific {
+        EntityType::Arc(ref arc) => {
+            assert_close(&Point::new(10.0, 0.0, 0.0), &arc.center);
+            assert_eq!(1.0, arc.radius);
+            assert_eq!((0.0, 180.0), (arc.start_angle.round(), arc.end_angle.round()));
+        },
+        _ => panic!("expected an arc"),
+    }
+    match entities[1].specific {
+        EntityType::Text(ref text) => assert_eq!("OK", text.value),
+        _ => panic!("expected text"),
+    }
+    match entities[2].specific {
+        EntityType::Polyline(ref poly) => {
+            let mesh = poly.as_polyface().unwrap();
+            assert_eq!(5, mesh.vertices.len());
+            // the pentagon is split into a fan of three triangles
+            assert_eq!(3, mesh.faces.len());
+            assert_eq!(vec![0, 2, 3], mesh.faces[1].vertex_indices);
+            assert_eq!(vec![true, false, true], mesh.faces[1].invisible_edges);
+        },
+        _ => panic!("expected a polyface mesh"),
+    }
+}
+
+#[test]
+fn decode_empty_and_truncated_graphics() {
+    let drawing = Drawing::default();
+    let entity = Entity::new(EntityType::Line(Line::default()));
+    assert!(drawing.proxy_graphics_entities(&entity).unwrap().is_empty());
+
+    let mut data = stream(vec![command(2, doubles(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]))]);
+    data.truncate(data.len() - 8);
+    assert!(drawing.decode_proxy_graphics(&data).is_err());
+}
@@ -0,0 +1,150 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+fn point_box(x: f64, y: f64) -> BoundingBox {
+    BoundingBox::new(Point::new(x, y, 0.0), Point::new(x + 1.0, y + 1.0, 0.0))
+}
+
+// a 20x20 grid of unit boxes spaced 2 units apart
+fn grid() -> RTree<usize> {
+    let mut tree = RTree::new();
+    for i in 0..400 {
+        tree.insert(i, point_box((i % 20) as f64 * 2.0, (i / 20) as f64 * 2.0));
+    }
+    tree
+}
+
+fn sorted(items: Vec<&usize>) -> Vec<usize> {
+    let mut items = items.into_iter().cloned().collect::<Vec<_>>();
+    items.sort();
+    items
+}
+
+#[test]
+fn arc_bounding_box() {
+    let ent = Entity::new(EntityType::Arc(Arc::new(Point::new(1.0, 1.0, 0.0), 2.0, 0.0, 90.0)));
+    let bounds = ent.bounding_box().unwrap();
+    assert!((bounds.min.x - 1.0).abs() < 1.0e-5);
+    assert!((bounds.min.y - 1.0).abs() < 1.0e-5);
+    assert!((bounds.max.x - 3.0).abs() < 1.0e-5);
+    assert!((bounds.max.y - 3.0).abs() < 1.0e-5);
+}
+
+#[test]
+fn unbounded_entity_has_no_bounding_box() {
+    let ent = Entity::new(EntityType::XLine(XLine::default()));
+    assert_eq!(None, ent.bounding_box());
+}
+
+fn assert_bounds(min: Point, max: Point, bounds: BoundingBox) {
+    assert!(min.distance_to(&bounds.min) < 1.0e-5, "expected {:?} but got {:?}", min, bounds.min);
+    assert!(max.distance_to(&bounds.max) < 1.0e-5, "expected {:?} but got {:?}", max, bounds.max);
+}
+
+#[test]
+fn arc_and_circle_bounding_boxes_are_exact() {
+    fn assert_exact(min: Point, max: Point, bounds: BoundingBox) {
+        assert!(min.distance_to(&bounds.min) < 1.0e-12, "expected {:?} but got {:?}", min, bounds.min);
+        assert!(max.distance_to(&bounds.max) < 1.0e-12, "expected {:?} but got {:?}", max, bounds.max);
+    }
+
+    // the arc crosses the top quadrant point only
+    let arc = Entity::new(EntityType::Arc(Arc::new(Point::origin(), 2.0, 30.0, 120.0)));
+    assert_exact(Point::new(-1.0, 1.0, 0.0), Point::new(3.0f64.sqrt(), 2.0, 0.0), arc.bounding_box().unwrap());
+
+    // a circle tilted 45 degrees about the X axis
+    let mut circle = Circle::new(Point::origin(), 1.0);
+    circle.normal = Vector::new(0.0, 1.0, 1.0);
+    let half = 0.5f64.sqrt();
+    assert_exact(Point::new(-1.0, -half, -half), Point::new(1.0, half, half), Entity::new(EntityType::Circle(circle)).bounding_box().unwrap());
+}
+
+#[test]
+fn hatch_bounding_box() {
+    // a closed half disc below the X axis at an elevation of 3
+    let mut hatch = Hatch::default();
+    hatch.elevation_point = Point::new(0.0, 0.0, 3.0);
+    hatch.boundary_paths.push(HatchBoundaryPath::new(HatchBoundaryPathGeometry::Polyline {
+        is_closed: true,
+        vertices: vec![
+            HatchPolylineVertex { location: Point::new(0.0, 0.0, 0.0), bulge: 1.0 },
+            HatchPolylineVertex { location: Point::new(2.0, 0.0, 0.0), bulge: 0.0 },
+        ],
+    }));
+    let ent = Entity::new(EntityType::Hatch(hatch));
+    assert_bounds(Point::new(0.0, -1.0, 3.0), Point::new(2.0, 0.0, 3.0), ent.bounding_box().unwrap());
+
+    // clockwise arcs store negated angles
+    let mut hatch = Hatch::default();
+    hatch.boundary_paths.push(HatchBoundaryPath::new(HatchBoundaryPathGeometry::Edges(vec![
+        HatchBoundaryEdge::CircularArc { center: Point::origin(), radius: 1.0, start_angle: 0.0, end_angle: 90.0, is_counter_clockwise: false },
+    ])));
+    let ent = Entity::new(EntityType::Hatch(hatch));
+    assert_bounds(Point::new(0.0, -1.0, 0.0), Point::new(1.0, 0.0, 0.0), ent.bounding_box().unwrap());
+}
+
+#[test]
+fn window_queries() {
+    let tree = grid();
+    assert_eq!(400, tree.len());
+    let window = BoundingBox::new(Point::new(-0.5, -0.5, 0.0), Point::new(3.5, 1.5, 0.0));
+    assert_eq!(vec![0, 1], sorted(tree.within(&window)));
+    assert_eq!(vec![0, 1, 20, 21], sorted(tree.crossing(&BoundingBox::new(Point::new(0.5, 0.5, 0.0), Point::new(2.5, 2.5, 0.0)))));
+}
+
+#[test]
+fn distance_and_nearest_queries() {
+    let tree = grid();
+    assert_eq!(vec![21], sorted(tree.within_distance(&Point::new(2.5, 2.5, 0.0), 0.5)));
+    assert_eq!(vec![0, 1, 20, 21], sorted(tree.within_distance(&Point::new(1.5, 1.5, 0.0), 0.75)));
+    let nearest = tree.nearest(&Point::new(39.0, 39.0, 0.0), 2);
+    assert_eq!(&399, nearest[0]);
+    assert_eq!(2, nearest.len());
+    assert_eq!(400, tree.nearest(&Point::origin(), 1000).len());
+}
+
+#[test]
+fn remove_items_keeps_tree_consistent() {
+    let mut tree = grid();
+    assert_eq!(200, tree.remove_where(|i| i % 2 == 0));
+    assert!(tree.remove(&1));
+    assert!(!tree.remove(&1));
+    assert_eq!(199, tree.len());
+    let everything = BoundingBox::new(Point::new(-1.0, -1.0, 0.0), Point::new(100.0, 100.0, 0.0));
+    let expected = (3..400).filter(|i| i % 2 == 1).collect::<Vec<_>>();
+    assert_eq!(expected, sorted(tree.crossing(&everything)));
+    assert_eq!(199, tree.iter().count());
+    tree.remove_where(|_| true);
+    assert!(tree.is_empty());
+    assert_eq!(None, tree.bounds());
+}
+
+#[test]
+fn drawing_spatial_index_assigns_handles() {
+    let mut drawing = Drawing::default();
+    drawing.entities.push(Entity::new(EntityType::Line(Line::new(Point::origin(), Point::new(1.0, 1.0, 0.0)))));
+    drawing.entities.push(Entity::new(EntityType::Circle(Circle::new(Point::new(10.0, 10.0, 0.0), 1.0))));
+    let mut block = Block::default();
+    block.name = String::from("b");
+    block.entities.push(Entity::new(EntityType::Line(Line::new(Point::origin(), Point::new(1.0, 0.0, 0.0)))));
+    drawing.blocks.push(block);
+    let mut insert = Insert::default();
+    insert.name = String::from("b");
+    insert.location = Point::new(20.0, 0.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::Insert(insert)));
+
+    let index = drawing.spatial_index();
+    assert_eq!(3, index.len());
+    assert!(drawing.entities.iter().all(|e| e.common.handle != 0));
+    let circle_handle = drawing.entities[1].common.handle;
+    let insert_handle = drawing.entities[2].common.handle;
+    assert_eq!(vec![&circle_handle], index.nearest(&Point::new(12.0, 10.0, 0.0), 1));
+    assert_eq!(vec![&insert_handle], index.within(&BoundingBox::new(Point::new(19.0, -1.0, 0.0), Point::new(22.0, 1.0, 0.0))));
+
+    let block_index = drawing.block_spatial_index("B").unwrap();
+    assert_eq!(1, block_index.len());
+    assert!(drawing.block_spatial_index("missing").is_none());
+}
@@ -0,0 +1,232 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1.0e-9, "expected {} but got {}", expected, actual);
+}
+
+fn lw_vertex(x: f64, y: f64, bulge: f64) -> LwPolylineVertex {
+    LwPolylineVertex { x, y, bulge, .. Default::default() }
+}
+
+#[test]
+fn lw_polyline_segments() {
+    let poly = LwPolyline {
+        vertices: vec![
+            lw_vertex(0.0, 0.0, 0.0),
+            lw_vertex(2.0, 0.0, 1.0), // counter-clockwise semicircle
+            lw_vertex(2.0, 2.0, 0.0),
+        ],
+        .. Default::default()
+    };
+    let segments = poly.segments().collect::<Vec<_>>();
+    assert_eq!(2, segments.len());
+    assert_eq!(Segment::Line { start: Point::new(0.0, 0.0, 0.0), end: Point::new(2.0, 0.0, 0.0), starting_width: 0.0, ending_width: 0.0 }, segments[0]);
+    match segments[1] {
+        Segment::Arc { center, radius, start_angle, end_angle, is_counter_clockwise, .. } => {
+            assert_eq!(Point::new(2.0, 1.0, 0.0), center);
+            assert_close(1.0, radius);
+            assert_close(270.0, start_angle);
+            assert_close(90.0, end_angle);
+            assert!(is_counter_clockwise);
+        },
+        _ => panic!("expected an arc"),
+    }
+    assert_close(180.0, segments[1].sweep_angle());
+    assert_eq!(Point::new(2.0, 2.0, 0.0), segments[1].end_point());
+
+    // a counter-clockwise semicircle from (2,0) to (2,2) bulges out to the right
+    let mid = segments[1].point_at(0.5);
+    assert_close(3.0, mid.x);
+    assert_close(1.0, mid.y);
+}
+
+#[test]
+fn lw_polyline_segments_clockwise_arc() {
+    let poly = LwPolyline {
+        vertices: vec![lw_vertex(0.0, 0.0, -1.0), lw_vertex(2.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    let segments = poly.segments().collect::<Vec<_>>();
+    assert_eq!(1, segments.len());
+    match segments[0] {
+        Segment::Arc { start_angle, end_angle, is_counter_clockwise, .. } => {
+            assert_close(180.0, start_angle);
+            assert_close(0.0, end_angle);
+            assert!(!is_counter_clockwise);
+        },
+        _ => panic!("expected an arc"),
+    }
+    assert_close(-180.0, segments[0].sweep_angle());
+    assert_close(1.0, segments[0].point_at(0.5).y);
+}
+
+#[test]
+fn closed_lw_polyline_segments() {
+    let mut poly = LwPolyline {
+        vertices: vec![
+            lw_vertex(0.0, 0.0, 0.0),
+            lw_vertex(1.0, 0.0, 0.0),
+            lw_vertex(1.0, 1.0, 0.0),
+            lw_vertex(1.0, 1.0, 0.0), // duplicate vertex produces no segment
+        ],
+        .. Default::default()
+    };
+    assert_eq!(2, poly.segments().count());
+    poly.set_is_closed(true);
+    let segments = poly.segments().collect::<Vec<_>>();
+    assert_eq!(3, segments.len());
+    assert_eq!(Point::new(1.0, 1.0, 0.0), segments[2].start_point());
+    assert_eq!(Point::new(0.0, 0.0, 0.0), segments[2].end_point());
+}
+
+#[test]
+fn lw_polyline_segment_widths() {
+    let poly = LwPolyline {
+        constant_width: 0.5,
+        vertices: vec![
+            LwPolylineVertex { x: 0.0, y: 0.0, starting_width: 1.0, ending_width: 3.0, .. Default::default() },
+            lw_vertex(1.0, 0.0, 0.0),
+            lw_vertex(2.0, 0.0, 0.0),
+        ],
+        .. Default::default()
+    };
+    let segments = poly.segments().collect::<Vec<_>>();
+    assert_close(1.0, segments[0].width_at(0.0));
+    assert_close(2.0, segments[0].width_at(0.5));
+    assert_close(3.0, segments[0].width_at(1.0));
+    assert_close(0.5, segments[1].width_at(0.5));
+}
+
+#[test]
+fn polyline_segments() {
+    let mut poly = Polyline::default();
+    poly.location = Point::new(0.0, 0.0, 5.0);
+    poly.default_starting_width = 2.0;
+    poly.default_ending_width = 2.0;
+    poly.set_is_closed(true);
+    let mut v = Vertex::new(Point::new(0.0, 0.0, 0.0));
+    v.bulge = 1.0;
+    poly.vertices.push(v);
+    poly.vertices.push(Vertex::new(Point::new(2.0, 0.0, 0.0)));
+    let segments = poly.segments().collect::<Vec<_>>();
+    assert_eq!(2, segments.len());
+    match segments[0] {
+        Segment::Arc { center, .. } => assert_eq!(Point::new(1.0, 0.0, 0.0), center),
+        _ => panic!("expected an arc"),
+    }
+    assert_eq!(Segment::Line { start: Point::new(2.0, 0.0, 0.0), end: Point::new(0.0, 0.0, 0.0), starting_width: 2.0, ending_width: 2.0 }, segments[1]);
+}
+
+#[test]
+fn lw_polyline_and_polyline_segments_apply_elevation_alike() {
+    let lw_poly = LwPolyline {
+        vertices: vec![lw_vertex(0.0, 0.0, 1.0), lw_vertex(2.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    let mut lw_ent = Entity::new(EntityType::LwPolyline(lw_poly.clone()));
+    lw_ent.common.elevation = 5.0;
+    let mut poly = Polyline::default();
+    poly.location = Point::new(0.0, 0.0, 5.0);
+    let mut v = Vertex::new(Point::new(0.0, 0.0, 0.0));
+    v.bulge = 1.0;
+    poly.vertices.push(v);
+    poly.vertices.push(Vertex::new(Point::new(2.0, 0.0, 0.0)));
+    let poly_ent = Entity::new(EntityType::Polyline(poly.clone()));
+
+    // both produce segments at an elevation of 0
+    assert_eq!(lw_poly.segments().collect::<Vec<_>>(), poly.segments().collect::<Vec<_>>());
+
+    // and both apply their elevation when exploded
+    for exploded in vec![lw_ent.explode_polyline().unwrap(), poly_ent.explode_polyline().unwrap()] {
+        match exploded[0].specific {
+            EntityType::Arc(ref arc) => assert_eq!(Point::new(1.0, 0.0, 5.0), arc.center),
+            _ => panic!("expected an arc"),
+        }
+    }
+}
+
+#[test]
+fn polyface_mesh_has_no_segments() {
+    let mut poly = Polyline::default();
+    poly.set_is_polyface_mesh(true);
+    poly.vertices.push(Vertex::new(Point::new(0.0, 0.0, 0.0)));
+    poly.vertices.push(Vertex::new(Point::new(1.0, 0.0, 0.0)));
+    assert_eq!(0, poly.segments().count());
+}
+
+#[test]
+fn explode_lw_polyline() {
+    let poly = LwPolyline {
+        thickness: 3.0,
+        vertices: vec![lw_vertex(0.0, 0.0, 0.0), lw_vertex(2.0, 0.0, -1.0), lw_vertex(4.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    let mut ent = Entity::new(EntityType::LwPolyline(poly));
+    ent.common.handle = 0x42;
+    ent.common.layer = String::from("some-layer");
+    ent.common.color = Color::from_index(3);
+    ent.common.elevation = 7.0;
+    let exploded = ent.explode_polyline().unwrap();
+    assert_eq!(2, exploded.len());
+    for e in &exploded {
+        assert_eq!(0, e.common.handle);
+        assert_eq!("some-layer", e.common.layer);
+        assert_eq!(Color::from_index(3), e.common.color);
+    }
+    match exploded[0].specific {
+        EntityType::Line(ref line) => {
+            assert_eq!(Point::new(0.0, 0.0, 7.0), line.p1);
+            assert_eq!(Point::new(2.0, 0.0, 7.0), line.p2);
+            assert_eq!(3.0, line.thickness);
+        },
+        _ => panic!("expected a line"),
+    }
+    match exploded[1].specific {
+        EntityType::Arc(ref arc) => {
+            // clockwise segments are reversed to run counter-clockwise
+            assert_eq!(Point::new(3.0, 0.0, 7.0), arc.center);
+            assert_close(1.0, arc.radius);
+            assert_close(0.0, arc.start_angle);
+            assert_close(180.0, arc.end_angle);
+        },
+        _ => panic!("expected an arc"),
+    }
+}
+
+#[test]
+fn explode_polyline_with_normal() {
+    let mut poly = Polyline::default();
+    poly.normal = Vector::new(0.0, 0.0, -1.0);
+    poly.vertices.push(Vertex::new(Point::new(1.0, 0.0, 0.0)));
+    poly.vertices.push(Vertex::new(Point::new(2.0, 0.0, 0.0)));
+    let exploded = Entity::new(EntityType::Polyline(poly)).explode_polyline().unwrap();
+    match exploded[0].specific {
+        // the OCS x-axis is mirrored
+        EntityType::Line(ref line) => {
+            assert_eq!(Point::new(-1.0, 0.0, 0.0), line.p1);
+            assert_eq!(Point::new(-2.0, 0.0, 0.0), line.p2);
+        },
+        _ => panic!("expected a line"),
+    }
+}
+
+#[test]
+fn explode_non_polyline() {
+    let ent = Entity::new(EntityType::Line(Line::default()));
+    assert!(ent.explode_polyline().is_none());
+}
+
+#[test]
+fn reversed_arc_segment() {
+    let segment = Segment::from_bulge(Point::new(1.0, 0.0, 0.0), Point::new(-1.0, 0.0, 0.0), 1.0, 0.5, 1.5);
+    let reversed = segment.reversed();
+    assert_eq!(Point::new(-1.0, 0.0, 0.0), reversed.start_point());
+    assert_eq!(Point::new(1.0, 0.0, 0.0), reversed.end_point());
+    assert!((reversed.sweep_angle() + 180.0).abs() < 1.0e-9);
+    assert_eq!(1.5, reversed.width_at(0.0));
+    assert!(reversed.point_at(0.5).distance_to(&segment.point_at(0.5)) < 1.0e-9);
+}
@@ -0,0 +1,189 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+fn assert_close(expected: &Point, actual: &Point) {
+    assert!(expected.distance_to(actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+fn quadratic_bezier() -> Spline {
+    Spline {
+        degree_of_curve: 2,
+        knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        control_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 2.0, 0.0), Point::new(2.0, 0.0, 0.0)],
+        .. Default::default()
+    }
+}
+
+fn cubic() -> Spline {
+    Spline {
+        degree_of_curve: 3,
+        knot_values: vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+        control_points: vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 3.0, 0.0),
+            Point::new(3.0, 3.0, 1.0),
+            Point::new(4.0, 0.0, 2.0),
+            Point::new(6.0, 1.0, 0.0),
+        ],
+        .. Default::default()
+    }
+}
+
+#[test]
+fn spline_parameter_range() {
+    assert_eq!(Some((0.0, 2.0)), cubic().parameter_range());
+
+    let mut invalid = cubic();
+    invalid.knot_values.pop();
+    assert_eq!(None, invalid.parameter_range());
+    assert!(invalid.point_at(0.5).is_err());
+}
+
+#[test]
+fn spline_point_and_derivatives() {
+    let spline = quadratic_bezier();
+    assert_close(&Point::new(0.0, 0.0, 0.0), &spline.point_at(0.0).unwrap());
+    assert_close(&Point::new(1.0, 1.0, 0.0), &spline.point_at(0.5).unwrap());
+    assert_close(&Point::new(2.0, 0.0, 0.0), &spline.point_at(1.0).unwrap());
+
+    // C'(0) = 2(P1 - P0) and C'' = 2(P0 - 2P1 + P2)
+    let derivatives = spline.derivatives_at(0.0, 3).unwrap();
+    assert_eq!(4, derivatives.len());
+    assert_eq!(Vector::new(2.0, 4.0, 0.0), derivatives[1]);
+    assert_eq!(Vector::new(0.0, -8.0, 0.0), derivatives[2]);
+    assert_eq!(Vector::zero(), derivatives[3]);
+    assert!(spline.point_at(1.5).is_err());
+}
+
+#[test]
+fn rational_spline_quarter_circle() {
+    let mut spline = quadratic_bezier();
+    spline.control_points = vec![Point::new(1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0), Point::new(0.0, 1.0, 0.0)];
+    spline.weights = vec![1.0, 0.5f64.sqrt(), 1.0];
+    spline.set_is_rational(true);
+    for i in 0..11 {
+        let p = spline.point_at(i as f64 / 10.0).unwrap();
+        assert!((p.distance_to(&Point::origin()) - 1.0).abs() < 1.0e-12);
+    }
+
+    // the tangent is perpendicular to the radius
+    let p = spline.point_at(0.3).unwrap();
+    assert!(spline.tangent_at(0.3).unwrap().dot(&p.as_vector()).abs() < 1.0e-12);
+}
+
+#[test]
+fn spline_knot_insertion_preserves_shape() {
+    let original = cubic();
+    let mut spline = original.clone();
+    spline.insert_knot(0.5, 2).unwrap();
+    assert_eq!(vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 1.0, 2.0, 2.0, 2.0, 2.0], spline.knot_values);
+    assert_eq!(7, spline.control_points.len());
+    for i in 0..21 {
+        let u = i as f64 / 10.0;
+        assert_close(&original.point_at(u).unwrap(), &spline.point_at(u).unwrap());
+    }
+}
+
+#[test]
+fn spline_knot_insertion_at_existing_knot() {
+    let original = cubic();
+    let mut spline = original.clone();
+    spline.insert_knot(1.0, 2).unwrap();
+    assert_eq!(vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0], spline.knot_values);
+    assert_close(&original.point_at(1.0).unwrap(), &spline.control_points[3]);
+
+    // the multiplicity can't exceed the degree
+    assert!(spline.insert_knot(1.0, 1).is_err());
+    assert!(spline.insert_knot(2.0, 1).is_err());
+}
+
+#[test]
+fn rational_spline_knot_refinement() {
+    let mut original = quadratic_bezier();
+    original.weights = vec![1.0, 3.0, 0.5];
+    let mut spline = original.clone();
+    spline.refine_knots(&[0.75, 0.25, 0.5]).unwrap();
+    assert_eq!(vec![0.0, 0.0, 0.0, 0.25, 0.5, 0.75, 1.0, 1.0, 1.0], spline.knot_values);
+    assert_eq!(6, spline.weights.len());
+    for i in 0..11 {
+        let u = i as f64 / 10.0;
+        assert_close(&original.point_at(u).unwrap(), &spline.point_at(u).unwrap());
+    }
+
+    // a failed refinement leaves the spline unchanged
+    assert!(spline.refine_knots(&[0.1, 5.0]).is_err());
+    assert_eq!(6, spline.control_points.len());
+}
+
+#[test]
+fn spline_interpolate_fit_points() {
+    let mut spline = Spline {
+        degree_of_curve: 3,
+        fit_points: vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+            Point::new(4.0, 4.0, 1.0),
+            Point::new(6.0, 3.0, 0.0),
+        ],
+        .. Default::default()
+    };
+    spline.interpolate_fit_points().unwrap();
+    assert_eq!(5, spline.control_points.len());
+    assert_eq!(9, spline.knot_values.len());
+    let (start, end) = spline.parameter_range().unwrap();
+    assert_eq!(0.0, start);
+    let mut u = 0.0;
+    for (i, fit_point) in spline.fit_points.iter().enumerate() {
+        if i > 0 {
+            u += spline.fit_points[i - 1].distance_to(fit_point);
+        }
+
+        assert_close(fit_point, &spline.point_at(u).unwrap());
+    }
+
+    assert!((end - u).abs() < 1.0e-12);
+}
+
+#[test]
+fn spline_interpolate_fit_points_with_tangents() {
+    let mut spline = Spline {
+        degree_of_curve: 3,
+        start_tangent: Point::new(0.0, 1.0, 0.0),
+        end_tangent: Point::new(2.0, 0.0, 0.0),
+        fit_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 0.0), Point::new(4.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    spline.interpolate_fit_points().unwrap();
+    assert_eq!(5, spline.control_points.len());
+    let (start, end) = spline.parameter_range().unwrap();
+    assert_close(&Point::new(0.0, 0.0, 0.0), &spline.point_at(start).unwrap());
+    assert_close(&Point::new(2.0, 2.0, 0.0), &spline.point_at(8.0f64.sqrt()).unwrap());
+    assert_close(&Point::new(4.0, 0.0, 0.0), &spline.point_at(end).unwrap());
+    assert!((spline.tangent_at(start).unwrap() - Vector::new(0.0, 1.0, 0.0)).length() < 1.0e-9);
+    assert!((spline.tangent_at(end).unwrap() - Vector::new(1.0, 0.0, 0.0)).length() < 1.0e-9);
+}
+
+#[test]
+fn spline_interpolate_reduces_degree() {
+    let mut spline = Spline {
+        degree_of_curve: 3,
+        fit_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)],
+        .. Default::default()
+    };
+    spline.interpolate_fit_points().unwrap();
+    assert_eq!(1, spline.degree_of_curve);
+    assert_eq!(vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)], spline.control_points);
+}
+
+#[test]
+fn spline_interpolate_coincident_fit_points() {
+    let mut spline = Spline {
+        fit_points: vec![Point::new(1.0, 1.0, 0.0), Point::new(1.0, 1.0, 0.0)],
+        .. Default::default()
+    };
+    assert!(spline.interpolate_fit_points().is_err());
+}
@@ -62,6 +62,29 @@ fn read_single_layer() {
     assert_eq!("layer-name", drawing.layers[0].name);
 }
 
+#[test]
+fn dim_style_unit_formats_default_to_decimal() {
+    // the defaults match the header's `$DIMUNIT`, `$DIMALTU`, and `$DIMLUNIT`
+    let header = Header::default();
+    let dim_style = DimStyle::default();
+    assert_eq!(UnitFormat::Decimal, dim_style.dimension_unit_format);
+    assert_eq!(UnitFormat::Decimal, dim_style.alternate_dimensioning_units);
+    assert_eq!(NonAngularUnits::Decimal, dim_style.dimension_non_angular_units);
+    assert_eq!(header.dimension_unit_format, dim_style.dimension_unit_format);
+    assert_eq!(header.alternate_dimensioning_units, dim_style.alternate_dimensioning_units);
+    assert_eq!(header.dimension_non_angular_units, dim_style.dimension_non_angular_units);
+
+    // a style that doesn't specify its units is read with the defaults
+    let drawing = read_table("DIMSTYLE", vec![
+        "0", "DIMSTYLE",
+        "2", "dim-style-name",
+    ]);
+    let dim_style = &drawing.dim_styles[0];
+    assert_eq!(UnitFormat::Decimal, dim_style.dimension_unit_format);
+    assert_eq!(UnitFormat::Decimal, dim_style.alternate_dimensioning_units);
+    assert_eq!(NonAngularUnits::Decimal, dim_style.dimension_non_angular_units);
+}
+
 #[test]
 fn read_variable_table_items() {
     let drawing = parse_drawing(vec![
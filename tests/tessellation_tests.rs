@@ -0,0 +1,149 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+fn assert_close(expected: &Point, actual: &Point) {
+    assert!(expected.distance_to(actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+#[test]
+fn tessellate_line() {
+    let ent = Entity::new(EntityType::Line(Line::new(Point::new(1.0, 2.0, 3.0), Point::new(4.0, 5.0, 6.0))));
+    let polylines = ent.tessellate(0.01);
+    assert_eq!(vec![vec![Point::new(1.0, 2.0, 3.0), Point::new(4.0, 5.0, 6.0)]], polylines);
+}
+
+#[test]
+fn tessellate_arc_within_tolerance() {
+    let tolerance = 0.001;
+    let ent = Entity::new(EntityType::Arc(Arc::new(Point::new(1.0, 1.0, 0.0), 2.0, 0.0, 90.0)));
+    let polylines = ent.tessellate(tolerance);
+    assert_eq!(1, polylines.len());
+    let points = &polylines[0];
+    assert_close(&Point::new(3.0, 1.0, 0.0), &points[0]);
+    assert_close(&Point::new(1.0, 3.0, 0.0), &points[points.len() - 1]);
+    for pair in points.windows(2) {
+        // the sagitta of each chord must be within the tolerance
+        let mid = Point::new((pair[0].x + pair[1].x) / 2.0, (pair[0].y + pair[1].y) / 2.0, 0.0);
+        assert!(2.0 - mid.distance_to(&Point::new(1.0, 1.0, 0.0)) <= tolerance);
+    }
+}
+
+#[test]
+fn tessellate_circle_is_closed() {
+    let ent = Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0)));
+    let points = &ent.tessellate(0.01)[0];
+    assert!(points.len() > 4);
+    assert_eq!(points[0], points[points.len() - 1]);
+}
+
+#[test]
+fn tessellate_circle_with_non_positive_tolerance_is_finest() {
+    let ent = Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0)));
+    let finest = ent.tessellate(0.0)[0].len();
+    assert_eq!(4097, finest);
+    assert_eq!(finest, ent.tessellate(-1.0)[0].len());
+    assert_eq!(finest, ent.tessellate(::std::f64::NAN)[0].len());
+    assert!(ent.tessellate(0.01)[0].len() < finest);
+}
+
+#[test]
+fn tessellate_circle_respects_ocs() {
+    // a normal of -Z mirrors the X axis
+    let mut circle = Circle::new(Point::new(5.0, 0.0, 0.0), 1.0);
+    circle.normal = Vector::new(0.0, 0.0, -1.0);
+    let points = &Entity::new(EntityType::Circle(circle)).tessellate(0.01)[0];
+    assert_close(&Point::new(-6.0, 0.0, 0.0), &points[0]);
+}
+
+#[test]
+fn tessellate_lw_polyline_with_bulge() {
+    // a bulge of 1 is a semicircle
+    let poly = LwPolyline {
+        vertices: vec![
+            LwPolylineVertex { x: 0.0, y: 0.0, bulge: 1.0, .. Default::default() },
+            LwPolylineVertex { x: 2.0, y: 0.0, .. Default::default() },
+        ],
+        .. Default::default()
+    };
+    let points = &Entity::new(EntityType::LwPolyline(poly)).tessellate(0.001)[0];
+    assert_close(&Point::new(0.0, 0.0, 0.0), &points[0]);
+    assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+    for p in points {
+        assert!((p.distance_to(&Point::new(1.0, 0.0, 0.0)) - 1.0).abs() < 1.0e-9);
+        assert!(p.y <= 1.0e-9); // counter-clockwise from (0,0) to (2,0) passes below the chord
+    }
+}
+
+#[test]
+fn tessellate_wide_lw_polyline_returns_outline() {
+    let poly = LwPolyline {
+        constant_width: 2.0,
+        vertices: vec![
+            LwPolylineVertex { x: 0.0, y: 0.0, .. Default::default() },
+            LwPolylineVertex { x: 10.0, y: 0.0, .. Default::default() },
+        ],
+        .. Default::default()
+    };
+    let polylines = Entity::new(EntityType::LwPolyline(poly)).tessellate(0.01);
+    assert_eq!(vec![vec![
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(10.0, 1.0, 0.0),
+        Point::new(10.0, -1.0, 0.0),
+        Point::new(0.0, -1.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+    ]], polylines);
+}
+
+#[test]
+fn tessellate_closed_polyline() {
+    let mut poly = Polyline::default();
+    poly.set_is_closed(true);
+    poly.vertices.push(Vertex::new(Point::new(0.0, 0.0, 0.0)));
+    poly.vertices.push(Vertex::new(Point::new(1.0, 0.0, 0.0)));
+    poly.vertices.push(Vertex::new(Point::new(1.0, 1.0, 0.0)));
+    let polylines = Entity::new(EntityType::Polyline(poly)).tessellate(0.01);
+    assert_eq!(vec![vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(1.0, 1.0, 0.0),
+        Point::new(0.0, 0.0, 0.0),
+    ]], polylines);
+}
+
+#[test]
+fn tessellate_ellipse() {
+    let ellipse = Ellipse {
+        major_axis: Vector::new(2.0, 0.0, 0.0),
+        minor_axis_ratio: 0.5,
+        .. Default::default()
+    };
+    let points = &Entity::new(EntityType::Ellipse(ellipse)).tessellate(0.01)[0];
+    assert_close(&Point::new(2.0, 0.0, 0.0), &points[0]);
+    for p in points {
+        // (x/a)^2 + (y/b)^2 = 1
+        assert!(((p.x / 2.0).powi(2) + (p.y / 1.0).powi(2) - 1.0).abs() < 1.0e-9);
+    }
+}
+
+#[test]
+fn tessellate_linear_spline() {
+    let spline = Spline {
+        degree_of_curve: 1,
+        knot_values: vec![0.0, 0.0, 1.0, 2.0, 2.0],
+        control_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0), Point::new(2.0, 0.0, 0.0)],
+        .. Default::default()
+    };
+    let points = &Entity::new(EntityType::Spline(spline)).tessellate(0.01)[0];
+    assert_close(&Point::new(0.0, 0.0, 0.0), &points[0]);
+    assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+    assert!(points.iter().any(|p| p.distance_to(&Point::new(1.0, 1.0, 0.0)) < 1.0e-9));
+}
+
+#[test]
+fn tessellate_unsupported_entity() {
+    let ent = Entity::new(EntityType::Text(Text::default()));
+    assert!(ent.tessellate(0.01).is_empty());
+}
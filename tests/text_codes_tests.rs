@@ -0,0 +1,60 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::enums::*;
+
+fn span(text: &str, is_underlined: bool, is_overlined: bool) -> TextSpan {
+    TextSpan { text: String::from(text), is_underlined, is_overlined }
+}
+
+#[test]
+fn decode_special_characters_and_toggles() {
+    let decoded = DecodedText::decode("%%c10 %%uDEPTH%%o 45%%D%%u%%p0.1%%O 100%%% %%169 \\U+00E9t\\U+D83D\\U+DE00 50%");
+    assert_eq!(vec![
+        span("\u{2300}10 ", false, false),
+        span("DEPTH", true, false),
+        span(" 45\u{b0}", true, true),
+        span("\u{b1}0.1", false, true),
+        span(" 100% \u{a9} \u{e9}t\u{1f600} 50%", false, false),
+    ], decoded.spans);
+    assert_eq!("\u{2300}10 DEPTH 45\u{b0}\u{b1}0.1 100% \u{a9} \u{e9}t\u{1f600} 50%", decoded.plain_text());
+
+    // unknown codes are left as they are
+    assert_eq!("%%x \\U+zz %%12", DecodedText::decode("%%x \\U+zz %%12").plain_text());
+}
+
+#[test]
+fn encode_and_round_trip() {
+    let decoded = DecodedText {
+        spans: vec![
+            span("\u{b1}5%", false, false),
+            span("%%d \u{e9}", true, false),
+            span("x\\U+", false, false),
+        ],
+    };
+    let unicode = decoded.encode(AcadVersion::R2013);
+    assert_eq!("%%p5%%%%%u%%%%d \u{e9}%%ux\\U+005CU+", unicode);
+    assert_eq!(decoded, DecodedText::decode(&unicode));
+
+    let ascii = decoded.encode(AcadVersion::R2000);
+    assert_eq!("%%p5%%%%%u%%%%d \\U+00E9%%ux\\U+005CU+", ascii);
+    assert_eq!(decoded, DecodedText::decode(&ascii));
+}
+
+#[test]
+fn text_and_attribute_values() {
+    let mut text = Text::default();
+    text.value = String::from("%%uNOTE%%u: 90%%d");
+    assert_eq!("NOTE: 90\u{b0}", text.plain_text());
+    assert!(text.decoded_value().spans[0].is_underlined);
+
+    let mut attribute = Attribute::default();
+    attribute.set_decoded_value(&text.decoded_value(), AcadVersion::R2013);
+    assert_eq!(text.value, attribute.value);
+
+    let mut definition = AttributeDefinition::default();
+    definition.value = String::from("\\U+2205%%c");
+    assert_eq!("\u{2205}\u{2300}", definition.plain_text());
+}
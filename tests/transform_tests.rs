@@ -0,0 +1,244 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+
+use std::f64::consts::PI;
+
+fn assert_close(expected: &Point, actual: &Point) {
+    assert!(expected.distance_to(actual) < 1.0e-9, "expected {:?} but got {:?}", expected, actual);
+}
+
+#[test]
+fn transform_line() {
+    let mut ent = Entity::new(EntityType::Line(Line::new(Point::new(1.0, 0.0, 0.0), Point::new(2.0, 0.0, 0.0))));
+    ent.transform(&TransformationMatrix::rotation_about_z(PI / 2.0));
+    match ent.specific {
+        EntityType::Line(ref line) => {
+            assert_close(&Point::new(0.0, 1.0, 0.0), &line.p1);
+            assert_close(&Point::new(0.0, 2.0, 0.0), &line.p2);
+        },
+        _ => panic!("expected a line"),
+    }
+}
+
+#[test]
+fn transform_arc_with_uniform_scale_and_rotation() {
+    let mut ent = Entity::new(EntityType::Arc(Arc::new(Point::new(1.0, 0.0, 0.0), 1.0, 0.0, 90.0)));
+    let matrix = TransformationMatrix::rotation_about_z(PI / 2.0).multiply(&TransformationMatrix::scale(2.0, 2.0, 2.0));
+    ent.transform(&matrix);
+    match ent.specific {
+        EntityType::Arc(ref arc) => {
+            assert_close(&Point::new(0.0, 2.0, 0.0), &arc.center);
+            assert!((arc.radius - 2.0).abs() < 1.0e-9);
+            assert!((arc.start_angle - 90.0).abs() < 1.0e-9);
+            assert!((arc.end_angle - 180.0).abs() < 1.0e-9);
+        },
+        _ => panic!("expected an arc"),
+    }
+}
+
+#[test]
+fn transform_arc_with_mirror_keeps_counter_clockwise_direction() {
+    let mut ent = Entity::new(EntityType::Arc(Arc::new(Point::origin(), 1.0, 0.0, 90.0)));
+    ent.transform(&TransformationMatrix::scale(-1.0, 1.0, 1.0));
+    let points = ent.tessellate(0.01);
+    let points = &points[0];
+    assert_close(&Point::new(-1.0, 0.0, 0.0), &points[0]);
+    assert_close(&Point::new(0.0, 1.0, 0.0), &points[points.len() - 1]);
+}
+
+fn hatch_with_edges(edges: Vec<HatchBoundaryEdge>) -> Entity {
+    let mut hatch = Hatch::default();
+    hatch.boundary_paths.push(HatchBoundaryPath::new(HatchBoundaryPathGeometry::Edges(edges)));
+    Entity::new(EntityType::Hatch(hatch))
+}
+
+#[test]
+fn transform_hatch_with_rotation() {
+    let mut ent = hatch_with_edges(vec![
+        HatchBoundaryEdge::Line { start: Point::new(1.0, 0.0, 0.0), end: Point::new(2.0, 0.0, 0.0) },
+        HatchBoundaryEdge::CircularArc { center: Point::new(1.0, 0.0, 0.0), radius: 1.0, start_angle: 0.0, end_angle: 360.0, is_counter_clockwise: true },
+        HatchBoundaryEdge::CircularArc { center: Point::origin(), radius: 1.0, start_angle: 0.0, end_angle: 90.0, is_counter_clockwise: false },
+    ]);
+    if let EntityType::Hatch(ref mut hatch) = ent.specific {
+        hatch.pattern_scale = 2.0;
+        hatch.pattern_definition_lines.push(HatchPatternDefinitionLine {
+            angle: 45.0,
+            base_point: Point::new(1.0, 0.0, 0.0),
+            offset: Vector::new(0.0, 1.0, 0.0),
+            dash_lengths: vec![0.5, -0.25],
+        });
+    }
+    let matrix = TransformationMatrix::rotation_about_z(PI / 2.0).multiply(&TransformationMatrix::scale(2.0, 2.0, 2.0));
+    ent.transform(&matrix);
+    match ent.specific {
+        EntityType::Hatch(ref hatch) => {
+            assert!((hatch.pattern_angle - 90.0).abs() < 1.0e-9);
+            assert!((hatch.pattern_scale - 4.0).abs() < 1.0e-9);
+            let line = &hatch.pattern_definition_lines[0];
+            assert!((line.angle - 135.0).abs() < 1.0e-9);
+            assert_close(&Point::new(0.0, 2.0, 0.0), &line.base_point);
+            assert_close(&Point::new(-2.0, 0.0, 0.0), &(Point::origin() + line.offset));
+            assert_eq!(vec![1.0, -0.5], line.dash_lengths);
+            let edges = match hatch.boundary_paths[0].geometry {
+                HatchBoundaryPathGeometry::Edges(ref edges) => edges,
+                _ => panic!("expected edges"),
+            };
+            match edges[0] {
+                HatchBoundaryEdge::Line { ref start, ref end } => {
+                    assert_close(&Point::new(0.0, 2.0, 0.0), start);
+                    assert_close(&Point::new(0.0, 4.0, 0.0), end);
+                },
+                _ => panic!("expected a line"),
+            }
+            match edges[1] {
+                HatchBoundaryEdge::CircularArc { ref center, radius, start_angle, end_angle, is_counter_clockwise } => {
+                    assert_close(&Point::new(0.0, 2.0, 0.0), center);
+                    assert!((radius - 2.0).abs() < 1.0e-9);
+                    assert!((start_angle - 90.0).abs() < 1.0e-9);
+                    assert!((end_angle - 450.0).abs() < 1.0e-9);
+                    assert!(is_counter_clockwise);
+                },
+                _ => panic!("expected a circular arc"),
+            }
+            match edges[2] {
+                // clockwise angles are negated, so the rotation is subtracted
+                HatchBoundaryEdge::CircularArc { start_angle, end_angle, is_counter_clockwise, .. } => {
+                    assert!((start_angle + 90.0).abs() < 1.0e-9);
+                    assert!(end_angle.abs() < 1.0e-9);
+                    assert!(!is_counter_clockwise);
+                },
+                _ => panic!("expected a circular arc"),
+            }
+        },
+        _ => panic!("expected a hatch"),
+    }
+}
+
+#[test]
+fn transform_hatch_arc_with_non_uniform_scale() {
+    let mut ent = hatch_with_edges(vec![
+        HatchBoundaryEdge::CircularArc { center: Point::new(1.0, 1.0, 0.0), radius: 1.0, start_angle: 0.0, end_angle: 90.0, is_counter_clockwise: true },
+    ]);
+    ent.transform(&TransformationMatrix::scale(1.0, 2.0, 1.0));
+    let bounds = ent.bounding_box().unwrap();
+    assert_close(&Point::new(1.0, 2.0, 0.0), &bounds.min);
+    assert_close(&Point::new(2.0, 4.0, 0.0), &bounds.max);
+    match ent.specific {
+        EntityType::Hatch(ref hatch) => match hatch.boundary_paths[0].geometry {
+            HatchBoundaryPathGeometry::Edges(ref edges) => match edges[0] {
+                HatchBoundaryEdge::EllipticArc { ref center, ref major_axis, minor_axis_ratio, is_counter_clockwise, .. } => {
+                    assert_close(&Point::new(1.0, 2.0, 0.0), center);
+                    assert!((major_axis.length() - 2.0).abs() < 1.0e-9);
+                    assert!((minor_axis_ratio - 0.5).abs() < 1.0e-9);
+                    assert!(is_counter_clockwise);
+                },
+                _ => panic!("expected an elliptic arc"),
+            },
+            _ => panic!("expected edges"),
+        },
+        _ => panic!("expected a hatch"),
+    }
+}
+
+#[test]
+fn transform_mleader() {
+    let mut leader = MLeaderLeader::default();
+    leader.last_leader_line_point = Point::new(2.0, 1.0, 0.0);
+    leader.dogleg_length = 0.5;
+    leader.lines.push(MLeaderLine { vertices: vec![Point::origin()], ..Default::default() });
+    let mut text = MLeaderTextContent::default();
+    text.location = Point::new(3.0, 1.0, 0.0);
+    let mut mleader = MLeader::default();
+    mleader.context_data.text_height = 0.25;
+    mleader.context_data.text_content = Some(text);
+    mleader.context_data.leaders.push(leader);
+    let mut ent = Entity::new(EntityType::MLeader(mleader));
+
+    let matrix = TransformationMatrix::translation(&Vector::new(1.0, 0.0, 0.0)).multiply(&TransformationMatrix::scale(2.0, 2.0, 2.0));
+    ent.transform(&matrix);
+    let bounds = ent.bounding_box().unwrap();
+    assert_close(&Point::new(1.0, 0.0, 0.0), &bounds.min);
+    assert_close(&Point::new(7.0, 2.0, 0.0), &bounds.max);
+    match ent.specific {
+        EntityType::MLeader(ref mleader) => {
+            let ctx = &mleader.context_data;
+            assert!((ctx.text_height - 0.5).abs() < 1.0e-9);
+            assert_close(&Point::new(7.0, 2.0, 0.0), &ctx.text_content.as_ref().unwrap().location);
+            assert_close(&Point::new(5.0, 2.0, 0.0), &ctx.leaders[0].last_leader_line_point);
+            assert!((ctx.leaders[0].dogleg_length - 1.0).abs() < 1.0e-9);
+            assert_close(&Point::new(1.0, 0.0, 0.0), &ctx.leaders[0].lines[0].vertices[0]);
+        },
+        _ => panic!("expected an mleader"),
+    }
+}
+
+#[test]
+fn transform_acad_table() {
+    let mut table = AcadTable::from_text_grid(&[vec!["a", "b"], vec!["c", "d"]], 1.0, 2.0);
+    table.cells[0].overrides.text_height = Some(0.25);
+    let mut ent = Entity::new(EntityType::AcadTable(table));
+    let matrix = TransformationMatrix::rotation_about_z(PI / 2.0).multiply(&TransformationMatrix::scale(2.0, 2.0, 2.0));
+    ent.transform(&matrix);
+    let bounds = ent.bounding_box().unwrap();
+    assert_close(&Point::new(0.0, 0.0, 0.0), &bounds.min);
+    assert_close(&Point::new(4.0, 8.0, 0.0), &bounds.max);
+    match ent.specific {
+        EntityType::AcadTable(ref table) => {
+            assert_close(&Point::new(0.0, 1.0, 0.0), &(Point::origin() + table.horizontal_direction));
+            assert_eq!(vec![4.0, 4.0], table.column_widths);
+            assert_eq!(vec![2.0, 2.0], table.row_heights);
+            assert_eq!(Some(0.5), table.cells[0].overrides.text_height);
+        },
+        _ => panic!("expected a table"),
+    }
+}
+
+#[test]
+fn transform_viewport() {
+    let vp = Viewport::new(Point::new(5.0, 4.0, 0.0), 8.0, 6.0, Point::new(50.0, 40.0, 0.0), 0.1);
+    let mut ent = Entity::new(EntityType::Viewport(vp));
+    let matrix = TransformationMatrix::translation(&Vector::new(1.0, 0.0, 0.0)).multiply(&TransformationMatrix::scale(2.0, 0.5, 1.0));
+    ent.transform(&matrix);
+    let bounds = ent.bounding_box().unwrap();
+    assert_close(&Point::new(3.0, 0.5, 0.0), &bounds.min);
+    assert_close(&Point::new(19.0, 3.5, 0.0), &bounds.max);
+    match ent.specific {
+        EntityType::Viewport(ref vp) => {
+            assert_close(&Point::new(11.0, 2.0, 0.0), &vp.center);
+            assert_close(&Point::new(50.0, 40.0, 0.0), &vp.view_center);
+            assert!((vp.view_height - 60.0).abs() < 1.0e-9);
+        },
+        _ => panic!("expected a viewport"),
+    }
+}
+
+#[test]
+fn transform_mesh() {
+    let mesh = Mesh::new(vec![Point::origin(), Point::new(1.0, 0.0, 0.0), Point::new(0.0, 1.0, 1.0)], vec![vec![0, 1, 2]]);
+    let mut ent = Entity::new(EntityType::Mesh(mesh));
+    ent.transform(&TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0)));
+    let bounds = ent.bounding_box().unwrap();
+    assert_close(&Point::new(1.0, 2.0, 3.0), &bounds.min);
+    assert_close(&Point::new(2.0, 3.0, 4.0), &bounds.max);
+}
+
+#[test]
+fn transform_extruded_surface() {
+    let mut surface = ExtrudedSurface::default();
+    surface.sweep_vector = Vector::new(0.0, 0.0, 2.0);
+    surface.draft_end_distance = 1.0;
+    let mut ent = Entity::new(EntityType::ExtrudedSurface(surface));
+    let matrix = TransformationMatrix::translation(&Vector::new(1.0, 0.0, 0.0)).multiply(&TransformationMatrix::scale(1.0, 1.0, 3.0));
+    ent.transform(&matrix);
+    match ent.specific {
+        EntityType::ExtrudedSurface(ref surface) => {
+            assert_close(&Point::new(0.0, 0.0, 6.0), &(Point::origin() + surface.sweep_vector));
+            assert!((surface.draft_end_distance - 3.0).abs() < 1.0e-9);
+            assert_eq!(matrix, surface.extruded_entity_transform);
+        },
+        _ => panic!("expected an extruded surface"),
+    }
+}
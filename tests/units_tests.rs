@@ -0,0 +1,126 @@
+// Copyright (c) IxMilia.  All Rights Reserved.  Licensed under the Apache License, Version 2.0.  See License.txt in the project root for license information.
+
+extern crate dxf;
+use self::dxf::*;
+use self::dxf::entities::*;
+use self::dxf::enums::*;
+use self::dxf::tables::*;
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1.0e-9, "expected {} but got {}", expected, actual);
+}
+
+#[test]
+fn unit_scale_factors() {
+    assert_close(25.4, Units::Inches.scale_factor_to(Units::Millimeters));
+    assert_close(1.0 / 12.0, Units::Inches.scale_factor_to(Units::Feet));
+    assert_close(1000.0, Units::Kilometers.scale_factor_to(Units::Meters));
+    assert_close(1.0, Units::Unitless.scale_factor_to(Units::Meters));
+    assert_close(1.0, Units::Feet.scale_factor_to(Units::Feet));
+}
+
+#[test]
+fn drawing_units_fall_back_to_measurement() {
+    let mut drawing = Drawing::default();
+    drawing.header.default_drawing_units = Units::Unitless;
+    drawing.header.drawing_units = DrawingUnits::English;
+    assert_eq!(Units::Inches, drawing.units());
+    drawing.header.drawing_units = DrawingUnits::Metric;
+    assert_eq!(Units::Millimeters, drawing.units());
+    drawing.header.default_drawing_units = Units::Feet;
+    assert_eq!(Units::Feet, drawing.units());
+}
+
+#[test]
+fn convert_inches_to_millimeters() {
+    let mut drawing = Drawing::default();
+    drawing.header.default_drawing_units = Units::Inches;
+    drawing.header.drawing_units = DrawingUnits::English;
+    drawing.header.default_text_height = 0.2;
+    drawing.header.maximum_drawing_extents = Point::new(1.0, 2.0, 0.0);
+    drawing.entities.push(Entity::new(EntityType::Line(Line::new(Point::origin(), Point::new(1.0, 0.0, 0.0)))));
+    let mut text = Text::default();
+    text.text_height = 0.125;
+    drawing.entities.push(Entity::new(EntityType::Text(text)));
+    let mut dim_style = DimStyle::default();
+    dim_style.dimensioning_text_height = 0.18;
+    drawing.dim_styles.push(dim_style);
+    let mut line_type = LineType::default();
+    line_type.total_pattern_length = 0.75;
+    line_type.dash_dot_space_lengths = vec![0.5, -0.25];
+    drawing.line_types.push(line_type);
+
+    drawing.convert_units(Units::Millimeters);
+    assert_eq!(Units::Millimeters, drawing.header.default_drawing_units);
+    assert_eq!(DrawingUnits::Metric, drawing.header.drawing_units);
+    assert_close(5.08, drawing.header.default_text_height);
+    assert_close(50.8, drawing.header.maximum_drawing_extents.y);
+    match drawing.entities[0].specific {
+        EntityType::Line(ref line) => assert_close(25.4, line.p2.x),
+        _ => panic!("expected a line"),
+    }
+    match drawing.entities[1].specific {
+        EntityType::Text(ref text) => assert_close(3.175, text.text_height),
+        _ => panic!("expected text"),
+    }
+    assert_close(4.572, drawing.dim_styles[0].dimensioning_text_height);
+    assert_close(19.05, drawing.line_types[0].total_pattern_length);
+    assert_close(-6.35, drawing.line_types[0].dash_dot_space_lengths[1]);
+}
+
+#[test]
+fn convert_units_respects_block_units() {
+    let mut drawing = Drawing::default();
+    drawing.header.default_drawing_units = Units::Meters;
+    for &(name, units) in &[("unitless", Units::Unitless), ("centimeters", Units::Centimeters)] {
+        let mut record = BlockRecord::default();
+        record.name = String::from(name);
+        record.insertion_units = units;
+        drawing.block_records.push(record);
+        let mut block = Block::default();
+        block.name = String::from(name);
+        block.entities.push(Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0))));
+        drawing.blocks.push(block);
+    }
+    let mut insert = Insert::default();
+    insert.name = String::from("unitless");
+    insert.location = Point::new(2.0, 0.0, 0.0);
+    insert.x_scale_factor = 3.0;
+    drawing.entities.push(Entity::new(EntityType::Insert(insert)));
+    assert_close(0.01, drawing.insert_units_scale("centimeters"));
+
+    drawing.convert_units(Units::Millimeters);
+    assert_close(10.0, drawing.insert_units_scale("centimeters"));
+    assert_close(1.0, drawing.insert_units_scale("unitless"));
+    let radius = |block: &Block| match block.entities[0].specific {
+        EntityType::Circle(ref circle) => circle.radius,
+        _ => panic!("expected a circle"),
+    };
+    assert_close(1000.0, radius(&drawing.blocks[0]));
+    assert_close(1.0, radius(&drawing.blocks[1]));
+    match drawing.entities[0].specific {
+        EntityType::Insert(ref insert) => {
+            assert_close(2000.0, insert.location.x);
+            assert_close(3.0, insert.x_scale_factor);
+        },
+        _ => panic!("expected an insert"),
+    }
+}
+
+#[test]
+fn convert_units_keeps_viewport_scale() {
+    let mut drawing = Drawing::default();
+    drawing.header.default_drawing_units = Units::Inches;
+    let vp = Viewport::new(Point::new(5.0, 4.0, 0.0), 8.0, 6.0, Point::new(50.0, 40.0, 0.0), 0.1);
+    drawing.entities.push(Entity::new(EntityType::Viewport(vp)));
+
+    drawing.convert_units(Units::Feet);
+    match drawing.entities[0].specific {
+        EntityType::Viewport(ref vp) => {
+            assert_close(0.5, vp.height);
+            assert_close(50.0 / 12.0, vp.view_center.x);
+            assert_close(0.1, vp.get_scale());
+        },
+        _ => panic!("expected a viewport"),
+    }
+}